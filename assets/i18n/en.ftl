@@ -0,0 +1,308 @@
+# Settings
+more = More
+wifi = Wi-Fi
+vpn = Vpn
+bluetooth = Bluetooth
+nearby-wifi = Nearby Wifi
+scanning = Scanning...
+airplane-mode = Airplane Mode
+idle-inhibitor = Idle Inhibitor
+no-devices-connected = No devices connected
+power-profile-balanced = Balanced
+power-profile-performance = Performance
+power-profile-power-saver = Power Saver
+
+# Power menu
+suspend = Suspend
+reboot = Reboot
+shutdown = Shutdown
+logout = Logout
+
+# Password dialog
+authentication-required = Authentication required
+insert-password = Insert password to connect to: { $ssid }
+wrong-password = Wrong password, try again
+remember-in-keyring = Remember in the keyring
+polkit-password = Password for { $user }
+cancel = Cancel
+confirm = Confirm
+
+# Updates
+up-to-date = Up to date ;)
+updates-available = { $count } Updates available
+update = Update
+check-now = Check now
+reboot-required = Restart needed to complete the update
+flatpak-updates-available = { $count } Flatpak updates available
+update-all = Update all
+updating-flatpak = Updating { $app } ({ $current }/{ $total })
+firmware-updates-available = { $count } Firmware updates available
+firmware-needs-reboot = The update will be installed on the next restart
+
+# System info
+low-disk-space = Low disk space on { $mount }
+disk-usage-details = { $usage }% used, { $available } available
+disk-usage = { $usage }% used · { $available } available
+scan = Scan
+scanning-mount = Scanning { $mount }...
+top-space-consumers = Top space consumers in { $mount }
+power-draw = Estimated power draw { $watts } W
+top-cpu-consumers = Top processes by CPU usage
+
+# Virtual machines
+no-virtual-machines = No virtual machines defined
+vm-running = Running
+vm-paused = Paused
+vm-shut-off = Shut off
+vm-unknown = Unknown
+
+# Removable media
+removable-media-connected = Removable media connected
+mounted-at = Mounted at { $mount }
+media-free = { $free } free of { $size }
+not-mounted = Not mounted
+no-removable-media = No removable media
+
+# Backup
+no-backup-jobs = No backup jobs configured
+backup-running = Running...
+backup-never-run = Never run
+backup-last-run = Last run { $elapsed } ago
+backup-overdue = overdue
+
+# Print queue
+no-print-jobs = No active print jobs
+resume-printer = Resume { $printer }
+
+# Clock
+change-format = Change format
+agenda-in = in { $time }
+agenda-now = now
+agenda-today = Today
+agenda-all-day = All day
+agenda-no-events = No events today
+
+# Solar events
+sunrise = Sunrise
+sunset = Sunset
+fajr = Fajr
+dhuhr = Dhuhr
+asr = Asr
+maghrib = Maghrib
+isha = Isha
+solar-event-in = { $event } in { $minutes } minutes
+
+# Workspaces
+workspace-preview = Workspace { $workspace }
+no-workspace-preview = Preview not available yet
+
+# Battery
+battery-level = { $capacity }% remaining
+battery-plugged = Charger connected
+battery-unplugged = Running on battery
+battery-full = Battery fully charged
+battery-low = Battery low
+battery-critical = Battery critically low
+
+# Sleep inhibitors
+sleep-inhibited = Sleep blocked by { $processes }
+inhibited-by = { $who } is blocking { $what }
+force-suspend = Suspend anyway
+
+# Emoji picker
+search-emoji = Search
+emoji = Emoji
+kaomoji = Kaomoji
+symbols = Symbols
+no-emoji-results = Nothing found
+
+# Touch
+screen-keyboard = Screen keyboard
+auto-rotate = Auto-rotate
+orientation-normal = Landscape
+orientation-bottom-up = Landscape, flipped
+orientation-left-up = Portrait
+orientation-right-up = Portrait, flipped
+
+# Idle lock
+locking-in = Locking in { $seconds }s — move the mouse to cancel
+lock-cancelled = Lock cancelled
+stay-awake = Stay awake for { $minutes } min
+
+# Global shortcuts
+shortcut-play-pause = Play or pause the media
+shortcut-next-track = Next track
+shortcut-previous-track = Previous track
+shortcut-settings = Open the settings
+shortcut-notifications = Open the notifications
+
+# Sleep timer
+sleep-timer = Sleep timer
+sleep-timer-pausing = Pausing in { $minutes } min
+sleep-timer-minutes = { $minutes } min
+sleep-timer-badge = { $minutes }m
+sleep-timer-all-players = All players
+
+# Data usage
+data-usage = Data used this month
+data-usage-of-quota = { $used } of { $quota }
+data-quota-warning = Data quota almost used
+data-quota-exceeded = Data quota used up
+data-quota-used = { $used } of { $quota } used on { $ssid } this month
+
+# Ping
+packet-loss = Packet loss
+packet-loss-body = { $loss }% of the recent pings to { $host } were lost
+packet-loss-percent = { $loss }% lost
+no-ping-replies = No replies
+ping-stats = min { $min } ms · avg { $avg } ms · max { $max } ms
+
+# Speed test
+speedtest = Speed test
+run-speedtest = Run
+speedtest-running = Testing…
+speedtest-result = ↓ { $download } Mbit/s · ↑ { $upload } Mbit/s · { $latency } ms
+speedtest-failed = Speed test failed: { $error }
+
+# Public IP
+public-ip = Public IP
+vpn-leak = VPN leak
+vpn-leak-body = The public IP { $ip } is outside the VPN address ranges
+vpn-leak-hint = The traffic is not going through the VPN
+
+# Firewall
+firewall-unavailable = No firewall found
+firewall-active = Active
+firewall-inactive = Inactive
+public-wifi-mode = Public Wi-Fi mode
+
+# KDE Connect
+no-paired-phones = No paired devices
+phone-disconnected = Not connected
+phone-connected = Connected
+phone-battery = Connected · { $charge }%
+phone-battery-charging = Connected · { $charge }%, charging
+send-file-to-phone = Send a file to the phone
+
+# Privacy
+recently-accessed = Recently accessed
+clear = Clear
+no-capture-sessions = No recent access
+screencast-monitor = Screen
+screencast-window = Window
+screencast = Screen share
+unknown-app = Unknown application
+capture-ongoing = Since { $start } · { $duration }
+capture-ended = At { $start } for { $duration }
+
+# Break reminder
+break-time = Time for a break
+break-look-away = Look at something far away for { $seconds } seconds
+break-snooze = Snooze { $minutes } min
+break-skip = Skip
+
+# Displays power
+turn-off-displays = Turn off displays
+turn-off-this-display = Turn off this display
+
+# Display modes
+keep-display-mode = Keep this mode? Reverting in { $seconds }s
+keep = Keep
+revert = Revert
+variable-refresh-rate = Variable refresh rate
+hdr = HDR
+
+# Input devices
+touchpad = Touchpad
+natural-scroll = Natural scrolling
+disable-while-typing = Disable while typing
+
+# Screen capture
+screen-capture = Screen capture
+record-region = Record region
+record-region-gif = Record region as GIF
+recording-saved = Recording saved
+recording-saved-body = { $path } copied to the clipboard
+recording-failed = Recording failed
+grab-text = Grab text
+text-copied = Text copied to the clipboard
+no-text-found = No text found in the selected region
+scan-qr-code = Scan QR code
+qr-code-copied = QR code content copied to the clipboard
+no-qr-code-found = No QR code found in the selected region
+open-link = Open link
+share-wifi = Share Wi-Fi
+share-wifi-network = Scan to join { $ssid }
+no-wifi-connection = Not connected to a Wi-Fi network
+
+# Wi-Fi password
+wifi-password-of = Password of { $ssid }
+wifi-password-unavailable = The password of { $ssid } is not available
+
+# Weather
+weather-unavailable = Weather not available
+weather-today = Today
+weather-feels-like = Feels like { $temperature }
+weather-clear = Clear
+weather-partly-cloudy = Partly cloudy
+weather-cloudy = Cloudy
+weather-fog = Fog
+weather-drizzle = Drizzle
+weather-rain = Rain
+weather-snow = Snow
+weather-thunderstorm = Thunderstorm
+
+# Notifications
+notifications = Notifications
+no-notifications = No notifications
+clear-all = Clear all
+do-not-disturb = Do not disturb
+
+# Configuration editor
+configuration = Configuration
+editor-appearance = Appearance
+editor-layout = Layout
+editor-modules = Modules
+bar-position = Bar position
+position-top = Top
+position-bottom = Bottom
+theme-presets = Theme presets
+theme-file = Theme file
+import = Import
+export = Export
+colors = Colors
+color-background = Background
+color-primary = Primary
+color-secondary = Secondary
+color-success = Success
+color-danger = Danger
+color-text = Text
+section-left = Left
+section-center = Center
+section-right = Right
+drag-modules-hint = Drag the modules to rearrange them or to move them to another section
+add-here = Add here
+module-name-placeholder = Module name, e.g. MediaPlayer
+clock-format = Clock format
+truncate-title-after = Truncate window title after
+fill-missing-workspaces = Fill missing workspaces
+apply = Apply
+
+# Welcome wizard
+welcome = Welcome to ashell
+environment-found = This is what ashell found on your system:
+environment-compositor = Compositor: { $compositor }
+environment-compositor-unknown = unknown
+environment-iwd-only = IWD found, network features require NetworkManager
+environment-upower = UPower (battery and power profiles)
+environment-bluez = BlueZ (bluetooth)
+detecting-environment = Detecting your environment...
+theme-dark = Dark
+theme-light = Light
+optional-modules = Optional modules
+module-system-info = System information
+module-tray = Tray
+module-media-player = Media player
+use-defaults = Use defaults
+next = Next
+back = Back
@@ -0,0 +1,308 @@
+# Settings
+more = Altro
+wifi = Wi-Fi
+vpn = Vpn
+bluetooth = Bluetooth
+nearby-wifi = Reti Wi-Fi vicine
+scanning = Ricerca...
+airplane-mode = Modalità aereo
+idle-inhibitor = Blocco inattività
+no-devices-connected = Nessun dispositivo connesso
+power-profile-balanced = Bilanciato
+power-profile-performance = Prestazioni
+power-profile-power-saver = Risparmio energetico
+
+# Power menu
+suspend = Sospendi
+reboot = Riavvia
+shutdown = Spegni
+logout = Esci
+
+# Password dialog
+authentication-required = Autenticazione richiesta
+insert-password = Inserisci la password per connetterti a: { $ssid }
+wrong-password = Password errata, riprova
+remember-in-keyring = Ricorda nel portachiavi
+polkit-password = Password di { $user }
+cancel = Annulla
+confirm = Conferma
+
+# Updates
+up-to-date = Tutto aggiornato ;)
+updates-available = { $count } aggiornamenti disponibili
+update = Aggiorna
+check-now = Controlla ora
+reboot-required = Riavvio necessario per completare l'aggiornamento
+flatpak-updates-available = { $count } aggiornamenti Flatpak disponibili
+update-all = Aggiorna tutto
+updating-flatpak = Aggiornamento di { $app } ({ $current }/{ $total })
+firmware-updates-available = { $count } aggiornamenti firmware disponibili
+firmware-needs-reboot = L'aggiornamento verrà installato al prossimo riavvio
+
+# System info
+low-disk-space = Spazio su disco in esaurimento su { $mount }
+disk-usage-details = { $usage }% usato, { $available } disponibili
+disk-usage = { $usage }% usato · { $available } disponibili
+scan = Analizza
+scanning-mount = Analisi di { $mount }...
+top-space-consumers = Cartelle più grandi in { $mount }
+power-draw = Consumo stimato { $watts } W
+top-cpu-consumers = Processi con più uso della CPU
+
+# Virtual machines
+no-virtual-machines = Nessuna macchina virtuale definita
+vm-running = In esecuzione
+vm-paused = In pausa
+vm-shut-off = Spenta
+vm-unknown = Sconosciuto
+
+# Removable media
+removable-media-connected = Supporto rimovibile collegato
+mounted-at = Montato in { $mount }
+media-free = { $free } liberi su { $size }
+not-mounted = Non montato
+no-removable-media = Nessun supporto rimovibile
+
+# Backup
+no-backup-jobs = Nessun backup configurato
+backup-running = In esecuzione...
+backup-never-run = Mai eseguito
+backup-last-run = Ultima esecuzione { $elapsed } fa
+backup-overdue = in ritardo
+
+# Print queue
+no-print-jobs = Nessuna stampa in corso
+resume-printer = Riprendi { $printer }
+
+# Clock
+change-format = Cambia formato
+agenda-in = tra { $time }
+agenda-now = in corso
+agenda-today = Oggi
+agenda-all-day = Tutto il giorno
+agenda-no-events = Nessun evento oggi
+
+# Solar events
+sunrise = Alba
+sunset = Tramonto
+fajr = Fajr
+dhuhr = Dhuhr
+asr = Asr
+maghrib = Maghrib
+isha = Isha
+solar-event-in = { $event } tra { $minutes } minuti
+
+# Workspaces
+workspace-preview = Workspace { $workspace }
+no-workspace-preview = Anteprima non ancora disponibile
+
+# Battery
+battery-level = { $capacity }% rimanente
+battery-plugged = Alimentatore collegato
+battery-unplugged = Alimentazione a batteria
+battery-full = Batteria completamente carica
+battery-low = Batteria scarica
+battery-critical = Batteria quasi esaurita
+
+# Sleep inhibitors
+sleep-inhibited = Sospensione bloccata da { $processes }
+inhibited-by = { $who } sta bloccando { $what }
+force-suspend = Sospendi comunque
+
+# Emoji picker
+search-emoji = Cerca
+emoji = Emoji
+kaomoji = Kaomoji
+symbols = Simboli
+no-emoji-results = Nessun risultato
+
+# Touch
+screen-keyboard = Tastiera a schermo
+auto-rotate = Rotazione automatica
+orientation-normal = Orizzontale
+orientation-bottom-up = Orizzontale, capovolto
+orientation-left-up = Verticale
+orientation-right-up = Verticale, capovolto
+
+# Idle lock
+locking-in = Blocco tra { $seconds }s — muovi il mouse per annullare
+lock-cancelled = Blocco annullato
+stay-awake = Resta attivo per { $minutes } min
+
+# Global shortcuts
+shortcut-play-pause = Riproduci o metti in pausa
+shortcut-next-track = Traccia successiva
+shortcut-previous-track = Traccia precedente
+shortcut-settings = Apri le impostazioni
+shortcut-notifications = Apri le notifiche
+
+# Sleep timer
+sleep-timer = Timer di spegnimento
+sleep-timer-pausing = Pausa tra { $minutes } min
+sleep-timer-minutes = { $minutes } min
+sleep-timer-badge = { $minutes }m
+sleep-timer-all-players = Tutti i lettori
+
+# Data usage
+data-usage = Dati usati questo mese
+data-usage-of-quota = { $used } di { $quota }
+data-quota-warning = Quota dati quasi esaurita
+data-quota-exceeded = Quota dati esaurita
+data-quota-used = { $used } di { $quota } usati su { $ssid } questo mese
+
+# Ping
+packet-loss = Perdita di pacchetti
+packet-loss-body = Il { $loss }% dei ping recenti verso { $host } è andato perso
+packet-loss-percent = { $loss }% persi
+no-ping-replies = Nessuna risposta
+ping-stats = min { $min } ms · media { $avg } ms · max { $max } ms
+
+# Speed test
+speedtest = Test di velocità
+run-speedtest = Avvia
+speedtest-running = Test in corso…
+speedtest-result = ↓ { $download } Mbit/s · ↑ { $upload } Mbit/s · { $latency } ms
+speedtest-failed = Test di velocità fallito: { $error }
+
+# Public IP
+public-ip = IP pubblico
+vpn-leak = Perdita della VPN
+vpn-leak-body = L'IP pubblico { $ip } è fuori dagli indirizzi della VPN
+vpn-leak-hint = Il traffico non passa dalla VPN
+
+# Firewall
+firewall-unavailable = Nessun firewall trovato
+firewall-active = Attivo
+firewall-inactive = Non attivo
+public-wifi-mode = Modalità Wi-Fi pubblico
+
+# KDE Connect
+no-paired-phones = Nessun dispositivo associato
+phone-disconnected = Non connesso
+phone-connected = Connesso
+phone-battery = Connesso · { $charge }%
+phone-battery-charging = Connesso · { $charge }%, in carica
+send-file-to-phone = Invia un file al telefono
+
+# Privacy
+recently-accessed = Accessi recenti
+clear = Cancella
+no-capture-sessions = Nessun accesso recente
+screencast-monitor = Schermo
+screencast-window = Finestra
+screencast = Condivisione schermo
+unknown-app = Applicazione sconosciuta
+capture-ongoing = Dalle { $start } · { $duration }
+capture-ended = Alle { $start } per { $duration }
+
+# Break reminder
+break-time = È ora di una pausa
+break-look-away = Guarda qualcosa di lontano per { $seconds } secondi
+break-snooze = Rimanda di { $minutes } min
+break-skip = Salta
+
+# Displays power
+turn-off-displays = Spegni gli schermi
+turn-off-this-display = Spegni questo schermo
+
+# Display modes
+keep-display-mode = Mantenere questa modalità? Ripristino tra { $seconds }s
+keep = Mantieni
+revert = Ripristina
+variable-refresh-rate = Frequenza di aggiornamento variabile
+hdr = HDR
+
+# Input devices
+touchpad = Touchpad
+natural-scroll = Scorrimento naturale
+disable-while-typing = Disattiva durante la digitazione
+
+# Screen capture
+screen-capture = Cattura schermo
+record-region = Registra area
+record-region-gif = Registra area come GIF
+recording-saved = Registrazione salvata
+recording-saved-body = { $path } copiato negli appunti
+recording-failed = Registrazione non riuscita
+grab-text = Estrai testo
+text-copied = Testo copiato negli appunti
+no-text-found = Nessun testo trovato nell'area selezionata
+scan-qr-code = Scansiona codice QR
+qr-code-copied = Contenuto del codice QR copiato negli appunti
+no-qr-code-found = Nessun codice QR trovato nell'area selezionata
+open-link = Apri link
+share-wifi = Condividi Wi-Fi
+share-wifi-network = Scansiona per connetterti a { $ssid }
+no-wifi-connection = Nessuna connessione Wi-Fi
+
+# Wi-Fi password
+wifi-password-of = Password di { $ssid }
+wifi-password-unavailable = La password di { $ssid } non è disponibile
+
+# Weather
+weather-unavailable = Meteo non disponibile
+weather-today = Oggi
+weather-feels-like = Percepita { $temperature }
+weather-clear = Sereno
+weather-partly-cloudy = Parzialmente nuvoloso
+weather-cloudy = Nuvoloso
+weather-fog = Nebbia
+weather-drizzle = Pioggerella
+weather-rain = Pioggia
+weather-snow = Neve
+weather-thunderstorm = Temporale
+
+# Notifications
+notifications = Notifiche
+no-notifications = Nessuna notifica
+clear-all = Cancella tutto
+do-not-disturb = Non disturbare
+
+# Configuration editor
+configuration = Configurazione
+editor-appearance = Aspetto
+editor-layout = Disposizione
+editor-modules = Moduli
+bar-position = Posizione della barra
+position-top = In alto
+position-bottom = In basso
+theme-presets = Temi predefiniti
+theme-file = File del tema
+import = Importa
+export = Esporta
+colors = Colori
+color-background = Sfondo
+color-primary = Primario
+color-secondary = Secondario
+color-success = Successo
+color-danger = Pericolo
+color-text = Testo
+section-left = Sinistra
+section-center = Centro
+section-right = Destra
+drag-modules-hint = Trascina i moduli per riordinarli o spostarli in un'altra sezione
+add-here = Aggiungi qui
+module-name-placeholder = Nome del modulo, es. MediaPlayer
+clock-format = Formato dell'orologio
+truncate-title-after = Tronca il titolo della finestra dopo
+fill-missing-workspaces = Riempi gli spazi di lavoro mancanti
+apply = Applica
+
+# Welcome wizard
+welcome = Benvenuto in ashell
+environment-found = Ecco cosa ha trovato ashell sul tuo sistema:
+environment-compositor = Compositor: { $compositor }
+environment-compositor-unknown = sconosciuto
+environment-iwd-only = IWD trovato, le funzioni di rete richiedono NetworkManager
+environment-upower = UPower (batteria e profili energetici)
+environment-bluez = BlueZ (bluetooth)
+detecting-environment = Rilevamento dell'ambiente in corso...
+theme-dark = Scuro
+theme-light = Chiaro
+optional-modules = Moduli opzionali
+module-system-info = Informazioni di sistema
+module-tray = Tray
+module-media-player = Lettore multimediale
+use-defaults = Usa i valori predefiniti
+next = Avanti
+back = Indietro
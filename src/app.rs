@@ -1,33 +1,91 @@
 use crate::{
     centerbox,
-    config::{self, Config},
+    components::{icons::set_mirrored, swipe_area::swipe_area},
+    config::{self, expand_home, update_config, Config, Direction, ModuleSection, Position},
+    desktop_widgets::{sync_widgets, DesktopWidget},
     get_log_spec,
-    menu::{menu_wrapper, MenuSize, MenuType},
+    ipc::{self, IpcCommand},
+    lock_countdown::{self, LockCountdown},
+    menu::{self, menu_wrapper, MenuSize, MenuType},
+    metrics,
     modules::{
-        self, app_launcher::AppLauncher, clipboard::Clipboard, clock::Clock,
-        keyboard_layout::KeyboardLayout, keyboard_submap::KeyboardSubmap,
-        media_player::MediaPlayer, privacy::Privacy, settings::Settings, system_info::SystemInfo,
-        tray::TrayModule, updates::Updates, window_title::WindowTitle, workspaces::Workspaces,
+        self,
+        app_launcher::AppLauncher,
+        backup::Backup,
+        break_reminder::BreakReminder,
+        clipboard::Clipboard,
+        clock::Clock,
+        config_editor::{self, ConfigEditor},
+        dock::Dock,
+        emoji_picker::EmojiPicker,
+        firewall::Firewall,
+        game_mode::GameMode,
+        kde_connect::KdeConnect,
+        keyboard_layout::KeyboardLayout,
+        keyboard_submap::KeyboardSubmap,
+        media_player::MediaPlayer,
+        notifications::Notifications,
+        ping::Ping,
+        print_queue::PrintQueue,
+        privacy::Privacy,
+        removable_media::RemovableMediaModule,
+        screen_capture::ScreenCapture,
+        settings::Settings,
+        solar::Solar,
+        system_info::SystemInfo,
+        tray::TrayModule,
+        updates::Updates,
+        virtual_machines::VirtualMachines,
+        weather::Weather,
+        window_title::WindowTitle,
+        workspaces::Workspaces,
     },
-    outputs::{HasOutput, Outputs},
+    onboarding::{self, Onboarding},
+    osd::{self, Osd, OsdKind},
+    outputs::{self, HasOutput, Outputs},
+    pinned_menu::{pin_header, PinnedMenu},
+    polkit_dialog::{self, PolkitDialog},
     position_button::ButtonUIRef,
-    style::ashell_theme,
+    services::{
+        idle_notify::IdleNotify,
+        media_keys::{MediaKey, MediaKeys},
+        polkit::{PolkitCommand, PolkitEvent, PolkitService},
+        portal_settings::ContrastPreference,
+        touch::{TabletModeSwitch, TouchInput},
+        wallpaper::WallpaperAccent,
+        ReadOnlyService, Service, ServiceEvent,
+    },
+    status_export::{self, Status},
+    style::{ashell_theme, bar_inset, floating_bar},
     utils, HEIGHT,
 };
 use flexi_logger::LoggerHandle;
 use iced::{
     daemon::Appearance,
     event::{listen_with, wayland::Event as WaylandEvent},
-    widget::Row,
+    platform_specific::shell::commands::layer_surface::destroy_layer_surface,
+    time::every,
+    widget::{container, mouse_area, Row},
     window::Id,
-    Alignment, Color, Element, Length, Subscription, Task, Theme,
+    Alignment, Color, Element, Length, Padding, Size, Subscription, Task, Theme,
 };
 use log::{debug, info, warn};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 pub struct App {
     logger: LoggerHandle,
     pub config: Config,
     pub outputs: Outputs,
+    pub onboarding: Option<Onboarding>,
+    pub config_editor: Option<ConfigEditor>,
+    pub osd: Option<Osd>,
+    // last status written by the status export
+    exported_status: Option<Status>,
+    pub lock_countdown: Option<LockCountdown>,
+    pub dragged_module: Option<(ModuleSection, usize)>,
     pub app_launcher: AppLauncher,
     pub updates: Updates,
     pub clipboard: Clipboard,
@@ -41,6 +99,30 @@ pub struct App {
     pub privacy: Privacy,
     pub settings: Settings,
     pub media_player: MediaPlayer,
+    pub virtual_machines: VirtualMachines,
+    pub print_queue: PrintQueue,
+    pub backup: Backup,
+    pub removable_media: RemovableMediaModule,
+    pub game_mode: GameMode,
+    pub solar: Solar,
+    pub dock: Dock,
+    pub polkit: Option<PolkitService>,
+    pub polkit_dialog: Option<PolkitDialog>,
+    pub emoji_picker: EmojiPicker,
+    pub touch_detected: bool,
+    // None when the device has no tablet mode switch
+    pub tablet_mode: Option<bool>,
+    pub pinned_menus: Vec<PinnedMenu>,
+    pub desktop_widgets: Vec<DesktopWidget>,
+    pub portal_high_contrast: bool,
+    pub wallpaper_accent: Option<Color>,
+    pub ping: Ping,
+    pub firewall: Firewall,
+    pub kde_connect: KdeConnect,
+    pub break_reminder: BreakReminder,
+    pub screen_capture: ScreenCapture,
+    pub weather: Weather,
+    pub notifications: Notifications,
 }
 
 #[derive(Debug, Clone)]
@@ -48,9 +130,21 @@ pub enum Message {
     None,
     ConfigChanged(Box<Config>),
     ToggleMenu(MenuType, Id, ButtonUIRef),
+    ShortcutMenu(MenuType),
     CloseMenu(Id),
+    DragModule(ModuleSection, usize),
+    DropModule(ModuleSection, usize),
+    CancelModuleDrag,
     OpenLauncher,
     OpenClipboard,
+    OpenConfigEditor,
+    ConfigEditor(config_editor::Message),
+    MediaKey(MediaKey),
+    ShowOsd(OsdKind),
+    ExportStatus,
+    Osd(osd::Message),
+    Idle(bool),
+    LockCountdown(lock_countdown::Message),
     Updates(modules::updates::Message),
     Workspaces(modules::workspaces::Message),
     WindowTitle(modules::window_title::Message),
@@ -62,19 +156,70 @@ pub enum Message {
     Privacy(modules::privacy::PrivacyMessage),
     Settings(modules::settings::Message),
     WaylandEvent(WaylandEvent),
+    SurfaceResized(Id, Size),
+    Onboarding(onboarding::Message),
     MediaPlayer(modules::media_player::Message),
+    VirtualMachines(modules::virtual_machines::Message),
+    PrintQueue(modules::print_queue::Message),
+    Backup(modules::backup::Message),
+    RemovableMedia(modules::removable_media::Message),
+    GameMode(modules::game_mode::Message),
+    Solar(modules::solar::Message),
+    Dock(modules::dock::Message),
+    Polkit(ServiceEvent<PolkitService>),
+    PolkitDialog(polkit_dialog::Message),
+    EmojiPicker(modules::emoji_picker::Message),
+    TouchDetected,
+    TabletMode(bool),
+    PinMenu(Id, MenuType, ButtonUIRef),
+    UnpinMenu(Id),
+    Ipc(IpcCommand),
+    AnimationFrame(Instant),
+    ContrastPreference(bool),
+    WallpaperAccent(Option<Color>),
+    Ping(modules::ping::Message),
+    Firewall(modules::firewall::Message),
+    KdeConnect(modules::kde_connect::Message),
+    BreakReminder(modules::break_reminder::Message),
+    ScreenCapture(modules::screen_capture::Message),
+    Weather(modules::weather::Message),
+    Notifications(modules::notifications::Message),
 }
 
 impl App {
     pub fn new((logger, config): (LoggerHandle, Config)) -> impl FnOnce() -> (Self, Task<Message>) {
         || {
+            outputs::set_bar_height(1., bar_inset(&config.bar));
+            outputs::set_bar_margin(config.bar.margin);
             let (outputs, task) = Outputs::new(config.position);
+            let (onboarding, onboarding_task) = if config::config_exists() {
+                (None, Task::none())
+            } else {
+                let (onboarding, task) = Onboarding::new();
+                (Some(onboarding), task.map(Message::Onboarding))
+            };
+            let mut desktop_widgets = Vec::new();
+            let widgets_task = sync_widgets(&mut desktop_widgets, &config.widgets);
             let enable_workspace_filling = config.workspaces.enable_workspace_filling;
+            set_mirrored(config.appearance.direction == Direction::Rtl);
+            utils::set_state_badges(config.appearance.state_badges);
+            menu::set_animated(config.menu.animation);
+            let blur_task = if config.menu.blur {
+                Task::perform(menu::set_blur(true), |_| Message::None)
+            } else {
+                Task::none()
+            };
             (
                 App {
                     logger,
                     config,
                     outputs,
+                    onboarding,
+                    config_editor: None,
+                    osd: None,
+                    exported_status: None,
+                    lock_countdown: None,
+                    dragged_module: None,
                     app_launcher: AppLauncher,
                     updates: Updates::default(),
                     clipboard: Clipboard,
@@ -88,8 +233,31 @@ impl App {
                     privacy: Privacy::default(),
                     settings: Settings::default(),
                     media_player: MediaPlayer::default(),
+                    virtual_machines: VirtualMachines::default(),
+                    print_queue: PrintQueue::default(),
+                    backup: Backup::default(),
+                    removable_media: RemovableMediaModule::default(),
+                    game_mode: GameMode::default(),
+                    solar: Solar::default(),
+                    dock: Dock::default(),
+                    polkit: None,
+                    polkit_dialog: None,
+                    emoji_picker: EmojiPicker::default(),
+                    touch_detected: false,
+                    tablet_mode: None,
+                    pinned_menus: Vec::new(),
+                    desktop_widgets,
+                    portal_high_contrast: false,
+                    wallpaper_accent: None,
+                    ping: Ping::default(),
+                    firewall: Firewall::default(),
+                    kde_connect: KdeConnect::default(),
+                    break_reminder: BreakReminder::default(),
+                    screen_capture: ScreenCapture::default(),
+                    weather: Weather::default(),
+                    notifications: Notifications::default(),
                 },
-                task,
+                Task::batch(vec![task, onboarding_task, widgets_task, blur_task]),
             )
         }
     }
@@ -99,7 +267,87 @@ impl App {
     }
 
     pub fn theme(&self, _id: Id) -> Theme {
-        ashell_theme(&self.config.appearance)
+        ashell_theme(
+            &self.config.appearance,
+            self.high_contrast(),
+            self.wallpaper_accent
+                .filter(|_| self.config.appearance.wallpaper_accent),
+        )
+    }
+
+    fn high_contrast(&self) -> bool {
+        self.config
+            .appearance
+            .high_contrast
+            .unwrap_or(self.portal_high_contrast)
+    }
+
+    /// Convertibles follow their tablet mode switch, other devices switch
+    /// to the touch layout once the user interacts with a touchscreen
+    fn touch_mode(&self) -> bool {
+        self.tablet_mode.unwrap_or(self.touch_detected)
+    }
+
+    fn set_touch_mode(&mut self, update: impl FnOnce(&mut Self)) -> Task<Message> {
+        let before = self.touch_mode();
+        update(self);
+
+        if before != self.touch_mode() {
+            self.outputs.resize_bars(
+                self.bar_scale(),
+                bar_inset(&self.config.bar),
+                self.config.bar.margin,
+            )
+        } else {
+            Task::none()
+        }
+    }
+
+    fn bar_scale(&self) -> f64 {
+        if self.touch_mode() {
+            self.config.touch_scale
+        } else {
+            1.
+        }
+    }
+
+    /// Bars and menus are scaled up in the touch layout
+    pub fn scale_factor(&self, id: Id) -> f64 {
+        if let Some(widget) = self.desktop_widgets.iter().find(|w| w.id == id) {
+            return widget.config.scale;
+        }
+
+        if self.touch_mode() && self.outputs.has(id).is_some() {
+            self.config.touch_scale
+        } else {
+            1.
+        }
+    }
+
+    /// Refreshes the exported status after a change of one of its parts
+    fn export_status(&self) -> Task<Message> {
+        if self.config.status_export.is_some() {
+            Task::done(Message::ExportStatus)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn show_osd(&mut self, kind: OsdKind) -> Task<Message> {
+        if !self.config.osd.enabled {
+            return Task::none();
+        }
+
+        let timeout = Duration::from_millis(self.config.osd.timeout);
+        match self.osd.as_mut() {
+            Some(osd) => osd.show(kind, timeout).map(Message::Osd),
+            None => {
+                let (osd, task) = Osd::new(kind, timeout);
+                self.osd = Some(osd);
+
+                task.map(Message::Osd)
+            }
+        }
     }
 
     pub fn style(&self, theme: &Theme) -> Appearance {
@@ -111,6 +359,8 @@ impl App {
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
+        metrics::record_message();
+
         match message {
             Message::None => Task::none(),
             Message::ConfigChanged(config) => {
@@ -120,14 +370,48 @@ impl App {
                     "Current outputs: {:?}, new outputs: {:?}",
                     self.config.outputs, config.outputs
                 );
-                if self.config.outputs != config.outputs || self.config.position != config.position
+                if self.config.outputs != config.outputs
+                    || self.config.outputs_exclude != config.outputs_exclude
+                    || self.config.position != config.position
                 {
                     warn!("Outputs changed, syncing");
-                    tasks.push(self.outputs.sync(&config.outputs, config.position));
+                    tasks.push(self.outputs.sync(
+                        &config.outputs,
+                        &config.outputs_exclude,
+                        config.position,
+                    ));
                 }
+                if (self.touch_mode() && self.config.touch_scale != config.touch_scale)
+                    || self.config.bar != config.bar
+                {
+                    let scale = if self.touch_mode() {
+                        config.touch_scale
+                    } else {
+                        1.
+                    };
+                    tasks.push(self.outputs.resize_bars(
+                        scale,
+                        bar_inset(&config.bar),
+                        config.bar.margin,
+                    ));
+                }
+                if self.config.menu.blur != config.menu.blur {
+                    tasks.push(Task::perform(menu::set_blur(config.menu.blur), |_| {
+                        Message::None
+                    }));
+                }
+                tasks.push(sync_widgets(&mut self.desktop_widgets, &config.widgets));
+                set_mirrored(config.appearance.direction == Direction::Rtl);
+                utils::set_state_badges(config.appearance.state_badges);
+                menu::set_animated(config.menu.animation);
                 self.config = *config;
                 self.logger
                     .set_new_spec(get_log_spec(&self.config.log_level));
+                let _ = self
+                    .logger
+                    .adapt_duplication_to_stdout(status_export::stdout_logs(
+                        self.config.status_export.as_ref(),
+                    ));
 
                 Task::batch(tasks)
             }
@@ -136,6 +420,12 @@ impl App {
                     MenuType::Updates => {
                         self.updates.is_updates_list_open = false;
                     }
+                    MenuType::Calendar => {
+                        self.clock.reset_calendar();
+                    }
+                    MenuType::Notifications => {
+                        self.notifications.mark_read();
+                    }
                     MenuType::Tray(name) => {
                         if let Some(_tray) = self
                             .tray
@@ -146,11 +436,78 @@ impl App {
                             self.tray.submenus.clear();
                         }
                     }
+                    MenuType::Network | MenuType::Bluetooth | MenuType::Audio => {
+                        if !self.outputs.is_menu_open(menu_type.clone()) {
+                            return Task::batch(vec![
+                                self.settings.open_section_menu(&menu_type),
+                                self.outputs.toggle_menu(id, menu_type, button_ui_ref),
+                            ]);
+                        }
+                    }
+                    MenuType::EmojiPicker => {
+                        if !self.outputs.is_menu_open(MenuType::EmojiPicker) {
+                            return Task::batch(vec![
+                                self.outputs.toggle_menu(id, menu_type, button_ui_ref),
+                                self.outputs.request_keyboard(id),
+                                self.emoji_picker.reset(),
+                            ]);
+                        }
+                    }
                     _ => {}
                 };
                 self.outputs.toggle_menu(id, menu_type, button_ui_ref)
             }
+            Message::ShortcutMenu(menu_type) => match self.outputs.menu_anchor(&menu_type) {
+                Some((id, button_ui_ref)) => {
+                    self.update(Message::ToggleMenu(menu_type, id, button_ui_ref))
+                }
+                None => Task::none(),
+            },
             Message::CloseMenu(id) => self.outputs.close_menu(id),
+            Message::SurfaceResized(id, size) => {
+                self.outputs.set_surface_size(id, (size.width, size.height));
+
+                Task::none()
+            }
+            Message::DragModule(section, index) => {
+                self.dragged_module = Some((section, index));
+
+                Task::none()
+            }
+            // the drag is dropped when the modules changed under it, e.g. by a
+            // config reload, and the indexes are out of date
+            Message::DropModule(section, index) => match self.dragged_module.take() {
+                Some((dragged_section, dragged_index))
+                    if dragged_section == section
+                        && dragged_index != index
+                        && dragged_index < self.config.modules.section(section).len()
+                        && index < self.config.modules.section(section).len() =>
+                {
+                    let mut config = self.config.clone();
+                    let modules = config.modules.section_mut(section);
+                    let module = modules.remove(dragged_index);
+                    modules.insert(index, module);
+
+                    let write_task =
+                        Task::perform(update_config(self.config.clone(), config.clone()), |res| {
+                            if let Err(err) = res {
+                                warn!("Failed to write the new modules order: {}", err);
+                            }
+                            Message::None
+                        });
+
+                    Task::batch(vec![
+                        self.update(Message::ConfigChanged(Box::new(config))),
+                        write_task,
+                    ])
+                }
+                _ => Task::none(),
+            },
+            Message::CancelModuleDrag => {
+                self.dragged_module = None;
+
+                Task::none()
+            }
             Message::Updates(message) => {
                 if let Some(updates_config) = self.config.updates.as_ref() {
                     self.updates
@@ -171,20 +528,166 @@ impl App {
                 }
                 Task::none()
             }
-            Message::Workspaces(msg) => {
-                self.workspaces.update(msg);
+            Message::OpenConfigEditor => {
+                if self.config_editor.is_some() {
+                    return Task::none();
+                }
 
-                Task::none()
+                let (config_editor, task) = ConfigEditor::new(&self.config);
+                self.config_editor = Some(config_editor);
+
+                task.map(Message::ConfigEditor)
             }
+            Message::ConfigEditor(msg) => {
+                if let Some(config_editor) = self.config_editor.as_mut() {
+                    match config_editor.update(msg) {
+                        config_editor::Action::None => Task::none(),
+                        config_editor::Action::Preview(config) => {
+                            self.update(Message::ConfigChanged(Box::new(config)))
+                        }
+                        config_editor::Action::Task(task) => task.map(Message::ConfigEditor),
+                        config_editor::Action::Close(restore) => {
+                            let id = config_editor.id;
+                            self.config_editor = None;
+
+                            let mut tasks = vec![destroy_layer_surface(id)];
+                            if let Some(config) = restore {
+                                tasks.push(self.update(Message::ConfigChanged(Box::new(config))));
+                            }
+
+                            Task::batch(tasks)
+                        }
+                    }
+                } else {
+                    Task::none()
+                }
+            }
+            Message::MediaKey(key) => self.show_osd(key.into()),
+            Message::ShowOsd(kind) => self.show_osd(kind),
+            Message::ExportStatus => {
+                let Some(export) = self.config.status_export.as_ref() else {
+                    return Task::none();
+                };
+                let status = Status::new(
+                    self.settings.battery(),
+                    self.settings.ssid(),
+                    self.settings.volume(),
+                    self.workspaces.active(),
+                    self.window_title.title(),
+                );
+                if self.exported_status.as_ref() == Some(&status) {
+                    return Task::none();
+                }
+
+                let content = match serde_json::to_string(&status) {
+                    Ok(content) => content,
+                    Err(err) => {
+                        warn!("Failed to serialize the status: {}", err);
+                        return Task::none();
+                    }
+                };
+                self.exported_status = Some(status);
+
+                match export.path.as_deref() {
+                    Some(path) => Task::perform(
+                        status_export::write(PathBuf::from(expand_home(path)), content),
+                        |_| Message::None,
+                    ),
+                    None => {
+                        println!("{}", content);
+                        Task::none()
+                    }
+                }
+            }
+            Message::Osd(msg) => match self.osd.as_mut() {
+                Some(osd) if osd.update(msg) => {
+                    let id = osd.id;
+                    self.osd = None;
+
+                    destroy_layer_surface(id)
+                }
+                _ => Task::none(),
+            },
+            Message::Idle(idle) => {
+                let Some(idle_lock) = self.config.settings.idle_lock.as_ref() else {
+                    return Task::none();
+                };
+                let warning = Duration::from_secs(idle_lock.warning);
+
+                match (idle, self.lock_countdown.as_mut()) {
+                    (true, Some(lock_countdown)) => {
+                        lock_countdown.restart(warning);
+                        Task::none()
+                    }
+                    (true, None) => {
+                        let (lock_countdown, task) = LockCountdown::new(warning);
+                        self.lock_countdown = Some(lock_countdown);
+
+                        task
+                    }
+                    (false, Some(lock_countdown)) if lock_countdown.is_counting() => {
+                        lock_countdown.cancel().map(Message::LockCountdown)
+                    }
+                    (false, _) => Task::none(),
+                }
+            }
+            Message::LockCountdown(msg) => {
+                let Some(lock_countdown) = self.lock_countdown.as_mut() else {
+                    return Task::none();
+                };
+                let action = lock_countdown.update(msg);
+                let id = lock_countdown.id;
+
+                match action {
+                    lock_countdown::Action::None => Task::none(),
+                    lock_countdown::Action::Lock => {
+                        self.lock_countdown = None;
+                        if let Some(lock_cmd) = &self.config.settings.lock_cmd {
+                            utils::launcher::execute_command(lock_cmd.to_string());
+                        }
+
+                        destroy_layer_surface(id)
+                    }
+                    lock_countdown::Action::Close => {
+                        self.lock_countdown = None;
+
+                        destroy_layer_surface(id)
+                    }
+                    lock_countdown::Action::StayAwake => {
+                        self.lock_countdown = None;
+                        let stay_awake = self
+                            .config
+                            .settings
+                            .idle_lock
+                            .as_ref()
+                            .map_or(0, |idle_lock| idle_lock.stay_awake);
+
+                        Task::batch(vec![
+                            destroy_layer_surface(id),
+                            self.update(Message::Settings(
+                                modules::settings::Message::InhibitIdleFor(Duration::from_secs(
+                                    stay_awake * 60,
+                                )),
+                            )),
+                        ])
+                    }
+                }
+            }
+            Message::Workspaces(msg) => Task::batch([
+                self.workspaces
+                    .update(msg, &self.config.workspaces, &mut self.outputs),
+                self.export_status(),
+            ]),
             Message::WindowTitle(message) => {
                 self.window_title
                     .update(message, self.config.truncate_title_after_length);
-                Task::none()
-            }
-            Message::SystemInfo(message) => {
-                self.system_info.update(message);
-                Task::none()
+                self.export_status()
             }
+            Message::SystemInfo(message) => self.system_info.update(
+                message,
+                &self.config.system,
+                self.outputs.is_menu_open(MenuType::SystemInfo),
+            ),
             Message::KeyboardLayout(message) => {
                 self.keyboard_layout.update(message);
                 Task::none()
@@ -193,15 +696,44 @@ impl App {
                 self.keyboard_submap.update(message);
                 Task::none()
             }
-            Message::Tray(msg) => self.tray.update(msg),
+            Message::Tray(msg) => self.tray.update(msg, &self.config.tray),
             Message::Clock(message) => {
-                self.clock.update(message);
+                self.clock.update(message, &self.config.clock);
                 Task::none()
             }
             Message::Privacy(msg) => self.privacy.update(msg),
             Message::Settings(message) => {
-                self.settings
-                    .update(message, &self.config.settings, &mut self.outputs)
+                let export = if message.changes_status() {
+                    self.export_status()
+                } else {
+                    Task::none()
+                };
+
+                Task::batch([
+                    self.settings.update(
+                        message,
+                        &self.config.settings,
+                        &self.config.audio,
+                        &mut self.outputs,
+                    ),
+                    export,
+                ])
+            }
+            Message::Onboarding(msg) => {
+                if let Some(onboarding) = self.onboarding.as_mut() {
+                    let (task, completed) = onboarding.update(msg);
+
+                    if completed {
+                        let id = onboarding.id;
+                        self.onboarding = None;
+
+                        destroy_layer_surface(id)
+                    } else {
+                        task.map(Message::Onboarding)
+                    }
+                } else {
+                    Task::none()
+                }
             }
             Message::WaylandEvent(event) => match event {
                 WaylandEvent::Output(event, wl_output) => match event {
@@ -214,6 +746,7 @@ impl App {
 
                         self.outputs.add(
                             &self.config.outputs,
+                            &self.config.outputs_exclude,
                             self.config.position,
                             name,
                             wl_output,
@@ -221,77 +754,508 @@ impl App {
                     }
                     iced::event::wayland::OutputEvent::Removed => {
                         info!("Output destroyed");
-                        self.outputs.remove(self.config.position, wl_output)
+                        self.outputs.remove(
+                            self.config.position,
+                            &self.config.outputs_exclude,
+                            wl_output,
+                        )
                     }
                     _ => Task::none(),
                 },
                 _ => Task::none(),
             },
             Message::MediaPlayer(msg) => self.media_player.update(msg, &self.config.media_player),
+            Message::VirtualMachines(msg) => self
+                .virtual_machines
+                .update(msg, &self.config.virtual_machines),
+            Message::PrintQueue(msg) => self.print_queue.update(msg),
+            Message::Backup(msg) => {
+                if let Some(backup_config) = self.config.backup.as_ref() {
+                    self.backup.update(msg, backup_config)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::RemovableMedia(msg) => self
+                .removable_media
+                .update(msg, &self.config.removable_media),
+            Message::GameMode(msg) => self.game_mode.update(msg, &self.config.game_mode),
+            Message::Solar(msg) => {
+                self.solar.update(msg, &self.config.solar);
+                Task::none()
+            }
+            Message::Dock(msg) => self.dock.update(msg, &self.config.dock).map(Message::Dock),
+            Message::Polkit(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.polkit = Some(service);
+                    Task::none()
+                }
+                ServiceEvent::Update(PolkitEvent::Request(request)) => {
+                    match self.polkit_dialog.as_mut() {
+                        Some(dialog) => {
+                            dialog.push(request);
+                            Task::none()
+                        }
+                        None => {
+                            let (dialog, task) = PolkitDialog::new(request);
+                            self.polkit_dialog = Some(dialog);
+
+                            task.map(Message::PolkitDialog)
+                        }
+                    }
+                }
+                ServiceEvent::Update(PolkitEvent::Failed(cookie)) => {
+                    if let Some(dialog) = self.polkit_dialog.as_mut() {
+                        dialog.failed(&cookie);
+                    }
+                    Task::none()
+                }
+                ServiceEvent::Update(PolkitEvent::Completed(cookie)) => {
+                    match self.polkit_dialog.as_mut() {
+                        Some(dialog) if dialog.completed(&cookie) => {
+                            let id = dialog.id;
+                            self.polkit_dialog = None;
+
+                            destroy_layer_surface(id)
+                        }
+                        _ => Task::none(),
+                    }
+                }
+                ServiceEvent::Error(_) => Task::none(),
+            },
+            Message::TouchDetected => self.set_touch_mode(|app| app.touch_detected = true),
+            Message::PinMenu(menu_id, menu_type, button_ui_ref) => {
+                let close_task = self.outputs.close_menu(menu_id);
+                if self.pinned_menus.iter().any(|p| p.menu_type == menu_type) {
+                    return close_task;
+                }
+
+                let width = self
+                    .menu_content(menu_id, &menu_type)
+                    .map_or(0., |(_, menu_size)| menu_size.width(&self.config.menu));
+                let (pinned_menu, task) =
+                    PinnedMenu::new(menu_type, button_ui_ref, self.config.position, width);
+                self.pinned_menus.push(pinned_menu);
+
+                Task::batch(vec![close_task, task])
+            }
+            Message::UnpinMenu(id) => {
+                self.pinned_menus.retain(|p| p.id != id);
+
+                destroy_layer_surface(id)
+            }
+            Message::TabletMode(tablet_mode) => {
+                self.set_touch_mode(|app| app.tablet_mode = Some(tablet_mode))
+            }
+            Message::PolkitDialog(msg) => {
+                let (Some(dialog), Some(polkit)) =
+                    (self.polkit_dialog.as_mut(), self.polkit.as_mut())
+                else {
+                    return Task::none();
+                };
+
+                let command = match dialog.update(msg) {
+                    polkit_dialog::Action::None => return Task::none(),
+                    polkit_dialog::Action::Authenticate { cookie, password } => {
+                        PolkitCommand::Authenticate { cookie, password }
+                    }
+                    polkit_dialog::Action::Cancel(cookie) => PolkitCommand::Cancel(cookie),
+                };
+
+                polkit.command(command).map(Message::Polkit)
+            }
+            Message::EmojiPicker(msg) => self.emoji_picker.update(msg, &mut self.outputs),
+            Message::Ipc(command) => {
+                match command {
+                    IpcCommand::Show(output) => {
+                        self.outputs.set_bar_visible(output.as_deref(), Some(true))
+                    }
+                    IpcCommand::Hide(output) => {
+                        self.outputs.set_bar_visible(output.as_deref(), Some(false))
+                    }
+                    IpcCommand::ToggleBar(output) => {
+                        self.outputs.set_bar_visible(output.as_deref(), None)
+                    }
+                    IpcCommand::Quit => {
+                        info!("Replaced by a new instance, quitting");
+                        return iced::exit();
+                    }
+                }
+                Task::none()
+            }
+            Message::AnimationFrame(now) => Task::batch(vec![
+                self.outputs.animate_bars(now),
+                self.outputs.animate_menus(now),
+            ]),
+            Message::ContrastPreference(high_contrast) => {
+                self.portal_high_contrast = high_contrast;
+                Task::none()
+            }
+            Message::WallpaperAccent(accent) => {
+                self.wallpaper_accent = accent;
+                Task::none()
+            }
+            Message::Ping(msg) => {
+                self.ping.update(msg, &self.config.ping);
+                Task::none()
+            }
+            Message::Firewall(msg) => self
+                .firewall
+                .update(msg, &self.config.firewall)
+                .map(Message::Firewall),
+            Message::KdeConnect(msg) => self
+                .kde_connect
+                .update(msg, &self.config.kde_connect)
+                .map(Message::KdeConnect),
+            Message::BreakReminder(msg) => self
+                .break_reminder
+                .update(msg, &self.config.break_reminder)
+                .map(Message::BreakReminder),
+            Message::ScreenCapture(msg) => {
+                self.screen_capture
+                    .update(msg, &self.config.screen_capture, &mut self.outputs)
+            }
+            Message::Weather(msg) => {
+                self.weather.update(msg);
+                Task::none()
+            }
+            Message::Notifications(msg) => self
+                .notifications
+                .update(msg, &self.config.notifications)
+                .map(Message::Notifications),
         }
     }
 
     pub fn view(&self, id: Id) -> Element<Message> {
+        if let Some(onboarding) = self.onboarding.as_ref().filter(|o| o.id == id) {
+            return onboarding.view().map(Message::Onboarding);
+        }
+
+        if let Some(config_editor) = self.config_editor.as_ref().filter(|c| c.id == id) {
+            return config_editor.view().map(Message::ConfigEditor);
+        }
+
+        if let Some(polkit_dialog) = self.polkit_dialog.as_ref().filter(|d| d.id == id) {
+            return polkit_dialog.view().map(Message::PolkitDialog);
+        }
+
+        if self.workspaces.preview_id() == Some(id) {
+            return self.workspaces.preview_view().map(Message::Workspaces);
+        }
+
+        if let Some(osd) = self.osd.as_ref().filter(|o| o.id == id) {
+            let (icon_type, value) = self.settings.osd_level(osd.kind);
+
+            return osd.view(icon_type, value).map(Message::Osd);
+        }
+
+        if let Some(lock_countdown) = self.lock_countdown.as_ref().filter(|l| l.id == id) {
+            let stay_awake = self
+                .config
+                .settings
+                .idle_lock
+                .as_ref()
+                .map_or(0, |idle_lock| idle_lock.stay_awake);
+
+            return lock_countdown.view(stay_awake).map(Message::LockCountdown);
+        }
+
+        if let Some(reminder) = self
+            .break_reminder
+            .reminder_view(id, &self.config.break_reminder)
+        {
+            return reminder.map(Message::BreakReminder);
+        }
+
+        if let Some(widget) = self.desktop_widgets.iter().find(|w| w.id == id) {
+            return self
+                .widget_view(widget.config.module, id)
+                .unwrap_or_else(|| Row::new().into());
+        }
+
+        if let Some(pinned_menu) = self.pinned_menus.iter().find(|p| p.id == id) {
+            return match self.menu_content(id, &pinned_menu.menu_type) {
+                Some((content, _)) => pinned_menu.view(content),
+                None => Row::new().into(),
+            };
+        }
+
         match self.outputs.has(id) {
+            Some(HasOutput::Main) if self.outputs.is_bar_hidden(id) => Row::new().into(),
             Some(HasOutput::Main) => {
-                let left = self.modules_section(&self.config.modules.left, id);
-                let center = self.modules_section(&self.config.modules.center, id);
-                let right = self.modules_section(&self.config.modules.right, id);
-
-                centerbox::Centerbox::new([left, center, right])
-                    .spacing(4)
-                    .padding([4, 4])
-                    .width(Length::Fill)
-                    .height(Length::Fixed(HEIGHT as f32))
-                    .align_items(Alignment::Center)
+                let left = self.modules_section(ModuleSection::Left, id);
+                let center = self.modules_section(ModuleSection::Center, id);
+                let right = self.modules_section(ModuleSection::Right, id);
+
+                let sections = match self.config.appearance.direction {
+                    Direction::Ltr => [left, center, right],
+                    Direction::Rtl => [right, center, left],
+                };
+
+                let reduce_transparency = self.config.appearance.reduce_transparency;
+                let bar_config = self.config.bar.clone();
+
+                let bar = container(
+                    centerbox::Centerbox::new(sections)
+                        .spacing(4)
+                        .padding([4, 4])
+                        .width(Length::Fill)
+                        .height(Length::Fixed(HEIGHT as f32))
+                        .align_items(Alignment::Center),
+                );
+                let bar = if bar_config.is_floating() {
+                    // room for the shadow on the inner side
+                    let inner = bar_inset(&bar_config) as f32;
+                    let (top, bottom) = match self.config.position {
+                        Position::Top => (0., inner),
+                        Position::Bottom => (inner, 0.),
+                    };
+
+                    container(bar.style(move |theme: &Theme| floating_bar(theme, &bar_config)))
+                        .padding(Padding {
+                            top,
+                            bottom,
+                            ..Padding::ZERO
+                        })
+                } else {
+                    // an opaque bar instead of the modules floating on the desktop
+                    bar.style(move |theme: &Theme| container::Style {
+                        background: reduce_transparency.then(|| theme.palette().background.into()),
+                        ..Default::default()
+                    })
+                };
+
+                // dropping a module outside of its section cancels the drag
+                swipe_area(mouse_area(bar).on_middle_release(Message::CancelModuleDrag))
+                    .on_horizontal(|direction| {
+                        Message::Workspaces(modules::workspaces::Message::Cycle(direction))
+                    })
                     .into()
             }
-            Some(HasOutput::Menu(menu_info)) => match menu_info {
-                Some((MenuType::Updates, button_ui_ref)) => menu_wrapper(
-                    id,
-                    self.updates.menu_view(id).map(Message::Updates),
-                    MenuSize::Normal,
-                    *button_ui_ref,
-                    self.config.position,
-                ),
-                Some((MenuType::Tray(name), button_ui_ref)) => menu_wrapper(
-                    id,
-                    self.tray.menu_view(name).map(Message::Tray),
-                    MenuSize::Normal,
-                    *button_ui_ref,
-                    self.config.position,
-                ),
-                Some((MenuType::Settings, button_ui_ref)) => menu_wrapper(
-                    id,
-                    self.settings
-                        .menu_view(id, &self.config.settings)
-                        .map(Message::Settings),
-                    MenuSize::Large,
-                    *button_ui_ref,
-                    self.config.position,
-                ),
-                Some((MenuType::MediaPlayer, button_ui_ref)) => menu_wrapper(
-                    id,
-                    self.media_player.menu_view().map(Message::MediaPlayer),
-                    MenuSize::Normal,
-                    *button_ui_ref,
-                    self.config.position,
-                ),
-                None => Row::new().into(),
-            },
+            Some(HasOutput::Menu(Some((menu_type, button_ui_ref)))) => {
+                match self.menu_content(id, menu_type) {
+                    Some((content, menu_size)) => menu_wrapper(
+                        id,
+                        if menu_type.is_pinnable() {
+                            pin_header(id, menu_type, *button_ui_ref, content)
+                        } else {
+                            content
+                        },
+                        menu_size,
+                        *button_ui_ref,
+                        &self.config,
+                        self.outputs.menu_transition(id, Instant::now()),
+                    ),
+                    None => Row::new().into(),
+                }
+            }
+            Some(HasOutput::Menu(None)) => Row::new().into(),
             None => Row::new().into(),
         }
     }
 
+    /// Content of a menu and the size of the menu it is shown in
+    fn menu_content(&self, id: Id, menu_type: &MenuType) -> Option<(Element<Message>, MenuSize)> {
+        match menu_type {
+            MenuType::Updates => Some((
+                self.updates.menu_view(id).map(Message::Updates),
+                MenuSize::Normal,
+            )),
+            MenuType::SystemInfo => Some((
+                self.system_info
+                    .menu_view(&self.config.system)
+                    .map(Message::SystemInfo),
+                MenuSize::Normal,
+            )),
+            MenuType::Tray(name) => Some((
+                self.tray.menu_view(name).map(Message::Tray),
+                MenuSize::Normal,
+            )),
+            MenuType::Settings => Some((
+                self.settings
+                    .menu_view(id, &self.config.settings, &self.config.audio)
+                    .map(Message::Settings),
+                MenuSize::Large,
+            )),
+            MenuType::Network => self
+                .settings
+                .network_menu_view(id, &self.config.settings)
+                .map(|view| (view.map(Message::Settings), MenuSize::Large)),
+            MenuType::Bluetooth => self
+                .settings
+                .bluetooth_menu_view(id, &self.config.settings)
+                .map(|view| (view.map(Message::Settings), MenuSize::Normal)),
+            MenuType::Audio => self
+                .settings
+                .audio_menu_view(id, &self.config.settings, &self.config.audio)
+                .map(|view| (view.map(Message::Settings), MenuSize::Normal)),
+            MenuType::Calendar => Some((
+                self.clock.menu_view(&self.config.clock).map(Message::Clock),
+                MenuSize::Normal,
+            )),
+            MenuType::MediaPlayer => Some((
+                self.media_player
+                    .menu_view(&self.config.media_player)
+                    .map(Message::MediaPlayer),
+                MenuSize::Normal,
+            )),
+            MenuType::VirtualMachines => Some((
+                self.virtual_machines
+                    .menu_view()
+                    .map(Message::VirtualMachines),
+                MenuSize::Normal,
+            )),
+            MenuType::PrintQueue => Some((
+                self.print_queue.menu_view().map(Message::PrintQueue),
+                MenuSize::Normal,
+            )),
+            MenuType::Backup => self.config.backup.as_ref().map(|backup_config| {
+                (
+                    self.backup.menu_view(backup_config).map(Message::Backup),
+                    MenuSize::Normal,
+                )
+            }),
+            MenuType::RemovableMedia => Some((
+                self.removable_media
+                    .menu_view()
+                    .map(Message::RemovableMedia),
+                MenuSize::Normal,
+            )),
+            MenuType::EmojiPicker => Some((
+                self.emoji_picker.menu_view(id).map(Message::EmojiPicker),
+                MenuSize::Normal,
+            )),
+            MenuType::Ping => Some((
+                self.ping.menu_view(&self.config.ping).map(Message::Ping),
+                MenuSize::Normal,
+            )),
+            MenuType::Firewall => Some((
+                self.firewall
+                    .menu_view(&self.config.firewall)
+                    .map(Message::Firewall),
+                MenuSize::Normal,
+            )),
+            MenuType::KdeConnect => Some((
+                self.kde_connect.menu_view().map(Message::KdeConnect),
+                MenuSize::Normal,
+            )),
+            MenuType::Privacy => Some((
+                self.privacy.menu_view().map(Message::Privacy),
+                MenuSize::Normal,
+            )),
+            MenuType::ScreenCapture => Some((
+                self.screen_capture
+                    .menu_view(id)
+                    .map(Message::ScreenCapture),
+                MenuSize::Normal,
+            )),
+            MenuType::Weather => Some((
+                self.weather.menu_view().map(Message::Weather),
+                MenuSize::Normal,
+            )),
+            MenuType::Notifications => Some((
+                self.notifications.menu_view().map(Message::Notifications),
+                MenuSize::Large,
+            )),
+        }
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
+        let polkit = if self.config.polkit_agent {
+            PolkitService::subscribe().map(Message::Polkit)
+        } else {
+            Subscription::none()
+        };
+
+        let touch = if self.touch_detected || self.tablet_mode.is_some() {
+            Subscription::none()
+        } else {
+            TouchInput::subscribe().map(|_| Message::TouchDetected)
+        };
+
+        // the notification comes when the countdown has to start
+        let idle = match self.config.settings.idle_lock.as_ref() {
+            Some(idle_lock) if self.config.settings.lock_cmd.is_some() => IdleNotify::subscribe(
+                Duration::from_secs(idle_lock.timeout.saturating_sub(idle_lock.warning).max(1)),
+            )
+            .map(Message::Idle),
+            _ => Subscription::none(),
+        };
+
+        let countdown = if self
+            .lock_countdown
+            .as_ref()
+            .is_some_and(|l| l.is_counting())
+        {
+            every(Duration::from_secs(1))
+                .map(|_| Message::LockCountdown(lock_countdown::Message::Tick))
+        } else {
+            Subscription::none()
+        };
+
+        let frames =
+            if self.outputs.is_bar_animating() || self.outputs.is_menu_animating(Instant::now()) {
+                every(Duration::from_millis(16)).map(Message::AnimationFrame)
+            } else {
+                Subscription::none()
+            };
+
+        let contrast = if self.config.appearance.high_contrast.is_none() {
+            ContrastPreference::subscribe().map(Message::ContrastPreference)
+        } else {
+            Subscription::none()
+        };
+
+        let wallpaper = if self.config.appearance.wallpaper_accent {
+            WallpaperAccent::subscribe().map(Message::WallpaperAccent)
+        } else {
+            Subscription::none()
+        };
+
+        let metrics = if let Some(metrics) = self.config.metrics.as_ref() {
+            metrics::subscription(metrics.port).map(|_| Message::None)
+        } else {
+            Subscription::none()
+        };
+
+        let media_shortcuts = if self.config.media_player.global_shortcuts {
+            MediaPlayer::global_shortcuts().map(Message::MediaPlayer)
+        } else {
+            Subscription::none()
+        };
+
+        let menu_shortcuts = if self.config.menu.global_shortcuts {
+            menu::global_shortcuts().map(Message::ShortcutMenu)
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch(vec![
             Subscription::batch(self.modules_subscriptions(&self.config.modules.left)),
             Subscription::batch(self.modules_subscriptions(&self.config.modules.center)),
             Subscription::batch(self.modules_subscriptions(&self.config.modules.right)),
+            Subscription::batch(self.widgets_subscriptions()),
             config::subscription(),
-            listen_with(|evt, _, _| {
-                if let iced::Event::PlatformSpecific(iced::event::PlatformSpecific::Wayland(evt)) =
-                    evt
+            MediaKeys::subscribe().map(Message::MediaKey),
+            polkit,
+            touch,
+            idle,
+            countdown,
+            ipc::subscription().map(Message::Ipc),
+            frames,
+            contrast,
+            wallpaper,
+            media_shortcuts,
+            menu_shortcuts,
+            metrics,
+            TabletModeSwitch::subscribe().map(Message::TabletMode),
+            listen_with(|evt, _, id| {
+                if let iced::Event::Window(iced::window::Event::Resized(size)) = evt {
+                    // sent for the configure events of the layer surfaces
+                    Some(Message::SurfaceResized(id, size))
+                } else if let iced::Event::PlatformSpecific(
+                    iced::event::PlatformSpecific::Wayland(evt),
+                ) = evt
                 {
                     if matches!(evt, WaylandEvent::Output(_, _)) {
                         debug!("Wayland event: {:?}", evt);
@@ -0,0 +1,45 @@
+use std::time::{Duration, Instant};
+
+/// Eased transition of a value between 0 and 1,
+/// the owner drives it with a frame subscription while `is_running` is true
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+}
+
+impl Animation {
+    pub fn new(value: f32, duration: Duration) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: Instant::now(),
+            duration,
+        }
+    }
+
+    pub fn go_to(&mut self, target: f32, now: Instant) {
+        self.from = self.value(now);
+        self.to = target;
+        self.start = now;
+    }
+
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+
+    pub fn value(&self, now: Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f32();
+        let progress = (elapsed / self.duration.as_secs_f32()).clamp(0., 1.);
+        // ease out cubic
+        let eased = 1. - (1. - progress).powi(3);
+
+        self.from + (self.to - self.from) * eased
+    }
+
+    pub fn is_running(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) < self.duration && self.from != self.to
+    }
+}
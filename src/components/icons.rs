@@ -1,9 +1,8 @@
-use iced::{
-    widget::{text, Text},
-    Font,
-};
+use crate::style::fonts;
+use iced::widget::{text, Text};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub enum Icons {
     #[default]
     None,
@@ -68,6 +67,99 @@ pub enum Icons {
     SkipPrevious,
     PlayPause,
     SkipNext,
+    VirtualMachine,
+    Play,
+    Stop,
+    Printer,
+    PrinterAlert,
+    Backup,
+    BackupOverdue,
+    UsbDrive,
+    Phone,
+    Mount,
+    Unmount,
+    Eject,
+    Disk,
+    GameMode,
+    Welcome,
+    CheckCircle,
+    CloseCircle,
+    Configure,
+    LeftChevron,
+    RightChevron,
+    MenuClosedRtl,
+    LeftArrow,
+    Sunrise,
+    Sunset,
+    Mosque,
+    SleepOff,
+    RebootRequired,
+    Download,
+    FirmwareUpdate,
+    FirmwareSecurity,
+    EmojiPicker,
+    Keyboard,
+    KeyboardOff,
+    ScreenRotation,
+    ScreenRotationLock,
+    Pin,
+    PinOff,
+    Alert,
+    AlertCircle,
+    Sleep,
+    Cash,
+    LanPending,
+    LanDisconnect,
+    ShieldCheck,
+    ShieldOff,
+    ShieldLock,
+    Cellphone,
+    CellphoneOff,
+    CellphoneSound,
+    FileSend,
+    History,
+    CheckboxBlankCircleOutline,
+    CircleSlice1,
+    CircleSlice2,
+    CircleSlice3,
+    CircleSlice4,
+    CircleSlice5,
+    CircleSlice6,
+    CircleSlice7,
+    CircleSlice8,
+    EyeOutline,
+    TimerPauseOutline,
+    MonitorOff,
+    Monitor,
+    Touchpad,
+    TouchpadOff,
+    Mouse,
+    Console,
+    MonitorScreenshot,
+    RecordRec,
+    VideoOutline,
+    FileGifBox,
+    TextRecognition,
+    QrcodeScan,
+    Qrcode,
+    ContentCopy,
+    BluetoothConnected,
+    BluetoothOff,
+    WeatherSunny,
+    WeatherNight,
+    WeatherPartlyCloudy,
+    WeatherNightPartlyCloudy,
+    WeatherCloudy,
+    WeatherFog,
+    WeatherRainy,
+    WeatherPouring,
+    WeatherSnowy,
+    WeatherLightning,
+    WaterPercent,
+    WeatherWindy,
+    Bell,
+    BellBadge,
+    BellOff,
 }
 
 impl From<Icons> for &'static str {
@@ -135,11 +227,133 @@ impl From<Icons> for &'static str {
             Icons::SkipPrevious => "󰒮",
             Icons::PlayPause => "󰐎",
             Icons::SkipNext => "󰒭",
+            Icons::VirtualMachine => "󰍹",
+            Icons::Play => "󰐊",
+            Icons::Stop => "󰓛",
+            Icons::Printer => "󰐪",
+            Icons::PrinterAlert => "󰐬",
+            Icons::Backup => "󰅠",
+            Icons::BackupOverdue => "󰧠",
+            Icons::UsbDrive => "󱊞",
+            Icons::Phone => "󰄜",
+            Icons::Mount => "󰝰",
+            Icons::Unmount => "󰉘",
+            Icons::Eject => "󰇪",
+            Icons::Disk => "󰋊",
+            Icons::GameMode => "󰊗",
+            Icons::Welcome => "󱠡",
+            Icons::CheckCircle => "󰗠",
+            Icons::CloseCircle => "󰅙",
+            Icons::Configure => "󰒓",
+            Icons::LeftChevron => "󰅁",
+            Icons::RightChevron => "󰅂",
+            Icons::MenuClosedRtl => "󰍞",
+            Icons::LeftArrow => "󰁍",
+            Icons::Sunrise => "󰖜",
+            Icons::Sunset => "󰖛",
+            Icons::Mosque => "󱠧",
+            Icons::SleepOff => "󰒳",
+            Icons::RebootRequired => "󱄌",
+            Icons::Download => "󰇚",
+            Icons::FirmwareUpdate => "󰘚",
+            Icons::FirmwareSecurity => "󰻌",
+            Icons::EmojiPicker => "󰇵",
+            Icons::Keyboard => "󰥻",
+            Icons::KeyboardOff => "󰹋",
+            Icons::ScreenRotation => "󰑵",
+            Icons::ScreenRotationLock => "󰑸",
+            Icons::Pin => "󰤱",
+            Icons::PinOff => "󰤰",
+            Icons::Alert => "󰀦",
+            Icons::AlertCircle => "󰀨",
+            Icons::Sleep => "󰒲",
+            Icons::Cash => "󰄔",
+            Icons::LanPending => "󰌚",
+            Icons::LanDisconnect => "󰌙",
+            Icons::ShieldCheck => "󰕥",
+            Icons::ShieldOff => "󰦞",
+            Icons::ShieldLock => "󰦝",
+            Icons::Cellphone => "󰄜",
+            Icons::CellphoneOff => "󰥐",
+            Icons::CellphoneSound => "󰥒",
+            Icons::FileSend => "󰈪",
+            Icons::History => "󰋚",
+            Icons::CheckboxBlankCircleOutline => "󰄰",
+            Icons::CircleSlice1 => "󰪞",
+            Icons::CircleSlice2 => "󰪟",
+            Icons::CircleSlice3 => "󰪠",
+            Icons::CircleSlice4 => "󰪡",
+            Icons::CircleSlice5 => "󰪢",
+            Icons::CircleSlice6 => "󰪣",
+            Icons::CircleSlice7 => "󰪤",
+            Icons::CircleSlice8 => "󰪥",
+            Icons::EyeOutline => "󰛐",
+            Icons::TimerPauseOutline => "󱫟",
+            Icons::MonitorOff => "󰶐",
+            Icons::Monitor => "󰍹",
+            Icons::Touchpad => "󰝁",
+            Icons::TouchpadOff => "󰍾",
+            Icons::Mouse => "󰍽",
+            Icons::Console => "󰆍",
+            Icons::MonitorScreenshot => "󰹑",
+            Icons::RecordRec => "󰑋",
+            Icons::VideoOutline => "󰯜",
+            Icons::FileGifBox => "󰵸",
+            Icons::TextRecognition => "󱄽",
+            Icons::QrcodeScan => "󰐳",
+            Icons::Qrcode => "󰐲",
+            Icons::ContentCopy => "󰆏",
+            Icons::BluetoothConnected => "󰂱",
+            Icons::BluetoothOff => "󰂲",
+            Icons::WeatherSunny => "󰖙",
+            Icons::WeatherNight => "󰖔",
+            Icons::WeatherPartlyCloudy => "󰖕",
+            Icons::WeatherNightPartlyCloudy => "󰼱",
+            Icons::WeatherCloudy => "󰖐",
+            Icons::WeatherFog => "󰖑",
+            Icons::WeatherRainy => "󰖗",
+            Icons::WeatherPouring => "󰖖",
+            Icons::WeatherSnowy => "󰖘",
+            Icons::WeatherLightning => "󰖓",
+            Icons::WaterPercent => "󰖎",
+            Icons::WeatherWindy => "󰖝",
+            Icons::Bell => "󰂚",
+            Icons::BellBadge => "󱅫",
+            Icons::BellOff => "󰂛",
         }
     }
 }
 
+static MIRRORED: AtomicBool = AtomicBool::new(false);
+
+/// Flip the directional icons, used by the right-to-left layout
+pub fn set_mirrored(value: bool) {
+    MIRRORED.store(value, Ordering::Relaxed);
+}
+
+impl Icons {
+    fn mirrored(self) -> Icons {
+        match self {
+            Icons::MenuClosed => Icons::MenuClosedRtl,
+            Icons::RightArrow => Icons::LeftArrow,
+            Icons::LeftChevron => Icons::RightChevron,
+            Icons::RightChevron => Icons::LeftChevron,
+            icon => icon,
+        }
+    }
+}
+
+/// A glyph of the icon font that isn't in `Icons`, like the ones chosen in the configuration
+pub fn icon_glyph<'a>(glyph: String) -> Text<'a> {
+    text(glyph).font(fonts().icons)
+}
+
 pub fn icon<'a>(r#type: Icons) -> Text<'a> {
-    text(std::convert::Into::<&'static str>::into(r#type))
-        .font(Font::with_name("Symbols Nerd Font"))
+    let r#type = if MIRRORED.load(Ordering::Relaxed) {
+        r#type.mirrored()
+    } else {
+        r#type
+    };
+
+    text(std::convert::Into::<&'static str>::into(r#type)).font(fonts().icons)
 }
@@ -1 +1,3 @@
+pub mod animation;
 pub mod icons;
+pub mod swipe_area;
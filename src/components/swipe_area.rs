@@ -0,0 +1,274 @@
+use iced::{
+    core::{
+        event::{self, Event},
+        layout, mouse, overlay, renderer, touch,
+        widget::{tree, Operation, Tree},
+        Clipboard, Layout, Shell, Widget,
+    },
+    Element, Length, Point, Rectangle, Size, Vector,
+};
+
+/// Distance a finger has to travel before the touch is handled as a swipe
+const SWIPE_THRESHOLD: f32 = 40.;
+/// Touchpad scroll distance, in pixels, equivalent to a swipe
+const SCROLL_THRESHOLD: f32 = 60.;
+
+/// Turns touch swipes and touchpad scrolls over the content into messages,
+/// 1 for a swipe to the left or up and -1 for a swipe to the right or down.
+///
+/// Taps are still delivered to the content, a touch becomes a swipe only
+/// after moving along an axis with a handler.
+pub struct SwipeArea<'a, Message, Theme = iced::Theme, Renderer = iced::Renderer> {
+    content: Element<'a, Message, Theme, Renderer>,
+    on_horizontal: Option<Box<dyn Fn(i32) -> Message + 'a>>,
+    on_vertical: Option<Box<dyn Fn(i32) -> Message + 'a>>,
+}
+
+impl<'a, Message, Theme, Renderer> SwipeArea<'a, Message, Theme, Renderer> {
+    pub fn new(content: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        Self {
+            content: content.into(),
+            on_horizontal: None,
+            on_vertical: None,
+        }
+    }
+
+    pub fn on_horizontal(mut self, on_horizontal: impl Fn(i32) -> Message + 'a) -> Self {
+        self.on_horizontal = Some(Box::new(on_horizontal));
+        self
+    }
+
+    pub fn on_vertical(mut self, on_vertical: impl Fn(i32) -> Message + 'a) -> Self {
+        self.on_vertical = Some(Box::new(on_vertical));
+        self
+    }
+
+    fn handler(&self, delta: Vector, threshold: f32) -> Option<Message> {
+        if delta.x.abs() >= delta.y.abs() {
+            self.on_horizontal
+                .as_ref()
+                .filter(|_| delta.x.abs() >= threshold)
+                .map(|on_horizontal| on_horizontal(if delta.x < 0. { 1 } else { -1 }))
+        } else {
+            self.on_vertical
+                .as_ref()
+                .filter(|_| delta.y.abs() >= threshold)
+                .map(|on_vertical| on_vertical(if delta.y < 0. { 1 } else { -1 }))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct State {
+    // finger and position where the touch started
+    touch: Option<(touch::Finger, Point)>,
+    swiping: bool,
+    scrolled: Vector,
+}
+
+impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for SwipeArea<'a, Message, Theme, Renderer>
+where
+    Renderer: iced::core::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&mut self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_mut(&mut self.content));
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let mut event = event;
+
+        match event {
+            Event::Touch(touch::Event::FingerPressed { id, position }) => {
+                if state.touch.is_none() && layout.bounds().contains(position) {
+                    state.touch = Some((id, position));
+                    state.swiping = false;
+                }
+            }
+            Event::Touch(touch::Event::FingerMoved { id, position }) => {
+                if let Some((finger, start)) = state.touch.filter(|(finger, _)| *finger == id) {
+                    if !state.swiping && self.handler(position - start, SWIPE_THRESHOLD).is_some() {
+                        state.swiping = true;
+                        // the content must not see the touch as a tap anymore
+                        event = Event::Touch(touch::Event::FingerLost {
+                            id: finger,
+                            position,
+                        });
+                    } else if state.swiping {
+                        return event::Status::Captured;
+                    }
+                }
+            }
+            Event::Touch(touch::Event::FingerLifted { id, position }) => {
+                if let Some((_, start)) = state.touch.filter(|(finger, _)| *finger == id) {
+                    let swiping = state.swiping;
+                    state.touch = None;
+                    state.swiping = false;
+
+                    if swiping {
+                        if let Some(message) = self.handler(position - start, SWIPE_THRESHOLD) {
+                            shell.publish(message);
+                        }
+
+                        return event::Status::Captured;
+                    }
+                }
+            }
+            Event::Touch(touch::Event::FingerLost { id, .. }) => {
+                if state.touch.is_some_and(|(finger, _)| finger == id) {
+                    state.touch = None;
+                    state.swiping = false;
+                }
+            }
+            // touchpads scroll with pixel deltas, mouse wheels are left to the content
+            Event::Mouse(mouse::Event::WheelScrolled {
+                delta: mouse::ScrollDelta::Pixels { x, y },
+            }) if cursor.is_over(layout.bounds()) => {
+                state.scrolled = state.scrolled + Vector::new(x, y);
+
+                if let Some(message) = self.handler(state.scrolled, SCROLL_THRESHOLD) {
+                    state.scrolled = Vector::ZERO;
+                    shell.publish(message);
+
+                    return event::Status::Captured;
+                } else if self.handler(state.scrolled, 0.).is_none() {
+                    // scrolling along an axis without a handler
+                    state.scrolled = Vector::ZERO;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorLeft) => {
+                state.scrolled = Vector::ZERO;
+            }
+            _ => {}
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+}
+
+impl<'a, Message, Theme, Renderer> From<SwipeArea<'a, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: 'a,
+    Renderer: iced::core::Renderer + 'a,
+{
+    fn from(swipe_area: SwipeArea<'a, Message, Theme, Renderer>) -> Self {
+        Self::new(swipe_area)
+    }
+}
+
+pub fn swipe_area<'a, Message, Theme, Renderer>(
+    content: impl Into<Element<'a, Message, Theme, Renderer>>,
+) -> SwipeArea<'a, Message, Theme, Renderer> {
+    SwipeArea::new(content)
+}
@@ -6,136 +6,1196 @@ use iced::{
     Color, Subscription,
 };
 use inotify::{EventMask, Inotify, WatchMask};
-use serde::{de::Error, Deserialize, Deserializer};
-use std::{any::TypeId, env, fs::File, path::Path, time::Duration};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use std::{any::TypeId, collections::HashMap, env, fs::File, path::Path, time::Duration};
 use tokio::time::sleep;
 
 use crate::app::Message;
 
 const CONFIG_PATH: &str = "~/.config/ashell.yml";
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdatesModuleConfig {
     pub check_cmd: String,
     pub update_cmd: String,
+    #[serde(default = "default_updates_interval", deserialize_with = "non_zero")]
+    pub interval: u64,
+    #[serde(default = "default_updates_jitter")]
+    pub jitter: u64,
+    #[serde(default)]
+    pub skip_on_metered: bool,
+    #[serde(default)]
+    pub min_battery: Option<u8>,
+    #[serde(default)]
+    pub flatpak: bool,
+    #[serde(default)]
+    pub firmware: bool,
+}
+
+fn default_updates_interval() -> u64 {
+    3600
+}
+
+fn default_updates_jitter() -> u64 {
+    300
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, PartialEq, Eq, Debug)]
+pub enum WorkspaceVisibilityMode {
+    #[default]
+    All,
+    MonitorSpecific,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacesModuleConfig {
+    #[serde(default)]
+    pub visibility_mode: WorkspaceVisibilityMode,
+    #[serde(default)]
+    pub enable_workspace_filling: bool,
+    #[serde(default)]
+    pub display_mode: WorkspaceDisplayMode,
+    #[serde(default = "default_fixed_workspaces")]
+    pub fixed_count: i32,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub monitor_workspaces: HashMap<String, Vec<i32>>,
+    #[serde(default)]
+    pub thumbnails: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum WorkspaceDisplayMode {
+    #[default]
+    All,
+    Occupied,
+    Fixed,
+}
+
+fn default_fixed_workspaces() -> i32 {
+    5
+}
+
+impl Default for WorkspacesModuleConfig {
+    fn default() -> Self {
+        Self {
+            visibility_mode: WorkspaceVisibilityMode::default(),
+            enable_workspace_filling: false,
+            display_mode: WorkspaceDisplayMode::default(),
+            fixed_count: default_fixed_workspaces(),
+            labels: HashMap::new(),
+            monitor_workspaces: HashMap::new(),
+            thumbnails: false,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemModuleConfig {
+    #[serde(default = "default_cpu_warn_threshold")]
+    pub cpu_warn_threshold: u32,
+    #[serde(default = "default_cpu_alert_threshold")]
+    pub cpu_alert_threshold: u32,
+    #[serde(default = "default_mem_warn_threshold")]
+    pub mem_warn_threshold: u32,
+    #[serde(default = "default_mem_alert_threshold")]
+    pub mem_alert_threshold: u32,
+    #[serde(default = "default_temp_warn_threshold")]
+    pub temp_warn_threshold: i32,
+    #[serde(default = "default_temp_alert_threshold")]
+    pub temp_alert_threshold: i32,
+    #[serde(default)]
+    pub disks: Vec<String>,
+    #[serde(default = "default_disk_warn_threshold")]
+    pub disk_warn_threshold: u32,
+    #[serde(default = "default_disk_alert_threshold")]
+    pub disk_alert_threshold: u32,
+    #[serde(default)]
+    pub power_consumers: bool,
+    #[serde(default)]
+    pub temperature: TemperatureConfig,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    pub fn format(&self, celsius: f32) -> String {
+        match self {
+            TemperatureUnit::Celsius => format!("{}°", celsius.round() as i32),
+            TemperatureUnit::Fahrenheit => {
+                format!("{}°F", (celsius * 9. / 5. + 32.).round() as i32)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TemperatureConfig {
+    #[serde(default)]
+    pub unit: TemperatureUnit,
+    #[serde(default = "default_temperature_sensor")]
+    pub sensor: String,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+impl Default for TemperatureConfig {
+    fn default() -> Self {
+        Self {
+            unit: TemperatureUnit::default(),
+            sensor: default_temperature_sensor(),
+            labels: HashMap::new(),
+        }
+    }
+}
+
+fn default_temperature_sensor() -> String {
+    "acpitz temp1".to_string()
+}
+
+fn default_cpu_warn_threshold() -> u32 {
+    60
+}
+
+fn default_cpu_alert_threshold() -> u32 {
+    80
+}
+
+fn default_mem_warn_threshold() -> u32 {
+    70
+}
+
+fn default_mem_alert_threshold() -> u32 {
+    85
+}
+
+fn default_temp_warn_threshold() -> i32 {
+    60
+}
+
+fn default_temp_alert_threshold() -> i32 {
+    80
+}
+
+fn default_disk_warn_threshold() -> u32 {
+    80
+}
+
+fn default_disk_alert_threshold() -> u32 {
+    90
+}
+
+impl Default for SystemModuleConfig {
+    fn default() -> Self {
+        Self {
+            cpu_warn_threshold: default_cpu_warn_threshold(),
+            cpu_alert_threshold: default_cpu_alert_threshold(),
+            mem_warn_threshold: default_mem_warn_threshold(),
+            mem_alert_threshold: default_mem_alert_threshold(),
+            temp_warn_threshold: default_temp_warn_threshold(),
+            temp_alert_threshold: default_temp_alert_threshold(),
+            disks: Vec::new(),
+            disk_warn_threshold: default_disk_warn_threshold(),
+            disk_alert_threshold: default_disk_alert_threshold(),
+            power_consumers: false,
+            temperature: TemperatureConfig::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockModuleConfig {
+    pub format: Option<String>,
+    #[serde(default)]
+    pub alternate_formats: Vec<String>,
+    pub hour12: Option<bool>,
+    pub calendar: Option<CalendarConfig>,
+    #[serde(default)]
+    pub world_clock: Vec<WorldClockEntry>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldClockEntry {
+    pub label: String,
+    pub timezone: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarConfig {
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default = "default_upcoming_events")]
+    pub upcoming_events: usize,
+    pub sync: Option<CalendarSyncConfig>,
+}
+
+fn default_upcoming_events() -> usize {
+    5
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CalendarBackend {
+    Eds,
+    Vdir,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarSyncConfig {
+    pub backend: CalendarBackend,
+    // vdir collections, or the directories containing them
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(
+        default = "default_calendar_sync_interval",
+        deserialize_with = "non_zero"
+    )]
+    pub interval: u64,
+}
+
+fn default_calendar_sync_interval() -> u64 {
+    300
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsModuleConfig {
+    pub lock_cmd: Option<String>,
+    pub audio_sinks_more_cmd: Option<String>,
+    pub audio_sources_more_cmd: Option<String>,
+    pub wifi_more_cmd: Option<String>,
+    pub vpn_more_cmd: Option<String>,
+    pub bluetooth_more_cmd: Option<String>,
+    pub osk_cmd: Option<String>,
+    pub rotate_cmd: Option<String>,
+    #[serde(default)]
+    pub idle_lock: Option<IdleLockConfig>,
+    #[serde(default)]
+    pub battery_saver: Option<BatterySaverConfig>,
+    #[serde(default)]
+    pub battery_notifications: BatteryNotificationsConfig,
+    #[serde(default)]
+    pub inhibit_sleep_processes: Vec<String>,
+    #[serde(default = "default_recent_devices")]
+    pub recent_devices: usize,
+    #[serde(default)]
+    pub metered_ssids: Vec<String>,
+    #[serde(default)]
+    pub data_quotas: Vec<DataQuota>,
+    #[serde(default = "default_data_quota_warning")]
+    pub data_quota_warning: u8,
+    #[serde(default, deserialize_with = "non_zero_option")]
+    pub public_ip_interval: Option<u64>,
+    #[serde(default)]
+    pub vpn_ip_ranges: Vec<String>,
+    #[serde(default)]
+    pub input_profiles: Vec<InputProfile>,
+    #[serde(default)]
+    pub compositor_commands: Vec<CompositorCommand>,
+    // the standalone indicators open their section instead of the whole menu
+    #[serde(default)]
+    pub section_menus: bool,
+}
+
+impl Default for SettingsModuleConfig {
+    fn default() -> Self {
+        Self {
+            lock_cmd: None,
+            audio_sinks_more_cmd: None,
+            audio_sources_more_cmd: None,
+            wifi_more_cmd: None,
+            vpn_more_cmd: None,
+            bluetooth_more_cmd: None,
+            osk_cmd: None,
+            rotate_cmd: None,
+            idle_lock: None,
+            battery_saver: None,
+            battery_notifications: BatteryNotificationsConfig::default(),
+            inhibit_sleep_processes: Vec::new(),
+            recent_devices: default_recent_devices(),
+            metered_ssids: Vec::new(),
+            data_quotas: Vec::new(),
+            data_quota_warning: default_data_quota_warning(),
+            public_ip_interval: None,
+            vpn_ip_ranges: Vec::new(),
+            input_profiles: Vec::new(),
+            compositor_commands: Vec::new(),
+            section_menus: false,
+        }
+    }
+}
+
+fn default_recent_devices() -> usize {
+    3
+}
+
+fn default_data_quota_warning() -> u8 {
+    80
+}
+
+/// Monthly data allowance of a Wi-Fi network
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataQuota {
+    pub ssid: String,
+    pub monthly_mb: u64,
+}
+
+impl DataQuota {
+    pub fn bytes(&self) -> u64 {
+        self.monthly_mb * 1024 * 1024
+    }
+}
+
+/// Button of the settings menu running a compositor command,
+/// e.g. `hyprctl keyword decoration:blur:enabled false`
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CompositorCommand {
+    pub label: String,
+    // a glyph of the icon font
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub command: String,
+}
+
+/// Pointer settings applied together, like a slower profile for gaming
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InputProfile {
+    pub name: String,
+    // from -1 to 1
+    #[serde(default)]
+    pub sensitivity: f32,
+    // flat or adaptive
+    #[serde(default)]
+    pub accel_profile: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryNotificationsConfig {
+    #[serde(default = "default_battery_notification")]
+    pub plugged: bool,
+    #[serde(default = "default_battery_notification")]
+    pub unplugged: bool,
+    #[serde(default = "default_battery_notification")]
+    pub full: bool,
+    #[serde(default = "default_battery_notification")]
+    pub low: bool,
+    #[serde(default = "default_battery_notification")]
+    pub critical: bool,
+}
+
+impl Default for BatteryNotificationsConfig {
+    fn default() -> Self {
+        Self {
+            plugged: true,
+            unplugged: true,
+            full: true,
+            low: true,
+            critical: true,
+        }
+    }
+}
+
+fn default_battery_notification() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct IdleLockConfig {
+    pub timeout: u64,
+    #[serde(default = "default_idle_lock_warning")]
+    pub warning: u64,
+    #[serde(default = "default_idle_lock_stay_awake")]
+    pub stay_awake: u64,
+}
+
+fn default_idle_lock_warning() -> u64 {
+    30
+}
+
+fn default_idle_lock_stay_awake() -> u64 {
+    30
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BatterySaverConfig {
+    #[serde(default = "default_battery_saver_threshold")]
+    pub threshold: i64,
+    #[serde(default)]
+    pub brightness: Option<u32>,
+}
+
+fn default_battery_saver_threshold() -> i64 {
+    20
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaPlayerModuleConfig {
+    #[serde(default = "default_media_player_max_title_length")]
+    pub max_title_length: u32,
+    #[serde(default)]
+    pub global_shortcuts: bool,
+    #[serde(default = "default_sleep_timer_presets")]
+    pub sleep_timer_presets: Vec<u64>,
+    #[serde(default = "default_sleep_timer_fade")]
+    pub sleep_timer_fade: bool,
+}
+
+impl Default for MediaPlayerModuleConfig {
+    fn default() -> Self {
+        MediaPlayerModuleConfig {
+            max_title_length: default_media_player_max_title_length(),
+            global_shortcuts: false,
+            sleep_timer_presets: default_sleep_timer_presets(),
+            sleep_timer_fade: true,
+        }
+    }
+}
+
+fn default_media_player_max_title_length() -> u32 {
+    100
+}
+
+fn default_sleep_timer_presets() -> Vec<u64> {
+    vec![15, 30, 60]
+}
+
+fn default_sleep_timer_fade() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualMachinesModuleConfig {
+    #[serde(default = "default_virtual_machines_uri")]
+    pub uri: String,
+    #[serde(
+        default = "default_virtual_machines_interval",
+        deserialize_with = "non_zero"
+    )]
+    pub interval: u64,
+}
+
+impl Default for VirtualMachinesModuleConfig {
+    fn default() -> Self {
+        Self {
+            uri: default_virtual_machines_uri(),
+            interval: default_virtual_machines_interval(),
+        }
+    }
+}
+
+fn default_virtual_machines_uri() -> String {
+    "qemu:///system".to_string()
+}
+
+fn default_virtual_machines_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintQueueModuleConfig {
+    #[serde(
+        default = "default_print_queue_interval",
+        deserialize_with = "non_zero"
+    )]
+    pub interval: u64,
+}
+
+impl Default for PrintQueueModuleConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_print_queue_interval(),
+        }
+    }
+}
+
+fn default_print_queue_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FirewallModuleConfig {
+    #[serde(default = "default_firewall_hardened_zone")]
+    pub hardened_zone: String,
+    #[serde(default = "default_firewall_restore_zone")]
+    pub restore_zone: String,
+}
+
+impl Default for FirewallModuleConfig {
+    fn default() -> Self {
+        Self {
+            hardened_zone: default_firewall_hardened_zone(),
+            restore_zone: default_firewall_restore_zone(),
+        }
+    }
+}
+
+fn default_firewall_hardened_zone() -> String {
+    "block".to_owned()
+}
+
+fn default_firewall_restore_zone() -> String {
+    "public".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KdeConnectModuleConfig {
+    #[serde(default = "default_kde_connect_forward_notifications")]
+    pub forward_notifications: bool,
+}
+
+impl Default for KdeConnectModuleConfig {
+    fn default() -> Self {
+        Self {
+            forward_notifications: default_kde_connect_forward_notifications(),
+        }
+    }
+}
+
+fn default_kde_connect_forward_notifications() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyModuleConfig {
+    #[serde(default)]
+    pub screenshare_chip: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SeparatorConfig {
+    // drawn instead of the line when set
+    #[serde(default)]
+    pub glyph: Option<String>,
+    #[serde(default = "default_separator_thickness")]
+    pub thickness: f32,
+    #[serde(default = "default_separator_length")]
+    pub length: f32,
+}
+
+impl Default for SeparatorConfig {
+    fn default() -> Self {
+        Self {
+            glyph: None,
+            thickness: default_separator_thickness(),
+            length: default_separator_length(),
+        }
+    }
+}
+
+fn default_separator_thickness() -> f32 {
+    1.
+}
+
+fn default_separator_length() -> f32 {
+    16.
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PingModuleConfig {
+    #[serde(default = "default_ping_host")]
+    pub host: String,
+    #[serde(default = "default_ping_interval", deserialize_with = "non_zero")]
+    pub interval: u64,
+    #[serde(default = "default_ping_warn_threshold")]
+    pub warn_threshold: u32,
+    #[serde(default = "default_ping_alert_threshold")]
+    pub alert_threshold: u32,
+    #[serde(default = "default_ping_loss_alert")]
+    pub loss_alert: u32,
+}
+
+impl Default for PingModuleConfig {
+    fn default() -> Self {
+        Self {
+            host: default_ping_host(),
+            interval: default_ping_interval(),
+            warn_threshold: default_ping_warn_threshold(),
+            alert_threshold: default_ping_alert_threshold(),
+            loss_alert: default_ping_loss_alert(),
+        }
+    }
+}
+
+fn default_ping_host() -> String {
+    "1.1.1.1".to_owned()
+}
+
+fn default_ping_interval() -> u64 {
+    5
+}
+
+fn default_ping_warn_threshold() -> u32 {
+    80
+}
+
+fn default_ping_alert_threshold() -> u32 {
+    150
+}
+
+fn default_ping_loss_alert() -> u32 {
+    20
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakReminderModuleConfig {
+    // minutes of activity between two breaks
+    #[serde(default = "default_break_interval", deserialize_with = "non_zero")]
+    pub interval: u64,
+    // seconds, being idle this long counts as a break
+    #[serde(default = "default_break_duration")]
+    pub break_duration: u64,
+    // minutes
+    #[serde(default = "default_break_snooze")]
+    pub snooze: u64,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default = "default_break_pause_in_fullscreen")]
+    pub pause_in_fullscreen: bool,
+}
+
+impl BreakReminderModuleConfig {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval * 60)
+    }
+}
+
+impl Default for BreakReminderModuleConfig {
+    fn default() -> Self {
+        Self {
+            interval: default_break_interval(),
+            break_duration: default_break_duration(),
+            snooze: default_break_snooze(),
+            fullscreen: false,
+            pause_in_fullscreen: default_break_pause_in_fullscreen(),
+        }
+    }
+}
+
+fn default_break_interval() -> u64 {
+    20
+}
+
+fn default_break_duration() -> u64 {
+    20
+}
+
+fn default_break_snooze() -> u64 {
+    5
+}
+
+fn default_break_pause_in_fullscreen() -> bool {
+    true
+}
+
+/// A credential referenced from the configuration instead of written in it
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Secret {
+    /// Secret Service item matching the attributes
+    Keyring(HashMap<String, String>),
+    /// File readable only by the user
+    File(String),
+    /// Output of a command, like `pass show backup`
+    Command(String),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupJobConfig {
+    pub name: String,
+    pub last_run_cmd: String,
+    pub run_cmd: String,
+    #[serde(default = "default_backup_max_age")]
+    pub max_age: u64,
+    #[serde(default)]
+    pub env: HashMap<String, Secret>,
+}
+
+fn default_backup_max_age() -> u64 {
+    24
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupModuleConfig {
+    pub jobs: Vec<BackupJobConfig>,
+    #[serde(default = "default_backup_interval", deserialize_with = "non_zero")]
+    pub interval: u64,
+}
+
+fn default_backup_interval() -> u64 {
+    300
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovableMediaModuleConfig {
+    #[serde(default = "default_removable_media_notify")]
+    pub notify: bool,
+}
+
+impl Default for RemovableMediaModuleConfig {
+    fn default() -> Self {
+        Self {
+            notify: default_removable_media_notify(),
+        }
+    }
+}
+
+fn default_removable_media_notify() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GameModeModuleConfig {
+    #[serde(default)]
+    pub compact_bar: bool,
+    #[serde(default = "default_game_mode_suppress_notifications")]
+    pub suppress_notifications: bool,
+}
+
+impl Default for GameModeModuleConfig {
+    fn default() -> Self {
+        Self {
+            compact_bar: false,
+            suppress_notifications: default_game_mode_suppress_notifications(),
+        }
+    }
+}
+
+fn default_game_mode_suppress_notifications() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FontsConfig {
+    #[serde(default)]
+    pub text: Vec<String>,
+    #[serde(default)]
+    pub icons: Vec<String>,
+    #[serde(default)]
+    pub monospace: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SolarMode {
+    #[default]
+    SunriseSunset,
+    Prayer,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SolarModuleConfig {
+    #[serde(default)]
+    pub mode: SolarMode,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub notify_before: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsModuleConfig {
+    // older notifications are dropped from the history
+    #[serde(default = "default_notifications_max_history")]
+    pub max_history: usize,
+}
+
+impl Default for NotificationsModuleConfig {
+    fn default() -> Self {
+        Self {
+            max_history: default_notifications_max_history(),
+        }
+    }
+}
+
+fn default_notifications_max_history() -> usize {
+    50
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeatherUnits {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WeatherModuleConfig {
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub units: WeatherUnits,
+    // seconds
+    #[serde(default = "default_weather_interval", deserialize_with = "non_zero")]
+    pub interval: u64,
+}
+
+impl Default for WeatherModuleConfig {
+    fn default() -> Self {
+        Self {
+            latitude: None,
+            longitude: None,
+            units: WeatherUnits::default(),
+            interval: default_weather_interval(),
+        }
+    }
+}
+
+fn default_weather_interval() -> u64 {
+    900
+}
+
+#[derive(Deserialize, Serialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DockModuleConfig {
+    #[serde(default)]
+    pub apps: Vec<DockApp>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DockApp {
+    pub app_id: String,
+    pub command: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioConfig {
+    #[serde(default = "default_volume_step")]
+    pub volume_step: u32,
+    #[serde(default = "default_max_volume")]
+    pub max_volume: u32,
+    #[serde(default)]
+    pub raise_maximum_on_scroll: bool,
+    #[serde(default)]
+    pub rules: Vec<AudioRule>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioPortType {
+    Headphones,
+    Headset,
+    Speaker,
+    Hdmi,
+}
+
+/// Applied when a matching output appears, e.g. when the headphones are plugged in
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioRule {
+    #[serde(default)]
+    pub port_type: Option<AudioPortType>,
+    // matched against the device and port descriptions, ignoring the case
+    #[serde(default)]
+    pub device: Option<String>,
+    #[serde(default = "default_audio_rule_set_default")]
+    pub set_default: bool,
+    #[serde(default)]
+    pub volume: Option<u32>,
+}
+
+fn default_audio_rule_set_default() -> bool {
+    true
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            volume_step: default_volume_step(),
+            max_volume: default_max_volume(),
+            raise_maximum_on_scroll: false,
+            rules: Vec::new(),
+        }
+    }
+}
+
+fn default_volume_step() -> u32 {
+    5
+}
+
+fn default_max_volume() -> u32 {
+    100
+}
+
+impl AudioConfig {
+    /// Highest volume reachable scrolling, the slider always goes up to `max_volume`
+    pub fn scroll_max_volume(&self) -> i32 {
+        if self.raise_maximum_on_scroll {
+            self.max_volume as i32
+        } else {
+            self.max_volume.min(100) as i32
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MenuAnimation {
+    #[default]
+    None,
+    Fade,
+    Slide,
+    Scale,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusExportConfig {
+    // written to stdout, one JSON object per line, when not set
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsConfig {
+    // served on 127.0.0.1 only
+    #[serde(default = "default_metrics_port")]
+    pub port: u16,
+}
+
+fn default_metrics_port() -> u16 {
+    9789
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            port: default_metrics_port(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OsdConfig {
+    #[serde(default = "default_osd_enabled")]
+    pub enabled: bool,
+    // milliseconds the overlay stays on screen after the last change
+    #[serde(default = "default_osd_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for OsdConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_osd_enabled(),
+            timeout: default_osd_timeout(),
+        }
+    }
 }
 
-#[derive(Deserialize, Clone, Default, PartialEq, Eq, Debug)]
-pub enum WorkspaceVisibilityMode {
-    #[default]
-    All,
-    MonitorSpecific,
+fn default_osd_enabled() -> bool {
+    true
 }
 
-#[derive(Deserialize, Clone, Default, Debug)]
+fn default_osd_timeout() -> u64 {
+    1500
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct WorkspacesModuleConfig {
+pub struct MenuConfig {
     #[serde(default)]
-    pub visibility_mode: WorkspaceVisibilityMode,
+    pub max_width: Option<f32>,
     #[serde(default)]
-    pub enable_workspace_filling: bool,
+    pub max_height: Option<f32>,
+    #[serde(default)]
+    pub animation: MenuAnimation,
+    // Hyprland only, the desktop behind the menus shows through
+    // a translucent background
+    #[serde(default)]
+    pub blur: bool,
+    #[serde(default = "default_menu_opacity")]
+    pub opacity: f32,
+    #[serde(default)]
+    pub global_shortcuts: bool,
 }
 
-#[derive(Deserialize, Clone, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct SystemModuleConfig {
-    #[serde(default = "default_cpu_warn_threshold")]
-    pub cpu_warn_threshold: u32,
-    #[serde(default = "default_cpu_alert_threshold")]
-    pub cpu_alert_threshold: u32,
-    #[serde(default = "default_mem_warn_threshold")]
-    pub mem_warn_threshold: u32,
-    #[serde(default = "default_mem_alert_threshold")]
-    pub mem_alert_threshold: u32,
-    #[serde(default = "default_temp_warn_threshold")]
-    pub temp_warn_threshold: i32,
-    #[serde(default = "default_temp_alert_threshold")]
-    pub temp_alert_threshold: i32,
+impl Default for MenuConfig {
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            max_height: None,
+            animation: MenuAnimation::default(),
+            blur: false,
+            opacity: default_menu_opacity(),
+            global_shortcuts: false,
+        }
+    }
 }
 
-fn default_cpu_warn_threshold() -> u32 {
-    60
+fn default_menu_opacity() -> f32 {
+    1.
 }
 
-fn default_cpu_alert_threshold() -> u32 {
-    80
+/// Distance of a floating bar from the screen edges
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BarMargin {
+    // from the edge the bar is anchored to
+    #[serde(default)]
+    pub edge: u32,
+    #[serde(default)]
+    pub sides: u32,
 }
 
-fn default_mem_warn_threshold() -> u32 {
-    70
+/// Floating look of the bar, inset from the screen edges
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BarConfig {
+    #[serde(default)]
+    pub margin: BarMargin,
+    #[serde(default)]
+    pub radius: f32,
+    #[serde(default)]
+    pub shadow: bool,
 }
 
-fn default_mem_alert_threshold() -> u32 {
-    85
+impl BarConfig {
+    pub fn is_floating(&self) -> bool {
+        *self != Self::default()
+    }
 }
 
-fn default_temp_warn_threshold() -> i32 {
-    60
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WidgetAnchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
 }
 
-fn default_temp_alert_threshold() -> i32 {
-    80
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopWidgetConfig {
+    pub module: ModuleName,
+    #[serde(default)]
+    pub anchor: WidgetAnchor,
+    // horizontal and vertical distance from the anchored edges
+    #[serde(default)]
+    pub margin: (i32, i32),
+    #[serde(default = "default_widget_scale")]
+    pub scale: f64,
 }
 
-impl Default for SystemModuleConfig {
-    fn default() -> Self {
-        Self {
-            cpu_warn_threshold: default_cpu_warn_threshold(),
-            cpu_alert_threshold: default_cpu_alert_threshold(),
-            mem_warn_threshold: default_mem_warn_threshold(),
-            mem_alert_threshold: default_mem_alert_threshold(),
-            temp_warn_threshold: default_temp_warn_threshold(),
-            temp_alert_threshold: default_temp_alert_threshold(),
-        }
-    }
+fn default_widget_scale() -> f64 {
+    3.
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ClockModuleConfig {
-    pub format: String,
+pub struct ScreenCaptureModuleConfig {
+    // defaults to $XDG_VIDEOS_DIR or ~/Videos
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default = "default_gif_fps")]
+    pub gif_fps: u32,
+    // tesseract language used to grab the text from the screen
+    #[serde(default = "default_ocr_language")]
+    pub ocr_language: String,
 }
 
-impl Default for ClockModuleConfig {
+impl Default for ScreenCaptureModuleConfig {
     fn default() -> Self {
         Self {
-            format: "%a %d %b %R".to_string(),
+            directory: None,
+            gif_fps: default_gif_fps(),
+            ocr_language: default_ocr_language(),
         }
     }
 }
 
-#[derive(Deserialize, Default, Clone, Debug)]
+fn default_gif_fps() -> u32 {
+    15
+}
+
+fn default_ocr_language() -> String {
+    "eng".to_owned()
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct SettingsModuleConfig {
-    pub lock_cmd: Option<String>,
-    pub audio_sinks_more_cmd: Option<String>,
-    pub audio_sources_more_cmd: Option<String>,
-    pub wifi_more_cmd: Option<String>,
-    pub vpn_more_cmd: Option<String>,
-    pub bluetooth_more_cmd: Option<String>,
+pub struct TrayIconOverride {
+    // id of the tray item, e.g. "nm-applet"
+    pub id: String,
+    // icon name, or path, used instead of the one sent by the app
+    #[serde(default)]
+    pub icon: Option<String>,
+    // shrinks, or enlarges, icons drawn too big or too small
+    #[serde(default = "default_tray_icon_scale")]
+    pub scale: f32,
+}
+
+fn default_tray_icon_scale() -> f32 {
+    1.
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct MediaPlayerModuleConfig {
-    #[serde(default = "default_media_player_max_title_length")]
-    pub max_title_length: u32,
+pub struct TrayModuleConfig {
+    #[serde(default = "default_tray_icon_size")]
+    pub icon_size: f32,
+    #[serde(default)]
+    pub overrides: Vec<TrayIconOverride>,
 }
 
-impl Default for MediaPlayerModuleConfig {
+impl Default for TrayModuleConfig {
     fn default() -> Self {
-        MediaPlayerModuleConfig {
-            max_title_length: default_media_player_max_title_length(),
+        Self {
+            icon_size: default_tray_icon_size(),
+            overrides: Vec::new(),
         }
     }
 }
 
-fn default_media_player_max_title_length() -> u32 {
-    100
+fn default_tray_icon_size() -> f32 {
+    14.
 }
 
-#[derive(Deserialize, Clone, Copy, Debug)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
 #[serde(untagged)]
 #[serde(rename_all = "camelCase")]
 pub enum AppearanceColor {
@@ -192,24 +1252,244 @@ impl AppearanceColor {
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemePreset {
+    Catppuccin,
+    Gruvbox,
+    Nord,
+    Dracula,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 4] = [
+        ThemePreset::Catppuccin,
+        ThemePreset::Gruvbox,
+        ThemePreset::Nord,
+        ThemePreset::Dracula,
+    ];
+
+    pub fn appearance(self) -> Appearance {
+        let complete = |base: HexColor,
+                        strong: Option<HexColor>,
+                        weak: Option<HexColor>,
+                        text: Option<HexColor>| AppearanceColor::Complete {
+            base,
+            strong,
+            weak,
+            text,
+        };
+
+        match self {
+            ThemePreset::Catppuccin => Appearance {
+                preset: Some(self),
+                ..Appearance::default()
+            },
+            ThemePreset::Gruvbox => Appearance {
+                preset: Some(self),
+                background_color: complete(
+                    HexColor::rgb(40, 40, 40),
+                    Some(HexColor::rgb(80, 73, 69)),
+                    Some(HexColor::rgb(60, 56, 54)),
+                    None,
+                ),
+                primary_color: complete(
+                    HexColor::rgb(254, 128, 25),
+                    None,
+                    None,
+                    Some(HexColor::rgb(40, 40, 40)),
+                ),
+                secondary_color: complete(
+                    HexColor::rgb(29, 32, 33),
+                    Some(HexColor::rgb(50, 48, 47)),
+                    None,
+                    None,
+                ),
+                success_color: AppearanceColor::Simple(HexColor::rgb(184, 187, 38)),
+                danger_color: complete(
+                    HexColor::rgb(251, 73, 52),
+                    None,
+                    Some(HexColor::rgb(250, 189, 47)),
+                    None,
+                ),
+                text_color: AppearanceColor::Simple(HexColor::rgb(235, 219, 178)),
+                workspace_colors: vec![
+                    AppearanceColor::Simple(HexColor::rgb(254, 128, 25)),
+                    AppearanceColor::Simple(HexColor::rgb(131, 165, 152)),
+                    AppearanceColor::Simple(HexColor::rgb(211, 134, 155)),
+                ],
+                special_workspace_colors: None,
+                direction: Direction::default(),
+                high_contrast: None,
+                reduce_transparency: false,
+                state_badges: false,
+                wallpaper_accent: false,
+            },
+            ThemePreset::Nord => Appearance {
+                preset: Some(self),
+                background_color: complete(
+                    HexColor::rgb(46, 52, 64),
+                    Some(HexColor::rgb(76, 86, 106)),
+                    Some(HexColor::rgb(59, 66, 82)),
+                    None,
+                ),
+                primary_color: complete(
+                    HexColor::rgb(136, 192, 208),
+                    None,
+                    None,
+                    Some(HexColor::rgb(46, 52, 64)),
+                ),
+                secondary_color: complete(
+                    HexColor::rgb(36, 41, 51),
+                    Some(HexColor::rgb(41, 46, 57)),
+                    None,
+                    None,
+                ),
+                success_color: AppearanceColor::Simple(HexColor::rgb(163, 190, 140)),
+                danger_color: complete(
+                    HexColor::rgb(191, 97, 106),
+                    None,
+                    Some(HexColor::rgb(235, 203, 139)),
+                    None,
+                ),
+                text_color: AppearanceColor::Simple(HexColor::rgb(236, 239, 244)),
+                workspace_colors: vec![
+                    AppearanceColor::Simple(HexColor::rgb(136, 192, 208)),
+                    AppearanceColor::Simple(HexColor::rgb(129, 161, 193)),
+                    AppearanceColor::Simple(HexColor::rgb(180, 142, 173)),
+                ],
+                special_workspace_colors: None,
+                direction: Direction::default(),
+                high_contrast: None,
+                reduce_transparency: false,
+                state_badges: false,
+                wallpaper_accent: false,
+            },
+            ThemePreset::Dracula => Appearance {
+                preset: Some(self),
+                background_color: complete(
+                    HexColor::rgb(40, 42, 54),
+                    Some(HexColor::rgb(68, 71, 90)),
+                    Some(HexColor::rgb(52, 55, 70)),
+                    None,
+                ),
+                primary_color: complete(
+                    HexColor::rgb(189, 147, 249),
+                    None,
+                    None,
+                    Some(HexColor::rgb(40, 42, 54)),
+                ),
+                secondary_color: complete(
+                    HexColor::rgb(33, 34, 44),
+                    Some(HexColor::rgb(25, 26, 33)),
+                    None,
+                    None,
+                ),
+                success_color: AppearanceColor::Simple(HexColor::rgb(80, 250, 123)),
+                danger_color: complete(
+                    HexColor::rgb(255, 85, 85),
+                    None,
+                    Some(HexColor::rgb(241, 250, 140)),
+                    None,
+                ),
+                text_color: AppearanceColor::Simple(HexColor::rgb(248, 248, 242)),
+                workspace_colors: vec![
+                    AppearanceColor::Simple(HexColor::rgb(189, 147, 249)),
+                    AppearanceColor::Simple(HexColor::rgb(255, 121, 198)),
+                    AppearanceColor::Simple(HexColor::rgb(139, 233, 253)),
+                ],
+                special_workspace_colors: None,
+                direction: Direction::default(),
+                high_contrast: None,
+                reduce_transparency: false,
+                state_badges: false,
+                wallpaper_accent: false,
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
+#[serde(from = "AppearanceDef")]
 pub struct Appearance {
-    #[serde(default = "default_background_color")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<ThemePreset>,
     pub background_color: AppearanceColor,
-    #[serde(default = "default_primary_color")]
     pub primary_color: AppearanceColor,
-    #[serde(default = "default_secondary_color")]
     pub secondary_color: AppearanceColor,
-    #[serde(default = "default_success_color")]
     pub success_color: AppearanceColor,
-    #[serde(default = "default_danger_color")]
     pub danger_color: AppearanceColor,
-    #[serde(default = "default_text_color")]
     pub text_color: AppearanceColor,
-    #[serde(default = "default_workspace_colors")]
     pub workspace_colors: Vec<AppearanceColor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub special_workspace_colors: Option<Vec<AppearanceColor>>,
+    pub direction: Direction,
+    // follows the desktop contrast preference when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_contrast: Option<bool>,
+    pub reduce_transparency: bool,
+    pub state_badges: bool,
+    // primary color taken from the wallpaper set with swww or hyprpaper
+    pub wallpaper_accent: bool,
+}
+
+// colors not set explicitly fall back to the selected preset
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppearanceDef {
+    preset: Option<ThemePreset>,
+    background_color: Option<AppearanceColor>,
+    primary_color: Option<AppearanceColor>,
+    secondary_color: Option<AppearanceColor>,
+    success_color: Option<AppearanceColor>,
+    danger_color: Option<AppearanceColor>,
+    text_color: Option<AppearanceColor>,
+    workspace_colors: Option<Vec<AppearanceColor>>,
+    special_workspace_colors: Option<Vec<AppearanceColor>>,
+    #[serde(default)]
+    direction: Direction,
+    high_contrast: Option<bool>,
+    #[serde(default)]
+    reduce_transparency: bool,
+    #[serde(default)]
+    state_badges: bool,
+    #[serde(default)]
+    wallpaper_accent: bool,
+}
+
+impl From<AppearanceDef> for Appearance {
+    fn from(value: AppearanceDef) -> Self {
+        let base = value
+            .preset
+            .map(ThemePreset::appearance)
+            .unwrap_or_default();
+
+        Self {
+            preset: value.preset,
+            background_color: value.background_color.unwrap_or(base.background_color),
+            primary_color: value.primary_color.unwrap_or(base.primary_color),
+            secondary_color: value.secondary_color.unwrap_or(base.secondary_color),
+            success_color: value.success_color.unwrap_or(base.success_color),
+            danger_color: value.danger_color.unwrap_or(base.danger_color),
+            text_color: value.text_color.unwrap_or(base.text_color),
+            workspace_colors: value.workspace_colors.unwrap_or(base.workspace_colors),
+            special_workspace_colors: value
+                .special_workspace_colors
+                .or(base.special_workspace_colors),
+            direction: value.direction,
+            high_contrast: value.high_contrast,
+            reduce_transparency: value.reduce_transparency,
+            state_badges: value.state_badges,
+            wallpaper_accent: value.wallpaper_accent,
+        }
+    }
 }
 
 static PRIMARY: HexColor = HexColor::rgb(250, 179, 135);
@@ -269,6 +1549,7 @@ fn default_workspace_colors() -> Vec<AppearanceColor> {
 impl Default for Appearance {
     fn default() -> Self {
         Self {
+            preset: None,
             background_color: default_background_color(),
             primary_color: default_primary_color(),
             secondary_color: default_secondary_color(),
@@ -277,18 +1558,23 @@ impl Default for Appearance {
             text_color: default_text_color(),
             workspace_colors: default_workspace_colors(),
             special_workspace_colors: None,
+            direction: Direction::default(),
+            high_contrast: None,
+            reduce_transparency: false,
+            state_badges: false,
+            wallpaper_accent: false,
         }
     }
 }
 
-#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Position {
     #[default]
     Top,
     Bottom,
 }
 
-#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ModuleName {
     AppLauncher,
     Updates,
@@ -302,10 +1588,32 @@ pub enum ModuleName {
     Clock,
     Privacy,
     Settings,
+    Battery,
+    Network,
+    Bluetooth,
+    Audio,
     MediaPlayer,
+    VirtualMachines,
+    PrintQueue,
+    Backup,
+    RemovableMedia,
+    GameMode,
+    Solar,
+    Dock,
+    EmojiPicker,
+    Ping,
+    Firewall,
+    KdeConnect,
+    BreakReminder,
+    ScreenCapture,
+    Weather,
+    Notifications,
+    Separator,
+    // empty space of the given width
+    Spacer(u32),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum ModuleDef {
@@ -313,7 +1621,7 @@ pub enum ModuleDef {
     Group(Vec<ModuleName>),
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Modules {
     #[serde(default)]
@@ -324,6 +1632,31 @@ pub struct Modules {
     pub right: Vec<ModuleDef>,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModuleSection {
+    Left,
+    Center,
+    Right,
+}
+
+impl Modules {
+    pub fn section(&self, section: ModuleSection) -> &Vec<ModuleDef> {
+        match section {
+            ModuleSection::Left => &self.left,
+            ModuleSection::Center => &self.center,
+            ModuleSection::Right => &self.right,
+        }
+    }
+
+    pub fn section_mut(&mut self, section: ModuleSection) -> &mut Vec<ModuleDef> {
+        match section {
+            ModuleSection::Left => &mut self.left,
+            ModuleSection::Center => &mut self.center,
+            ModuleSection::Right => &mut self.right,
+        }
+    }
+}
+
 impl Default for Modules {
     fn default() -> Self {
         Self {
@@ -338,7 +1671,7 @@ impl Default for Modules {
     }
 }
 
-#[derive(Deserialize, Clone, Default, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Default, Debug, PartialEq, Eq)]
 pub enum Outputs {
     #[default]
     All,
@@ -360,7 +1693,30 @@ where
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+// tokio panics on a zero interval, and a zero timer would fire on every tick
+fn non_zero<'de, D>(d: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = u64::deserialize(d)?;
+    if value == 0 {
+        Err(D::Error::custom("need a positive interval"))
+    } else {
+        Ok(value)
+    }
+}
+
+fn non_zero_option<'de, D>(d: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<u64>::deserialize(d)? {
+        Some(0) => Err(D::Error::custom("need a positive interval")),
+        value => Ok(value),
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     #[serde(default = "default_log_level")]
@@ -370,6 +1726,8 @@ pub struct Config {
     #[serde(default)]
     pub outputs: Outputs,
     #[serde(default)]
+    pub outputs_exclude: Vec<String>,
+    #[serde(default)]
     pub modules: Modules,
     pub app_launcher_cmd: Option<String>,
     pub clipboard_cmd: Option<String>,
@@ -389,12 +1747,72 @@ pub struct Config {
     pub appearance: Appearance,
     #[serde(default)]
     pub media_player: MediaPlayerModuleConfig,
+    #[serde(default)]
+    pub virtual_machines: VirtualMachinesModuleConfig,
+    #[serde(default)]
+    pub print_queue: PrintQueueModuleConfig,
+    #[serde(default)]
+    pub backup: Option<BackupModuleConfig>,
+    #[serde(default)]
+    pub removable_media: RemovableMediaModuleConfig,
+    #[serde(default)]
+    pub game_mode: GameModeModuleConfig,
+    #[serde(default)]
+    pub fonts: FontsConfig,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub solar: SolarModuleConfig,
+    #[serde(default)]
+    pub dock: DockModuleConfig,
+    #[serde(default)]
+    pub audio: AudioConfig,
+    #[serde(default)]
+    pub polkit_agent: bool,
+    #[serde(default = "default_touch_scale")]
+    pub touch_scale: f64,
+    #[serde(default)]
+    pub menu: MenuConfig,
+    #[serde(default)]
+    pub bar: BarConfig,
+    #[serde(default)]
+    pub separator: SeparatorConfig,
+    #[serde(default)]
+    pub widgets: Vec<DesktopWidgetConfig>,
+    #[serde(default)]
+    pub ping: PingModuleConfig,
+    #[serde(default)]
+    pub firewall: FirewallModuleConfig,
+    #[serde(default)]
+    pub kde_connect: KdeConnectModuleConfig,
+    #[serde(default)]
+    pub privacy: PrivacyModuleConfig,
+    #[serde(default)]
+    pub break_reminder: BreakReminderModuleConfig,
+    #[serde(default)]
+    pub screen_capture: ScreenCaptureModuleConfig,
+    #[serde(default)]
+    pub tray: TrayModuleConfig,
+    #[serde(default)]
+    pub weather: WeatherModuleConfig,
+    #[serde(default)]
+    pub notifications: NotificationsModuleConfig,
+    #[serde(default)]
+    pub osd: OsdConfig,
+    #[serde(default)]
+    pub status_export: Option<StatusExportConfig>,
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
 }
 
 fn default_log_level() -> String {
     "warn".to_owned()
 }
 
+fn default_touch_scale() -> f64 {
+    1.25
+}
+
 fn default_truncate_title_after_length() -> u32 {
     150
 }
@@ -405,6 +1823,7 @@ impl Default for Config {
             log_level: default_log_level(),
             position: Position::Top,
             outputs: Outputs::default(),
+            outputs_exclude: Vec::new(),
             modules: Modules::default(),
             app_launcher_cmd: None,
             clipboard_cmd: None,
@@ -416,14 +1835,193 @@ impl Default for Config {
             settings: SettingsModuleConfig::default(),
             appearance: Appearance::default(),
             media_player: MediaPlayerModuleConfig::default(),
+            virtual_machines: VirtualMachinesModuleConfig::default(),
+            print_queue: PrintQueueModuleConfig::default(),
+            backup: None,
+            removable_media: RemovableMediaModuleConfig::default(),
+            game_mode: GameModeModuleConfig::default(),
+            fonts: FontsConfig::default(),
+            locale: None,
+            solar: SolarModuleConfig::default(),
+            dock: DockModuleConfig::default(),
+            audio: AudioConfig::default(),
+            polkit_agent: false,
+            touch_scale: default_touch_scale(),
+            menu: MenuConfig::default(),
+            bar: BarConfig::default(),
+            separator: SeparatorConfig::default(),
+            widgets: Vec::new(),
+            ping: PingModuleConfig::default(),
+            firewall: FirewallModuleConfig::default(),
+            kde_connect: KdeConnectModuleConfig::default(),
+            privacy: PrivacyModuleConfig::default(),
+            break_reminder: BreakReminderModuleConfig::default(),
+            screen_capture: ScreenCaptureModuleConfig::default(),
+            tray: TrayModuleConfig::default(),
+            weather: WeatherModuleConfig::default(),
+            notifications: NotificationsModuleConfig::default(),
+            osd: OsdConfig::default(),
+            status_export: None,
+            metrics: None,
         }
     }
 }
 
-pub fn read_config() -> Result<Config, serde_yaml::Error> {
+pub fn expand_home(path: &str) -> String {
     let home_dir = env::var("HOME").expect("Could not get HOME environment variable");
-    let file_path = format!("{}{}", home_dir, CONFIG_PATH.replace('~', ""));
-    let config_file = File::open(file_path);
+    path.replacen('~', &home_dir, 1)
+}
+
+fn config_file_path() -> String {
+    expand_home(CONFIG_PATH)
+}
+
+pub fn config_exists() -> bool {
+    Path::new(&config_file_path()).exists()
+}
+
+pub async fn write_config(content: String) -> std::io::Result<()> {
+    let file_path = config_file_path();
+    let tmp_path = format!("{}.tmp", file_path);
+
+    if let Some(parent) = Path::new(&file_path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    // write a temporary file and move it in place so the config watcher never sees a partial file
+    tokio::fs::write(&tmp_path, content).await?;
+    tokio::fs::rename(&tmp_path, &file_path).await
+}
+
+/// Applies the changes between `original` and `updated` to the value parsed
+/// from the config file, the untouched settings and defaults stay out of it
+fn patch_value(
+    target: &mut serde_yaml::Value,
+    original: Option<&serde_yaml::Value>,
+    updated: &serde_yaml::Value,
+) {
+    match (target, original, updated) {
+        (
+            serde_yaml::Value::Mapping(target),
+            Some(serde_yaml::Value::Mapping(original)),
+            serde_yaml::Value::Mapping(updated),
+        ) => {
+            for (key, value) in updated {
+                let original = original.get(key);
+                if original != Some(value) {
+                    let entry = target.entry(key.clone()).or_insert(serde_yaml::Value::Null);
+                    patch_value(entry, original, value);
+                }
+            }
+        }
+        (target, _, updated) => *target = updated.clone(),
+    }
+}
+
+/// Replaces the top level `key` block of the file, keeping the rest of the
+/// lines, comments included, as the user wrote them
+fn replace_top_level_key(content: &str, key: &str, block: &str) -> String {
+    let lines = content.lines().collect::<Vec<_>>();
+    let is_key = |line: &str| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(':'))
+    };
+
+    let Some(start) = lines.iter().position(|line| is_key(line)) else {
+        let mut content = content.to_owned();
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(block);
+
+        return content;
+    };
+
+    let mut end = lines[start + 1..]
+        .iter()
+        .position(|line| {
+            !line.is_empty() && !line.starts_with([' ', '\t', '#']) && !line.starts_with("- ")
+        })
+        .map_or(lines.len(), |offset| start + 1 + offset);
+    // blank lines and comments before the next key belong to it
+    while end > start + 1 && (lines[end - 1].trim().is_empty() || lines[end - 1].starts_with('#')) {
+        end -= 1;
+    }
+
+    let mut updated = lines[..start].join("\n");
+    if start > 0 {
+        updated.push('\n');
+    }
+    updated.push_str(block);
+    if end < lines.len() {
+        updated.push_str(&lines[end..].join("\n"));
+        updated.push('\n');
+    }
+
+    updated
+}
+
+/// Writes only the settings that differ between `original` and `updated`,
+/// the rest of the config file is left as the user wrote it
+pub async fn update_config(original: Config, updated: Config) -> anyhow::Result<()> {
+    let original = serde_yaml::to_value(original)?;
+    let updated = serde_yaml::to_value(updated)?;
+    let (serde_yaml::Value::Mapping(original), serde_yaml::Value::Mapping(updated)) =
+        (original, updated)
+    else {
+        return Ok(());
+    };
+
+    let mut content = tokio::fs::read_to_string(config_file_path())
+        .await
+        .unwrap_or_default();
+    let document = serde_yaml::from_str::<serde_yaml::Value>(&content)?;
+
+    for (key, value) in &updated {
+        let original = original.get(key);
+        if original == Some(value) {
+            continue;
+        }
+        let Some(name) = key.as_str() else {
+            continue;
+        };
+
+        let mut patched = document
+            .get(name)
+            .cloned()
+            .unwrap_or(serde_yaml::Value::Null);
+        patch_value(&mut patched, original, value);
+
+        let mut block = serde_yaml::Mapping::new();
+        block.insert(key.clone(), patched);
+        content = replace_top_level_key(&content, name, &serde_yaml::to_string(&block)?);
+    }
+
+    write_config(content).await?;
+
+    Ok(())
+}
+
+pub async fn export_theme(appearance: Appearance, path: String) -> anyhow::Result<()> {
+    let path = expand_home(&path);
+
+    if let Some(parent) = Path::new(&path).parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    tokio::fs::write(path, serde_yaml::to_string(&appearance)?).await?;
+
+    Ok(())
+}
+
+pub async fn import_theme(path: String) -> anyhow::Result<Appearance> {
+    let content = tokio::fs::read_to_string(expand_home(&path)).await?;
+
+    Ok(serde_yaml::from_str(&content)?)
+}
+
+pub fn read_config() -> Result<Config, serde_yaml::Error> {
+    let config_file = File::open(config_file_path());
 
     if let Ok(config_file) = config_file {
         log::info!("Reading config file");
@@ -439,8 +2037,7 @@ pub fn subscription() -> Subscription<Message> {
     Subscription::run_with_id(
         id,
         channel(100, |mut output| async move {
-            let home_dir = env::var("HOME").expect("Could not get HOME environment variable");
-            let file_path = format!("{}{}", home_dir, CONFIG_PATH.replace('~', ""));
+            let file_path = config_file_path();
 
             loop {
                 let inotify = Inotify::init().expect("Failed to initialize inotify");
@@ -455,6 +2052,7 @@ pub fn subscription() -> Subscription<Message> {
                             WatchMask::MODIFY
                                 .union(WatchMask::CLOSE_WRITE)
                                 .union(WatchMask::DELETE)
+                                .union(WatchMask::DELETE_SELF)
                                 .union(WatchMask::MOVE_SELF),
                         )
                         .expect("Failed to add file watch");
@@ -519,6 +2117,32 @@ pub fn subscription() -> Subscription<Message> {
 
                             break;
                         }
+                        Some(Ok(inotify::Event {
+                            mask: EventMask::DELETE_SELF | EventMask::IGNORED,
+                            ..
+                        })) => {
+                            // editors and write_config replace the file, the watch
+                            // has to be set again on the new one
+                            sleep(Duration::from_millis(500)).await;
+
+                            if path.exists() {
+                                log::info!("Config file replaced");
+
+                                let new_config = read_config();
+                                if let Ok(new_config) = new_config {
+                                    let _ = output
+                                        .send(Message::ConfigChanged(Box::new(new_config)))
+                                        .await;
+                                } else {
+                                    log::warn!("Failed to read config file: {:?}", new_config);
+                                }
+                            } else {
+                                log::info!("Config file deleted");
+                                let _ = output.send(Message::ConfigChanged(Box::default())).await;
+                            }
+
+                            break;
+                        }
                         Some(Ok(inotify::Event {
                             mask: EventMask::DELETE,
                             ..
@@ -537,3 +2161,78 @@ pub fn subscription() -> Subscription<Message> {
         }),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_only_the_changed_key() {
+        let content = "# my bar\nposition: Top\nmodules:\n  # left side\n  left:\n    - Workspaces\n\n# the clock\nclock:\n  format: \"%R\"\n";
+        let updated = replace_top_level_key(content, "modules", "modules:\n  left:\n  - Clock\n");
+
+        assert_eq!(
+            updated,
+            "# my bar\nposition: Top\nmodules:\n  left:\n  - Clock\n\n# the clock\nclock:\n  format: \"%R\"\n"
+        );
+        assert_eq!(
+            replace_top_level_key("position: Top", "modules", "modules: {}\n"),
+            "position: Top\nmodules: {}\n"
+        );
+    }
+
+    #[test]
+    fn virtual_machines_interval_must_be_positive() {
+        assert!(serde_yaml::from_str::<VirtualMachinesModuleConfig>("interval: 0").is_err());
+        assert_eq!(
+            serde_yaml::from_str::<VirtualMachinesModuleConfig>("interval: 10")
+                .unwrap()
+                .interval,
+            10
+        );
+    }
+
+    #[test]
+    fn print_queue_interval_must_be_positive() {
+        assert!(serde_yaml::from_str::<PrintQueueModuleConfig>("interval: 0").is_err());
+        assert_eq!(
+            serde_yaml::from_str::<PrintQueueModuleConfig>("interval: 10")
+                .unwrap()
+                .interval,
+            10
+        );
+    }
+
+    #[test]
+    fn zero_intervals_are_rejected() {
+        assert!(serde_yaml::from_str::<SettingsModuleConfig>("publicIpInterval: 0").is_err());
+        assert!(serde_yaml::from_str::<BreakReminderModuleConfig>("interval: 0").is_err());
+        assert!(serde_yaml::from_str::<PingModuleConfig>("interval: 0").is_err());
+        assert!(serde_yaml::from_str::<BackupModuleConfig>("jobs: []\ninterval: 0").is_err());
+        assert!(serde_yaml::from_str::<WeatherModuleConfig>("interval: 0").is_err());
+        assert!(serde_yaml::from_str::<UpdatesModuleConfig>(
+            "checkCmd: a\nupdateCmd: b\ninterval: 0"
+        )
+        .is_err());
+        assert!(serde_yaml::from_str::<CalendarSyncConfig>("backend: Vdir\ninterval: 0").is_err());
+        assert_eq!(
+            serde_yaml::from_str::<SettingsModuleConfig>("publicIpInterval: 300")
+                .unwrap()
+                .public_ip_interval,
+            Some(300)
+        );
+    }
+
+    #[test]
+    fn patch_keeps_the_unchanged_settings_out() {
+        let mut target = serde_yaml::from_str("format: \"%R\"").unwrap();
+        let original = serde_yaml::from_str("format: \"%R\"\nshowSeconds: false").unwrap();
+        let updated = serde_yaml::from_str("format: \"%H\"\nshowSeconds: false").unwrap();
+        patch_value(&mut target, Some(&original), &updated);
+
+        assert_eq!(
+            target,
+            serde_yaml::from_str::<serde_yaml::Value>("format: \"%H\"").unwrap()
+        );
+    }
+}
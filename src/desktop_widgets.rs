@@ -0,0 +1,86 @@
+use crate::config::{DesktopWidgetConfig, WidgetAnchor};
+use iced::{
+    platform_specific::shell::commands::layer_surface::{
+        destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    window::Id,
+    Limits, Task,
+};
+
+/// A module drawn on the background layer, below the windows
+#[derive(Debug, Clone)]
+pub struct DesktopWidget {
+    pub id: Id,
+    pub config: DesktopWidgetConfig,
+}
+
+impl DesktopWidget {
+    fn new<Message: 'static>(config: DesktopWidgetConfig) -> (Self, Task<Message>) {
+        let id = Id::unique();
+        let anchor = match config.anchor {
+            WidgetAnchor::TopLeft => Anchor::TOP | Anchor::LEFT,
+            WidgetAnchor::Top => Anchor::TOP,
+            WidgetAnchor::TopRight => Anchor::TOP | Anchor::RIGHT,
+            WidgetAnchor::Left => Anchor::LEFT,
+            WidgetAnchor::Center => Anchor::empty(),
+            WidgetAnchor::Right => Anchor::RIGHT,
+            WidgetAnchor::BottomLeft => Anchor::BOTTOM | Anchor::LEFT,
+            WidgetAnchor::Bottom => Anchor::BOTTOM,
+            WidgetAnchor::BottomRight => Anchor::BOTTOM | Anchor::RIGHT,
+        };
+        let (x, y) = config.margin;
+
+        (
+            Self { id, config },
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id,
+                // sized after the module view
+                size: None,
+                size_limits: Limits::NONE.min_width(1.).min_height(1.),
+                layer: Layer::Background,
+                pointer_interactivity: false,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                exclusive_zone: -1,
+                output: IcedOutput::Active,
+                anchor,
+                margin: IcedMargin {
+                    top: y,
+                    bottom: y,
+                    left: x,
+                    right: x,
+                },
+                ..Default::default()
+            }),
+        )
+    }
+}
+
+/// Recreates the widgets when their configuration changes
+pub fn sync_widgets<Message: 'static>(
+    widgets: &mut Vec<DesktopWidget>,
+    configs: &[DesktopWidgetConfig],
+) -> Task<Message> {
+    if widgets
+        .iter()
+        .map(|widget| &widget.config)
+        .eq(configs.iter())
+    {
+        return Task::none();
+    }
+
+    let mut tasks = widgets
+        .drain(..)
+        .map(|widget| destroy_layer_surface(widget.id))
+        .collect::<Vec<_>>();
+
+    for config in configs {
+        let (widget, task) = DesktopWidget::new(config.clone());
+        widgets.push(widget);
+        tasks.push(task);
+    }
+
+    Task::batch(tasks)
+}
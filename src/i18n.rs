@@ -0,0 +1,109 @@
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use log::{info, warn};
+use std::{env, sync::OnceLock};
+use unic_langid::LanguageIdentifier;
+
+const FALLBACK_LOCALE: &str = "en";
+
+const LOCALES: [(&str, &str); 2] = [
+    ("en", include_str!("../assets/i18n/en.ftl")),
+    ("it", include_str!("../assets/i18n/it.ftl")),
+];
+
+static BUNDLES: OnceLock<Vec<FluentBundle<FluentResource>>> = OnceLock::new();
+
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+fn bundle(locale: &str, source: &str) -> FluentBundle<FluentResource> {
+    let lang_id: LanguageIdentifier = locale.parse().expect("Invalid bundled locale");
+    let resource =
+        FluentResource::try_new(source.to_string()).expect("Invalid bundled translation");
+
+    let mut bundle = FluentBundle::new_concurrent(vec![lang_id]);
+    // unicode isolation marks are not rendered correctly by the bar fonts
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .expect("Duplicated bundled translation");
+
+    bundle
+}
+
+fn load(locale: Option<&str>) -> Vec<FluentBundle<FluentResource>> {
+    let requested = locale
+        .map(|locale| locale.to_string())
+        .or_else(system_locale)
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string());
+    // "it_IT.UTF-8" and "it-IT" are both reduced to the language "it"
+    let language = requested
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or(FALLBACK_LOCALE)
+        .to_lowercase();
+
+    let mut bundles = Vec::new();
+
+    match LOCALES.iter().find(|(locale, _)| *locale == language) {
+        Some((locale, source)) => {
+            info!("Using locale {}", locale);
+
+            if *locale != FALLBACK_LOCALE {
+                bundles.push(bundle(locale, source));
+            }
+        }
+        None => warn!(
+            "Locale {} not available, falling back to {}",
+            requested, FALLBACK_LOCALE
+        ),
+    }
+
+    bundles.push(bundle(FALLBACK_LOCALE, LOCALES[0].1));
+
+    bundles
+}
+
+pub fn init(locale: Option<&str>) {
+    BUNDLES.get_or_init(|| load(locale));
+}
+
+fn format(id: &str, args: Option<&FluentArgs>) -> String {
+    BUNDLES
+        .get_or_init(|| load(None))
+        .iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            let value = bundle.format_pattern(pattern, args, &mut errors);
+
+            if !errors.is_empty() {
+                warn!("Failed to format translation {}: {:?}", id, errors);
+            }
+
+            Some(value.to_string())
+        })
+        .unwrap_or_else(|| {
+            warn!("Missing translation {}", id);
+
+            id.to_string()
+        })
+}
+
+/// Translate a user-facing string
+pub fn tr(id: &str) -> String {
+    format(id, None)
+}
+
+/// Translate a user-facing string with the given arguments
+pub fn tr_args(id: &str, args: &[(&str, String)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+
+    format(id, Some(&fluent_args))
+}
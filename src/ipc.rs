@@ -0,0 +1,140 @@
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::{
+    any::TypeId,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+};
+
+const REPLACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Commands sent to the running instance, e.g. `ashell toggle-bar DP-1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcCommand {
+    // the optional output name restricts the command to the bar on that output
+    Show(Option<String>),
+    Hide(Option<String>),
+    ToggleBar(Option<String>),
+    // sent by a new instance started with --replace
+    Quit,
+}
+
+impl IpcCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let verb = words.next()?;
+        let output = words.next().map(str::to_owned);
+
+        if words.next().is_some() {
+            return None;
+        }
+
+        match verb {
+            "quit" if output.is_none() => Some(Self::Quit),
+            "show" => Some(Self::Show(output)),
+            "hide" => Some(Self::Hide(output)),
+            "toggle-bar" => Some(Self::ToggleBar(output)),
+            _ => None,
+        }
+    }
+}
+
+fn socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map_or_else(std::env::temp_dir, PathBuf::from)
+        .join("ashell.sock")
+}
+
+/// Sends the command line arguments to the running instance and returns its reply
+pub fn send(args: &[String]) -> anyhow::Result<String> {
+    let line = args.join(" ");
+    if IpcCommand::parse(&line).is_none() {
+        anyhow::bail!("unknown command: {}", line);
+    }
+
+    let mut stream = UnixStream::connect(socket_path())
+        .map_err(|err| anyhow::anyhow!("ashell is not running: {}", err))?;
+    stream.write_all(format!("{}\n", line).as_bytes())?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+
+    Ok(reply.trim().to_owned())
+}
+
+/// An instance is running while something answers on the command socket,
+/// a socket left by a crashed instance refuses the connection
+pub fn is_running() -> bool {
+    UnixStream::connect(socket_path()).is_ok()
+}
+
+/// Asks the running instance to quit and waits for it to release
+/// its surfaces and bus names
+pub fn replace() -> anyhow::Result<()> {
+    send(&["quit".to_owned()])?;
+
+    let deadline = Instant::now() + REPLACE_TIMEOUT;
+    while is_running() {
+        if Instant::now() > deadline {
+            anyhow::bail!("the running instance didn't quit");
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    Ok(())
+}
+
+pub fn subscription() -> Subscription<IpcCommand> {
+    let id = TypeId::of::<IpcCommand>();
+
+    Subscription::run_with_id(
+        id,
+        channel(10, |mut output| async move {
+            let path = socket_path();
+            // a socket left by a previous instance that didn't exit cleanly
+            let _ = std::fs::remove_file(&path);
+
+            match UnixListener::bind(&path) {
+                Ok(listener) => {
+                    info!("Listening for commands on {:?}", path);
+
+                    while let Ok((stream, _)) = listener.accept().await {
+                        let mut reader = BufReader::new(stream);
+                        let mut line = String::new();
+
+                        if reader.read_line(&mut line).await.is_err() {
+                            continue;
+                        }
+
+                        debug!("Received command: {}", line.trim());
+                        let reply = match IpcCommand::parse(&line) {
+                            Some(command) => {
+                                let _ = output.send(command).await;
+                                "ok\n"
+                            }
+                            None => "unknown command\n",
+                        };
+
+                        let _ = reader.into_inner().write_all(reply.as_bytes()).await;
+                    }
+                }
+                Err(err) => {
+                    warn!("Failed to bind the command socket {:?}: {}", path, err);
+                }
+            }
+
+            let _ = pending::<u8>().next().await;
+        }),
+    )
+}
@@ -0,0 +1,145 @@
+use crate::{
+    components::icons::{icon, Icons},
+    i18n::{tr, tr_args},
+    style::OutlineButtonStyle,
+};
+use iced::{
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    widget::{button, container, horizontal_space, row, text},
+    window::Id,
+    Alignment, Border, Element, Length, Task, Theme,
+};
+use std::time::{Duration, Instant};
+
+const WIDTH: u32 = 460;
+const HEIGHT: u32 = 56;
+// the toast stays a bit after the countdown is cancelled,
+// moving the mouse to reach its button already cancels it
+const CANCELLED_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+    Hide,
+    StayAwake,
+}
+
+pub enum Action {
+    None,
+    Lock,
+    Close,
+    StayAwake,
+}
+
+/// Toast counting down the seconds left before the idle lock
+pub struct LockCountdown {
+    pub id: Id,
+    // None once the user is back
+    deadline: Option<Instant>,
+    remaining: u64,
+}
+
+impl LockCountdown {
+    pub fn new<M: 'static>(warning: Duration) -> (Self, Task<M>) {
+        let id = Id::unique();
+
+        (
+            Self {
+                id,
+                deadline: Some(Instant::now() + warning),
+                remaining: warning.as_secs(),
+            },
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id,
+                size: Some((Some(WIDTH), Some(HEIGHT))),
+                layer: Layer::Overlay,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                output: IcedOutput::Active,
+                anchor: Anchor::TOP,
+                margin: IcedMargin {
+                    top: 64,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+    }
+
+    pub fn is_counting(&self) -> bool {
+        self.deadline.is_some()
+    }
+
+    /// The user went idle again before the toast was hidden
+    pub fn restart(&mut self, warning: Duration) {
+        self.deadline = Some(Instant::now() + warning);
+        self.remaining = warning.as_secs();
+    }
+
+    pub fn cancel(&mut self) -> Task<Message> {
+        self.deadline = None;
+
+        Task::perform(tokio::time::sleep(CANCELLED_TIMEOUT), |_| Message::Hide)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::Tick => match self.deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        Action::Lock
+                    } else {
+                        self.remaining = remaining.as_secs_f32().ceil() as u64;
+                        Action::None
+                    }
+                }
+                None => Action::None,
+            },
+            Message::Hide if !self.is_counting() => Action::Close,
+            Message::Hide => Action::None,
+            Message::StayAwake => Action::StayAwake,
+        }
+    }
+
+    pub fn view(&self, stay_awake: u64) -> Element<Message> {
+        container(
+            row!(
+                icon(Icons::Lock).size(22),
+                text(if self.is_counting() {
+                    tr_args("locking-in", &[("seconds", self.remaining.to_string())])
+                } else {
+                    tr("lock-cancelled")
+                }),
+                horizontal_space(),
+                button(text(tr_args(
+                    "stay-awake",
+                    &[("minutes", stay_awake.to_string())]
+                )))
+                .padding([4, 12])
+                .style(OutlineButtonStyle.into_style())
+                .on_press(Message::StayAwake)
+            )
+            .spacing(16)
+            .align_y(Alignment::Center),
+        )
+        .padding([8, 16])
+        .width(Length::Fill)
+        .center_y(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 28.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
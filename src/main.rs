@@ -11,12 +11,22 @@ mod app;
 mod centerbox;
 mod components;
 mod config;
+mod desktop_widgets;
+mod i18n;
+mod ipc;
+mod lock_countdown;
 mod menu;
+mod metrics;
 mod modules;
+mod onboarding;
+mod osd;
 mod outputs;
 mod password_dialog;
+mod pinned_menu;
+mod polkit_dialog;
 mod position_button;
 mod services;
+mod status_export;
 mod style;
 mod utils;
 
@@ -31,12 +41,41 @@ fn get_log_spec(log_level: &str) -> LogSpecification {
 
 #[tokio::main]
 async fn main() -> iced::Result {
+    // with arguments ashell only forwards a command to the running instance
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let replace = args.iter().map(String::as_str).eq(["--replace"]);
+    if !args.is_empty() && !replace {
+        match ipc::send(&args) {
+            Ok(reply) => {
+                println!("{}", reply);
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if ipc::is_running() {
+        if !replace {
+            eprintln!("ashell is already running, start it with --replace to take over");
+            std::process::exit(1);
+        }
+
+        if let Err(err) = ipc::replace() {
+            eprintln!("Failed to replace the running instance: {}", err);
+            std::process::exit(1);
+        }
+    }
+
     let logger = Logger::with(
         LogSpecBuilder::new()
             .default(log::LevelFilter::Info)
             .build(),
     )
     .log_to_file(FileSpec::default().directory("/tmp/ashell"))
+    .format_for_files(metrics::counting_format)
     .duplicate_to_stdout(flexi_logger::Duplicate::All)
     .rotate(
         Criterion::Age(Age::Day),
@@ -49,6 +88,7 @@ async fn main() -> iced::Result {
         logger
     };
     let logger = logger.start().unwrap();
+    metrics::init();
     panic::set_hook(Box::new(|info| {
         let b = Backtrace::capture();
         error!("Panic: {} \n {}", info, b);
@@ -59,11 +99,18 @@ async fn main() -> iced::Result {
     });
 
     logger.set_new_spec(get_log_spec(&config.log_level));
+    let _ = logger
+        .adapt_duplication_to_stdout(status_export::stdout_logs(config.status_export.as_ref()));
+
+    let fonts = style::init_fonts(&config.fonts);
+    i18n::init(config.locale.as_deref());
 
     iced::daemon(App::title, App::update, App::view)
         .subscription(App::subscription)
         .theme(App::theme)
         .style(App::style)
+        .scale_factor(App::scale_factor)
         .font(Cow::from(ICON_FONT))
+        .default_font(fonts.text)
         .run_with(App::new((logger, config)))
 }
@@ -1,6 +1,10 @@
 use crate::app::{self};
-use crate::config::Position;
+use crate::components::animation::Animation;
+use crate::config::{Config, MenuAnimation, MenuConfig, Position};
+use crate::i18n::tr;
 use crate::position_button::ButtonUIRef;
+use crate::services::global_shortcuts::{GlobalShortcuts, Shortcut};
+use crate::utils::launcher::is_niri;
 use iced::alignment::{Horizontal, Vertical};
 use iced::platform_specific::shell::commands::layer_surface::{
     set_keyboard_interactivity, set_layer, KeyboardInteractivity, Layer,
@@ -8,8 +12,57 @@ use iced::platform_specific::shell::commands::layer_surface::{
 use iced::widget::container::Style;
 use iced::widget::mouse_area;
 use iced::window::Id;
-use iced::{self, widget::container, Element, Task, Theme};
-use iced::{Border, Length, Padding};
+use iced::{self, widget::container, Element, Subscription, Task, Theme};
+use iced::{Border, Length, Padding, Point};
+use log::warn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::process;
+
+const MENU_ANIMATION: Duration = Duration::from_millis(150);
+// distance travelled by a sliding menu
+const SLIDE_DISTANCE: f32 = 24.;
+/// Namespace of the menu surfaces, the target of the compositor layer rules
+pub const MENU_NAMESPACE: &str = "ashell-menu";
+
+static ANIMATED: AtomicBool = AtomicBool::new(false);
+
+/// Menus wait for their closing animation before leaving the overlay layer
+pub fn set_animated(animation: MenuAnimation) {
+    ANIMATED.store(animation != MenuAnimation::None, Ordering::Relaxed);
+}
+
+fn is_animated() -> bool {
+    ANIMATED.load(Ordering::Relaxed)
+}
+
+/// Adds or removes the Hyprland layer rules blurring the desktop behind the menus,
+/// the transparent part of the surfaces around the menu is left alone
+pub async fn set_blur(enabled: bool) {
+    if is_niri() {
+        return;
+    }
+
+    let rules = if enabled {
+        vec![
+            format!("blur,{}", MENU_NAMESPACE),
+            format!("ignorezero,{}", MENU_NAMESPACE),
+        ]
+    } else {
+        vec![format!("unset,{}", MENU_NAMESPACE)]
+    };
+
+    for rule in rules {
+        let res = process::Command::new("hyprctl")
+            .args(["keyword", "layerrule", &rule])
+            .output()
+            .await;
+
+        if let Err(err) = res {
+            warn!("Failed to set the layer rule {}: {}", rule, err);
+        }
+    }
+}
 
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum MenuType {
@@ -17,12 +70,57 @@ pub enum MenuType {
     Settings,
     Tray(String),
     MediaPlayer,
+    VirtualMachines,
+    PrintQueue,
+    Backup,
+    RemovableMedia,
+    SystemInfo,
+    Calendar,
+    EmojiPicker,
+    Ping,
+    Firewall,
+    KdeConnect,
+    Privacy,
+    ScreenCapture,
+    // sections of the settings menu opened by the standalone indicators
+    Network,
+    Bluetooth,
+    Audio,
+    Weather,
+    Notifications,
+}
+
+impl MenuType {
+    /// Menus that can be detached from the bar and kept open as widgets
+    pub fn is_pinnable(&self) -> bool {
+        matches!(
+            self,
+            MenuType::MediaPlayer
+                | MenuType::SystemInfo
+                | MenuType::Calendar
+                | MenuType::VirtualMachines
+                | MenuType::PrintQueue
+                | MenuType::Ping
+                | MenuType::KdeConnect
+                | MenuType::Backup
+                | MenuType::RemovableMedia
+                | MenuType::Weather
+        )
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Menu {
     pub id: Id,
     pub menu_info: Option<(MenuType, ButtonUIRef)>,
+    // menu still drawn until its closing animation ends
+    closing: Option<(MenuType, ButtonUIRef)>,
+    // 1 when the menu is open, 0 when closed
+    transition: Animation,
+    // last button that opened each menu, reused by the global shortcuts
+    anchors: Vec<(MenuType, ButtonUIRef)>,
+    // when a menu was last opened on this surface
+    pub last_opened: Option<Instant>,
 }
 
 impl Menu {
@@ -30,15 +128,57 @@ impl Menu {
         Self {
             id,
             menu_info: None,
+            closing: None,
+            transition: Animation::new(0., MENU_ANIMATION),
+            anchors: Vec::new(),
+            last_opened: None,
         }
     }
 
+    fn remember_anchor(&mut self, menu_type: &MenuType, button_ui_ref: ButtonUIRef) {
+        match self
+            .anchors
+            .iter_mut()
+            .find(|(anchor_type, _)| anchor_type == menu_type)
+        {
+            Some((_, anchor)) => *anchor = button_ui_ref,
+            None => self.anchors.push((menu_type.clone(), button_ui_ref)),
+        }
+    }
+
+    /// Where to open a menu without a button press: under the button that
+    /// opened it last time, otherwise at the right edge of the output
+    /// if any menu has been opened from this bar
+    pub fn anchor(&self, menu_type: &MenuType, exact: bool) -> Option<ButtonUIRef> {
+        self.anchors
+            .iter()
+            .find(|(anchor_type, _)| anchor_type == menu_type)
+            .map(|(_, anchor)| *anchor)
+            .or_else(|| {
+                self.anchors
+                    .first()
+                    .filter(|_| !exact)
+                    .map(|(_, anchor)| ButtonUIRef {
+                        position: Point::new(anchor.viewport.0, 0.),
+                        viewport: anchor.viewport,
+                    })
+            })
+    }
+
     pub fn open<Message: 'static>(
         &mut self,
         menu_type: MenuType,
         button_ui_ref: ButtonUIRef,
     ) -> Task<Message> {
+        self.remember_anchor(&menu_type, button_ui_ref);
+        self.last_opened = Some(Instant::now());
         self.menu_info.replace((menu_type, button_ui_ref));
+        self.closing = None;
+        if is_animated() {
+            self.transition.go_to(1., Instant::now());
+        } else {
+            self.transition = Animation::new(1., MENU_ANIMATION);
+        }
 
         Task::batch(vec![
             set_layer(self.id, Layer::Overlay),
@@ -48,7 +188,15 @@ impl Menu {
 
     pub fn close<Message: 'static>(&mut self) -> Task<Message> {
         if self.menu_info.is_some() {
+            if is_animated() {
+                self.closing = self.menu_info.take();
+                self.transition.go_to(0., Instant::now());
+
+                return set_keyboard_interactivity(self.id, KeyboardInteractivity::None);
+            }
+
             self.menu_info.take();
+            self.transition = Animation::new(0., MENU_ANIMATION);
 
             Task::batch(vec![
                 set_layer(self.id, Layer::Background),
@@ -64,6 +212,7 @@ impl Menu {
         menu_type: MenuType,
         button_ui_ref: ButtonUIRef,
     ) -> Task<Message> {
+        self.remember_anchor(&menu_type, button_ui_ref);
         match self.menu_info.as_mut() {
             None => self.open(menu_type, button_ui_ref),
             Some((current_type, _)) if *current_type == menu_type => self.close(),
@@ -87,6 +236,30 @@ impl Menu {
         }
     }
 
+    /// Open menu or the one that is still closing
+    pub fn shown(&self) -> Option<&(MenuType, ButtonUIRef)> {
+        self.menu_info.as_ref().or(self.closing.as_ref())
+    }
+
+    pub fn transition(&self, now: Instant) -> f32 {
+        self.transition.value(now)
+    }
+
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.transition.is_running(now) || self.closing.is_some()
+    }
+
+    /// Moves the surface back to the background layer once the menu has faded out
+    pub fn animate<Message: 'static>(&mut self, now: Instant) -> Task<Message> {
+        if self.closing.is_some() && !self.transition.is_running(now) {
+            self.closing.take();
+
+            set_layer(self.id, Layer::Background)
+        } else {
+            Task::none()
+        }
+    }
+
     pub fn request_keyboard<Message: 'static>(&self) -> Task<Message> {
         set_keyboard_interactivity(self.id, KeyboardInteractivity::OnDemand)
     }
@@ -96,6 +269,28 @@ impl Menu {
     }
 }
 
+/// Opens the menus from the keyboard on any compositor implementing
+/// the GlobalShortcuts portal
+pub fn global_shortcuts() -> Subscription<MenuType> {
+    let shortcut = |id, description, trigger: &str| Shortcut {
+        id,
+        description: tr(description),
+        preferred_trigger: Some(trigger.to_owned()),
+    };
+
+    GlobalShortcuts::subscribe(
+        "ashell_menus",
+        vec![
+            shortcut("settings", "shortcut-settings", "LOGO+s"),
+            shortcut("notifications", "shortcut-notifications", "LOGO+n"),
+        ],
+    )
+    .map(|id| match id {
+        "notifications" => MenuType::Notifications,
+        _ => MenuType::Settings,
+    })
+}
+
 pub enum MenuSize {
     Normal,
     Large,
@@ -108,27 +303,99 @@ impl MenuSize {
             MenuSize::Large => 350.,
         }
     }
+
+    pub fn width(&self, config: &MenuConfig) -> f32 {
+        config
+            .max_width
+            .map_or(self.size(), |max_width| max_width.min(self.size()))
+    }
+}
+
+// minimum distance between a menu and the edges of the output
+const MENU_MARGIN: f32 = 8.;
+
+/// Horizontal alignment and padding that keep the menu inside the output:
+/// centered under the button when there is enough room, otherwise clamped
+/// to the left edge or flipped to grow from the right edge
+fn menu_placement(button_ui_ref: ButtonUIRef, width: f32) -> (Horizontal, Padding) {
+    let x = button_ui_ref.position.x;
+    let viewport = button_ui_ref.viewport.0;
+
+    if x - width / 2. < MENU_MARGIN {
+        (Horizontal::Left, Padding::new(0.).left(MENU_MARGIN))
+    } else if x + width / 2. > viewport - MENU_MARGIN {
+        (Horizontal::Right, Padding::new(0.).right(MENU_MARGIN))
+    } else {
+        // a window centered on the button, large enough for the menu
+        let half = x.min(viewport - x);
+
+        (
+            Horizontal::Center,
+            Padding::new(0.).left(x - half).right(viewport - x - half),
+        )
+    }
 }
 
-pub fn menu_wrapper(
+pub fn menu_wrapper<'a>(
     id: Id,
-    content: Element<app::Message>,
+    content: Element<'a, app::Message>,
     menu_size: MenuSize,
     button_ui_ref: ButtonUIRef,
-    bar_position: Position,
-) -> Element<app::Message> {
+    config: &Config,
+    transition: f32,
+) -> Element<'a, app::Message> {
+    let width = menu_size
+        .width(&config.menu)
+        .min(button_ui_ref.viewport.0 - MENU_MARGIN * 2.);
+    let (align_x, mut padding) = menu_placement(button_ui_ref, width);
+
+    let mut max_height = config.menu.max_height.unwrap_or(f32::INFINITY);
+    match config.menu.animation {
+        MenuAnimation::None | MenuAnimation::Fade => {}
+        MenuAnimation::Slide => {
+            let offset = (1. - transition) * SLIDE_DISTANCE;
+            padding = match config.position {
+                Position::Top => padding.top(offset),
+                Position::Bottom => padding.bottom(offset),
+            };
+        }
+        MenuAnimation::Scale => {
+            // the menu unfolds from the bar
+            max_height = max_height.min(button_ui_ref.viewport.1) * transition;
+        }
+    }
+    let opacity = if config.appearance.reduce_transparency {
+        1.
+    } else {
+        config.menu.opacity
+    };
+
     mouse_area(
         container(
             mouse_area(
                 container(content)
                     .height(Length::Shrink)
                     .width(Length::Shrink)
-                    .max_width(menu_size.size())
+                    .max_width(width)
+                    .max_height(max_height)
+                    .clip(true)
                     .padding(16)
-                    .style(|theme: &Theme| Style {
-                        background: Some(theme.palette().background.into()),
+                    .style(move |theme: &Theme| Style {
+                        background: Some(
+                            theme
+                                .palette()
+                                .background
+                                .scale_alpha(opacity * transition)
+                                .into(),
+                        ),
+                        text_color: Some(theme.palette().text.scale_alpha(transition)),
                         border: Border {
-                            color: theme.extended_palette().secondary.base.color,
+                            color: theme
+                                .extended_palette()
+                                .secondary
+                                .base
+                                .color
+                                .scale_alpha(transition),
                             width: 1.,
                             radius: 16.0.into(),
                         },
@@ -137,22 +404,63 @@ pub fn menu_wrapper(
             )
             .on_release(app::Message::None),
         )
-        .align_y(match bar_position {
+        .align_y(match config.position {
             Position::Top => Vertical::Top,
             Position::Bottom => Vertical::Bottom,
         })
-        .align_x(Horizontal::Left)
-        .padding({
-            let size = menu_size.size();
-
-            Padding::new(0.).left(f32::min(
-                f32::max(button_ui_ref.position.x - size / 2., 8.),
-                button_ui_ref.viewport.0 - size - 8.,
-            ))
-        })
+        .align_x(align_x)
+        .padding(padding)
         .width(Length::Fill)
         .height(Length::Fill),
     )
     .on_release(app::Message::CloseMenu(id))
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animated_menu_is_shown_until_closed() {
+        set_animated(MenuAnimation::Fade);
+
+        let mut menu = Menu::new(Id::unique());
+        let button_ui_ref = ButtonUIRef {
+            position: Point::new(100., 0.),
+            viewport: (1920., 1080.),
+        };
+        let _: Task<()> = menu.open(MenuType::Calendar, button_ui_ref);
+        let _: Task<()> = menu.close();
+
+        let now = Instant::now();
+        assert!(menu.menu_info.is_none());
+        assert_eq!(
+            menu.shown().map(|(menu_type, _)| menu_type),
+            Some(&MenuType::Calendar)
+        );
+        assert!(menu.is_animating(now));
+
+        let _: Task<()> = menu.animate(now + MENU_ANIMATION);
+        assert!(menu.shown().is_none());
+        assert!(!menu.is_animating(now + MENU_ANIMATION));
+    }
+
+    #[test]
+    fn shortcuts_reuse_the_last_button() {
+        let mut menu = Menu::new(Id::unique());
+        assert!(menu.anchor(&MenuType::Settings, false).is_none());
+
+        let button_ui_ref = ButtonUIRef {
+            position: Point::new(100., 0.),
+            viewport: (1920., 1080.),
+        };
+        let _: Task<()> = menu.toggle(MenuType::Calendar, button_ui_ref);
+
+        let anchor = menu.anchor(&MenuType::Calendar, true).unwrap();
+        assert_eq!(anchor.position, Point::new(100., 0.));
+        assert!(menu.anchor(&MenuType::Settings, true).is_none());
+        let anchor = menu.anchor(&MenuType::Settings, false).unwrap();
+        assert_eq!(anchor.position, Point::new(1920., 0.));
+    }
+}
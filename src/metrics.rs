@@ -0,0 +1,275 @@
+use flexi_logger::DeferredNow;
+use iced::{
+    futures::{stream::pending, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn, Level, Record};
+use std::{
+    any::TypeId,
+    fmt::Write as _,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+// a scraper that doesn't send its request or read the response in time is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+// only the request line is read, longer ones are cut
+const MAX_REQUEST_LINE: u64 = 8192;
+
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static MESSAGES: AtomicU64 = AtomicU64::new(0);
+static WARNINGS: AtomicU64 = AtomicU64::new(0);
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static SYSTEM: Mutex<Option<SystemSample>> = Mutex::new(None);
+
+/// Last sample of the system info module
+#[derive(Debug, Clone, Default)]
+pub struct SystemSample {
+    pub cpu_usage: u32,
+    pub memory_usage: u32,
+    pub temperature: Option<f32>,
+    pub sensors: Vec<(String, f32)>,
+}
+
+pub fn init() {
+    STARTED.get_or_init(Instant::now);
+}
+
+/// Counts the messages handled by the application
+pub fn record_message() {
+    MESSAGES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Kept up to date by the system info module
+pub fn set_system_sample(sample: SystemSample) {
+    if let Ok(mut current) = SYSTEM.lock() {
+        *current = Some(sample);
+    }
+}
+
+/// Format of the log file, counting the warnings and the errors on the way
+pub fn counting_format(
+    w: &mut dyn io::Write,
+    now: &mut DeferredNow,
+    record: &Record,
+) -> io::Result<()> {
+    match record.level() {
+        Level::Warn => {
+            WARNINGS.fetch_add(1, Ordering::Relaxed);
+        }
+        Level::Error => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+
+    flexi_logger::default_format(w, now, record)
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, String)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{}{} {}", name, labels, value);
+    }
+}
+
+/// Text exposition format read by Prometheus
+fn render(uptime: Duration, system: Option<&SystemSample>) -> String {
+    let mut out = String::new();
+
+    metric(
+        &mut out,
+        "ashell_uptime_seconds",
+        "gauge",
+        "Time since ashell started",
+        &[(String::new(), uptime.as_secs_f64().to_string())],
+    );
+    metric(
+        &mut out,
+        "ashell_messages_total",
+        "counter",
+        "Events handled by the bar",
+        &[(String::new(), MESSAGES.load(Ordering::Relaxed).to_string())],
+    );
+    metric(
+        &mut out,
+        "ashell_log_messages_total",
+        "counter",
+        "Warnings and errors logged",
+        &[
+            (
+                "{level=\"warn\"}".to_owned(),
+                WARNINGS.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "{level=\"error\"}".to_owned(),
+                ERRORS.load(Ordering::Relaxed).to_string(),
+            ),
+        ],
+    );
+
+    if let Some(system) = system {
+        metric(
+            &mut out,
+            "ashell_cpu_usage_percent",
+            "gauge",
+            "CPU usage",
+            &[(String::new(), system.cpu_usage.to_string())],
+        );
+        metric(
+            &mut out,
+            "ashell_memory_usage_percent",
+            "gauge",
+            "Memory usage",
+            &[(String::new(), system.memory_usage.to_string())],
+        );
+        if let Some(temperature) = system.temperature {
+            metric(
+                &mut out,
+                "ashell_temperature_celsius",
+                "gauge",
+                "Temperature of the configured sensor",
+                &[(String::new(), temperature.to_string())],
+            );
+        }
+        if !system.sensors.is_empty() {
+            metric(
+                &mut out,
+                "ashell_sensor_temperature_celsius",
+                "gauge",
+                "Temperature of the labeled sensors",
+                &system
+                    .sensors
+                    .iter()
+                    .map(|(label, temperature)| {
+                        (
+                            format!("{{sensor=\"{}\"}}", escape_label(label)),
+                            temperature.to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    out
+}
+
+async fn serve(stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream).take(MAX_REQUEST_LINE);
+    let mut request = String::new();
+
+    if timeout(REQUEST_TIMEOUT, reader.read_line(&mut request))
+        .await
+        .is_err()
+    {
+        return Ok(());
+    }
+    let path = request.split_whitespace().nth(1).unwrap_or_default();
+
+    let response = if path == "/metrics" {
+        let uptime = STARTED.get().map(Instant::elapsed).unwrap_or_default();
+        let system = SYSTEM.lock().ok().and_then(|system| system.clone());
+        let body = render(uptime, system.as_ref());
+
+        format!(
+            "HTTP/1.1 200 OK\r\n\
+            Content-Type: text/plain; version=0.0.4\r\n\
+            Content-Length: {}\r\n\
+            Connection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned()
+    };
+
+    let mut stream = reader.into_inner().into_inner();
+    match timeout(REQUEST_TIMEOUT, stream.write_all(response.as_bytes())).await {
+        Ok(result) => result,
+        Err(_) => Ok(()),
+    }
+}
+
+/// Serves the metrics on localhost only
+pub fn subscription(port: u16) -> Subscription<()> {
+    let id = TypeId::of::<SystemSample>();
+
+    Subscription::run_with_id(
+        (id, port),
+        channel(1, move |_| async move {
+            let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+            match TcpListener::bind(address).await {
+                Ok(listener) => {
+                    info!("Serving the metrics on http://{}/metrics", address);
+
+                    // a slow scraper doesn't hold back the others
+                    while let Ok((stream, _)) = listener.accept().await {
+                        tokio::spawn(async move {
+                            if let Err(err) = serve(stream).await {
+                                debug!("Failed to serve the metrics: {}", err);
+                            }
+                        });
+                    }
+                }
+                Err(err) => {
+                    warn!("Failed to listen on {}: {}", address, err);
+                }
+            }
+
+            let _ = pending::<u8>().next().await;
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposition_format() {
+        let system = SystemSample {
+            cpu_usage: 12,
+            memory_usage: 40,
+            temperature: None,
+            sensors: vec![("GPU \"edge\"".to_owned(), 55.5)],
+        };
+        let out = render(Duration::from_secs(90), Some(&system));
+
+        assert!(out.contains("# TYPE ashell_uptime_seconds gauge\nashell_uptime_seconds 90\n"));
+        assert!(out.contains("ashell_log_messages_total{level=\"error\"} "));
+        assert!(out.contains("ashell_cpu_usage_percent 12\n"));
+        assert!(!out.contains("ashell_temperature_celsius "));
+        assert!(
+            out.contains("ashell_sensor_temperature_celsius{sensor=\"GPU \\\"edge\\\"\"} 55.5\n")
+        );
+    }
+
+    #[test]
+    fn no_system_sample() {
+        let out = render(Duration::ZERO, None);
+
+        assert!(out.contains("ashell_messages_total "));
+        assert!(!out.contains("ashell_cpu_usage_percent"));
+    }
+}
@@ -0,0 +1,286 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::{BackupJobConfig, BackupModuleConfig},
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    style::SettingsButtonStyle,
+    utils::{format_duration, secrets::resolve_secrets},
+};
+use chrono::{DateTime, Local};
+use iced::{
+    stream::channel,
+    widget::{button, column, container, row, text, Column},
+    Alignment, Element, Length, Subscription, Task, Theme,
+};
+use log::error;
+use std::{
+    any::TypeId,
+    collections::{HashMap, HashSet},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{process, time::sleep};
+
+async fn get_last_run(
+    last_run_cmd: &str,
+    env: &HashMap<String, String>,
+) -> Option<DateTime<Local>> {
+    let last_run_cmd = process::Command::new("bash")
+        .arg("-c")
+        .arg(last_run_cmd)
+        .envs(env)
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match last_run_cmd {
+        Ok(last_run_cmd) => {
+            let cmd_output = String::from_utf8_lossy(&last_run_cmd.stdout);
+            let value = cmd_output.trim();
+
+            // systemctl show --timestamp=unix prints the timestamp as `@<seconds>`
+            if let Ok(timestamp) = value.trim_start_matches('@').parse::<i64>() {
+                DateTime::from_timestamp(timestamp, 0).map(|date| date.with_timezone(&Local))
+            } else {
+                DateTime::parse_from_rfc3339(value)
+                    .ok()
+                    .map(|date| date.with_timezone(&Local))
+            }
+        }
+        Err(e) => {
+            error!("Error: {:?}", e);
+            None
+        }
+    }
+}
+
+async fn get_last_runs(jobs: &[BackupJobConfig]) -> Vec<Option<DateTime<Local>>> {
+    let mut last_runs = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let env = resolve_secrets(&job.env).await;
+        last_runs.push(get_last_run(&job.last_run_cmd, &env).await);
+    }
+
+    last_runs
+}
+
+async fn run_backup(run_cmd: &str, env: &HashMap<String, String>) {
+    let res = process::Command::new("bash")
+        .arg("-c")
+        .arg(run_cmd)
+        .envs(env)
+        .output()
+        .await;
+
+    if let Err(e) = res {
+        error!("Failed to execute backup command {}: {:?}", run_cmd, e);
+    }
+}
+
+fn is_overdue(last_run: Option<&DateTime<Local>>, job: &BackupJobConfig) -> bool {
+    last_run.map_or(true, |last_run| {
+        Local::now().signed_duration_since(last_run).num_hours() >= job.max_age as i64
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    StatusUpdated(Vec<Option<DateTime<Local>>>),
+    RunNow(usize),
+    RunFinished(usize, Option<DateTime<Local>>),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Backup {
+    last_runs: Vec<Option<DateTime<Local>>>,
+    running: HashSet<usize>,
+}
+
+impl Backup {
+    pub fn update(&mut self, message: Message, config: &BackupModuleConfig) -> Task<app::Message> {
+        match message {
+            Message::StatusUpdated(last_runs) => {
+                self.last_runs = last_runs;
+
+                Task::none()
+            }
+            Message::RunNow(index) => match config.jobs.get(index) {
+                Some(job) if !self.running.contains(&index) => {
+                    self.running.insert(index);
+
+                    let run_cmd = job.run_cmd.clone();
+                    let last_run_cmd = job.last_run_cmd.clone();
+                    let env = job.env.clone();
+                    Task::perform(
+                        async move {
+                            let env = resolve_secrets(&env).await;
+                            run_backup(&run_cmd, &env).await;
+                            get_last_run(&last_run_cmd, &env).await
+                        },
+                        move |last_run| app::Message::Backup(Message::RunFinished(index, last_run)),
+                    )
+                }
+                _ => Task::none(),
+            },
+            Message::RunFinished(index, last_run) => {
+                self.running.remove(&index);
+                if let Some(current) = self.last_runs.get_mut(index) {
+                    *current = last_run;
+                }
+
+                Task::none()
+            }
+        }
+    }
+
+    pub fn menu_view(&self, config: &BackupModuleConfig) -> Element<Message> {
+        if config.jobs.is_empty() {
+            text(tr("no-backup-jobs")).into()
+        } else {
+            Column::with_children(
+                config
+                    .jobs
+                    .iter()
+                    .enumerate()
+                    .map(|(index, job)| {
+                        let last_run = self.last_runs.get(index).and_then(Option::as_ref);
+                        let overdue = self
+                            .last_runs
+                            .get(index)
+                            .is_some_and(|last_run| is_overdue(last_run.as_ref(), job));
+                        let running = self.running.contains(&index);
+
+                        let status = if running {
+                            tr("backup-running")
+                        } else {
+                            match last_run {
+                                Some(last_run) => {
+                                    let elapsed = Local::now()
+                                        .signed_duration_since(last_run)
+                                        .to_std()
+                                        .unwrap_or_default();
+
+                                    let status = tr_args(
+                                        "backup-last-run",
+                                        &[("elapsed", format_duration(&elapsed))],
+                                    );
+
+                                    if overdue {
+                                        format!("{} · {}", status, tr("backup-overdue"))
+                                    } else {
+                                        status
+                                    }
+                                }
+                                None => tr("backup-never-run"),
+                            }
+                        };
+
+                        row!(
+                            column!(
+                                text(job.name.clone()),
+                                container(text(status).size(10)).style(move |theme: &Theme| {
+                                    container::Style {
+                                        text_color: if overdue && !running {
+                                            Some(theme.extended_palette().danger.weak.color)
+                                        } else {
+                                            None
+                                        },
+                                        ..Default::default()
+                                    }
+                                })
+                            )
+                            .spacing(4)
+                            .width(Length::Fill),
+                            button(icon(if running { Icons::Refresh } else { Icons::Play }))
+                                .padding([4, 10])
+                                .on_press_maybe(if running {
+                                    None
+                                } else {
+                                    Some(Message::RunNow(index))
+                                })
+                                .style(SettingsButtonStyle.into_style()),
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(8)
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8)
+            .into()
+        }
+    }
+}
+
+impl Module for Backup {
+    type ViewData<'a> = &'a Option<BackupModuleConfig>;
+    type SubscriptionData<'a> = &'a BackupModuleConfig;
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        config.as_ref().map(|config| {
+            let overdue = config.jobs.iter().enumerate().any(|(index, job)| {
+                self.last_runs
+                    .get(index)
+                    .is_some_and(|last_run| is_overdue(last_run.as_ref(), job))
+            });
+
+            (
+                container(icon(if !self.running.is_empty() {
+                    Icons::Refresh
+                } else if overdue {
+                    Icons::BackupOverdue
+                } else {
+                    Icons::Backup
+                }))
+                .style(move |theme: &Theme| container::Style {
+                    text_color: if overdue {
+                        Some(theme.extended_palette().danger.weak.color)
+                    } else {
+                        None
+                    },
+                    ..Default::default()
+                })
+                .into(),
+                Some(OnModulePress::ToggleMenu(MenuType::Backup)),
+            )
+        })
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let id = TypeId::of::<Self>();
+        let jobs = config.jobs.clone();
+        let interval = config.interval;
+
+        Some(
+            Subscription::run_with_id(
+                format!(
+                    "{:?}-{}-{}",
+                    id,
+                    jobs.iter()
+                        .map(|job| job.last_run_cmd.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                    interval
+                ),
+                channel(10, move |mut output| async move {
+                    loop {
+                        let last_runs = get_last_runs(&jobs).await;
+
+                        let _ = output.try_send(Message::StatusUpdated(last_runs));
+
+                        sleep(Duration::from_secs(interval)).await;
+                    }
+                }),
+            )
+            .map(app::Message::Backup),
+        )
+    }
+}
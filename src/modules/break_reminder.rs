@@ -0,0 +1,315 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::BreakReminderModuleConfig,
+    i18n::{tr, tr_args},
+    services::idle_notify::IdleNotify,
+    style::{GhostButtonStyle, OutlineButtonStyle},
+};
+use hyprland::{
+    data::{Client, FullscreenMode},
+    shared::HyprDataActiveOptional,
+};
+use iced::{
+    platform_specific::shell::commands::layer_surface::{
+        destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    time::every,
+    widget::{button, column, container, horizontal_space, row, text},
+    window::Id,
+    Alignment, Border, Element, Length, Subscription, Task, Theme,
+};
+use std::time::Duration;
+
+// active time is counted in steps, the ring doesn't need more precision
+const TICK: Duration = Duration::from_secs(5);
+const TOAST_WIDTH: u32 = 520;
+const TOAST_HEIGHT: u32 = 64;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+    Fullscreen(bool),
+    Idle(bool),
+    TogglePause,
+    Countdown,
+    Snooze,
+    Skip,
+}
+
+/// Reminder surface, counting down the seconds of the break
+#[derive(Debug, Clone)]
+pub struct Reminder {
+    pub id: Id,
+    remaining: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct BreakReminder {
+    // time spent at the computer since the last break
+    active: Duration,
+    idle: bool,
+    paused: bool,
+    fullscreen: bool,
+    reminder: Option<Reminder>,
+}
+
+async fn is_fullscreen() -> bool {
+    Client::get_active_async()
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|client| client.fullscreen != FullscreenMode::None)
+}
+
+fn progress_icon(progress: f32) -> Icons {
+    match (progress.clamp(0., 1.) * 8.).floor() as u8 {
+        0 => Icons::CheckboxBlankCircleOutline,
+        1 => Icons::CircleSlice1,
+        2 => Icons::CircleSlice2,
+        3 => Icons::CircleSlice3,
+        4 => Icons::CircleSlice4,
+        5 => Icons::CircleSlice5,
+        6 => Icons::CircleSlice6,
+        7 => Icons::CircleSlice7,
+        _ => Icons::CircleSlice8,
+    }
+}
+
+impl BreakReminder {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &BreakReminderModuleConfig,
+    ) -> Task<Message> {
+        match message {
+            Message::Tick => {
+                if config.pause_in_fullscreen {
+                    Task::perform(is_fullscreen(), Message::Fullscreen)
+                } else {
+                    self.count_active(config)
+                }
+            }
+            Message::Fullscreen(fullscreen) => {
+                self.fullscreen = fullscreen;
+                self.count_active(config)
+            }
+            Message::Idle(idle) => {
+                self.idle = idle;
+                // away from the keyboard for as long as a break, that was the break
+                if idle {
+                    self.active = Duration::ZERO;
+                }
+                Task::none()
+            }
+            Message::TogglePause => {
+                self.paused = !self.paused;
+                Task::none()
+            }
+            Message::Countdown => match self.reminder.as_mut() {
+                Some(reminder) if reminder.remaining > 1 => {
+                    reminder.remaining -= 1;
+                    Task::none()
+                }
+                Some(_) => {
+                    self.active = Duration::ZERO;
+                    self.close_reminder()
+                }
+                None => Task::none(),
+            },
+            Message::Snooze => {
+                self.active = config
+                    .interval()
+                    .saturating_sub(Duration::from_secs(config.snooze * 60));
+                self.close_reminder()
+            }
+            Message::Skip => {
+                self.active = Duration::ZERO;
+                self.close_reminder()
+            }
+        }
+    }
+
+    fn count_active(&mut self, config: &BreakReminderModuleConfig) -> Task<Message> {
+        if self.idle || self.paused || self.fullscreen || self.reminder.is_some() {
+            return Task::none();
+        }
+
+        self.active += TICK;
+        if self.active >= config.interval() {
+            self.open_reminder(config)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn open_reminder(&mut self, config: &BreakReminderModuleConfig) -> Task<Message> {
+        let id = Id::unique();
+        self.reminder = Some(Reminder {
+            id,
+            remaining: config.break_duration.max(1),
+        });
+
+        get_layer_surface(if config.fullscreen {
+            SctkLayerSurfaceSettings {
+                id,
+                size: Some((None, None)),
+                layer: Layer::Overlay,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                exclusive_zone: -1,
+                output: IcedOutput::Active,
+                anchor: Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT,
+                ..Default::default()
+            }
+        } else {
+            SctkLayerSurfaceSettings {
+                id,
+                size: Some((Some(TOAST_WIDTH), Some(TOAST_HEIGHT))),
+                layer: Layer::Overlay,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                output: IcedOutput::Active,
+                anchor: Anchor::TOP,
+                margin: IcedMargin {
+                    top: 64,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        })
+    }
+
+    fn close_reminder(&mut self) -> Task<Message> {
+        match self.reminder.take() {
+            Some(reminder) => destroy_layer_surface(reminder.id),
+            None => Task::none(),
+        }
+    }
+
+    pub fn reminder_view(
+        &self,
+        id: Id,
+        config: &BreakReminderModuleConfig,
+    ) -> Option<Element<Message>> {
+        let reminder = self.reminder.as_ref().filter(|r| r.id == id)?;
+
+        let content = row!(
+            icon(Icons::EyeOutline).size(22),
+            column!(
+                text(tr("break-time")),
+                text(tr_args(
+                    "break-look-away",
+                    &[("seconds", reminder.remaining.to_string())]
+                ))
+                .size(12),
+            )
+            .spacing(2),
+            horizontal_space(),
+            button(text(tr_args(
+                "break-snooze",
+                &[("minutes", config.snooze.to_string())]
+            )))
+            .padding([4, 12])
+            .style(OutlineButtonStyle.into_style())
+            .on_press(Message::Snooze),
+            button(text(tr("break-skip")))
+                .padding([4, 12])
+                .style(GhostButtonStyle.into_style())
+                .on_press(Message::Skip),
+        )
+        .spacing(16)
+        .align_y(Alignment::Center);
+
+        Some(if config.fullscreen {
+            container(container(content).max_width(TOAST_WIDTH as f32))
+                .center(Length::Fill)
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.palette().background.scale_alpha(0.92).into()),
+                    ..Default::default()
+                })
+                .into()
+        } else {
+            container(content)
+                .padding([8, 16])
+                .width(Length::Fill)
+                .center_y(Length::Fill)
+                .style(|theme: &Theme| container::Style {
+                    background: Some(theme.palette().background.into()),
+                    border: Border {
+                        color: theme.extended_palette().secondary.base.color,
+                        width: 1.,
+                        radius: 32.0.into(),
+                    },
+                    ..Default::default()
+                })
+                .into()
+        })
+    }
+}
+
+impl Module for BreakReminder {
+    type ViewData<'a> = &'a BreakReminderModuleConfig;
+    type SubscriptionData<'a> = &'a BreakReminderModuleConfig;
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let icon_type = if self.paused || self.fullscreen {
+            Icons::TimerPauseOutline
+        } else {
+            progress_icon(self.active.as_secs_f32() / config.interval().as_secs_f32().max(1.))
+        };
+
+        Some((
+            icon(icon_type).into(),
+            Some(OnModulePress::Action(app::Message::BreakReminder(
+                Message::TogglePause,
+            ))),
+        ))
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        // the seat goes idle after as long as a break
+        let idle = IdleNotify::subscribe(Duration::from_secs(config.break_duration.max(1)))
+            .map(Message::Idle);
+
+        let active = if self.idle || self.paused || self.reminder.is_some() {
+            Subscription::none()
+        } else {
+            every(TICK).map(|_| Message::Tick)
+        };
+
+        let countdown = if self.reminder.is_some() {
+            every(Duration::from_secs(1)).map(|_| Message::Countdown)
+        } else {
+            Subscription::none()
+        };
+
+        Some(Subscription::batch(vec![idle, active, countdown]).map(app::Message::BreakReminder))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_icon_steps() {
+        assert!(matches!(
+            progress_icon(0.1),
+            Icons::CheckboxBlankCircleOutline
+        ));
+        assert!(matches!(progress_icon(0.5), Icons::CircleSlice4));
+        assert!(matches!(progress_icon(1.), Icons::CircleSlice8));
+        assert!(matches!(progress_icon(3.), Icons::CircleSlice8));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::config::expand_home;
+use chrono::{Datelike, NaiveDate};
+use log::warn;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarEvent {
+    pub name: String,
+    pub month: u32,
+    pub day: u32,
+    // None for the events that repeat every year
+    pub year: Option<i32>,
+}
+
+impl CalendarEvent {
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        self.month == date.month()
+            && self.day == date.day()
+            && self.year.map_or(true, |year| year == date.year())
+    }
+
+    pub fn next_occurrence(&self, from: NaiveDate) -> Option<NaiveDate> {
+        match self.year {
+            Some(year) => NaiveDate::from_ymd_opt(year, self.month, self.day),
+            // the loop handles the events on 29 February
+            None => (from.year()..=from.year() + 4)
+                .filter_map(|year| NaiveDate::from_ymd_opt(year, self.month, self.day))
+                .find(|date| *date >= from),
+        }
+        .filter(|date| *date >= from)
+    }
+}
+
+#[derive(Deserialize)]
+struct EventDef {
+    date: String,
+    name: String,
+}
+
+/// Parse `YYYY-MM-DD` or `MM-DD` for yearly events
+fn parse_date(value: &str) -> Option<(Option<i32>, u32, u32)> {
+    let parts = value.trim().split('-').collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [year, month, day] => Some((
+            Some(year.parse().ok()?),
+            month.parse().ok()?,
+            day.parse().ok()?,
+        )),
+        [month, day] => Some((None, month.parse().ok()?, day.parse().ok()?)),
+        _ => None,
+    }
+}
+
+fn parse_yaml(content: &str) -> anyhow::Result<Vec<CalendarEvent>> {
+    Ok(serde_yaml::from_str::<Vec<EventDef>>(content)?
+        .into_iter()
+        .filter_map(|event| {
+            let parsed = parse_date(&event.date);
+            if parsed.is_none() {
+                warn!("Invalid calendar event date: {}", event.date);
+            }
+
+            parsed.map(|(year, month, day)| CalendarEvent {
+                name: event.name,
+                month,
+                day,
+                year,
+            })
+        })
+        .collect())
+}
+
+fn parse_ics(content: &str) -> Vec<CalendarEvent> {
+    // unfold the lines continued with a leading space
+    let unfolded = content.replace("\r\n ", "").replace("\n ", "");
+
+    let mut events = Vec::new();
+    let mut date = None;
+    let mut name = None;
+    let mut yearly = false;
+
+    for line in unfolded.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        // drop the parameters, e.g. DTSTART;VALUE=DATE
+        let key = key.split(';').next().unwrap_or_default();
+
+        match key {
+            "BEGIN" if value == "VEVENT" => {
+                date = None;
+                name = None;
+                yearly = false;
+            }
+            "DTSTART" => {
+                date = value
+                    .get(0..8)
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok());
+            }
+            "SUMMARY" => name = Some(value.replace("\\,", ",").replace("\\;", ";")),
+            "RRULE" => yearly = value.contains("FREQ=YEARLY"),
+            "END" if value == "VEVENT" => {
+                if let (Some(date), Some(name)) = (date.take(), name.take()) {
+                    events.push(CalendarEvent {
+                        name,
+                        month: date.month(),
+                        day: date.day(),
+                        year: (!yearly).then_some(date.year()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+pub async fn load_events(files: &[String]) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+
+    for file in files {
+        let path = expand_home(file);
+
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) if path.ends_with(".ics") => events.extend(parse_ics(&content)),
+            Ok(content) => match parse_yaml(&content) {
+                Ok(parsed) => events.extend(parsed),
+                Err(err) => warn!("Failed to parse calendar file {}: {}", path, err),
+            },
+            Err(err) => warn!("Failed to read calendar file {}: {}", path, err),
+        }
+    }
+
+    events
+}
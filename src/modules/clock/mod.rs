@@ -0,0 +1,563 @@
+use crate::{
+    app,
+    components::{
+        animation::Animation,
+        icons::{icon, Icons},
+    },
+    config::{expand_home, CalendarBackend, ClockModuleConfig},
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    services::{
+        agenda::{set_query, AgendaEvent, AgendaQuery, AgendaService, AgendaSources},
+        ReadOnlyService, ServiceEvent,
+    },
+    style::{fonts, GhostButtonStyle, SettingsButtonStyle},
+    utils::state::{read_state, write_state},
+};
+
+use super::{Module, OnModulePress};
+use calendar::{load_events, CalendarEvent};
+use chrono::{DateTime, Datelike, Days, Local, Locale, Months, NaiveDate, NaiveTime, Timelike};
+use chrono_tz::Tz;
+use iced::{
+    alignment::Horizontal,
+    stream::channel,
+    time::every,
+    widget::{button, column, container, horizontal_rule, mouse_area, row, text, Column, Row},
+    Alignment, Border, Element, Length, Subscription, Theme,
+};
+use log::warn;
+use std::{
+    any::TypeId,
+    env,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+mod calendar;
+
+const FORMAT_STATE: &str = "clock_format";
+const WORLD_CLOCK_ENTRY_WIDTH: f32 = 90.;
+// longer event names are cut in the bar
+const AGENDA_SUMMARY_LENGTH: usize = 20;
+
+pub struct Clock {
+    date: DateTime<Local>,
+    locale: Locale,
+    format_index: usize,
+    shown_month: NaiveDate,
+    events: Vec<CalendarEvent>,
+    agenda: Option<AgendaService>,
+    world_clock: Animation,
+    now: Instant,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        let date = Local::now();
+
+        Self {
+            date,
+            locale: time_locale(),
+            format_index: read_state(FORMAT_STATE)
+                .and_then(|index| index.parse().ok())
+                .unwrap_or_default(),
+            shown_month: first_of_month(date.date_naive()),
+            events: Vec::new(),
+            agenda: None,
+            world_clock: Animation::new(0., Duration::from_millis(200)),
+            now: Instant::now(),
+        }
+    }
+}
+
+pub fn time_locale() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| {
+            // "it_IT.UTF-8" -> "it_IT"
+            let name = value.split(['.', '@']).next().unwrap_or_default();
+            Locale::try_from(name).ok()
+        })
+        .unwrap_or(Locale::POSIX)
+}
+
+fn uses_12_hours(locale: Locale) -> bool {
+    let afternoon = NaiveTime::from_hms_opt(13, 0, 0).unwrap_or_default();
+
+    !afternoon
+        .format_localized("%X", locale)
+        .to_string()
+        .contains("13")
+}
+
+/// Compact time until an event, e.g. "20m" or "1h 5m"
+fn format_countdown(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+fn ordinal_suffix(day: u32) -> &'static str {
+    match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Expand the `%o` specifier (ordinal day of the month) not supported by chrono
+fn expand_ordinal(format: &str, date: &DateTime<Local>) -> String {
+    let mut result = String::with_capacity(format.len());
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('%', Some('o')) => {
+                chars.next();
+                result.push_str(&format!("{}{}", date.day(), ordinal_suffix(date.day())));
+            }
+            ('%', Some('%')) => {
+                chars.next();
+                result.push_str("%%");
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Update,
+    CycleFormat,
+    EventsLoaded(Vec<CalendarEvent>),
+    Agenda(ServiceEvent<AgendaService>),
+    PreviousMonth,
+    NextMonth,
+    Hover(bool),
+    Frame(Instant),
+}
+
+impl Clock {
+    pub fn update(&mut self, message: Message, config: &ClockModuleConfig) {
+        match message {
+            Message::Update => {
+                self.date = Local::now();
+            }
+            Message::CycleFormat => {
+                self.format_index = (self.format_index + 1) % (config.alternate_formats.len() + 1);
+                write_state(FORMAT_STATE, &self.format_index.to_string());
+            }
+            Message::EventsLoaded(events) => {
+                self.events = events;
+            }
+            Message::Agenda(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.agenda = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(agenda) = self.agenda.as_mut() {
+                        agenda.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+            Message::PreviousMonth => {
+                self.shown_month = self
+                    .shown_month
+                    .checked_sub_months(Months::new(1))
+                    .unwrap_or(self.shown_month);
+            }
+            Message::NextMonth => {
+                self.shown_month = self
+                    .shown_month
+                    .checked_add_months(Months::new(1))
+                    .unwrap_or(self.shown_month);
+            }
+            Message::Hover(hovered) => {
+                if !config.world_clock.is_empty() {
+                    self.now = Instant::now();
+                    self.world_clock
+                        .go_to(if hovered { 1. } else { 0. }, self.now);
+                }
+            }
+            Message::Frame(now) => {
+                self.now = now;
+            }
+        }
+    }
+
+    pub fn reset_calendar(&mut self) {
+        self.shown_month = first_of_month(self.date.date_naive());
+    }
+
+    fn current_format(&self, config: &ClockModuleConfig) -> String {
+        if self.format_index > 0 {
+            if let Some(format) = config.alternate_formats.get(self.format_index - 1) {
+                return format.clone();
+            }
+        }
+
+        config.format.clone().unwrap_or_else(|| {
+            if self.hour12(config) {
+                "%a %d %b %I:%M %p".to_string()
+            } else {
+                "%a %d %b %R".to_string()
+            }
+        })
+    }
+
+    fn hour12(&self, config: &ClockModuleConfig) -> bool {
+        config.hour12.unwrap_or_else(|| uses_12_hours(self.locale))
+    }
+
+    /// Short event time, e.g. "3pm" or "15:00"
+    fn event_time(&self, time: DateTime<Local>, config: &ClockModuleConfig) -> String {
+        let format = match (self.hour12(config), time.minute()) {
+            (true, 0) => "%-I%P",
+            (true, _) => "%-I:%M%P",
+            (false, _) => "%R",
+        };
+
+        time.format(format).to_string()
+    }
+
+    /// Next event of the day, e.g. "3pm Standup in 20m"
+    fn agenda_view(&self, config: &ClockModuleConfig) -> Option<Element<app::Message>> {
+        let event = self.agenda.as_ref()?.next(self.date)?;
+
+        let mut summary = event.summary.clone();
+        if summary.chars().count() > AGENDA_SUMMARY_LENGTH {
+            summary = summary
+                .chars()
+                .take(AGENDA_SUMMARY_LENGTH - 1)
+                .chain(['…'])
+                .collect();
+        }
+
+        let content = if event.start > self.date {
+            let minutes = (event.start - self.date).num_minutes() + 1;
+
+            format!(
+                "{} {} {}",
+                self.event_time(event.start, config),
+                summary,
+                tr_args("agenda-in", &[("time", format_countdown(minutes))])
+            )
+        } else {
+            format!("{} {}", summary, tr("agenda-now"))
+        };
+
+        Some(text(content).size(12).into())
+    }
+
+    fn agenda_event_view(
+        &self,
+        event: &AgendaEvent,
+        config: &ClockModuleConfig,
+    ) -> Element<Message> {
+        let time = if event.all_day {
+            tr("agenda-all-day")
+        } else {
+            format!(
+                "{} - {}",
+                self.event_time(event.start, config),
+                self.event_time(event.end, config)
+            )
+        };
+        let past = !event.all_day && event.end <= self.date;
+
+        row!(
+            text(time).size(12).width(Length::Fixed(100.)),
+            text(event.summary.clone())
+                .size(12)
+                .style(move |theme: &Theme| text::Style {
+                    color: past.then(|| theme.extended_palette().background.strong.color),
+                }),
+        )
+        .into()
+    }
+
+    fn world_clock_view(&self, config: &ClockModuleConfig) -> Option<Element<app::Message>> {
+        let expansion = self.world_clock.value(self.now);
+
+        if expansion <= 0. || config.world_clock.is_empty() {
+            return None;
+        }
+
+        let entries = config.world_clock.iter().filter_map(|entry| {
+            let timezone = entry
+                .timezone
+                .parse::<Tz>()
+                .inspect_err(|_| warn!("Invalid timezone: {}", entry.timezone))
+                .ok()?;
+
+            Some(
+                container(
+                    text(format!(
+                        "{} {}",
+                        entry.label,
+                        self.date.with_timezone(&timezone).format("%R")
+                    ))
+                    .size(12),
+                )
+                .width(Length::Fixed(WORLD_CLOCK_ENTRY_WIDTH))
+                .into(),
+            )
+        });
+
+        Some(
+            container(Row::with_children(entries))
+                .width(Length::Fixed(
+                    expansion * WORLD_CLOCK_ENTRY_WIDTH * config.world_clock.len() as f32,
+                ))
+                .clip(true)
+                .into(),
+        )
+    }
+
+    fn day_cell(&self, date: NaiveDate) -> Element<Message> {
+        let today = date == self.date.date_naive();
+        let has_event = self.events.iter().any(|event| event.occurs_on(date));
+        let other_month = date.month() != self.shown_month.month();
+
+        container(text(date.day()).size(12))
+            .center_x(Length::Fixed(30.))
+            .center_y(Length::Fixed(26.))
+            .style(move |theme: &Theme| {
+                let palette = theme.extended_palette();
+
+                container::Style {
+                    background: today.then(|| palette.primary.base.color.into()),
+                    text_color: Some(if today {
+                        palette.primary.base.text
+                    } else if has_event {
+                        palette.primary.base.color
+                    } else if other_month {
+                        palette.background.strong.color
+                    } else {
+                        palette.background.base.text
+                    }),
+                    border: Border {
+                        color: palette.primary.base.color,
+                        width: if has_event { 1. } else { 0. },
+                        radius: 8.0.into(),
+                    },
+                    ..Default::default()
+                }
+            })
+            .into()
+    }
+
+    pub fn menu_view(&self, config: &ClockModuleConfig) -> Element<Message> {
+        let today = self.date.date_naive();
+        let start =
+            self.shown_month - Days::new(self.shown_month.weekday().num_days_from_monday() as u64);
+
+        let header = row!(
+            button(icon(Icons::LeftChevron))
+                .padding([4, 10])
+                .on_press(Message::PreviousMonth)
+                .style(SettingsButtonStyle.into_style()),
+            text(
+                self.shown_month
+                    .format_localized("%B %Y", self.locale)
+                    .to_string()
+            )
+            .width(Length::Fill)
+            .align_x(Horizontal::Center),
+            button(icon(Icons::RightChevron))
+                .padding([4, 10])
+                .on_press(Message::NextMonth)
+                .style(SettingsButtonStyle.into_style()),
+        )
+        .align_y(Alignment::Center);
+
+        let weekdays = Row::with_children((0..7).map(|offset| {
+            let day = start + Days::new(offset);
+
+            container(text(day.format_localized("%a", self.locale).to_string()).size(10))
+                .center_x(Length::Fixed(30.))
+                .into()
+        }))
+        .spacing(2);
+
+        let weeks = Column::with_children((0..6).map(|week| {
+            Row::with_children((0..7).map(|day| self.day_cell(start + Days::new(week * 7 + day))))
+                .spacing(2)
+                .into()
+        }))
+        .spacing(2);
+
+        let upcoming = config.calendar.as_ref().map(|calendar| {
+            let mut upcoming = self
+                .events
+                .iter()
+                .filter_map(|event| event.next_occurrence(today).map(|date| (date, event)))
+                .collect::<Vec<_>>();
+            upcoming.sort_by_key(|(date, _)| *date);
+
+            Column::with_children(upcoming.into_iter().take(calendar.upcoming_events).map(
+                |(date, event)| {
+                    row!(
+                        text(date.format_localized("%d %b", self.locale).to_string())
+                            .size(12)
+                            .width(Length::Fixed(60.)),
+                        text(event.name.clone()).size(12),
+                    )
+                    .into()
+                },
+            ))
+            .spacing(4)
+        });
+
+        let agenda = self.agenda.as_ref().map(|agenda| {
+            let events = if agenda.events.is_empty() {
+                vec![text(tr("agenda-no-events")).size(12).into()]
+            } else {
+                agenda
+                    .events
+                    .iter()
+                    .map(|event| self.agenda_event_view(event, config))
+                    .collect()
+            };
+
+            column!(
+                horizontal_rule(1),
+                text(tr("agenda-today")),
+                Column::with_children(events).spacing(4)
+            )
+            .spacing(8)
+        });
+
+        column!(header, weekdays, weeks)
+            .push_maybe(agenda)
+            .push_maybe(upcoming.map(|upcoming| column!(horizontal_rule(1), upcoming).spacing(8)))
+            .push_maybe((!config.alternate_formats.is_empty()).then(|| {
+                button(text(tr("change-format")))
+                    .padding([4, 12])
+                    .width(Length::Fill)
+                    .on_press(Message::CycleFormat)
+                    .style(GhostButtonStyle.into_style())
+            }))
+            .spacing(8)
+            .align_x(Alignment::Center)
+            .into()
+    }
+}
+
+impl Module for Clock {
+    type ViewData<'a> = &'a ClockModuleConfig;
+    type SubscriptionData<'a> = &'a ClockModuleConfig;
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let format = expand_ordinal(&self.current_format(config), &self.date);
+        let mut content = text(self.date.format_localized(&format, self.locale).to_string());
+
+        // a monospace font keeps the clock width stable while the digits change
+        if let Some(font) = fonts().monospace {
+            content = content.font(font);
+        }
+
+        let content = mouse_area(
+            row!(content)
+                .push_maybe(self.agenda_view(config))
+                .push_maybe(self.world_clock_view(config))
+                .align_y(Alignment::Center)
+                .spacing(8),
+        )
+        .on_enter(app::Message::Clock(Message::Hover(true)))
+        .on_exit(app::Message::Clock(Message::Hover(false)));
+
+        let on_press = if config.calendar.is_some() {
+            Some(OnModulePress::ToggleMenu(MenuType::Calendar))
+        } else {
+            (!config.alternate_formats.is_empty()).then_some(OnModulePress::Action(
+                app::Message::Clock(Message::CycleFormat),
+            ))
+        };
+
+        Some((content.into(), on_press))
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let tick = every(Duration::from_secs(5))
+            .map(|_| Message::Update)
+            .map(app::Message::Clock);
+
+        let events = config.calendar.as_ref().map(|calendar| {
+            let id = TypeId::of::<Self>();
+            let files = calendar.files.clone();
+
+            Subscription::run_with_id(
+                format!("{:?}-{}", id, files.join(";")),
+                channel(10, move |mut output| async move {
+                    loop {
+                        let events = load_events(&files).await;
+
+                        let _ = output.try_send(Message::EventsLoaded(events));
+
+                        sleep(Duration::from_secs(600)).await;
+                    }
+                }),
+            )
+            .map(app::Message::Clock)
+        });
+
+        let sync = config
+            .calendar
+            .as_ref()
+            .and_then(|calendar| calendar.sync.as_ref());
+        set_query(sync.map(|sync| {
+            AgendaQuery {
+                sources: match sync.backend {
+                    CalendarBackend::Eds => AgendaSources::Eds,
+                    CalendarBackend::Vdir => AgendaSources::Vdir(
+                        sync.paths
+                            .iter()
+                            .map(|path| PathBuf::from(expand_home(path)))
+                            .collect(),
+                    ),
+                },
+                interval: Duration::from_secs(sync.interval),
+            }
+        }));
+        let agenda = sync.map(|_| {
+            AgendaService::subscribe()
+                .map(Message::Agenda)
+                .map(app::Message::Clock)
+        });
+
+        let frames = self.world_clock.is_running(self.now).then(|| {
+            every(Duration::from_millis(16))
+                .map(Message::Frame)
+                .map(app::Message::Clock)
+        });
+
+        Some(Subscription::batch(
+            vec![tick]
+                .into_iter()
+                .chain(events)
+                .chain(agenda)
+                .chain(frames),
+        ))
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{
+    components::icons::{icon, Icons},
+    config::{
+        export_theme, import_theme, update_config, Appearance, AppearanceColor, Config, ModuleDef,
+        ModuleName, Position, ThemePreset,
+    },
+    i18n::tr,
+    style::{
+        dragged_module, ConfirmButtonStyle, OutlineButtonStyle, QuickSettingsButtonStyle,
+        SettingsButtonStyle, TextInputStyle,
+    },
+};
+use hex_color::HexColor;
+use iced::{
+    alignment::{Horizontal, Vertical},
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{IcedOutput, SctkLayerSurfaceSettings},
+    widget::{
+        button, column, container, horizontal_space, mouse_area, row, scrollable, text, text_input,
+        toggler, Column, Row,
+    },
+    window::Id,
+    Alignment, Border, Element, Length, Task, Theme,
+};
+use log::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    Appearance,
+    Layout,
+    Modules,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorField {
+    Background,
+    Primary,
+    Secondary,
+    Success,
+    Danger,
+    Text,
+}
+
+const COLOR_FIELDS: [(ColorField, &str); 6] = [
+    (ColorField::Background, "color-background"),
+    (ColorField::Primary, "color-primary"),
+    (ColorField::Secondary, "color-secondary"),
+    (ColorField::Success, "color-success"),
+    (ColorField::Danger, "color-danger"),
+    (ColorField::Text, "color-text"),
+];
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SelectTab(Tab),
+    PositionSelected(Position),
+    ColorChanged(ColorField, String),
+    PresetSelected(ThemePreset),
+    ThemePathChanged(String),
+    ExportTheme,
+    ImportTheme,
+    ThemeExported,
+    ThemeImported(Option<Appearance>),
+    DragModule(Section, usize),
+    DropModule(Section, usize),
+    CancelModuleDrag,
+    RemoveModule(Section, usize),
+    NewModuleChanged(String),
+    AddModule(Section),
+    ClockFormatChanged(String),
+    TruncateTitleChanged(String),
+    ToggleWorkspaceFilling(bool),
+    Apply,
+    Cancel,
+    Saved,
+}
+
+pub enum Action {
+    None,
+    Preview(Config),
+    Task(Task<Message>),
+    Close(Option<Config>),
+}
+
+fn color_to_hex(color: &AppearanceColor) -> String {
+    let base = match color {
+        AppearanceColor::Simple(color) => color,
+        AppearanceColor::Complete { base, .. } => base,
+    };
+
+    base.display_rgb().to_string()
+}
+
+fn with_base(color: &AppearanceColor, new_base: HexColor) -> AppearanceColor {
+    match *color {
+        AppearanceColor::Simple(_) => AppearanceColor::Simple(new_base),
+        AppearanceColor::Complete {
+            strong, weak, text, ..
+        } => AppearanceColor::Complete {
+            base: new_base,
+            strong,
+            weak,
+            text,
+        },
+    }
+}
+
+pub struct ConfigEditor {
+    pub id: Id,
+    tab: Tab,
+    original: Config,
+    draft: Config,
+    colors: Vec<(ColorField, String)>,
+    theme_path: String,
+    new_module: String,
+    truncate_title: String,
+    dragged_module: Option<(Section, usize)>,
+}
+
+impl ConfigEditor {
+    pub fn new(config: &Config) -> (Self, Task<Message>) {
+        let id = Id::unique();
+
+        (
+            Self {
+                id,
+                tab: Tab::Appearance,
+                original: config.clone(),
+                draft: config.clone(),
+                colors: ConfigEditor::color_inputs(&config.appearance),
+                theme_path: "~/.config/ashell-theme.yml".to_string(),
+                new_module: String::new(),
+                truncate_title: config.truncate_title_after_length.to_string(),
+                dragged_module: None,
+            },
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id,
+                size: Some((Some(640), Some(560))),
+                layer: Layer::Overlay,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::OnDemand,
+                output: IcedOutput::Active,
+                anchor: Anchor::empty(),
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn color_inputs(appearance: &Appearance) -> Vec<(ColorField, String)> {
+        COLOR_FIELDS
+            .iter()
+            .map(|(field, _)| {
+                (
+                    *field,
+                    color_to_hex(ConfigEditor::color(appearance, *field)),
+                )
+            })
+            .collect()
+    }
+
+    fn set_appearance(&mut self, appearance: Appearance) {
+        self.colors = ConfigEditor::color_inputs(&appearance);
+        self.draft.appearance = appearance;
+    }
+
+    fn color(appearance: &Appearance, field: ColorField) -> &AppearanceColor {
+        match field {
+            ColorField::Background => &appearance.background_color,
+            ColorField::Primary => &appearance.primary_color,
+            ColorField::Secondary => &appearance.secondary_color,
+            ColorField::Success => &appearance.success_color,
+            ColorField::Danger => &appearance.danger_color,
+            ColorField::Text => &appearance.text_color,
+        }
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut Vec<ModuleDef> {
+        match section {
+            Section::Left => &mut self.draft.modules.left,
+            Section::Center => &mut self.draft.modules.center,
+            Section::Right => &mut self.draft.modules.right,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        match message {
+            Message::SelectTab(tab) => {
+                self.tab = tab;
+
+                return Action::None;
+            }
+            Message::PositionSelected(position) => self.draft.position = position,
+            Message::ColorChanged(field, value) => {
+                if let Ok(color) = HexColor::parse(&value) {
+                    let appearance = &mut self.draft.appearance;
+                    let target = match field {
+                        ColorField::Background => &mut appearance.background_color,
+                        ColorField::Primary => &mut appearance.primary_color,
+                        ColorField::Secondary => &mut appearance.secondary_color,
+                        ColorField::Success => &mut appearance.success_color,
+                        ColorField::Danger => &mut appearance.danger_color,
+                        ColorField::Text => &mut appearance.text_color,
+                    };
+                    *target = with_base(target, color);
+                }
+
+                if let Some((_, current)) = self.colors.iter_mut().find(|(f, _)| *f == field) {
+                    *current = value;
+                }
+            }
+            Message::PresetSelected(preset) => self.set_appearance(preset.appearance()),
+            Message::ThemePathChanged(value) => {
+                self.theme_path = value;
+
+                return Action::None;
+            }
+            Message::ExportTheme => {
+                let appearance = self.draft.appearance.clone();
+                let path = self.theme_path.clone();
+
+                return Action::Task(Task::perform(
+                    async move {
+                        match export_theme(appearance, path.clone()).await {
+                            Ok(_) => info!("Theme exported to {}", path),
+                            Err(err) => error!("Failed to export the theme: {}", err),
+                        }
+                    },
+                    |_| Message::ThemeExported,
+                ));
+            }
+            Message::ImportTheme => {
+                let path = self.theme_path.clone();
+
+                return Action::Task(Task::perform(
+                    async move {
+                        import_theme(path)
+                            .await
+                            .inspect_err(|err| error!("Failed to import the theme: {}", err))
+                            .ok()
+                    },
+                    Message::ThemeImported,
+                ));
+            }
+            Message::ThemeExported | Message::ThemeImported(None) => return Action::None,
+            Message::ThemeImported(Some(appearance)) => self.set_appearance(appearance),
+            Message::DragModule(section, index) => {
+                self.dragged_module = Some((section, index));
+
+                return Action::None;
+            }
+            Message::DropModule(section, index) => {
+                let Some((dragged_section, dragged_index)) = self.dragged_module.take() else {
+                    return Action::None;
+                };
+                if (dragged_section, dragged_index) == (section, index)
+                    || dragged_index >= self.section_mut(dragged_section).len()
+                {
+                    return Action::None;
+                }
+
+                let module = self.section_mut(dragged_section).remove(dragged_index);
+                let modules = self.section_mut(section);
+                modules.insert(index.min(modules.len()), module);
+            }
+            Message::CancelModuleDrag => {
+                self.dragged_module = None;
+
+                return Action::None;
+            }
+            Message::RemoveModule(section, index) => {
+                let modules = self.section_mut(section);
+                if index < modules.len() {
+                    modules.remove(index);
+                }
+            }
+            Message::NewModuleChanged(value) => {
+                self.new_module = value;
+
+                return Action::None;
+            }
+            Message::AddModule(section) => {
+                match serde_yaml::from_str::<ModuleName>(self.new_module.trim()) {
+                    Ok(module) => {
+                        self.section_mut(section).push(ModuleDef::Single(module));
+                        self.new_module.clear();
+                    }
+                    Err(_) => return Action::None,
+                }
+            }
+            Message::ClockFormatChanged(format) => {
+                self.draft.clock.format = Some(format).filter(|format| !format.is_empty());
+            }
+            Message::TruncateTitleChanged(value) => {
+                if let Ok(length) = value.parse::<u32>() {
+                    self.draft.truncate_title_after_length = length;
+                }
+                self.truncate_title = value;
+            }
+            Message::ToggleWorkspaceFilling(value) => {
+                self.draft.workspaces.enable_workspace_filling = value;
+            }
+            Message::Apply => {
+                return Action::Task(Task::perform(
+                    update_config(self.original.clone(), self.draft.clone()),
+                    |res| {
+                        if let Err(err) = res {
+                            error!("Failed to write the config file: {}", err);
+                        }
+                        Message::Saved
+                    },
+                ));
+            }
+            Message::Cancel => return Action::Close(Some(self.original.clone())),
+            Message::Saved => return Action::Close(None),
+        }
+
+        Action::Preview(self.draft.clone())
+    }
+
+    fn appearance_view(&self) -> Element<Message> {
+        let position_button = |label: String, position: Position| {
+            button(text(label).width(Length::Fill).align_x(Horizontal::Center))
+                .padding([8, 16])
+                .width(Length::Fill)
+                .style(QuickSettingsButtonStyle(self.draft.position == position).into_style())
+                .on_press(Message::PositionSelected(position))
+        };
+
+        column!(
+            text(tr("bar-position")),
+            row!(
+                position_button(tr("position-top"), Position::Top),
+                position_button(tr("position-bottom"), Position::Bottom)
+            )
+            .spacing(8),
+            text(tr("theme-presets")),
+            Row::with_children(
+                ThemePreset::ALL
+                    .iter()
+                    .map(|preset| {
+                        button(
+                            text(format!("{:?}", preset))
+                                .width(Length::Fill)
+                                .align_x(Horizontal::Center),
+                        )
+                        .padding([8, 16])
+                        .width(Length::Fill)
+                        .style(
+                            QuickSettingsButtonStyle(self.draft.appearance.preset == Some(*preset))
+                                .into_style(),
+                        )
+                        .on_press(Message::PresetSelected(*preset))
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8),
+            text(tr("theme-file")),
+            row!(
+                text_input("~/.config/ashell-theme.yml", &self.theme_path)
+                    .padding([4, 8])
+                    .style(TextInputStyle.into_style())
+                    .on_input(Message::ThemePathChanged),
+                button(text(tr("import")))
+                    .padding([4, 16])
+                    .style(OutlineButtonStyle.into_style())
+                    .on_press(Message::ImportTheme),
+                button(text(tr("export")))
+                    .padding([4, 16])
+                    .style(OutlineButtonStyle.into_style())
+                    .on_press(Message::ExportTheme),
+            )
+            .align_y(Alignment::Center)
+            .spacing(8),
+            text(tr("colors")),
+            Column::with_children(
+                self.colors
+                    .iter()
+                    .map(|(field, value)| {
+                        let label = COLOR_FIELDS
+                            .iter()
+                            .find(|(f, _)| f == field)
+                            .map(|(_, label)| *label)
+                            .unwrap_or_default();
+                        let field = *field;
+
+                        row!(
+                            text(tr(label)).width(Length::Fixed(120.)),
+                            text_input("#rrggbb", value)
+                                .padding([4, 8])
+                                .style(TextInputStyle.into_style())
+                                .on_input(move |value| Message::ColorChanged(field, value)),
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(8)
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8),
+        )
+        .spacing(12)
+        .into()
+    }
+
+    fn section_view(&self, label: String, section: Section) -> Element<Message> {
+        let modules = match section {
+            Section::Left => &self.draft.modules.left,
+            Section::Center => &self.draft.modules.center,
+            Section::Right => &self.draft.modules.right,
+        };
+
+        let small_button = |i: Icons, message: Message| {
+            button(icon(i).size(12))
+                .padding([2, 6])
+                .style(SettingsButtonStyle.into_style())
+                .on_press(message)
+        };
+
+        // dropping a module on the free space of a section appends it
+        let content = column!(
+            text(label),
+            Column::with_children(
+                modules
+                    .iter()
+                    .enumerate()
+                    .map(|(index, module)| {
+                        let name = match module {
+                            ModuleDef::Single(module) => format!("{:?}", module),
+                            ModuleDef::Group(group) => format!(
+                                "[{}]",
+                                group
+                                    .iter()
+                                    .map(|module| format!("{:?}", module))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        };
+
+                        let module = container(
+                            row!(
+                                text(name).size(12).width(Length::Fill),
+                                small_button(Icons::Close, Message::RemoveModule(section, index)),
+                            )
+                            .align_y(Alignment::Center)
+                            .spacing(4),
+                        )
+                        .padding([2, 6]);
+                        let module = if self.dragged_module == Some((section, index)) {
+                            module.style(dragged_module)
+                        } else {
+                            module
+                        };
+
+                        // dropping a module on another one takes its place
+                        mouse_area(module)
+                            .on_press(Message::DragModule(section, index))
+                            .on_release(Message::DropModule(section, index))
+                            .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8),
+            button(text(tr("add-here")).size(12))
+                .padding([2, 8])
+                .style(OutlineButtonStyle.into_style())
+                .on_press_maybe(
+                    (!self.new_module.trim().is_empty()).then_some(Message::AddModule(section)),
+                ),
+        )
+        .spacing(8)
+        .width(Length::Fill);
+
+        mouse_area(content)
+            .on_release(Message::DropModule(section, modules.len()))
+            .into()
+    }
+
+    fn layout_view(&self) -> Element<Message> {
+        column!(
+            text(tr("drag-modules-hint")).size(12),
+            row!(
+                self.section_view(tr("section-left"), Section::Left),
+                self.section_view(tr("section-center"), Section::Center),
+                self.section_view(tr("section-right"), Section::Right),
+            )
+            .spacing(16),
+            text_input(&tr("module-name-placeholder"), &self.new_module)
+                .padding([4, 8])
+                .style(TextInputStyle.into_style())
+                .on_input(Message::NewModuleChanged),
+        )
+        .spacing(16)
+        .into()
+    }
+
+    fn modules_view(&self) -> Element<Message> {
+        column!(
+            text(tr("clock-format")),
+            text_input(
+                "%a %d %b %R",
+                self.draft.clock.format.as_deref().unwrap_or_default(),
+            )
+            .padding([4, 8])
+            .style(TextInputStyle.into_style())
+            .on_input(Message::ClockFormatChanged),
+            text(tr("truncate-title-after")),
+            text_input("150", &self.truncate_title)
+                .padding([4, 8])
+                .style(TextInputStyle.into_style())
+                .on_input(Message::TruncateTitleChanged),
+            toggler(self.draft.workspaces.enable_workspace_filling)
+                .label(tr("fill-missing-workspaces"))
+                .on_toggle(Message::ToggleWorkspaceFilling),
+        )
+        .spacing(12)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let tab_button = |label: String, tab: Tab| {
+            button(text(label).width(Length::Fill).align_x(Horizontal::Center))
+                .padding([8, 16])
+                .width(Length::Fill)
+                .style(QuickSettingsButtonStyle(self.tab == tab).into_style())
+                .on_press(Message::SelectTab(tab))
+        };
+
+        let editor = container(
+            column!(
+                row!(
+                    icon(Icons::Configure).size(32),
+                    text(tr("configuration")).size(22)
+                )
+                .spacing(16)
+                .align_y(Alignment::Center),
+                row!(
+                    tab_button(tr("editor-appearance"), Tab::Appearance),
+                    tab_button(tr("editor-layout"), Tab::Layout),
+                    tab_button(tr("editor-modules"), Tab::Modules),
+                )
+                .spacing(8),
+                container(scrollable(match self.tab {
+                    Tab::Appearance => self.appearance_view(),
+                    Tab::Layout => self.layout_view(),
+                    Tab::Modules => self.modules_view(),
+                }))
+                .height(Length::Fill),
+                row!(
+                    horizontal_space(),
+                    button(text(tr("cancel")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(OutlineButtonStyle.into_style())
+                        .on_press(Message::Cancel),
+                    button(text(tr("apply")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(ConfirmButtonStyle.into_style())
+                        .on_press(Message::Apply),
+                )
+                .spacing(8)
+                .width(Length::Fill),
+            )
+            .spacing(16),
+        )
+        .padding(24)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        });
+
+        // a module released outside of the sections stays where it was
+        mouse_area(editor)
+            .on_release(Message::CancelModuleDrag)
+            .into()
+    }
+}
@@ -0,0 +1,258 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::DockModuleConfig,
+    style::GhostButtonStyle,
+    utils::{
+        icons::{resolve_icon, AppIcon},
+        launcher::execute_command,
+    },
+};
+use hyprland::{
+    data::{Client, Clients},
+    dispatch::{Dispatch, DispatchType, WindowIdentifier},
+    event_listener::AsyncEventListener,
+    shared::{HyprData, HyprDataActiveOptional, HyprDataVec},
+};
+use iced::{
+    stream::channel,
+    widget::{button, column, container, Row},
+    Alignment, Background, Border, Element, Length, Subscription, Task, Theme,
+};
+use log::{debug, error};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct RunningApps {
+    pub classes: Vec<String>,
+    pub active: Option<String>,
+}
+
+fn get_running_apps() -> RunningApps {
+    RunningApps {
+        classes: Clients::get()
+            .map(|c| c.to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| c.class.to_lowercase())
+            .collect(),
+        active: Client::get_active()
+            .ok()
+            .flatten()
+            .map(|c| c.class.to_lowercase()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    RunningAppsChanged(RunningApps),
+    Activate(usize),
+    IconResolved(String, Option<AppIcon>),
+}
+
+#[derive(Debug, Default)]
+pub struct Dock {
+    running: RunningApps,
+    icons: HashMap<String, Option<AppIcon>>,
+}
+
+impl Dock {
+    pub fn update(&mut self, message: Message, config: &DockModuleConfig) -> Task<Message> {
+        match message {
+            Message::RunningAppsChanged(running) => {
+                self.running = running;
+
+                let mut lookups = Vec::new();
+                for app in &config.apps {
+                    let name = app.icon.as_ref().unwrap_or(&app.app_id);
+                    if !self.icons.contains_key(name) {
+                        // the placeholder is shown until the lookup completes
+                        self.icons.insert(name.clone(), None);
+                        let name = name.clone();
+                        lookups.push(Task::perform(resolve_icon(name.clone()), move |icon| {
+                            Message::IconResolved(name, icon)
+                        }));
+                    }
+                }
+
+                Task::batch(lookups)
+            }
+            Message::IconResolved(name, icon) => {
+                self.icons.insert(name, icon);
+
+                Task::none()
+            }
+            Message::Activate(index) => {
+                if let Some(app) = config.apps.get(index) {
+                    if self.is_running(&app.app_id) {
+                        debug!("focusing {}", app.app_id);
+                        let class = format!("(?i)^{}$", app.app_id);
+                        let res = Dispatch::call(DispatchType::FocusWindow(
+                            WindowIdentifier::ClassRegularExpression(&class),
+                        ));
+
+                        if let Err(e) = res {
+                            error!("failed to focus {}: {:?}", app.app_id, e);
+                        }
+                    } else {
+                        execute_command(app.command.clone());
+                    }
+                }
+
+                Task::none()
+            }
+        }
+    }
+
+    fn is_running(&self, app_id: &str) -> bool {
+        self.running.classes.contains(&app_id.to_lowercase())
+    }
+}
+
+impl Module for Dock {
+    type ViewData<'a> = &'a DockModuleConfig;
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        if config.apps.is_empty() {
+            return None;
+        }
+
+        Some((
+            Into::<Element<Message>>::into(
+                Row::with_children(
+                    config
+                        .apps
+                        .iter()
+                        .enumerate()
+                        .map(|(index, app)| {
+                            let name = app.icon.as_ref().unwrap_or(&app.app_id);
+                            let running = self.is_running(&app.app_id);
+                            let active = self.running.active.as_deref()
+                                == Some(app.app_id.to_lowercase().as_str());
+
+                            let app_icon = match self.icons.get(name) {
+                                Some(Some(app_icon)) => app_icon.view(18.),
+                                _ => icon(Icons::AppLauncher).into(),
+                            };
+
+                            button(
+                                column!(
+                                    app_icon,
+                                    container(Row::new())
+                                        .width(Length::Fixed(if active { 8. } else { 4. }))
+                                        .height(Length::Fixed(2.))
+                                        .style(move |theme: &Theme| container::Style {
+                                            background: running.then(|| {
+                                                Background::Color(
+                                                    theme.extended_palette().primary.base.color,
+                                                )
+                                            }),
+                                            border: Border {
+                                                radius: 1.0.into(),
+                                                ..Default::default()
+                                            },
+                                            ..Default::default()
+                                        })
+                                )
+                                .spacing(2)
+                                .align_x(Alignment::Center),
+                            )
+                            .padding([0, 4])
+                            .style(GhostButtonStyle.into_style())
+                            .on_press(Message::Activate(index))
+                            .into()
+                        })
+                        .collect::<Vec<Element<'_, _, _>>>(),
+                )
+                .align_y(Alignment::Center)
+                .spacing(2),
+            )
+            .map(app::Message::Dock),
+            None,
+        ))
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        let id = TypeId::of::<Self>();
+
+        Some(
+            Subscription::run_with_id(
+                id,
+                channel(10, |mut output| async move {
+                    let _ = output.try_send(Message::RunningAppsChanged(get_running_apps()));
+
+                    let output = Arc::new(RwLock::new(output));
+                    loop {
+                        let mut event_listener = AsyncEventListener::new();
+
+                        event_listener.add_window_opened_handler({
+                            let output = output.clone();
+                            move |_| {
+                                let output = output.clone();
+                                Box::pin(async move {
+                                    if let Ok(mut output) = output.write() {
+                                        output
+                                            .try_send(Message::RunningAppsChanged(
+                                                get_running_apps(),
+                                            ))
+                                            .expect("error getting clients: window open event");
+                                    }
+                                })
+                            }
+                        });
+
+                        event_listener.add_window_closed_handler({
+                            let output = output.clone();
+                            move |_| {
+                                let output = output.clone();
+                                Box::pin(async move {
+                                    if let Ok(mut output) = output.write() {
+                                        output
+                                            .try_send(Message::RunningAppsChanged(
+                                                get_running_apps(),
+                                            ))
+                                            .expect("error getting clients: window close event");
+                                    }
+                                })
+                            }
+                        });
+
+                        event_listener.add_active_window_changed_handler({
+                            let output = output.clone();
+                            move |_| {
+                                let output = output.clone();
+                                Box::pin(async move {
+                                    if let Ok(mut output) = output.write() {
+                                        output
+                                            .try_send(Message::RunningAppsChanged(
+                                                get_running_apps(),
+                                            ))
+                                            .expect(
+                                                "error getting clients: active window change event",
+                                            );
+                                    }
+                                })
+                            }
+                        });
+
+                        let res = event_listener.start_listener_async().await;
+
+                        if let Err(e) = res {
+                            error!("restarting dock listener due to error: {:?}", e);
+                        }
+                    }
+                }),
+            )
+            .map(app::Message::Dock),
+        )
+    }
+}
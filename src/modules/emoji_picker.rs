@@ -0,0 +1,328 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    i18n::tr,
+    menu::MenuType,
+    outputs::Outputs,
+    style::{GhostButtonStyle, QuickSettingsSubMenuButtonStyle, TextInputStyle},
+    utils::{
+        launcher::copy_to_clipboard,
+        state::{read_state, write_state},
+    },
+};
+use emojis::SkinTone;
+use iced::{
+    widget::{button, column, container, row, scrollable, text, text_input, Column, Row},
+    window::Id,
+    Alignment, Element, Length, Task,
+};
+use std::sync::LazyLock;
+
+const RECENT_STATE: &str = "emoji-recent";
+const SKIN_TONE_STATE: &str = "emoji-skin-tone";
+const MAX_RECENT: usize = 24;
+const MAX_RESULTS: usize = 96;
+const COLUMNS: usize = 8;
+
+pub static SEARCH_INPUT_ID: LazyLock<text_input::Id> =
+    LazyLock::new(|| text_input::Id::new("emoji-search"));
+
+const KAOMOJI: [(&str, &str); 24] = [
+    ("shrug", "¯\\_(ツ)_/¯"),
+    ("table flip", "(╯°□°)╯︵ ┻━┻"),
+    ("table back", "┬─┬ノ( º _ ºノ)"),
+    ("lenny", "( ͡° ͜ʖ ͡°)"),
+    ("disapproval", "ಠ_ಠ"),
+    ("happy", "(＾▽＾)"),
+    ("joy", "(ﾉ◕ヮ◕)ﾉ*:･ﾟ✧"),
+    ("excited", "ヽ(°〇°)ﾉ"),
+    ("love", "(♡‿♡)"),
+    ("hug", "(づ｡◕‿‿◕｡)づ"),
+    ("wink", "(^_−)☆"),
+    ("sad", "(╥﹏╥)"),
+    ("cry", "(T_T)"),
+    ("angry", "(╬ Ò﹏Ó)"),
+    ("surprised", "(⊙_⊙)"),
+    ("confused", "(・・ ) ?"),
+    ("sleepy", "(－_－) zzZ"),
+    ("cat", "(=^･ω･^=)"),
+    ("bear", "ʕ•ᴥ•ʔ"),
+    ("dog", "U・ᴥ・U"),
+    ("fight", "(ง'̀-'́)ง"),
+    ("cool", "(⌐■_■)"),
+    ("bow", "m(_ _)m"),
+    ("music", "♪~ ᕕ(ᐛ)ᕗ"),
+];
+
+const SYMBOLS: [(&str, &str); 40] = [
+    ("right arrow", "→"),
+    ("left arrow", "←"),
+    ("up arrow", "↑"),
+    ("down arrow", "↓"),
+    ("double right arrow", "⇒"),
+    ("left right arrow", "↔"),
+    ("return", "↵"),
+    ("bullet", "•"),
+    ("ellipsis", "…"),
+    ("em dash", "—"),
+    ("en dash", "–"),
+    ("section", "§"),
+    ("paragraph", "¶"),
+    ("degree", "°"),
+    ("plus minus", "±"),
+    ("multiplication", "×"),
+    ("division", "÷"),
+    ("not equal", "≠"),
+    ("almost equal", "≈"),
+    ("less or equal", "≤"),
+    ("greater or equal", "≥"),
+    ("infinity", "∞"),
+    ("square root", "√"),
+    ("sum", "∑"),
+    ("pi", "π"),
+    ("micro", "µ"),
+    ("check mark", "✓"),
+    ("cross mark", "✗"),
+    ("star", "★"),
+    ("heart", "♥"),
+    ("euro", "€"),
+    ("pound", "£"),
+    ("yen", "¥"),
+    ("cent", "¢"),
+    ("bitcoin", "₿"),
+    ("copyright", "©"),
+    ("registered", "®"),
+    ("trademark", "™"),
+    ("command", "⌘"),
+    ("option", "⌥"),
+];
+
+const SKIN_TONES: [SkinTone; 6] = [
+    SkinTone::Default,
+    SkinTone::Light,
+    SkinTone::MediumLight,
+    SkinTone::Medium,
+    SkinTone::MediumDark,
+    SkinTone::Dark,
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tab {
+    #[default]
+    Emoji,
+    Kaomoji,
+    Symbols,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueryChanged(String),
+    SelectTab(Tab),
+    SelectSkinTone(usize),
+    Pick(Id, String),
+}
+
+#[derive(Debug, Clone)]
+pub struct EmojiPicker {
+    query: String,
+    tab: Tab,
+    skin_tone: usize,
+    recent: Vec<String>,
+}
+
+impl Default for EmojiPicker {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            tab: Tab::default(),
+            skin_tone: read_state(SKIN_TONE_STATE)
+                .and_then(|tone| tone.parse().ok())
+                .filter(|tone| *tone < SKIN_TONES.len())
+                .unwrap_or_default(),
+            recent: read_state(RECENT_STATE)
+                .map(|content| content.lines().map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl EmojiPicker {
+    /// Clears the search every time the menu is opened
+    pub fn reset(&mut self) -> Task<app::Message> {
+        self.query.clear();
+
+        text_input::focus(SEARCH_INPUT_ID.clone())
+    }
+
+    pub fn update(&mut self, message: Message, outputs: &mut Outputs) -> Task<app::Message> {
+        match message {
+            Message::QueryChanged(query) => {
+                self.query = query;
+
+                Task::none()
+            }
+            Message::SelectTab(tab) => {
+                self.tab = tab;
+
+                Task::none()
+            }
+            Message::SelectSkinTone(skin_tone) => {
+                self.skin_tone = skin_tone;
+                write_state(SKIN_TONE_STATE, &skin_tone.to_string());
+
+                Task::none()
+            }
+            Message::Pick(id, value) => {
+                self.recent.retain(|recent| *recent != value);
+                self.recent.insert(0, value.clone());
+                self.recent.truncate(MAX_RECENT);
+                write_state(RECENT_STATE, &self.recent.join("\n"));
+
+                Task::batch(vec![
+                    Task::perform(copy_to_clipboard(value), |_| app::Message::None),
+                    outputs.close_menu_if(id, MenuType::EmojiPicker),
+                ])
+            }
+        }
+    }
+
+    fn with_skin_tone(&self, emoji: &'static emojis::Emoji) -> &'static str {
+        emoji
+            .with_skin_tone(SKIN_TONES[self.skin_tone])
+            .unwrap_or(emoji)
+            .as_str()
+    }
+
+    /// Matching entries of the current tab, the recently used ones first
+    fn entries(&self) -> Vec<String> {
+        let query = self.query.to_lowercase();
+        let mut entries: Vec<String> = match self.tab {
+            Tab::Emoji => emojis::iter()
+                .filter(|emoji| {
+                    query.is_empty()
+                        || emoji.name().contains(&query)
+                        || emoji.shortcodes().any(|code| code.contains(&query))
+                })
+                .map(|emoji| self.with_skin_tone(emoji).to_string())
+                .collect(),
+            Tab::Kaomoji | Tab::Symbols => {
+                let index: &[(&str, &str)] = if self.tab == Tab::Kaomoji {
+                    &KAOMOJI
+                } else {
+                    &SYMBOLS
+                };
+
+                index
+                    .iter()
+                    .filter(|(name, _)| query.is_empty() || name.contains(&query))
+                    .map(|(_, value)| value.to_string())
+                    .collect()
+            }
+        };
+
+        entries.sort_by_key(|entry| {
+            self.recent
+                .iter()
+                .position(|recent| recent == entry)
+                .unwrap_or(usize::MAX)
+        });
+        entries.truncate(MAX_RESULTS);
+
+        entries
+    }
+
+    pub fn menu_view(&self, id: Id) -> Element<Message> {
+        let tabs = Row::with_children(
+            [
+                (Tab::Emoji, tr("emoji")),
+                (Tab::Kaomoji, tr("kaomoji")),
+                (Tab::Symbols, tr("symbols")),
+            ]
+            .into_iter()
+            .map(|(tab, label)| {
+                button(text(label).size(12))
+                    .padding([4, 12])
+                    .style(QuickSettingsSubMenuButtonStyle(self.tab == tab).into_style())
+                    .on_press(Message::SelectTab(tab))
+                    .into()
+            }),
+        )
+        .spacing(4);
+
+        let skin_tones = (self.tab == Tab::Emoji).then(|| {
+            Row::with_children(SKIN_TONES.iter().enumerate().map(|(index, tone)| {
+                let sample = emojis::get("👋")
+                    .and_then(|emoji| emoji.with_skin_tone(*tone))
+                    .map_or("👋", |emoji| emoji.as_str());
+
+                button(text(sample).size(14))
+                    .padding([2, 4])
+                    .style(QuickSettingsSubMenuButtonStyle(self.skin_tone == index).into_style())
+                    .on_press(Message::SelectSkinTone(index))
+                    .into()
+            }))
+            .spacing(2)
+        });
+
+        let entries = self.entries();
+        let results: Element<Message> = if entries.is_empty() {
+            container(text(tr("no-emoji-results")))
+                .padding(8)
+                .center_x(Length::Fill)
+                .into()
+        } else {
+            let size = if self.tab == Tab::Kaomoji { 1 } else { COLUMNS };
+            scrollable(
+                Column::with_children(entries.chunks(size).map(|chunk| {
+                    Row::with_children(chunk.iter().map(|entry| {
+                        button(
+                            container(text(entry.clone()).size(if size == 1 { 14 } else { 20 }))
+                                .center_x(Length::Fill),
+                        )
+                        .width(Length::Fill)
+                        .padding(4)
+                        .style(GhostButtonStyle.into_style())
+                        .on_press(Message::Pick(id, entry.clone()))
+                        .into()
+                    }))
+                    .spacing(2)
+                    .into()
+                }))
+                .spacing(2),
+            )
+            .height(Length::Fixed(280.))
+            .into()
+        };
+
+        column!(
+            text_input(&tr("search-emoji"), &self.query)
+                .id(SEARCH_INPUT_ID.clone())
+                .size(14)
+                .padding([6, 12])
+                .style(TextInputStyle.into_style())
+                .on_input(Message::QueryChanged),
+            row!(tabs)
+                .push_maybe(skin_tones.map(|tones| container(tones).align_right(Length::Fill)))
+                .align_y(Alignment::Center),
+            results,
+        )
+        .spacing(8)
+        .into()
+    }
+}
+
+impl Module for EmojiPicker {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        Some((
+            icon(Icons::EmojiPicker).into(),
+            Some(OnModulePress::ToggleMenu(MenuType::EmojiPicker)),
+        ))
+    }
+}
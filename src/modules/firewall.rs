@@ -0,0 +1,215 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::FirewallModuleConfig,
+    i18n::tr,
+    menu::MenuType,
+    services::{
+        firewall::{FirewallBackend, FirewallCommand, FirewallService},
+        ReadOnlyService, Service, ServiceEvent,
+    },
+    style::GhostButtonStyle,
+    utils::state::{read_state, write_state},
+};
+use iced::{
+    widget::{button, column, container, horizontal_rule, row, text, toggler, Column},
+    Alignment, Element, Length, Subscription, Task, Theme,
+};
+
+// zone to go back to when the public wifi mode is turned off
+const RESTORE_ZONE_STATE: &str = "firewall-restore-zone";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<FirewallService>),
+    SetActive(bool),
+    SetZone(String),
+    TogglePublicWifi(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct Firewall {
+    service: Option<FirewallService>,
+    restore_zone: Option<String>,
+}
+
+impl Default for Firewall {
+    fn default() -> Self {
+        Self {
+            service: None,
+            restore_zone: read_state(RESTORE_ZONE_STATE).filter(|zone| !zone.is_empty()),
+        }
+    }
+}
+
+impl Firewall {
+    pub fn update(&mut self, message: Message, config: &FirewallModuleConfig) -> Task<Message> {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                    }
+                    ServiceEvent::Error(_) => {}
+                }
+                Task::none()
+            }
+            Message::SetActive(active) => self.command(FirewallCommand::SetActive(active)),
+            Message::SetZone(zone) => self.command(FirewallCommand::SetZone(zone)),
+            Message::TogglePublicWifi(enable) => {
+                let Some(current) = self
+                    .service
+                    .as_ref()
+                    .and_then(|service| service.default_zone.clone())
+                else {
+                    return Task::none();
+                };
+
+                let zone = if enable {
+                    self.restore_zone = Some(current);
+                    config.hardened_zone.clone()
+                } else {
+                    self.restore_zone
+                        .take()
+                        .unwrap_or_else(|| config.restore_zone.clone())
+                };
+                write_state(
+                    RESTORE_ZONE_STATE,
+                    self.restore_zone.as_deref().unwrap_or_default(),
+                );
+
+                self.command(FirewallCommand::SetZone(zone))
+            }
+        }
+    }
+
+    fn command(&mut self, command: FirewallCommand) -> Task<Message> {
+        match self.service.as_mut() {
+            Some(service) => service.command(command).map(Message::Event),
+            None => Task::none(),
+        }
+    }
+
+    fn is_hardened(&self, config: &FirewallModuleConfig) -> bool {
+        self.service
+            .as_ref()
+            .is_some_and(|service| service.default_zone.as_ref() == Some(&config.hardened_zone))
+    }
+
+    pub fn menu_view(&self, config: &FirewallModuleConfig) -> Element<Message> {
+        let Some(service) = self.service.as_ref() else {
+            return text(tr("firewall-unavailable")).into();
+        };
+
+        let status = row!(
+            column!(
+                text(match service.backend {
+                    FirewallBackend::Firewalld => "firewalld",
+                    FirewallBackend::Ufw => "ufw",
+                }),
+                text(if service.active {
+                    tr("firewall-active")
+                } else {
+                    tr("firewall-inactive")
+                })
+                .size(12),
+            )
+            .spacing(4)
+            .width(Length::Fill),
+            toggler(service.active)
+                .on_toggle(Message::SetActive)
+                .width(Length::Shrink),
+        )
+        .align_y(Alignment::Center);
+
+        if service.backend != FirewallBackend::Firewalld || !service.active {
+            return status.into();
+        }
+
+        column!(
+            status,
+            horizontal_rule(1),
+            row!(
+                text(tr("public-wifi-mode")).width(Length::Fill),
+                toggler(self.is_hardened(config))
+                    .on_toggle(Message::TogglePublicWifi)
+                    .width(Length::Shrink),
+            )
+            .align_y(Alignment::Center),
+            horizontal_rule(1),
+            Column::with_children(
+                service
+                    .zones
+                    .iter()
+                    .map(|zone| {
+                        let is_default = service.default_zone.as_ref() == Some(zone);
+
+                        button(container(text(zone.clone())).style(move |theme: &Theme| {
+                            container::Style {
+                                text_color: if is_default {
+                                    Some(theme.palette().success)
+                                } else {
+                                    None
+                                },
+                                ..Default::default()
+                            }
+                        }))
+                        .padding([4, 8])
+                        .width(Length::Fill)
+                        .style(GhostButtonStyle.into_style())
+                        .on_press_maybe((!is_default).then(|| Message::SetZone(zone.clone())))
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(4),
+        )
+        .spacing(12)
+        .into()
+    }
+}
+
+impl Module for Firewall {
+    type ViewData<'a> = &'a FirewallModuleConfig;
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let service = self.service.as_ref()?;
+        let active = service.active;
+
+        Some((
+            container(icon(if !active {
+                Icons::ShieldOff
+            } else if self.is_hardened(config) {
+                Icons::ShieldLock
+            } else {
+                Icons::ShieldCheck
+            }))
+            .style(move |theme: &Theme| container::Style {
+                text_color: if active {
+                    None
+                } else {
+                    Some(theme.palette().danger)
+                },
+                ..Default::default()
+            })
+            .into(),
+            Some(OnModulePress::ToggleMenu(MenuType::Firewall)),
+        ))
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        Some(
+            FirewallService::subscribe().map(|event| app::Message::Firewall(Message::Event(event))),
+        )
+    }
+}
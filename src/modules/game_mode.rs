@@ -0,0 +1,93 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::GameModeModuleConfig,
+    modules::settings,
+    services::{game_mode::GameModeService, ReadOnlyService, ServiceEvent},
+};
+use iced::{
+    widget::{row, text},
+    Alignment, Element, Subscription, Task,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<GameModeService>),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GameMode {
+    service: Option<GameModeService>,
+}
+
+impl GameMode {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &GameModeModuleConfig,
+    ) -> Task<app::Message> {
+        let was_active = self.is_active();
+
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+
+        // the notification daemon silences every application, ashell included
+        let active = self.is_active();
+        if config.suppress_notifications && active != was_active {
+            Task::done(app::Message::Settings(settings::Message::GameModeDnd(
+                active,
+            )))
+        } else {
+            Task::none()
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.service
+            .as_ref()
+            .is_some_and(|service| service.is_active())
+    }
+}
+
+impl Module for GameMode {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        self.service
+            .as_ref()
+            .filter(|service| service.is_active())
+            .map(|service| {
+                let mut content = row!(icon(Icons::GameMode))
+                    .align_y(Alignment::Center)
+                    .spacing(4);
+
+                if service.client_count > 1 {
+                    content = content.push(text(service.client_count));
+                }
+
+                (content.into(), None)
+            })
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        Some(
+            GameModeService::subscribe().map(|event| app::Message::GameMode(Message::Event(event))),
+        )
+    }
+}
@@ -0,0 +1,170 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::KdeConnectModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    services::{
+        kde_connect::{KdeConnectCommand, KdeConnectEvent, KdeConnectService, PhoneDevice},
+        portal::pick_file,
+        ReadOnlyService, Service, ServiceEvent,
+    },
+    style::SettingsButtonStyle,
+    utils::launcher::send_notification,
+};
+use iced::{
+    widget::{button, column, row, text, Column},
+    Alignment, Element, Length, Subscription, Task,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<KdeConnectService>),
+    Ring(String),
+    PickFile(String),
+    FilePicked(String, Option<String>),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct KdeConnect {
+    service: Option<KdeConnectService>,
+}
+
+impl KdeConnect {
+    pub fn update(&mut self, message: Message, config: &KdeConnectModuleConfig) -> Task<Message> {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(KdeConnectEvent::Notification(notification)) => {
+                        if config.forward_notifications {
+                            send_notification(
+                                format!("{} · {}", notification.app, notification.title),
+                                if notification.text.is_empty() {
+                                    notification.device
+                                } else {
+                                    notification.text
+                                },
+                            );
+                        }
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                        }
+                    }
+                    ServiceEvent::Error(_) => {}
+                }
+                Task::none()
+            }
+            Message::Ring(device) => self.command(KdeConnectCommand::Ring(device)),
+            Message::PickFile(device) => Task::perform(
+                async move { pick_file(&tr("send-file-to-phone")).await },
+                move |uri| Message::FilePicked(device, uri),
+            ),
+            Message::FilePicked(device, Some(uri)) => {
+                self.command(KdeConnectCommand::Share(device, uri))
+            }
+            Message::FilePicked(_, None) => Task::none(),
+        }
+    }
+
+    fn command(&mut self, command: KdeConnectCommand) -> Task<Message> {
+        match self.service.as_mut() {
+            Some(service) => service.command(command).map(Message::Event),
+            None => Task::none(),
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        let devices = self.service.as_ref().map(|service| service.as_slice());
+
+        match devices {
+            Some(devices) if !devices.is_empty() => {
+                Column::with_children(devices.iter().map(device_view).collect::<Vec<_>>())
+                    .spacing(12)
+                    .into()
+            }
+            _ => text(tr("no-paired-phones")).into(),
+        }
+    }
+}
+
+fn device_view(device: &PhoneDevice) -> Element<Message> {
+    let status = match (device.reachable, device.battery) {
+        (false, _) => tr("phone-disconnected"),
+        (true, None) => tr("phone-connected"),
+        (true, Some(battery)) => tr_args(
+            if battery.charging {
+                "phone-battery-charging"
+            } else {
+                "phone-battery"
+            },
+            &[("charge", battery.charge.to_string())],
+        ),
+    };
+
+    row!(
+        column!(text(device.name.clone()), text(status).size(12))
+            .spacing(4)
+            .width(Length::Fill),
+        button(icon(Icons::CellphoneSound))
+            .padding([4, 10])
+            .style(SettingsButtonStyle.into_style())
+            .on_press_maybe(device.reachable.then(|| Message::Ring(device.id.clone()))),
+        button(icon(Icons::FileSend))
+            .padding([4, 10])
+            .style(SettingsButtonStyle.into_style())
+            .on_press_maybe(
+                device
+                    .reachable
+                    .then(|| Message::PickFile(device.id.clone()))
+            ),
+    )
+    .align_y(Alignment::Center)
+    .spacing(8)
+    .into()
+}
+
+impl Module for KdeConnect {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let devices = self
+            .service
+            .as_ref()
+            .filter(|devices| !devices.is_empty())?;
+        let connected = devices.iter().find(|device| device.reachable);
+
+        Some((
+            row!(icon(if connected.is_some() {
+                Icons::Cellphone
+            } else {
+                Icons::CellphoneOff
+            }))
+            .push_maybe(
+                connected
+                    .and_then(|device| device.battery)
+                    .map(|battery| text(format!("{}%", battery.charge))),
+            )
+            .align_y(Alignment::Center)
+            .spacing(4)
+            .into(),
+            Some(OnModulePress::ToggleMenu(MenuType::KdeConnect)),
+        ))
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        Some(
+            KdeConnectService::subscribe()
+                .map(|event| app::Message::KdeConnect(Message::Event(event))),
+        )
+    }
+}
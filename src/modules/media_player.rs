@@ -1,17 +1,25 @@
-use std::{any::TypeId, ops::Not, process::Stdio, time::Duration};
+use std::{
+    any::TypeId,
+    ops::Not,
+    process::Stdio,
+    time::{Duration, Instant},
+};
 
 use super::{Module, OnModulePress};
 use crate::{
     app,
     components::icons::{icon, Icons},
     config::MediaPlayerModuleConfig,
+    i18n::{tr, tr_args},
     menu::MenuType,
-    style::SettingsButtonStyle,
+    services::global_shortcuts::{GlobalShortcuts, Shortcut},
+    style::{QuickSettingsButtonStyle, SettingsButtonStyle},
     utils::launcher::execute_command,
 };
 use iced::{
     stream::channel,
-    widget::{button, column, row, slider, text},
+    time::every,
+    widget::{button, column, horizontal_rule, row, slider, text, Row},
     Alignment::Center,
     Element, Subscription, Task,
 };
@@ -42,10 +50,13 @@ async fn get_current_song() -> Option<String> {
     }
 }
 
-async fn get_volume() -> Option<f64> {
+async fn get_volume(player: Option<&str>) -> Option<f64> {
     let get_volume_cmd = process::Command::new("bash")
         .arg("-c")
-        .arg("playerctl volume")
+        .arg(match player {
+            Some(player) => format!("playerctl --player={} volume", player),
+            None => "playerctl volume".to_owned(),
+        })
         .stdout(Stdio::piped())
         .output()
         .await;
@@ -75,10 +86,74 @@ async fn get_volume() -> Option<f64> {
     }
 }
 
+async fn get_players() -> Vec<String> {
+    match process::Command::new("playerctl")
+        .arg("--list-all")
+        .stdout(Stdio::piped())
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::trim)
+            .filter(|player| !player.is_empty())
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// the volume goes down to zero in the last minute of the sleep timer
+const SLEEP_FADE: Duration = Duration::from_secs(60);
+
+struct SleepTimer {
+    deadline: Instant,
+    // None pauses all the players
+    player: Option<String>,
+    // volume of each player before the fade, restored once they are paused.
+    // Empty while the volumes are being read
+    volumes: Option<Vec<(String, f64)>>,
+}
+
+impl SleepTimer {
+    fn playerctl(&self, command: &str) -> String {
+        match &self.player {
+            Some(player) => format!("playerctl --player={} {}", player, command),
+            None => format!("playerctl --all-players {}", command),
+        }
+    }
+
+    /// Sets the volume of every faded player to its volume before the fade, scaled by `fade`
+    fn set_volumes(&self, fade: f64) -> String {
+        self.volumes
+            .iter()
+            .flatten()
+            .map(|(player, volume)| {
+                format!(
+                    "playerctl --player={} volume {}",
+                    player,
+                    volume / 100.0 * fade
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn remaining_minutes(&self) -> u64 {
+        self.deadline
+            .saturating_duration_since(Instant::now())
+            .as_secs()
+            .div_ceil(60)
+    }
+}
+
 #[derive(Default)]
 pub struct MediaPlayer {
     song: Option<String>,
     volume: Option<f64>,
+    players: Vec<String>,
+    sleep_player: Option<String>,
+    sleep_timer: Option<SleepTimer>,
 }
 
 #[derive(Debug, Clone)]
@@ -89,6 +164,12 @@ pub enum Message {
     Next,
     SetVolume(Option<f64>),
     SyncVolume(Option<f64>),
+    SetPlayers(Vec<String>),
+    SelectSleepPlayer(Option<String>),
+    StartSleepTimer(u64),
+    CancelSleepTimer,
+    SleepTick,
+    SleepFadeVolumes(Vec<(String, f64)>),
 }
 
 impl MediaPlayer {
@@ -145,10 +226,182 @@ impl MediaPlayer {
                 self.volume = v;
                 Task::none()
             }
+            Message::SetPlayers(players) => {
+                if self
+                    .sleep_player
+                    .as_ref()
+                    .is_some_and(|player| !players.contains(player))
+                {
+                    self.sleep_player = None;
+                }
+                self.players = players;
+                Task::none()
+            }
+            Message::SelectSleepPlayer(player) => {
+                self.sleep_player = player;
+                Task::none()
+            }
+            Message::StartSleepTimer(minutes) => {
+                self.cancel_sleep_timer();
+                self.sleep_timer = Some(SleepTimer {
+                    deadline: Instant::now() + Duration::from_secs(minutes * 60),
+                    player: self.sleep_player.clone(),
+                    volumes: None,
+                });
+                Task::none()
+            }
+            Message::CancelSleepTimer => {
+                self.cancel_sleep_timer();
+                Task::none()
+            }
+            Message::SleepTick => {
+                let Some(timer) = &mut self.sleep_timer else {
+                    return Task::none();
+                };
+                let remaining = timer.deadline.saturating_duration_since(Instant::now());
+
+                if remaining.is_zero() {
+                    let restore = timer.set_volumes(1.0);
+                    let mut command = timer.playerctl("pause");
+                    if !restore.is_empty() {
+                        command = format!("{}; {}", command, restore);
+                    }
+                    execute_command(command);
+                    self.sleep_timer = None;
+                } else if config.sleep_timer_fade && remaining < SLEEP_FADE {
+                    if timer.volumes.is_none() {
+                        // the players can be at different volumes, each one fades from its own
+                        timer.volumes = Some(Vec::new());
+                        let players = match &timer.player {
+                            Some(player) => vec![player.clone()],
+                            None => self.players.clone(),
+                        };
+
+                        return Task::perform(
+                            async move {
+                                let mut volumes = Vec::with_capacity(players.len());
+                                for player in players {
+                                    if let Some(volume) = get_volume(Some(&player)).await {
+                                        volumes.push((player, volume));
+                                    }
+                                }
+                                volumes
+                            },
+                            |volumes| app::Message::MediaPlayer(Message::SleepFadeVolumes(volumes)),
+                        );
+                    }
+
+                    let fade = remaining.as_secs_f64() / SLEEP_FADE.as_secs_f64();
+                    let command = timer.set_volumes(fade);
+                    if !command.is_empty() {
+                        execute_command(command);
+                    }
+                }
+
+                Task::none()
+            }
+            Message::SleepFadeVolumes(volumes) => {
+                if let Some(timer) = &mut self.sleep_timer {
+                    timer.volumes = Some(volumes);
+                }
+
+                Task::none()
+            }
         }
     }
 
-    pub fn menu_view(&self) -> Element<Message> {
+    fn cancel_sleep_timer(&mut self) {
+        // the fade already started, the volume goes back to where it was
+        if let Some(timer) = self.sleep_timer.take() {
+            let restore = timer.set_volumes(1.0);
+            if !restore.is_empty() {
+                execute_command(restore);
+            }
+        }
+    }
+
+    /// Play/pause, next and previous registered through the portal,
+    /// they drive the active player like the menu buttons
+    pub fn global_shortcuts() -> Subscription<Message> {
+        let shortcut = |id, description, trigger: &str| Shortcut {
+            id,
+            description: tr(description),
+            preferred_trigger: Some(trigger.to_owned()),
+        };
+
+        GlobalShortcuts::subscribe(
+            "ashell_media",
+            vec![
+                shortcut("play-pause", "shortcut-play-pause", "XF86AudioPlay"),
+                shortcut("next", "shortcut-next-track", "XF86AudioNext"),
+                shortcut("previous", "shortcut-previous-track", "XF86AudioPrev"),
+            ],
+        )
+        .map(|id| match id {
+            "next" => Message::Next,
+            "previous" => Message::Prev,
+            _ => Message::Play,
+        })
+    }
+
+    fn sleep_timer_view(&self, config: &MediaPlayerModuleConfig) -> Element<Message> {
+        let status = match &self.sleep_timer {
+            Some(timer) => tr_args(
+                "sleep-timer-pausing",
+                &[("minutes", timer.remaining_minutes().to_string())],
+            ),
+            None => tr("sleep-timer"),
+        };
+
+        column![
+            row![icon(Icons::Sleep), text(status)]
+                .spacing(8)
+                .align_y(Center),
+            Row::with_children(config.sleep_timer_presets.iter().map(|minutes| {
+                button(text(tr_args(
+                    "sleep-timer-minutes",
+                    &[("minutes", minutes.to_string())],
+                )))
+                .padding([4, 12])
+                .style(SettingsButtonStyle.into_style())
+                .on_press(Message::StartSleepTimer(*minutes))
+                .into()
+            }))
+            .push_maybe(self.sleep_timer.as_ref().map(|_| {
+                button(text(tr("cancel")))
+                    .padding([4, 12])
+                    .style(SettingsButtonStyle.into_style())
+                    .on_press(Message::CancelSleepTimer)
+            }))
+            .spacing(8),
+        ]
+        .push_maybe((self.players.len() > 1).then(|| {
+            Row::with_children(
+                std::iter::once(None)
+                    .chain(self.players.iter().map(Some))
+                    .map(|player| {
+                        button(text(
+                            player
+                                .cloned()
+                                .unwrap_or_else(|| tr("sleep-timer-all-players")),
+                        ))
+                        .padding([4, 12])
+                        .style(
+                            QuickSettingsButtonStyle(self.sleep_player.as_ref() == player)
+                                .into_style(),
+                        )
+                        .on_press(Message::SelectSleepPlayer(player.cloned()))
+                        .into()
+                    }),
+            )
+            .spacing(8)
+        }))
+        .spacing(8)
+        .align_x(Center)
+        .into()
+    }
+
+    pub fn menu_view(&self, config: &MediaPlayerModuleConfig) -> Element<Message> {
         column![]
             .push_maybe(
                 self.volume
@@ -170,6 +423,8 @@ impl MediaPlayer {
                 ]
                 .spacing(8),
             )
+            .push(horizontal_rule(1))
+            .push(self.sleep_timer_view(config))
             .spacing(8)
             .align_x(Center)
             .into()
@@ -186,7 +441,22 @@ impl Module for MediaPlayer {
     ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
         self.song.clone().map(|s| {
             (
-                text(s).size(12).into(),
+                row![text(s).size(12)]
+                    .push_maybe(self.sleep_timer.as_ref().map(|timer| {
+                        row![
+                            icon(Icons::Sleep).size(12),
+                            text(tr_args(
+                                "sleep-timer-badge",
+                                &[("minutes", timer.remaining_minutes().to_string())],
+                            ))
+                            .size(12)
+                        ]
+                        .spacing(2)
+                        .align_y(Center)
+                    }))
+                    .spacing(8)
+                    .align_y(Center)
+                    .into(),
                 Some(OnModulePress::ToggleMenu(MenuType::MediaPlayer)),
             )
         })
@@ -195,19 +465,30 @@ impl Module for MediaPlayer {
     fn subscription(&self, (): Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
         let id = TypeId::of::<Self>();
 
+        let sleep_timer = if self.sleep_timer.is_some() {
+            every(Duration::from_secs(1)).map(|_| Message::SleepTick)
+        } else {
+            Subscription::none()
+        };
+
         Some(
-            Subscription::run_with_id(
-                id,
-                channel(10, |mut output| async move {
-                    loop {
-                        let song = get_current_song().await;
-                        let _ = output.try_send(Message::SetSong(song));
-                        let volume = get_volume().await;
-                        let _ = output.try_send(Message::SyncVolume(volume));
-                        sleep(Duration::from_secs(1)).await;
-                    }
-                }),
-            )
+            Subscription::batch(vec![
+                Subscription::run_with_id(
+                    id,
+                    channel(10, |mut output| async move {
+                        loop {
+                            let song = get_current_song().await;
+                            let _ = output.try_send(Message::SetSong(song));
+                            let volume = get_volume(None).await;
+                            let _ = output.try_send(Message::SyncVolume(volume));
+                            let players = get_players().await;
+                            let _ = output.try_send(Message::SetPlayers(players));
+                            sleep(Duration::from_secs(1)).await;
+                        }
+                    }),
+                ),
+                sleep_timer,
+            ])
             .map(app::Message::MediaPlayer),
         )
     }
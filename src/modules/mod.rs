@@ -1,29 +1,48 @@
 use crate::{
     app::{self, App, Message},
-    config::{ModuleDef, ModuleName},
+    config::{Direction, ModuleDef, ModuleName, ModuleSection},
     menu::MenuType,
+    modules::settings::SubMenu,
     position_button::position_button,
     style::{
-        module_first_label, module_label, module_last_label, module_middle_label, ModuleButtonStyle,
+        dragged_module, module_first_label, module_label, module_last_label, module_middle_label,
+        ModuleButtonStyle,
     },
 };
 use iced::{
-    widget::{container, row, Row},
+    widget::{container, mouse_area, row, Row},
     window::Id,
     Alignment, Element, Length, Subscription,
 };
 
 pub mod app_launcher;
+pub mod backup;
+pub mod break_reminder;
 pub mod clipboard;
 pub mod clock;
+pub mod config_editor;
+pub mod dock;
+pub mod emoji_picker;
+pub mod firewall;
+pub mod game_mode;
+pub mod kde_connect;
 pub mod keyboard_layout;
 pub mod keyboard_submap;
 pub mod media_player;
+pub mod notifications;
+pub mod ping;
+pub mod print_queue;
 pub mod privacy;
+pub mod removable_media;
+pub mod screen_capture;
+pub mod separator;
 pub mod settings;
+pub mod solar;
 pub mod system_info;
 pub mod tray;
 pub mod updates;
+pub mod virtual_machines;
+pub mod weather;
 pub mod window_title;
 pub mod workspaces;
 
@@ -31,6 +50,8 @@ pub mod workspaces;
 pub enum OnModulePress {
     Action(Message),
     ToggleMenu(MenuType),
+    // opens the settings menu, optionally with one of its submenus expanded
+    ToggleSettings(Option<SubMenu>),
 }
 
 pub trait Module {
@@ -56,17 +77,46 @@ enum ModuleGroupPosition {
 }
 
 impl App {
-    pub fn modules_section(&self, modules_def: &Vec<ModuleDef>, id: Id) -> Element<Message> {
+    pub fn modules_section(&self, section: ModuleSection, id: Id) -> Element<Message> {
         let mut row = row!()
             .height(Length::Shrink)
             .align_y(Alignment::Center)
             .spacing(4);
 
-        for module_def in modules_def {
-            row = row.push_maybe(match module_def {
+        let mut modules_def = self
+            .config
+            .modules
+            .section(section)
+            .iter()
+            .enumerate()
+            .collect::<Vec<_>>();
+        if self.config.appearance.direction == Direction::Rtl {
+            modules_def.reverse();
+        }
+
+        for (index, module_def) in modules_def {
+            let module = match module_def {
+                // drawn between the modules, without their background
+                ModuleDef::Single(module @ (ModuleName::Separator | ModuleName::Spacer(_))) => self
+                    .get_module_view(*module, id)
+                    .map(|(content, _)| content),
                 ModuleDef::Single(module) => self.single_module_wrapper(*module, id),
                 ModuleDef::Group(group) => self.group_module_wrapper(group, id),
-            });
+            };
+
+            // modules are rearranged dragging them with the middle mouse button
+            row = row.push_maybe(module.map(|module| {
+                let module: Element<Message> = if self.dragged_module == Some((section, index)) {
+                    container(module).style(dragged_module).into()
+                } else {
+                    module
+                };
+
+                mouse_area(module)
+                    .on_middle_press(Message::DragModule(section, index))
+                    .on_middle_release(Message::DropModule(section, index))
+                    .into()
+            }));
         }
 
         row.into()
@@ -107,6 +157,15 @@ impl App {
                             Message::ToggleMenu(menu_type.clone(), id, button_ui_ref)
                         })
                     }
+                    OnModulePress::ToggleSettings(sub_menu) => {
+                        button.on_press_with_position(move |button_ui_ref| {
+                            Message::Settings(settings::Message::ToggleMenu(
+                                id,
+                                button_ui_ref,
+                                sub_menu,
+                            ))
+                        })
+                    }
                 }
                 .into()
             } else {
@@ -121,10 +180,13 @@ impl App {
     }
 
     fn group_module_wrapper(&self, group: &[ModuleName], id: Id) -> Option<Element<Message>> {
-        let modules = group
+        let mut modules = group
             .iter()
             .filter_map(|module| self.get_module_view(*module, id))
             .collect::<Vec<_>>();
+        if self.config.appearance.direction == Direction::Rtl {
+            modules.reverse();
+        }
 
         let modules_len = modules.len();
 
@@ -177,6 +239,14 @@ impl App {
                                                 button_ui_ref,
                                             )
                                         }),
+                                    OnModulePress::ToggleSettings(sub_menu) => button
+                                        .on_press_with_position(move |button_ui_ref| {
+                                            Message::Settings(settings::Message::ToggleMenu(
+                                                id,
+                                                button_ui_ref,
+                                                sub_menu,
+                                            ))
+                                        }),
                                 }
                                 .into()
                             } else {
@@ -200,11 +270,33 @@ impl App {
         }
     }
 
+    /// View of a module drawn as a desktop widget, without the bar button
+    pub fn widget_view(&self, module_name: ModuleName, id: Id) -> Option<Element<Message>> {
+        self.get_module_view(module_name, id)
+            .map(|(content, _)| content)
+    }
+
+    pub fn widgets_subscriptions(&self) -> Vec<Subscription<Message>> {
+        self.config
+            .widgets
+            .iter()
+            .filter_map(|widget| self.get_module_subscription(widget.module))
+            .collect()
+    }
+
     fn get_module_view(
         &self,
         module_name: ModuleName,
         id: Id,
     ) -> Option<(Element<Message>, Option<OnModulePress>)> {
+        // while a game is running the compact bar keeps only the essential modules
+        if self.config.game_mode.compact_bar
+            && self.game_mode.is_active()
+            && !matches!(module_name, ModuleName::GameMode | ModuleName::Clock)
+        {
+            return None;
+        }
+
         match module_name {
             ModuleName::AppLauncher => self.app_launcher.view(&self.config.app_launcher_cmd),
             ModuleName::Updates => self.updates.view(&self.config.updates),
@@ -220,11 +312,32 @@ impl App {
             ModuleName::SystemInfo => self.system_info.view(&self.config.system),
             ModuleName::KeyboardLayout => self.keyboard_layout.view(()),
             ModuleName::KeyboardSubmap => self.keyboard_submap.view(()),
-            ModuleName::Tray => self.tray.view(id),
-            ModuleName::Clock => self.clock.view(&self.config.clock.format),
-            ModuleName::Privacy => self.privacy.view(()),
+            ModuleName::Tray => self.tray.view((id, &self.config.tray)),
+            ModuleName::Clock => self.clock.view(&self.config.clock),
+            ModuleName::Privacy => self.privacy.view(&self.config.privacy),
             ModuleName::Settings => self.settings.view(()),
+            ModuleName::Battery => self.settings.battery_view(),
+            ModuleName::Network => self.settings.network_view(&self.config.settings),
+            ModuleName::Bluetooth => self.settings.bluetooth_view(&self.config.settings),
+            ModuleName::Audio => self.settings.audio_view(&self.config.settings),
             ModuleName::MediaPlayer => self.media_player.view(()),
+            ModuleName::VirtualMachines => self.virtual_machines.view(()),
+            ModuleName::PrintQueue => self.print_queue.view(()),
+            ModuleName::Backup => self.backup.view(&self.config.backup),
+            ModuleName::RemovableMedia => self.removable_media.view(()),
+            ModuleName::GameMode => self.game_mode.view(()),
+            ModuleName::Solar => self.solar.view(()),
+            ModuleName::Dock => self.dock.view(&self.config.dock),
+            ModuleName::EmojiPicker => self.emoji_picker.view(()),
+            ModuleName::Ping => self.ping.view(&self.config.ping),
+            ModuleName::Firewall => self.firewall.view(&self.config.firewall),
+            ModuleName::KdeConnect => self.kde_connect.view(()),
+            ModuleName::BreakReminder => self.break_reminder.view(&self.config.break_reminder),
+            ModuleName::ScreenCapture => self.screen_capture.view(()),
+            ModuleName::Separator => separator::Separator.view(&self.config.separator),
+            ModuleName::Spacer(width) => Some((separator::spacer(width), None)),
+            ModuleName::Weather => self.weather.view(()),
+            ModuleName::Notifications => self.notifications.view(()),
         }
     }
 
@@ -243,10 +356,42 @@ impl App {
             ModuleName::KeyboardLayout => self.keyboard_layout.subscription(()),
             ModuleName::KeyboardSubmap => self.keyboard_submap.subscription(()),
             ModuleName::Tray => self.tray.subscription(()),
-            ModuleName::Clock => self.clock.subscription(()),
-            ModuleName::Privacy => self.privacy.subscription(()),
-            ModuleName::Settings => self.settings.subscription(()),
+            ModuleName::Clock => self.clock.subscription(&self.config.clock),
+            ModuleName::Privacy => self.privacy.subscription(&self.config.privacy),
+            // the standalone indicators share the services of the settings module
+            ModuleName::Settings
+            | ModuleName::Battery
+            | ModuleName::Network
+            | ModuleName::Bluetooth
+            | ModuleName::Audio => self.settings.subscription((
+                &self.config.settings,
+                self.outputs.is_menu_open(MenuType::Settings),
+            )),
             ModuleName::MediaPlayer => self.media_player.subscription(()),
+            ModuleName::VirtualMachines => self
+                .virtual_machines
+                .subscription(&self.config.virtual_machines),
+            ModuleName::PrintQueue => self.print_queue.subscription(&self.config.print_queue),
+            ModuleName::Backup => self
+                .config
+                .backup
+                .as_ref()
+                .and_then(|backup_config| self.backup.subscription(backup_config)),
+            ModuleName::RemovableMedia => self.removable_media.subscription(()),
+            ModuleName::GameMode => self.game_mode.subscription(()),
+            ModuleName::Solar => self.solar.subscription(&self.config.solar),
+            ModuleName::Dock => self.dock.subscription(()),
+            ModuleName::EmojiPicker => self.emoji_picker.subscription(()),
+            ModuleName::Ping => self.ping.subscription(&self.config.ping),
+            ModuleName::Firewall => self.firewall.subscription(()),
+            ModuleName::KdeConnect => self.kde_connect.subscription(()),
+            ModuleName::BreakReminder => self
+                .break_reminder
+                .subscription(&self.config.break_reminder),
+            ModuleName::ScreenCapture => self.screen_capture.subscription(()),
+            ModuleName::Separator | ModuleName::Spacer(_) => None,
+            ModuleName::Weather => self.weather.subscription(&self.config.weather),
+            ModuleName::Notifications => self.notifications.subscription(()),
         }
     }
 }
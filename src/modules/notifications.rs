@@ -0,0 +1,200 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::NotificationsModuleConfig,
+    i18n::tr,
+    menu::MenuType,
+    services::{
+        notifications::{
+            is_dnd, Notification, NotificationsCommand, NotificationsEvent, NotificationsService,
+            Urgency,
+        },
+        ReadOnlyService, Service, ServiceEvent,
+    },
+    style::{GhostButtonStyle, SettingsButtonStyle},
+};
+use iced::{
+    widget::{button, column, container, horizontal_rule, row, scrollable, text, Column},
+    Alignment, Element, Length, Subscription, Task, Theme,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<NotificationsService>),
+    Dismiss(u32),
+    ClearAll,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Notifications {
+    service: Option<NotificationsService>,
+}
+
+impl Notifications {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &NotificationsModuleConfig,
+    ) -> Task<Message> {
+        match message {
+            Message::Event(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.service = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(service) = self.service.as_mut() {
+                            service.update(data);
+                            self.trim_history(config.max_history);
+                        }
+                    }
+                    ServiceEvent::Error(_) => {}
+                }
+                Task::none()
+            }
+            Message::Dismiss(id) => self.command(NotificationsCommand::Dismiss(id)),
+            Message::ClearAll => self.command(NotificationsCommand::ClearAll),
+        }
+    }
+
+    fn command(&mut self, command: NotificationsCommand) -> Task<Message> {
+        match self.service.as_mut() {
+            Some(service) => service.command(command).map(Message::Event),
+            None => Task::none(),
+        }
+    }
+
+    /// Forgets the oldest notifications beyond the configured history size
+    fn trim_history(&mut self, max_history: usize) {
+        let Some(service) = self.service.as_mut() else {
+            return;
+        };
+
+        let mut ids = service
+            .notifications
+            .iter()
+            .map(|(id, notification)| (notification.received, *id))
+            .collect::<Vec<_>>();
+        if ids.len() <= max_history {
+            return;
+        }
+        ids.sort();
+
+        let excess = ids.len() - max_history;
+        for (_, id) in ids.into_iter().take(excess) {
+            service.update(NotificationsEvent::Dismissed(id));
+        }
+    }
+
+    /// Opening the history marks its notifications as seen
+    pub fn mark_read(&mut self) {
+        if let Some(service) = self.service.as_mut() {
+            service.update(NotificationsEvent::Read);
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        let mut notifications = self
+            .service
+            .as_ref()
+            .map(|service| service.notifications.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        if notifications.is_empty() {
+            return container(text(tr("no-notifications")))
+                .padding([8, 8])
+                .into();
+        }
+        notifications.sort_by(|(_, a), (_, b)| b.received.cmp(&a.received));
+
+        column!(
+            row!(
+                text(tr("notifications")).width(Length::Fill),
+                button(text(tr("clear-all")))
+                    .padding([4, 12])
+                    .style(GhostButtonStyle.into_style())
+                    .on_press(Message::ClearAll),
+            )
+            .align_y(Alignment::Center),
+            horizontal_rule(1),
+            container(scrollable(
+                Column::with_children(
+                    notifications
+                        .into_iter()
+                        .map(|(id, notification)| notification_view(*id, notification))
+                        .collect::<Vec<_>>()
+                )
+                .spacing(12)
+            ))
+            .max_height(400),
+        )
+        .spacing(8)
+        .into()
+    }
+}
+
+fn notification_view(id: u32, notification: &Notification) -> Element<Message> {
+    let critical = notification.urgency == Urgency::Critical;
+
+    row!(
+        column!(
+            text(format!(
+                "{} · {}",
+                notification.app_name,
+                notification.received.format("%R")
+            ))
+            .size(10),
+            text(notification.summary.clone()).style(move |theme: &Theme| text::Style {
+                color: critical.then(|| theme.palette().danger),
+            }),
+        )
+        .push_maybe(
+            (!notification.body.is_empty()).then(|| text(notification.body.clone()).size(12))
+        )
+        .spacing(4)
+        .width(Length::Fill),
+        button(icon(Icons::Close))
+            .padding([4, 10])
+            .style(SettingsButtonStyle.into_style())
+            .on_press(Message::Dismiss(id)),
+    )
+    .align_y(Alignment::Center)
+    .spacing(8)
+    .into()
+}
+
+impl Module for Notifications {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let unread = self.service.as_ref()?.unread();
+
+        let content = if unread > 0 {
+            row!(icon(Icons::BellBadge), text(unread))
+                .align_y(Alignment::Center)
+                .spacing(4)
+                .into()
+        } else if is_dnd() {
+            icon(Icons::BellOff).into()
+        } else {
+            icon(Icons::Bell).into()
+        };
+
+        Some((
+            content,
+            Some(OnModulePress::ToggleMenu(MenuType::Notifications)),
+        ))
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        Some(
+            NotificationsService::subscribe()
+                .map(|event| app::Message::Notifications(Message::Event(event))),
+        )
+    }
+}
@@ -0,0 +1,283 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::PingModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    utils::{launcher::send_notification, IndicatorState},
+};
+use iced::{
+    stream::channel,
+    widget::{column, container, row, text, Row, Space},
+    Alignment, Color, Element, Length, Subscription, Theme,
+};
+use log::error;
+use std::{any::TypeId, collections::VecDeque, process::Stdio, time::Duration};
+use tokio::{process, time::sleep};
+
+// samples drawn in the menu sparkline
+const HISTORY: usize = 40;
+// the packet loss is measured on the last samples only,
+// so that the alert goes away soon after the connection recovers
+const LOSS_WINDOW: usize = 10;
+const SPARKLINE_HEIGHT: f32 = 40.;
+
+/// Round trip time in milliseconds, None when the packet was lost
+async fn ping(host: &str) -> Option<f32> {
+    let ping_cmd = process::Command::new("ping")
+        .args(["-n", "-c", "1", "-W", "2", host])
+        .env("LC_ALL", "C")
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match ping_cmd {
+        Ok(ping_cmd) => parse_latency(&String::from_utf8_lossy(&ping_cmd.stdout)),
+        Err(e) => {
+            error!("Error: {:?}", e);
+            None
+        }
+    }
+}
+
+// e.g. 64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms
+fn parse_latency(output: &str) -> Option<f32> {
+    output
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("time="))?
+        .parse()
+        .ok()
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Pinged(Option<f32>),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Ping {
+    history: VecDeque<Option<f32>>,
+    loss_alerted: bool,
+}
+
+impl Ping {
+    pub fn update(&mut self, message: Message, config: &PingModuleConfig) {
+        match message {
+            Message::Pinged(latency) => {
+                if self.history.len() == HISTORY {
+                    self.history.pop_front();
+                }
+                self.history.push_back(latency);
+
+                let Some(loss) = self.packet_loss() else {
+                    return;
+                };
+                if loss >= config.loss_alert && !self.loss_alerted {
+                    send_notification(
+                        tr("packet-loss"),
+                        tr_args(
+                            "packet-loss-body",
+                            &[("host", config.host.clone()), ("loss", loss.to_string())],
+                        ),
+                    );
+                }
+                self.loss_alerted = loss >= config.loss_alert;
+            }
+        }
+    }
+
+    /// Percentage of the lost packets among the last samples,
+    /// None until there are enough of them
+    fn packet_loss(&self) -> Option<u32> {
+        if self.history.len() < LOSS_WINDOW {
+            return None;
+        }
+
+        let lost = self
+            .history
+            .iter()
+            .rev()
+            .take(LOSS_WINDOW)
+            .filter(|latency| latency.is_none())
+            .count();
+
+        Some((lost * 100 / LOSS_WINDOW) as u32)
+    }
+
+    fn state(latency: Option<f32>, config: &PingModuleConfig) -> IndicatorState {
+        match latency {
+            Some(latency) => IndicatorState::from_thresholds(
+                latency,
+                config.warn_threshold as f32,
+                config.alert_threshold as f32,
+            ),
+            None => IndicatorState::Danger,
+        }
+    }
+
+    pub fn menu_view(&self, config: &PingModuleConfig) -> Element<Message> {
+        let received = self.history.iter().flatten().copied().collect::<Vec<_>>();
+        let max = received.iter().copied().fold(0., f32::max);
+        let stats = if received.is_empty() {
+            tr("no-ping-replies")
+        } else {
+            tr_args(
+                "ping-stats",
+                &[
+                    (
+                        "min",
+                        format!("{:.0}", received.iter().copied().fold(f32::MAX, f32::min)),
+                    ),
+                    (
+                        "avg",
+                        format!(
+                            "{:.0}",
+                            received.iter().sum::<f32>() / received.len() as f32
+                        ),
+                    ),
+                    ("max", format!("{:.0}", max)),
+                ],
+            )
+        };
+
+        let sparkline = Row::with_children(
+            self.history
+                .iter()
+                .map(|latency| {
+                    let state = Self::state(*latency, config);
+                    let height = latency.map_or(SPARKLINE_HEIGHT, |latency| {
+                        (latency / max.max(1.) * SPARKLINE_HEIGHT).max(2.)
+                    });
+
+                    container(
+                        container(Space::new(Length::Fill, Length::Fixed(height))).style(
+                            move |theme: &Theme| container::Style {
+                                background: Some(state_color(theme, state).into()),
+                                ..Default::default()
+                            },
+                        ),
+                    )
+                    .width(Length::Fill)
+                    .height(Length::Fixed(SPARKLINE_HEIGHT))
+                    .align_bottom(Length::Fixed(SPARKLINE_HEIGHT))
+                    .into()
+                })
+                .collect::<Vec<Element<Message>>>(),
+        )
+        .spacing(2);
+
+        column!(
+            row!(
+                text(config.host.clone()).width(Length::Fill),
+                text(self.packet_loss().map_or_else(String::new, |loss| {
+                    tr_args("packet-loss-percent", &[("loss", loss.to_string())])
+                }))
+                .size(12),
+            )
+            .align_y(Alignment::Center),
+            sparkline,
+            text(stats).size(12),
+        )
+        .spacing(8)
+        .into()
+    }
+}
+
+fn state_color(theme: &Theme, state: IndicatorState) -> Color {
+    match state {
+        IndicatorState::Warning => theme.extended_palette().danger.weak.color,
+        IndicatorState::Danger => theme.palette().danger,
+        _ => theme.palette().success,
+    }
+}
+
+impl Module for Ping {
+    type ViewData<'a> = &'a PingModuleConfig;
+    type SubscriptionData<'a> = &'a PingModuleConfig;
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let latency = *self.history.back()?;
+        let state = Self::state(latency, config);
+
+        Some((
+            container(
+                row!(
+                    icon(if latency.is_some() {
+                        Icons::LanPending
+                    } else {
+                        Icons::LanDisconnect
+                    }),
+                    text(latency.map_or_else(
+                        || "—".to_string(),
+                        |latency| { format!("{:.0} ms", latency) }
+                    )),
+                )
+                .push_maybe(state.badge().map(|badge| icon(badge).size(10)))
+                .align_y(Alignment::Center)
+                .spacing(4),
+            )
+            .style(move |theme: &Theme| container::Style {
+                text_color: match state {
+                    IndicatorState::Normal | IndicatorState::Success => None,
+                    state => Some(state_color(theme, state)),
+                },
+                ..Default::default()
+            })
+            .into(),
+            Some(OnModulePress::ToggleMenu(MenuType::Ping)),
+        ))
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let id = TypeId::of::<Self>();
+        let host = config.host.clone();
+        let interval = config.interval;
+
+        Some(
+            Subscription::run_with_id(
+                format!("{:?}-{}-{}", id, host, interval),
+                channel(10, move |mut output| async move {
+                    loop {
+                        let latency = ping(&host).await;
+
+                        let _ = output.try_send(Message::Pinged(latency));
+
+                        sleep(Duration::from_secs(interval)).await;
+                    }
+                }),
+            )
+            .map(app::Message::Ping),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_round_trip_time() {
+        let output = "PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.\n\
+            64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms\n";
+
+        assert_eq!(parse_latency(output), Some(12.3));
+        assert_eq!(parse_latency("1 packets transmitted, 0 received"), None);
+    }
+
+    #[test]
+    fn packet_loss_uses_the_last_samples() {
+        let mut ping = Ping::default();
+        ping.history.extend([None; 5]);
+        assert_eq!(ping.packet_loss(), None);
+
+        ping.history.extend([Some(10.); 8]);
+        assert_eq!(ping.packet_loss(), Some(20));
+    }
+}
@@ -0,0 +1,293 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::PrintQueueModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    style::{GhostButtonStyle, SettingsButtonStyle},
+};
+use iced::{
+    stream::channel,
+    widget::{button, column, container, horizontal_rule, row, text, Column},
+    Alignment, Element, Length, Subscription, Task, Theme,
+};
+use log::error;
+use std::{any::TypeId, process::Stdio, time::Duration};
+use tokio::{process, time::sleep};
+
+#[derive(Debug, Clone)]
+pub struct PrintJob {
+    pub id: String,
+    pub printer: String,
+    pub user: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Printer {
+    pub name: String,
+    pub paused: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PrintQueueData {
+    pub jobs: Vec<PrintJob>,
+    pub printers: Vec<Printer>,
+}
+
+// the CUPS client tools translate their output, the parsing expects the C locale
+async fn lpstat(arg: &str) -> Option<String> {
+    let lpstat_cmd = process::Command::new("lpstat")
+        .arg(arg)
+        .env("LC_ALL", "C")
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match lpstat_cmd {
+        Ok(lpstat_cmd) => Some(String::from_utf8_lossy(&lpstat_cmd.stdout).into_owned()),
+        Err(e) => {
+            error!("Error: {:?}", e);
+            None
+        }
+    }
+}
+
+async fn get_print_queue() -> PrintQueueData {
+    let jobs = lpstat("-o")
+        .await
+        .map(|output| {
+            output
+                .lines()
+                .filter_map(|line| {
+                    let mut data = line.split_whitespace();
+                    let id = data.next()?;
+                    let user = data.next()?;
+                    let size = data.next().and_then(|s| s.parse::<u64>().ok())?;
+                    let printer = id.rsplit_once('-').map_or(id, |(printer, _)| printer);
+
+                    Some(PrintJob {
+                        id: id.to_string(),
+                        printer: printer.to_string(),
+                        user: user.to_string(),
+                        size,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let printers = lpstat("-p")
+        .await
+        .map(|output| {
+            output
+                .lines()
+                .filter_map(|line| {
+                    let name = line.strip_prefix("printer ")?.split_whitespace().next()?;
+
+                    Some(Printer {
+                        name: name.to_string(),
+                        paused: line.contains("disabled"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    PrintQueueData { jobs, printers }
+}
+
+async fn run_cups_command(cmd: &str, arg: &str) {
+    let res = process::Command::new(cmd)
+        .arg(arg)
+        .env("LC_ALL", "C")
+        .output()
+        .await;
+
+    if let Err(e) = res {
+        error!("Failed to execute {} {}: {:?}", cmd, arg, e);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    QueueUpdated(PrintQueueData),
+    CancelJob(String),
+    ResumePrinter(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PrintQueue {
+    data: PrintQueueData,
+}
+
+impl PrintQueue {
+    pub fn update(&mut self, message: Message) -> Task<crate::app::Message> {
+        match message {
+            Message::QueueUpdated(data) => {
+                self.data = data;
+
+                Task::none()
+            }
+            Message::CancelJob(id) => Task::perform(
+                async move {
+                    run_cups_command("cancel", &id).await;
+                    get_print_queue().await
+                },
+                |data| app::Message::PrintQueue(Message::QueueUpdated(data)),
+            ),
+            Message::ResumePrinter(name) => Task::perform(
+                async move {
+                    run_cups_command("cupsenable", &name).await;
+                    get_print_queue().await
+                },
+                |data| app::Message::PrintQueue(Message::QueueUpdated(data)),
+            ),
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        let paused_printers = self
+            .data
+            .printers
+            .iter()
+            .filter(|p| p.paused)
+            .collect::<Vec<_>>();
+
+        let jobs: Element<Message> = if self.data.jobs.is_empty() {
+            text(tr("no-print-jobs")).into()
+        } else {
+            Column::with_children(
+                self.data
+                    .jobs
+                    .iter()
+                    .map(|job| {
+                        row!(
+                            column!(
+                                text(job.id.clone()),
+                                text(format!(
+                                    "{} · {} · {} KiB",
+                                    job.printer,
+                                    job.user,
+                                    job.size / 1024
+                                ))
+                                .size(10)
+                            )
+                            .spacing(4)
+                            .width(Length::Fill),
+                            button(icon(Icons::Close))
+                                .padding([4, 10])
+                                .on_press(Message::CancelJob(job.id.clone()))
+                                .style(SettingsButtonStyle.into_style()),
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(8)
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8)
+            .into()
+        };
+
+        if paused_printers.is_empty() {
+            jobs
+        } else {
+            column!(
+                jobs,
+                horizontal_rule(1),
+                Column::with_children(
+                    paused_printers
+                        .into_iter()
+                        .map(|printer| {
+                            button(
+                                row!(
+                                    icon(Icons::PrinterAlert),
+                                    text(tr_args(
+                                        "resume-printer",
+                                        &[("printer", printer.name.clone())]
+                                    ))
+                                )
+                                .spacing(16),
+                            )
+                            .padding([4, 12])
+                            .on_press(Message::ResumePrinter(printer.name.clone()))
+                            .width(Length::Fill)
+                            .style(GhostButtonStyle.into_style())
+                            .into()
+                        })
+                        .collect::<Vec<Element<Message>>>(),
+                )
+                .spacing(4)
+            )
+            .spacing(12)
+            .into()
+        }
+    }
+}
+
+impl Module for PrintQueue {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = &'a PrintQueueModuleConfig;
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let paused = self.data.printers.iter().any(|p| p.paused);
+
+        if self.data.jobs.is_empty() && !paused {
+            None
+        } else {
+            Some((
+                container(
+                    row!(
+                        icon(if paused {
+                            Icons::PrinterAlert
+                        } else {
+                            Icons::Printer
+                        }),
+                        text(self.data.jobs.len())
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(4),
+                )
+                .style(move |theme: &Theme| container::Style {
+                    text_color: if paused {
+                        Some(theme.extended_palette().danger.weak.color)
+                    } else {
+                        None
+                    },
+                    ..Default::default()
+                })
+                .into(),
+                Some(OnModulePress::ToggleMenu(MenuType::PrintQueue)),
+            ))
+        }
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let id = TypeId::of::<Self>();
+        let interval = config.interval;
+
+        Some(
+            Subscription::run_with_id(
+                format!("{:?}-{}", id, interval),
+                channel(10, move |mut output| async move {
+                    loop {
+                        let data = get_print_queue().await;
+
+                        let _ = output.try_send(Message::QueueUpdated(data));
+
+                        sleep(Duration::from_secs(interval)).await;
+                    }
+                }),
+            )
+            .map(app::Message::PrintQueue),
+        )
+    }
+}
@@ -2,21 +2,48 @@ use super::{Module, OnModulePress};
 use crate::{
     app,
     components::icons::{icon, Icons},
-    services::{privacy::PrivacyService, ReadOnlyService, ServiceEvent},
+    config::PrivacyModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    services::{
+        privacy::{CaptureDevice, CaptureSession, PrivacyService},
+        ReadOnlyService, ServiceEvent,
+    },
+    style::GhostButtonStyle,
 };
+use chrono::Local;
+use hyprland::event_listener::AsyncEventListener;
 use iced::{
-    widget::{container, Row},
-    Alignment, Element, Subscription, Task,
+    stream::channel,
+    widget::{button, column, container, horizontal_rule, row, text, Column, Row},
+    Alignment, Element, Length, Subscription, Task, Theme,
 };
+use log::{debug, error, info};
+use std::{
+    any::TypeId,
+    sync::{Arc, RwLock},
+};
+use tokio::process;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreencastSource {
+    Monitor,
+    Window,
+}
 
 #[derive(Debug, Clone)]
 pub enum PrivacyMessage {
     Event(ServiceEvent<PrivacyService>),
+    ClearHistory,
+    Screencast(Option<ScreencastSource>),
+    EndScreenShare,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct Privacy {
     pub service: Option<PrivacyService>,
+    // what the compositor is casting, reported by Hyprland
+    screencast: Option<ScreencastSource>,
 }
 
 impl Privacy {
@@ -35,49 +62,262 @@ impl Privacy {
                 }
                 ServiceEvent::Error(_) => Task::none(),
             },
+            PrivacyMessage::ClearHistory => {
+                if let Some(privacy) = self.service.as_mut() {
+                    privacy.clear_history();
+                }
+                Task::none()
+            }
+            PrivacyMessage::Screencast(source) => {
+                self.screencast = source;
+                Task::none()
+            }
+            PrivacyMessage::EndScreenShare => {
+                let nodes = self
+                    .service
+                    .as_ref()
+                    .map(|service| service.screenshare_nodes())
+                    .unwrap_or_default();
+
+                Task::perform(end_screen_share(nodes), |_| {
+                    crate::app::Message::Privacy(PrivacyMessage::Screencast(None))
+                })
+            }
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<PrivacyMessage> {
+        let sessions = self
+            .service
+            .as_ref()
+            .map(|service| service.sessions().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        column!(
+            row!(
+                text(tr("recently-accessed")).width(Length::Fill),
+                button(text(tr("clear")))
+                    .padding([4, 12])
+                    .style(GhostButtonStyle.into_style())
+                    .on_press_maybe(
+                        sessions
+                            .iter()
+                            .any(|session| session.end.is_some())
+                            .then_some(PrivacyMessage::ClearHistory)
+                    ),
+            )
+            .align_y(Alignment::Center),
+            horizontal_rule(1),
+            if sessions.is_empty() {
+                Element::from(text(tr("no-capture-sessions")))
+            } else {
+                Column::with_children(sessions.into_iter().map(session_view))
+                    .spacing(8)
+                    .into()
+            },
+        )
+        .spacing(8)
+        .into()
+    }
+}
+
+/// Destroys the screen cast streams of the applications, the portal
+/// session can only be closed by the application that started it
+async fn end_screen_share(nodes: Vec<u32>) {
+    for node in nodes {
+        info!("Ending screen share stream {}", node);
+
+        let res = process::Command::new("pw-cli")
+            .args(["destroy", &node.to_string()])
+            .output()
+            .await;
+
+        if let Err(e) = res {
+            error!(
+                "Failed to destroy the screen share stream {}: {:?}",
+                node, e
+            );
         }
     }
 }
 
+fn session_view<'a>(session: &CaptureSession) -> Element<'a, PrivacyMessage> {
+    let ongoing = session.end.is_none();
+    let duration = session.end.unwrap_or_else(Local::now) - session.start;
+    let seconds = duration.num_seconds().max(0);
+    let duration = if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3600 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}h {}m", seconds / 3600, seconds / 60 % 60)
+    };
+
+    container(
+        row!(
+            icon(match session.device {
+                CaptureDevice::Microphone => Icons::Mic1,
+                CaptureDevice::Camera => Icons::Webcam,
+                CaptureDevice::Screen => Icons::ScreenShare,
+            }),
+            column!(
+                text(if session.app.is_empty() {
+                    tr("unknown-app")
+                } else {
+                    session.app.clone()
+                }),
+                text(tr_args(
+                    if ongoing {
+                        "capture-ongoing"
+                    } else {
+                        "capture-ended"
+                    },
+                    &[
+                        ("start", session.start.format("%H:%M").to_string()),
+                        ("duration", duration),
+                    ],
+                ))
+                .size(12),
+            )
+            .spacing(4),
+        )
+        .align_y(Alignment::Center)
+        .spacing(12),
+    )
+    .style(move |theme: &Theme| container::Style {
+        text_color: if ongoing {
+            Some(theme.extended_palette().danger.weak.color)
+        } else {
+            None
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
 impl Module for Privacy {
-    type ViewData<'a> = ();
-    type SubscriptionData<'a> = ();
+    type ViewData<'a> = &'a PrivacyModuleConfig;
+    type SubscriptionData<'a> = &'a PrivacyModuleConfig;
 
     fn view(
         &self,
-        _: Self::ViewData<'_>,
+        config: Self::ViewData<'_>,
     ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
-        if let Some(service) = self.service.as_ref() {
-            if !service.no_access() {
-                Some((
-                    container(
-                        Row::new()
-                            .push_maybe(
-                                service
-                                    .screenshare_access()
-                                    .then(|| icon(Icons::ScreenShare)),
-                            )
-                            .push_maybe(service.webcam_access().then(|| icon(Icons::Webcam)))
-                            .push_maybe(service.microphone_access().then(|| icon(Icons::Mic1)))
-                            .align_y(Alignment::Center)
-                            .spacing(8),
+        let service = self.service.as_ref()?;
+
+        if !service.no_access() {
+            let screenshare_chip =
+                (config.screenshare_chip && service.screenshare_access()).then(|| {
+                    let apps = service
+                        .screenshare_apps()
+                        .into_iter()
+                        .filter(|app| !app.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let source = match self.screencast {
+                        Some(ScreencastSource::Monitor) => tr("screencast-monitor"),
+                        Some(ScreencastSource::Window) => tr("screencast-window"),
+                        None => tr("screencast"),
+                    };
+
+                    row!(
+                        text(if apps.is_empty() {
+                            source
+                        } else {
+                            format!("{} · {}", source, apps)
+                        }),
+                        button(icon(Icons::Close).size(10))
+                            .padding([2, 4])
+                            .style(GhostButtonStyle.into_style())
+                            .on_press(app::Message::Privacy(PrivacyMessage::EndScreenShare)),
                     )
-                    .style(|theme| container::Style {
-                        text_color: Some(theme.extended_palette().danger.weak.color),
-                        ..Default::default()
-                    })
-                    .into(),
-                    None,
-                ))
-            } else {
-                None
-            }
+                    .align_y(Alignment::Center)
+                    .spacing(4)
+                });
+
+            Some((
+                container(
+                    Row::new()
+                        .push_maybe(
+                            service
+                                .screenshare_access()
+                                .then(|| icon(Icons::ScreenShare)),
+                        )
+                        .push_maybe(screenshare_chip)
+                        .push_maybe(service.webcam_access().then(|| icon(Icons::Webcam)))
+                        .push_maybe(service.microphone_access().then(|| icon(Icons::Mic1)))
+                        .align_y(Alignment::Center)
+                        .spacing(8),
+                )
+                .style(|theme| container::Style {
+                    text_color: Some(theme.extended_palette().danger.weak.color),
+                    ..Default::default()
+                })
+                .into(),
+                Some(OnModulePress::ToggleMenu(MenuType::Privacy)),
+            ))
+        } else if service.sessions().next().is_some() {
+            // nothing is capturing, the recent accesses are still one click away
+            Some((
+                icon(Icons::History).into(),
+                Some(OnModulePress::ToggleMenu(MenuType::Privacy)),
+            ))
         } else {
             None
         }
     }
 
-    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
-        Some(PrivacyService::subscribe().map(|e| app::Message::Privacy(PrivacyMessage::Event(e))))
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let service =
+            PrivacyService::subscribe().map(|e| app::Message::Privacy(PrivacyMessage::Event(e)));
+
+        Some(if config.screenshare_chip {
+            Subscription::batch(vec![service, screencast_subscription()])
+        } else {
+            service
+        })
     }
 }
+
+fn screencast_subscription() -> Subscription<app::Message> {
+    let id = TypeId::of::<ScreencastSource>();
+
+    Subscription::run_with_id(
+        id,
+        channel(10, |output| async move {
+            let output = Arc::new(RwLock::new(output));
+            loop {
+                let mut event_listener = AsyncEventListener::new();
+
+                event_listener.add_screencast_handler({
+                    let output = output.clone();
+                    move |screencast| {
+                        debug!("screencast changed: {:?}", screencast);
+                        let output = output.clone();
+                        Box::pin(async move {
+                            if let Ok(mut output) = output.write() {
+                                let _ = output.try_send(PrivacyMessage::Screencast(
+                                    screencast.turning_on.then_some(if screencast.is_monitor {
+                                        ScreencastSource::Monitor
+                                    } else {
+                                        ScreencastSource::Window
+                                    }),
+                                ));
+                            }
+                        })
+                    }
+                });
+
+                let res = event_listener.start_listener_async().await;
+
+                if let Err(e) = res {
+                    error!("restarting screencast listener due to error: {:?}", e);
+                }
+            }
+        }),
+    )
+    .map(app::Message::Privacy)
+}
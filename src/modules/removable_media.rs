@@ -0,0 +1,174 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::RemovableMediaModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    services::{
+        udisks::{MediaKind, RemovableMedia, UDisksCommand, UDisksService},
+        ReadOnlyService, Service, ServiceEvent,
+    },
+    style::SettingsButtonStyle,
+    utils::{format_size, launcher::send_notification},
+};
+use iced::{
+    widget::{button, column, row, text, Column},
+    Alignment, Element, Length, Subscription, Task,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<UDisksService>),
+    Mount(String),
+    Unmount(String),
+    Eject(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RemovableMediaModule {
+    service: Option<UDisksService>,
+}
+
+impl RemovableMediaModule {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &RemovableMediaModuleConfig,
+    ) -> Task<app::Message> {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                    Task::none()
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        let inserted = data
+                            .media
+                            .iter()
+                            .filter(|media| !service.media.iter().any(|old| old.id == media.id))
+                            .map(|media| media.label.clone())
+                            .collect::<Vec<_>>();
+
+                        service.update(data);
+
+                        if config.notify {
+                            for label in inserted {
+                                send_notification(tr("removable-media-connected"), label);
+                            }
+                        }
+                    }
+                    Task::none()
+                }
+                ServiceEvent::Error(_) => Task::none(),
+            },
+            Message::Mount(id) => self.command(UDisksCommand::Mount(id)),
+            Message::Unmount(id) => self.command(UDisksCommand::Unmount(id)),
+            Message::Eject(id) => self.command(UDisksCommand::Eject(id)),
+        }
+    }
+
+    fn command(&mut self, command: UDisksCommand) -> Task<app::Message> {
+        if let Some(service) = self.service.as_mut() {
+            service
+                .command(command)
+                .map(|event| app::Message::RemovableMedia(Message::Event(event)))
+        } else {
+            Task::none()
+        }
+    }
+
+    fn media_entry(media: &RemovableMedia) -> Element<Message> {
+        let details = match (&media.mount_point, media.free) {
+            (Some(_), Some(free)) if media.size > 0 => tr_args(
+                "media-free",
+                &[
+                    ("free", format_size(free)),
+                    ("size", format_size(media.size)),
+                ],
+            ),
+            (Some(mount_point), _) => tr_args("mounted-at", &[("mount", mount_point.clone())]),
+            (None, _) if media.size > 0 => {
+                format!("{} · {}", tr("not-mounted"), format_size(media.size))
+            }
+            (None, _) => tr("not-mounted"),
+        };
+
+        row!(
+            icon(match media.kind {
+                MediaKind::Block { .. } => Icons::UsbDrive,
+                MediaKind::Mtp { .. } => Icons::Phone,
+            }),
+            column!(text(media.label.clone()), text(details).size(10))
+                .spacing(4)
+                .width(Length::Fill),
+            button(icon(if media.is_mounted() {
+                Icons::Unmount
+            } else {
+                Icons::Mount
+            }))
+            .padding([4, 10])
+            .on_press(if media.is_mounted() {
+                Message::Unmount(media.id.clone())
+            } else {
+                Message::Mount(media.id.clone())
+            })
+            .style(SettingsButtonStyle.into_style()),
+        )
+        .push_maybe(media.is_ejectable().then(|| {
+            button(icon(Icons::Eject))
+                .padding([4, 10])
+                .on_press(Message::Eject(media.id.clone()))
+                .style(SettingsButtonStyle.into_style())
+        }))
+        .align_y(Alignment::Center)
+        .spacing(8)
+        .into()
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        match self.service.as_ref() {
+            Some(service) if !service.media.is_empty() => Column::with_children(
+                service
+                    .media
+                    .iter()
+                    .map(RemovableMediaModule::media_entry)
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8)
+            .into(),
+            _ => text(tr("no-removable-media")).into(),
+        }
+    }
+}
+
+impl Module for RemovableMediaModule {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        self.service
+            .as_ref()
+            .filter(|service| !service.media.is_empty())
+            .map(|service| {
+                (
+                    row!(icon(Icons::UsbDrive), text(service.media.len()))
+                        .align_y(Alignment::Center)
+                        .spacing(4)
+                        .into(),
+                    Some(OnModulePress::ToggleMenu(MenuType::RemovableMedia)),
+                )
+            })
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        Some(
+            UDisksService::subscribe()
+                .map(|event| app::Message::RemovableMedia(Message::Event(event))),
+        )
+    }
+}
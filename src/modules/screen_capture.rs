@@ -0,0 +1,558 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::ScreenCaptureModuleConfig,
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    outputs::Outputs,
+    services::network::connected_wifi_credentials,
+    style::GhostButtonStyle,
+    utils::launcher::{copy_to_clipboard, notification_action, send_notification},
+};
+use chrono::Local;
+use iced::{
+    time::every,
+    widget::{button, column, container, qr_code, row, text},
+    window::Id,
+    Alignment, Element, Length, Subscription, Task, Theme,
+};
+use log::{info, warn};
+use std::{env, path::PathBuf, process::Stdio, time::Duration};
+use tokio::{io::AsyncWriteExt, process};
+
+// characters of the recognized text shown in the notification
+const PREVIEW_LENGTH: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    Mp4,
+    Gif,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Record(Id, CaptureFormat),
+    RegionSelected(CaptureFormat, Option<String>),
+    Tick,
+    Stop,
+    RecorderExited,
+    Saved(Option<PathBuf>),
+    GrabText(Id),
+    TextGrabbed(Option<String>),
+    ScanQrCode(Id),
+    QrCodeScanned(Option<String>),
+    ShareWifi,
+    WifiCredentials(Option<(String, Option<String>)>),
+    CloseWifiQr,
+}
+
+/// Running wf-recorder process, stopped with SIGINT to let it finalize the file
+#[derive(Debug, Clone)]
+struct Recording {
+    pid: u32,
+    path: PathBuf,
+    format: CaptureFormat,
+    elapsed: u64,
+}
+
+/// QR code sharing the connected Wi-Fi network, shown in the menu
+#[derive(Debug)]
+struct WifiQr {
+    ssid: String,
+    data: qr_code::Data,
+}
+
+#[derive(Debug, Default)]
+pub struct ScreenCapture {
+    recording: Option<Recording>,
+    wifi_qr: Option<WifiQr>,
+}
+
+fn recordings_dir(config: &ScreenCaptureModuleConfig) -> PathBuf {
+    config
+        .directory
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_VIDEOS_DIR").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join("Videos")))
+        .unwrap_or_else(env::temp_dir)
+}
+
+fn format_elapsed(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Lets the user draw the region to capture, None if the selection was cancelled
+async fn select_region() -> Option<String> {
+    let output = process::Command::new("slurp").output().await.ok()?;
+
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        None
+    }
+}
+
+/// PNG image of a region selected by the user
+async fn capture_region() -> Option<Vec<u8>> {
+    let region = select_region().await?;
+    let output = process::Command::new("grim")
+        .args(["-g", &region, "-"])
+        .output()
+        .await
+        .inspect_err(|err| warn!("Failed to run grim: {}", err))
+        .ok()?;
+
+    output.status.success().then_some(output.stdout)
+}
+
+/// Reads the text of a region with tesseract
+async fn grab_text(language: String) -> Option<String> {
+    let image = capture_region().await?;
+
+    let mut tesseract = process::Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", &language])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .inspect_err(|err| warn!("Failed to run tesseract: {}", err))
+        .ok()?;
+
+    let mut stdin = tesseract.stdin.take()?;
+    stdin.write_all(&image).await.ok()?;
+    drop(stdin);
+
+    let output = tesseract.wait_with_output().await.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Private copy of the captured image for zbarimg. The file is created in the
+/// runtime directory and never opened through an existing path or symlink
+async fn write_private_image(image: &[u8]) -> Option<PathBuf> {
+    let path = env::var_os("XDG_RUNTIME_DIR")
+        .map_or_else(env::temp_dir, PathBuf::from)
+        .join(format!("ashell-qr-code-{}.png", std::process::id()));
+    let _ = tokio::fs::remove_file(&path).await;
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .await
+        .inspect_err(|err| warn!("Failed to write the captured region: {}", err))
+        .ok()?;
+    file.write_all(image).await.ok()?;
+
+    Some(path)
+}
+
+/// Decodes the QR code in a region with zbarimg
+async fn scan_qr_code() -> Option<String> {
+    let image = capture_region().await?;
+    let path = write_private_image(&image).await?;
+
+    let output = process::Command::new("zbarimg")
+        .args(["--raw", "--quiet"])
+        .arg(&path)
+        .output()
+        .await
+        .inspect_err(|err| warn!("Failed to run zbarimg: {}", err));
+    let _ = tokio::fs::remove_file(&path).await;
+
+    let content = String::from_utf8_lossy(&output.ok()?.stdout)
+        .trim()
+        .to_owned();
+
+    (!content.is_empty()).then_some(content)
+}
+
+fn escape_wifi_field(value: &str) -> String {
+    value.chars().fold(String::new(), |mut escaped, c| {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+/// Wi-Fi network in the format understood by the phone cameras
+fn wifi_qr_content(ssid: &str, psk: Option<&str>) -> String {
+    match psk {
+        Some(psk) => format!(
+            "WIFI:T:WPA;S:{};P:{};;",
+            escape_wifi_field(ssid),
+            escape_wifi_field(psk)
+        ),
+        None => format!("WIFI:T:nopass;S:{};;", escape_wifi_field(ssid)),
+    }
+}
+
+fn text_preview(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.chars().count() > PREVIEW_LENGTH {
+        format!("{}…", text.chars().take(PREVIEW_LENGTH).collect::<String>())
+    } else {
+        text
+    }
+}
+
+async fn open_url(url: String) {
+    let res = process::Command::new("xdg-open").arg(&url).status().await;
+
+    if let Err(err) = res {
+        warn!("Failed to open {}: {}", url, err);
+    }
+}
+
+async fn stop_recorder(pid: u32) {
+    let res = process::Command::new("kill")
+        .args(["-INT", &pid.to_string()])
+        .status()
+        .await;
+
+    if let Err(err) = res {
+        warn!("Failed to stop the recorder: {}", err);
+    }
+}
+
+/// Converts the recording to a GIF if needed, the intermediate mp4 is removed
+async fn finish_recording(path: PathBuf, format: CaptureFormat, gif_fps: u32) -> Option<PathBuf> {
+    if format == CaptureFormat::Mp4 {
+        return Some(path);
+    }
+
+    let gif = path.with_extension("gif");
+    let res = process::Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(&path)
+        .arg("-vf")
+        .arg(format!(
+            "fps={},split[a][b];[a]palettegen[p];[b][p]paletteuse",
+            gif_fps
+        ))
+        .arg(&gif)
+        .status()
+        .await;
+    let _ = tokio::fs::remove_file(&path).await;
+
+    match res {
+        Ok(status) if status.success() => Some(gif),
+        Ok(status) => {
+            warn!("ffmpeg failed to convert the recording: {}", status);
+            None
+        }
+        Err(err) => {
+            warn!("Failed to run ffmpeg: {}", err);
+            None
+        }
+    }
+}
+
+impl ScreenCapture {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &ScreenCaptureModuleConfig,
+        outputs: &mut Outputs,
+    ) -> Task<app::Message> {
+        match message {
+            Message::Record(id, format) => Task::batch(vec![
+                outputs.close_menu_if(id, MenuType::ScreenCapture),
+                Task::perform(select_region(), move |region| {
+                    app::Message::ScreenCapture(Message::RegionSelected(format, region))
+                }),
+            ]),
+            Message::RegionSelected(format, Some(region)) if self.recording.is_none() => {
+                let dir = recordings_dir(config);
+                if let Err(err) = std::fs::create_dir_all(&dir) {
+                    warn!(
+                        "Failed to create the recordings directory {:?}: {}",
+                        dir, err
+                    );
+                }
+                let path = dir.join(format!(
+                    "recording-{}.mp4",
+                    Local::now().format("%Y-%m-%d_%H-%M-%S")
+                ));
+
+                let child = process::Command::new("wf-recorder")
+                    .args(["-g", &region, "-f"])
+                    .arg(&path)
+                    .spawn();
+
+                match child {
+                    Ok(mut child) => {
+                        info!("Recording {} to {:?}", region, path);
+                        self.recording = child.id().map(|pid| Recording {
+                            pid,
+                            path,
+                            format,
+                            elapsed: 0,
+                        });
+
+                        Task::perform(async move { child.wait().await }, |_| {
+                            app::Message::ScreenCapture(Message::RecorderExited)
+                        })
+                    }
+                    Err(err) => {
+                        warn!("Failed to start wf-recorder: {}", err);
+                        Task::none()
+                    }
+                }
+            }
+            Message::RegionSelected(..) => Task::none(),
+            Message::Tick => {
+                if let Some(recording) = self.recording.as_mut() {
+                    recording.elapsed += 1;
+                }
+
+                Task::none()
+            }
+            Message::Stop => match self.recording.as_ref() {
+                Some(recording) => {
+                    Task::perform(stop_recorder(recording.pid), |_| app::Message::None)
+                }
+                None => Task::none(),
+            },
+            Message::RecorderExited => match self.recording.take() {
+                Some(recording) => Task::perform(
+                    finish_recording(recording.path, recording.format, config.gif_fps),
+                    |path| app::Message::ScreenCapture(Message::Saved(path)),
+                ),
+                None => Task::none(),
+            },
+            Message::Saved(Some(path)) => {
+                let path = path.to_string_lossy().into_owned();
+                send_notification(
+                    tr("recording-saved"),
+                    tr_args("recording-saved-body", &[("path", path.clone())]),
+                );
+
+                Task::perform(copy_to_clipboard(path), |_| app::Message::None)
+            }
+            Message::Saved(None) => {
+                send_notification(tr("recording-failed"), String::new());
+
+                Task::none()
+            }
+            Message::GrabText(id) => Task::batch(vec![
+                outputs.close_menu_if(id, MenuType::ScreenCapture),
+                Task::perform(grab_text(config.ocr_language.clone()), |text| {
+                    app::Message::ScreenCapture(Message::TextGrabbed(text))
+                }),
+            ]),
+            Message::TextGrabbed(Some(text)) => {
+                send_notification(tr("text-copied"), text_preview(&text));
+
+                Task::perform(copy_to_clipboard(text), |_| app::Message::None)
+            }
+            Message::TextGrabbed(None) => {
+                send_notification(tr("no-text-found"), String::new());
+
+                Task::none()
+            }
+            Message::ScanQrCode(id) => Task::batch(vec![
+                outputs.close_menu_if(id, MenuType::ScreenCapture),
+                Task::perform(scan_qr_code(), |content| {
+                    app::Message::ScreenCapture(Message::QrCodeScanned(content))
+                }),
+            ]),
+            Message::QrCodeScanned(Some(content)) => {
+                // links are only opened when the user asks for it from the notification
+                let notify = if content.starts_with("https://") || content.starts_with("http://") {
+                    let url = content.clone();
+
+                    Task::perform(
+                        async move {
+                            if notification_action(
+                                tr("qr-code-copied"),
+                                text_preview(&url),
+                                tr("open-link"),
+                            )
+                            .await
+                            {
+                                open_url(url).await;
+                            }
+                        },
+                        |_| app::Message::None,
+                    )
+                } else {
+                    send_notification(tr("qr-code-copied"), text_preview(&content));
+
+                    Task::none()
+                };
+
+                Task::batch(vec![
+                    Task::perform(copy_to_clipboard(content), |_| app::Message::None),
+                    notify,
+                ])
+            }
+            Message::QrCodeScanned(None) => {
+                send_notification(tr("no-qr-code-found"), String::new());
+
+                Task::none()
+            }
+            Message::ShareWifi => Task::perform(connected_wifi_credentials(), |credentials| {
+                app::Message::ScreenCapture(Message::WifiCredentials(credentials))
+            }),
+            Message::WifiCredentials(Some((ssid, psk))) => {
+                match qr_code::Data::new(wifi_qr_content(&ssid, psk.as_deref())) {
+                    Ok(data) => self.wifi_qr = Some(WifiQr { ssid, data }),
+                    Err(err) => warn!("Failed to encode the Wi-Fi QR code: {}", err),
+                }
+
+                Task::none()
+            }
+            Message::WifiCredentials(None) => {
+                send_notification(tr("no-wifi-connection"), String::new());
+
+                Task::none()
+            }
+            Message::CloseWifiQr => {
+                self.wifi_qr = None;
+
+                Task::none()
+            }
+        }
+    }
+
+    pub fn menu_view(&self, id: Id) -> Element<Message> {
+        if let Some(wifi_qr) = self.wifi_qr.as_ref() {
+            return column!(
+                row!(
+                    text(tr_args(
+                        "share-wifi-network",
+                        &[("ssid", wifi_qr.ssid.clone())]
+                    ))
+                    .width(Length::Fill),
+                    button(icon(Icons::Close))
+                        .padding([4, 8])
+                        .style(GhostButtonStyle.into_style())
+                        .on_press(Message::CloseWifiQr),
+                )
+                .align_y(Alignment::Center),
+                container(qr_code(&wifi_qr.data).cell_size(5.)).center_x(Length::Fill),
+            )
+            .spacing(8)
+            .into();
+        }
+
+        column!(
+            text(tr("screen-capture")).size(12),
+            action_button(
+                Icons::VideoOutline,
+                tr("record-region"),
+                Message::Record(id, CaptureFormat::Mp4)
+            ),
+            action_button(
+                Icons::FileGifBox,
+                tr("record-region-gif"),
+                Message::Record(id, CaptureFormat::Gif)
+            ),
+            action_button(
+                Icons::TextRecognition,
+                tr("grab-text"),
+                Message::GrabText(id)
+            ),
+            action_button(
+                Icons::QrcodeScan,
+                tr("scan-qr-code"),
+                Message::ScanQrCode(id)
+            ),
+            action_button(Icons::Qrcode, tr("share-wifi"), Message::ShareWifi),
+        )
+        .spacing(4)
+        .into()
+    }
+}
+
+fn action_button<'a>(icon_type: Icons, label: String, message: Message) -> Element<'a, Message> {
+    button(
+        row!(icon(icon_type), text(label))
+            .align_y(Alignment::Center)
+            .spacing(16),
+    )
+    .on_press(message)
+    .padding([4, 12])
+    .width(Length::Fill)
+    .style(GhostButtonStyle.into_style())
+    .into()
+}
+
+impl Module for ScreenCapture {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        Some(match self.recording.as_ref() {
+            Some(recording) => (
+                container(
+                    row!(
+                        icon(Icons::RecordRec),
+                        text(format_elapsed(recording.elapsed))
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(4),
+                )
+                .style(|theme: &Theme| container::Style {
+                    text_color: Some(theme.palette().danger),
+                    ..Default::default()
+                })
+                .into(),
+                Some(OnModulePress::Action(app::Message::ScreenCapture(
+                    Message::Stop,
+                ))),
+            ),
+            None => (
+                icon(Icons::MonitorScreenshot).into(),
+                Some(OnModulePress::ToggleMenu(MenuType::ScreenCapture)),
+            ),
+        })
+    }
+
+    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+        self.recording.as_ref().map(|_| {
+            every(Duration::from_secs(1)).map(|_| app::Message::ScreenCapture(Message::Tick))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_is_minutes_and_seconds() {
+        assert_eq!(format_elapsed(0), "00:00");
+        assert_eq!(format_elapsed(75), "01:15");
+        assert_eq!(format_elapsed(3600), "60:00");
+    }
+
+    #[test]
+    fn wifi_qr_content_escapes_special_characters() {
+        assert_eq!(
+            wifi_qr_content("Home;Net", Some("pa:ss\\word")),
+            "WIFI:T:WPA;S:Home\\;Net;P:pa\\:ss\\\\word;;"
+        );
+        assert_eq!(wifi_qr_content("Cafe", None), "WIFI:T:nopass;S:Cafe;;");
+    }
+
+    #[test]
+    fn preview_is_a_single_truncated_line() {
+        assert_eq!(text_preview("hello\n  world"), "hello world");
+
+        let preview = text_preview(&"a".repeat(200));
+        assert_eq!(preview.chars().count(), PREVIEW_LENGTH + 1);
+        assert!(preview.ends_with('…'));
+    }
+}
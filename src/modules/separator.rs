@@ -0,0 +1,44 @@
+use super::{Module, OnModulePress};
+use crate::{app, config::SeparatorConfig};
+use iced::{
+    widget::{container, text, Space},
+    Element, Length, Theme,
+};
+
+/// Divider between modules, a thin line or the configured glyph
+#[derive(Default, Debug, Clone)]
+pub struct Separator;
+
+impl Module for Separator {
+    type ViewData<'a> = &'a SeparatorConfig;
+    type SubscriptionData<'a> = ();
+
+    fn view(
+        &self,
+        config: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let content: Element<app::Message> = match &config.glyph {
+            Some(glyph) => text(glyph.clone())
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.extended_palette().secondary.base.color),
+                })
+                .into(),
+            None => container(Space::new(
+                Length::Fixed(config.thickness),
+                Length::Fixed(config.length),
+            ))
+            .style(|theme: &Theme| container::Style {
+                background: Some(theme.extended_palette().secondary.base.color.into()),
+                ..Default::default()
+            })
+            .into(),
+        };
+
+        Some((content, None))
+    }
+}
+
+/// Empty space of the given width
+pub fn spacer<'a>(width: u32) -> Element<'a, app::Message> {
+    Space::with_width(Length::Fixed(width as f32)).into()
+}
@@ -1,16 +1,22 @@
 use super::{Message, SubMenu};
 use crate::{
     components::icons::{icon, Icons},
+    config::{AudioConfig, AudioPortType, AudioRule},
+    i18n::tr,
     services::{
-        audio::{AudioData, AudioService, DeviceType, Sinks},
+        audio::{AudioCommand, AudioData, AudioService, Device, DeviceType, Port, Sinks},
         ServiceEvent,
     },
     style::{GhostButtonStyle, SettingsButtonStyle},
 };
 use iced::{
-    widget::{button, column, container, horizontal_rule, row, slider, text, Column, Row},
+    mouse::ScrollDelta,
+    widget::{
+        button, column, container, horizontal_rule, mouse_area, progress_bar, row, slider, text,
+        Column, Row,
+    },
     window::Id,
-    Alignment, Element, Length, Theme,
+    Alignment, Border, Element, Length, Theme,
 };
 
 #[derive(Debug, Clone)]
@@ -24,9 +30,68 @@ pub enum AudioMessage {
     SourceVolumeChanged(i32),
     SinksMore(Id),
     SourcesMore(Id),
+    SourceLevel(f32),
+    // 1 when scrolling up, -1 when scrolling down
+    SinkVolumeScrolled(i32),
+    SourceVolumeScrolled(i32),
+}
+
+impl From<AudioPortType> for DeviceType {
+    fn from(value: AudioPortType) -> Self {
+        match value {
+            AudioPortType::Headphones => DeviceType::Headphones,
+            AudioPortType::Headset => DeviceType::Headset,
+            AudioPortType::Speaker => DeviceType::Speaker,
+            AudioPortType::Hdmi => DeviceType::Hdmi,
+        }
+    }
+}
+
+fn rule_matches(rule: &AudioRule, device: &Device, port: &Port) -> bool {
+    rule.port_type.map_or(true, |port_type| {
+        DeviceType::from(port_type) == port.device_type
+    }) && rule.device.as_ref().map_or(true, |name| {
+        let name = name.to_lowercase();
+        device.description.to_lowercase().contains(&name)
+            || port.description.to_lowercase().contains(&name)
+    })
 }
 
 impl AudioData {
+    /// Commands of the first rule matching an output that wasn't in the
+    /// current sink list, e.g. headphones plugged in or a bluetooth speaker connected
+    pub fn routing_commands(&self, sinks: &[Device], rules: &[AudioRule]) -> Vec<AudioCommand> {
+        if !self.sinks_loaded || rules.is_empty() {
+            return Vec::new();
+        }
+
+        let appeared = sinks.iter().flat_map(|sink| {
+            sink.ports
+                .iter()
+                .filter(|port| {
+                    !self.sinks.iter().any(|old| {
+                        old.name == sink.name && old.ports.iter().any(|old| old.name == port.name)
+                    })
+                })
+                .map(move |port| (sink, port))
+        });
+
+        for (sink, port) in appeared {
+            if let Some(rule) = rules.iter().find(|rule| rule_matches(rule, sink, port)) {
+                return rule
+                    .set_default
+                    .then(|| AudioCommand::DefaultSink(sink.name.clone(), port.name.clone()))
+                    .into_iter()
+                    .chain(rule.volume.map(|volume| {
+                        AudioCommand::DeviceSinkVolume(sink.name.clone(), volume as i32)
+                    }))
+                    .collect();
+            }
+        }
+
+        Vec::new()
+    }
+
     pub fn sink_indicator<Message>(&self) -> Option<Element<Message>> {
         if !self.sinks.is_empty() {
             let icon_type = self.sinks.get_icon(&self.server_info.default_sink);
@@ -40,6 +105,7 @@ impl AudioData {
     pub fn audio_sliders(
         &self,
         sub_menu: Option<SubMenu>,
+        config: &AudioConfig,
     ) -> (Option<Element<Message>>, Option<Element<Message>>) {
         let active_sink = self
             .sinks
@@ -52,7 +118,9 @@ impl AudioData {
                 s.is_mute,
                 Message::Audio(AudioMessage::ToggleSinkMute),
                 self.cur_sink_volume,
+                config,
                 |v| Message::Audio(AudioMessage::SinkVolumeChanged(v)),
+                |direction| Message::Audio(AudioMessage::SinkVolumeScrolled(direction)),
                 if self.sinks.iter().map(|s| s.ports.len()).sum::<usize>() > 1 {
                     Some((sub_menu, Message::ToggleSubMenu(SubMenu::Sinks)))
                 } else {
@@ -70,10 +138,15 @@ impl AudioData {
             let source_slider = active_source.map(|s| {
                 audio_slider(
                     SliderType::Source,
-                    s.is_mute,
+                    s.is_mute
+                        || self
+                            .hardware_mic_mute
+                            .is_some_and(|(card, muted)| muted && s.alsa_card == Some(card)),
                     Message::Audio(AudioMessage::ToggleSourceMute),
                     self.cur_source_volume,
+                    config,
                     |v| Message::Audio(AudioMessage::SourceVolumeChanged(v)),
+                    |direction| Message::Audio(AudioMessage::SourceVolumeScrolled(direction)),
                     if self.sources.iter().map(|s| s.ports.len()).sum::<usize>() > 1 {
                         Some((sub_menu, Message::ToggleSubMenu(SubMenu::Sources)))
                     } else {
@@ -112,8 +185,8 @@ impl AudioData {
         )
     }
 
-    pub fn sources_submenu(&self, id: Id, show_more: bool) -> Element<Message> {
-        audio_submenu(
+    pub fn sources_submenu(&self, id: Id, show_more: bool, level: f32) -> Element<Message> {
+        let submenu = audio_submenu(
             self.sources
                 .iter()
                 .flat_map(|s| {
@@ -133,10 +206,36 @@ impl AudioData {
             } else {
                 None
             },
+        );
+
+        column!(
+            row!(icon(Icons::Mic1), level_meter(level))
+                .align_y(Alignment::Center)
+                .spacing(16)
+                .padding([4, 12]),
+            submenu
         )
+        .spacing(8)
+        .into()
     }
 }
 
+fn level_meter<'a, Message: 'a>(level: f32) -> Element<'a, Message> {
+    progress_bar(0.0..=1., level)
+        .height(Length::Fixed(6.))
+        .style(|theme: &Theme| progress_bar::Style {
+            background: theme.extended_palette().background.weak.color.into(),
+            bar: if level > 0.9 {
+                theme.palette().danger
+            } else {
+                theme.palette().success
+            }
+            .into(),
+            border: Border::default().rounded(3),
+        })
+        .into()
+}
+
 pub enum SliderType {
     Sink,
     Source,
@@ -147,7 +246,9 @@ pub fn audio_slider<'a, Message: 'a + Clone>(
     is_mute: bool,
     toggle_mute: Message,
     volume: i32,
+    config: &AudioConfig,
     volume_changed: impl Fn(i32) -> Message + 'a,
+    volume_scrolled: impl Fn(i32) -> Message + 'a,
     with_submenu: Option<(Option<SubMenu>, Message)>,
 ) -> Element<'a, Message> {
     Row::new()
@@ -174,9 +275,18 @@ pub fn audio_slider<'a, Message: 'a + Clone>(
             .style(SettingsButtonStyle.into_style()),
         )
         .push(
-            slider(0..=100, volume, volume_changed)
-                .step(1)
-                .width(Length::Fill),
+            mouse_area(
+                slider(0..=config.max_volume as i32, volume, volume_changed)
+                    .step(config.volume_step.max(1) as i32)
+                    .width(Length::Fill),
+            )
+            .on_scroll(move |delta| {
+                let y = match delta {
+                    ScrollDelta::Lines { y, .. } | ScrollDelta::Pixels { y, .. } => y,
+                };
+
+                volume_scrolled(if y > 0. { 1 } else { -1 })
+            }),
         )
         .push_maybe(with_submenu.map(|(submenu, msg)| {
             button(icon(match (slider_type, submenu) {
@@ -242,7 +352,7 @@ pub fn audio_submenu<'a, Message: 'a + Clone>(
         column!(
             entries,
             horizontal_rule(1),
-            button("More")
+            button(text(tr("more")))
                 .on_press(more_msg)
                 .padding([4, 12])
                 .width(Length::Fill)
@@ -1,6 +1,11 @@
-use super::{quick_setting_button, sub_menu_wrapper, Message, SubMenu};
+use super::{
+    quick_setting_button,
+    recent::{recent_chips, RecentEntry},
+    sub_menu_wrapper, Message, SubMenu,
+};
 use crate::{
     components::icons::{icon, Icons},
+    i18n::tr,
     services::{
         bluetooth::{BluetoothData, BluetoothService, BluetoothState},
         ServiceEvent,
@@ -12,11 +17,13 @@ use iced::{
     window::Id,
     Element, Length, Theme,
 };
+use zbus::zvariant::OwnedObjectPath;
 
 #[derive(Debug, Clone)]
 pub enum BluetoothMessage {
     Event(ServiceEvent<BluetoothService>),
     Toggle,
+    Connect(OwnedObjectPath),
     More(Id),
 }
 
@@ -26,11 +33,12 @@ impl BluetoothData {
         id: Id,
         sub_menu: Option<SubMenu>,
         show_more_button: bool,
+        recent: &[RecentEntry],
     ) -> Option<(Element<Message>, Option<Element<Message>>)> {
         Some((
             quick_setting_button(
                 Icons::Bluetooth,
-                "Bluetooth".to_owned(),
+                tr("bluetooth"),
                 None,
                 self.state == BluetoothState::Active,
                 Message::Bluetooth(BluetoothMessage::Toggle),
@@ -43,13 +51,36 @@ impl BluetoothData {
             ),
             sub_menu
                 .filter(|menu_type| *menu_type == SubMenu::Bluetooth)
-                .map(|_| sub_menu_wrapper(self.bluetooth_menu(id, show_more_button))),
+                .map(|_| sub_menu_wrapper(self.bluetooth_menu(id, show_more_button, recent))),
         ))
     }
 
-    pub fn bluetooth_menu(&self, id: Id, show_more_button: bool) -> Element<Message> {
-        let main = if self.devices.is_empty() {
-            text("No devices connected").into()
+    pub fn bluetooth_menu(
+        &self,
+        id: Id,
+        show_more_button: bool,
+        recent: &[RecentEntry],
+    ) -> Element<Message> {
+        let recent = recent_chips(
+            recent
+                .iter()
+                .filter(|entry| !self.devices.iter().any(|d| d.path.as_str() == entry.id))
+                .map(|entry| {
+                    (
+                        entry.name.clone(),
+                        self.known_devices
+                            .iter()
+                            .find(|path| path.as_str() == entry.id)
+                            .map(|path| {
+                                Message::Bluetooth(BluetoothMessage::Connect(path.clone()))
+                            }),
+                    )
+                })
+                .collect(),
+        );
+
+        let devices: Element<Message> = if self.devices.is_empty() {
+            text(tr("no-devices-connected")).into()
         } else {
             Column::with_children(
                 self.devices
@@ -65,12 +96,13 @@ impl BluetoothData {
             .spacing(8)
             .into()
         };
+        let main = Column::new().push_maybe(recent).push(devices).spacing(12);
 
         if show_more_button {
             column!(
                 main,
                 horizontal_rule(1),
-                button("More")
+                button(text(tr("more")))
                     .on_press(Message::Bluetooth(BluetoothMessage::More(id)))
                     .padding([4, 12])
                     .width(Length::Fill)
@@ -79,7 +111,7 @@ impl BluetoothData {
             .spacing(12)
             .into()
         } else {
-            main
+            main.into()
         }
     }
 
@@ -0,0 +1,131 @@
+use crate::utils::state::{read_state, write_state};
+use std::{collections::HashMap, fs, path::Path};
+
+const STATE_NAME: &str = "data-usage";
+const NET_FOLDER: &str = "/sys/class/net";
+
+/// Bytes received and sent by all the wireless interfaces since they came up
+pub fn wireless_bytes() -> u64 {
+    let counter = |interface: &Path, name: &str| {
+        fs::read_to_string(interface.join("statistics").join(name))
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+
+    fs::read_dir(NET_FOLDER)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|interface| interface.join("wireless").exists())
+                .map(|interface| counter(&interface, "rx_bytes") + counter(&interface, "tx_bytes"))
+                .sum()
+        })
+        .unwrap_or_default()
+}
+
+/// Data used on each Wi-Fi network in the current month, persisted across restarts
+#[derive(Debug)]
+pub struct DataUsage {
+    // e.g. 2025-03, the usage starts from zero every month
+    month: String,
+    used: HashMap<String, u64>,
+    last_sample: Option<u64>,
+}
+
+impl DataUsage {
+    pub fn load() -> Self {
+        let content = read_state(STATE_NAME).unwrap_or_default();
+        let mut lines = content.lines();
+
+        Self {
+            month: lines.next().unwrap_or_default().to_string(),
+            used: lines
+                .filter_map(|line| {
+                    let (ssid, bytes) = line.rsplit_once('\t')?;
+                    Some((ssid.to_string(), bytes.parse().ok()?))
+                })
+                .collect(),
+            last_sample: None,
+        }
+    }
+
+    pub fn used(&self, ssid: &str) -> u64 {
+        self.used.get(ssid).copied().unwrap_or_default()
+    }
+
+    /// Adds the bytes transferred since the previous sample to the network,
+    /// returns its usage before and after the sample
+    pub fn sample(&mut self, month: &str, ssid: Option<&str>, total: u64) -> Option<(u64, u64)> {
+        if self.month != month {
+            self.month = month.to_string();
+            self.used.clear();
+        }
+
+        let previous = self.last_sample.replace(total);
+        // the counters restart when the interface goes down
+        let delta = previous
+            .filter(|previous| *previous <= total)
+            .map(|previous| total - previous)?;
+        let ssid = ssid?;
+
+        let before = self.used(ssid);
+        if delta > 0 {
+            self.used.insert(ssid.to_string(), before + delta);
+        }
+
+        Some((before, before + delta))
+    }
+
+    pub fn save(&self) {
+        write_state(
+            STATE_NAME,
+            &std::iter::once(self.month.clone())
+                .chain(
+                    self.used
+                        .iter()
+                        .map(|(ssid, bytes)| format!("{}\t{}", ssid, bytes)),
+                )
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(month: &str) -> DataUsage {
+        DataUsage {
+            month: month.to_string(),
+            used: HashMap::new(),
+            last_sample: None,
+        }
+    }
+
+    #[test]
+    fn counts_only_the_traffic_between_samples() {
+        let mut usage = usage("2025-03");
+        usage.used.insert("home".to_string(), 100);
+
+        assert_eq!(usage.sample("2025-03", Some("home"), 1000), None);
+        assert_eq!(
+            usage.sample("2025-03", Some("home"), 1500),
+            Some((100, 600))
+        );
+        // the interface went down and its counters restarted
+        assert_eq!(usage.sample("2025-03", Some("home"), 200), None);
+        assert_eq!(usage.sample("2025-03", Some("home"), 300), Some((600, 700)));
+    }
+
+    #[test]
+    fn starts_over_every_month() {
+        let mut usage = usage("2025-03");
+        usage.used.insert("home".to_string(), 100);
+
+        assert_eq!(usage.sample("2025-04", Some("home"), 1000), None);
+        assert_eq!(usage.used("home"), 0);
+    }
+}
@@ -0,0 +1,211 @@
+use super::{Message, SubMenu};
+use crate::{
+    components::icons::{icon, Icons},
+    i18n::{tr, tr_args},
+    services::output_management::OutputMode,
+    style::{GhostButtonStyle, OutlineButtonStyle, SettingsButtonStyle},
+    utils::launcher::is_niri,
+};
+use iced::{
+    widget::{button, container, horizontal_rule, row, scrollable, text, toggler, Column},
+    Alignment, Element, Length, Theme,
+};
+use log::warn;
+use tokio::process;
+
+// seconds to confirm a new mode before the previous one comes back
+pub const REVERT_TIMEOUT: u64 = 15;
+
+#[derive(Debug, Clone)]
+pub enum DisplayMessage {
+    SetMode(OutputMode),
+    KeepMode,
+    RevertMode,
+    RevertTick,
+    ToggleAdaptiveSync(bool),
+    ToggleHdr(bool),
+    HdrState(Option<bool>),
+}
+
+/// Previous mode of the output, restored unless the new one is confirmed in time
+#[derive(Debug, Clone)]
+pub struct ModeRevert {
+    pub output: String,
+    pub previous: OutputMode,
+    pub remaining: u64,
+}
+
+/// HDR state of the output, only Hyprland exposes it through its color management preset
+pub async fn hdr_state(output: String) -> Option<bool> {
+    if is_niri() {
+        return None;
+    }
+
+    let monitors = process::Command::new("hyprctl")
+        .args(["monitors", "-j"])
+        .output()
+        .await
+        .ok()?;
+    let monitors: Vec<serde_json::Value> = serde_json::from_slice(&monitors.stdout).ok()?;
+
+    monitors
+        .iter()
+        .find(|monitor| monitor["name"] == output.as_str())?
+        .get("colorManagementPreset")?
+        .as_str()
+        .map(|preset| preset == "hdr")
+}
+
+/// Hyprland monitor rule keeping the current mode, position and scale
+pub fn hdr_rule(
+    output: &str,
+    mode: OutputMode,
+    position: (i32, i32),
+    scale: f64,
+    hdr: bool,
+) -> String {
+    format!(
+        "{},{}x{}@{:.3},{}x{},{},{}",
+        output,
+        mode.width,
+        mode.height,
+        mode.refresh as f32 / 1000.,
+        position.0,
+        position.1,
+        scale,
+        if hdr {
+            "bitdepth,10,cm,hdr"
+        } else {
+            "bitdepth,8,cm,srgb"
+        }
+    )
+}
+
+pub async fn set_hdr(output: String, rule: String) -> Option<bool> {
+    let res = process::Command::new("hyprctl")
+        .args(["keyword", "monitor", &rule])
+        .output()
+        .await;
+
+    if let Err(err) = res {
+        warn!("Failed to set the monitor rule {}: {}", rule, err);
+    }
+
+    hdr_state(output).await
+}
+
+pub fn display_row<'a>(
+    current: OutputMode,
+    sub_menu: Option<SubMenu>,
+    revert: Option<&ModeRevert>,
+) -> Element<'a, Message> {
+    let content: Element<'a, Message> = match revert {
+        Some(revert) => row!(
+            text(tr_args(
+                "keep-display-mode",
+                &[("seconds", revert.remaining.to_string())]
+            ))
+            .width(Length::Fill),
+            button(text(tr("keep")))
+                .padding([4, 12])
+                .style(OutlineButtonStyle.into_style())
+                .on_press(Message::Display(DisplayMessage::KeepMode)),
+            button(text(tr("revert")))
+                .padding([4, 12])
+                .style(GhostButtonStyle.into_style())
+                .on_press(Message::Display(DisplayMessage::RevertMode)),
+        )
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into(),
+        None => row!(
+            text(current.label()).width(Length::Fill),
+            button(icon(if sub_menu == Some(SubMenu::Displays) {
+                Icons::Close
+            } else {
+                Icons::RightArrow
+            }))
+            .padding([8, 13])
+            .on_press(Message::ToggleSubMenu(SubMenu::Displays))
+            .style(SettingsButtonStyle.into_style()),
+        )
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into(),
+    };
+
+    row!(container(icon(Icons::Monitor)).padding([8, 11]), content)
+        .align_y(Alignment::Center)
+        .spacing(8)
+        .into()
+}
+
+fn toggle_row<'a>(
+    title: String,
+    value: bool,
+    on_toggle: fn(bool) -> DisplayMessage,
+) -> Element<'a, Message> {
+    row!(
+        text(title).width(Length::Fill),
+        toggler(value)
+            .on_toggle(move |value| Message::Display(on_toggle(value)))
+            .width(Length::Shrink),
+    )
+    .padding([0, 12])
+    .align_y(Alignment::Center)
+    .into()
+}
+
+pub fn modes_submenu<'a>(
+    modes: Vec<OutputMode>,
+    current: OutputMode,
+    adaptive_sync: Option<bool>,
+    hdr: Option<bool>,
+) -> Element<'a, Message> {
+    let toggles = adaptive_sync
+        .map(|value| {
+            toggle_row(
+                tr("variable-refresh-rate"),
+                value,
+                DisplayMessage::ToggleAdaptiveSync,
+            )
+        })
+        .into_iter()
+        .chain(hdr.map(|value| toggle_row(tr("hdr"), value, DisplayMessage::ToggleHdr)))
+        .collect::<Vec<_>>();
+    let has_toggles = !toggles.is_empty();
+
+    let modes = container(scrollable(
+        Column::with_children(
+            modes
+                .into_iter()
+                .map(|mode| {
+                    if mode == current {
+                        container(text(mode.label()))
+                            .padding([4, 12])
+                            .style(|theme: &Theme| container::Style {
+                                text_color: Some(theme.palette().success),
+                                ..Default::default()
+                            })
+                            .into()
+                    } else {
+                        button(text(mode.label()))
+                            .on_press(Message::Display(DisplayMessage::SetMode(mode)))
+                            .padding([4, 12])
+                            .width(Length::Fill)
+                            .style(GhostButtonStyle.into_style())
+                            .into()
+                    }
+                })
+                .collect::<Vec<_>>(),
+        )
+        .spacing(4),
+    ))
+    .max_height(200);
+
+    Column::with_children(toggles)
+        .push_maybe(has_toggles.then(|| horizontal_rule(1)))
+        .push(modes)
+        .spacing(8)
+        .into()
+}
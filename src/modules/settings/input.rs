@@ -0,0 +1,194 @@
+use super::Message;
+use crate::{
+    components::icons::{icon, Icons},
+    config::InputProfile,
+    i18n::tr,
+    style::GhostButtonStyle,
+    utils::launcher::is_niri,
+};
+use iced::{
+    widget::{button, column, container, horizontal_rule, row, text, toggler, Column},
+    Alignment, Element, Length, Theme,
+};
+use log::{info, warn};
+use tokio::process;
+
+const NATURAL_SCROLL: &str = "input:touchpad:natural_scroll";
+const DISABLE_WHILE_TYPING: &str = "input:touchpad:disable_while_typing";
+
+#[derive(Debug, Clone)]
+pub enum InputMessage {
+    State(Option<InputState>),
+    ToggleTouchpad,
+    SetNaturalScroll(bool),
+    SetDisableWhileTyping(bool),
+    SetProfile(String),
+}
+
+/// Input settings read back from Hyprland, niri can only change them
+/// editing its configuration file
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    pub touchpad: Option<String>,
+    pub touchpad_enabled: bool,
+    pub natural_scroll: bool,
+    pub disable_while_typing: bool,
+}
+
+async fn hyprctl(args: &[&str]) -> Option<serde_json::Value> {
+    let output = process::Command::new("hyprctl")
+        .args(args)
+        .arg("-j")
+        .output()
+        .await
+        .ok()?;
+
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+async fn get_option(option: &str) -> Option<bool> {
+    hyprctl(&["getoption", option])
+        .await?
+        .get("int")?
+        .as_i64()
+        .map(|value| value != 0)
+}
+
+async fn set_keyword(keyword: &str, value: &str) {
+    info!("Setting {} to {}", keyword, value);
+
+    let res = process::Command::new("hyprctl")
+        .args(["keyword", keyword, value])
+        .output()
+        .await;
+
+    if let Err(err) = res {
+        warn!("Failed to set {}: {}", keyword, err);
+    }
+}
+
+pub async fn input_state() -> Option<InputState> {
+    // niri has no IPC to change the input settings, the quick setting is hidden
+    if is_niri() {
+        return None;
+    }
+
+    let touchpad = hyprctl(&["devices"])
+        .await?
+        .get("mice")?
+        .as_array()?
+        .iter()
+        .filter_map(|mouse| mouse.get("name")?.as_str())
+        .find(|name| name.contains("touchpad"))
+        .map(str::to_owned);
+    // a device without its own section is enabled
+    let touchpad_enabled = match touchpad.as_ref() {
+        Some(touchpad) => get_option(&format!("device[{}]:enabled", touchpad))
+            .await
+            .unwrap_or(true),
+        None => true,
+    };
+
+    Some(InputState {
+        touchpad,
+        touchpad_enabled,
+        natural_scroll: get_option(NATURAL_SCROLL).await?,
+        disable_while_typing: get_option(DISABLE_WHILE_TYPING).await?,
+    })
+}
+
+pub async fn set_touchpad_enabled(touchpad: String, enabled: bool) -> Option<InputState> {
+    set_keyword(
+        &format!("device[{}]:enabled", touchpad),
+        if enabled { "true" } else { "false" },
+    )
+    .await;
+
+    input_state().await
+}
+
+pub async fn set_natural_scroll(enabled: bool) -> Option<InputState> {
+    set_keyword(NATURAL_SCROLL, if enabled { "true" } else { "false" }).await;
+
+    input_state().await
+}
+
+pub async fn set_disable_while_typing(enabled: bool) -> Option<InputState> {
+    set_keyword(DISABLE_WHILE_TYPING, if enabled { "true" } else { "false" }).await;
+
+    input_state().await
+}
+
+pub async fn apply_profile(profile: InputProfile) {
+    set_keyword("input:sensitivity", &profile.sensitivity.to_string()).await;
+    if let Some(accel_profile) = profile.accel_profile {
+        set_keyword("input:accel_profile", &accel_profile).await;
+    }
+}
+
+fn toggle_row<'a>(
+    title: String,
+    value: bool,
+    on_toggle: fn(bool) -> InputMessage,
+) -> Element<'a, Message> {
+    row!(
+        text(title).width(Length::Fill),
+        toggler(value)
+            .on_toggle(move |value| Message::Input(on_toggle(value)))
+            .width(Length::Shrink),
+    )
+    .padding([0, 12])
+    .align_y(Alignment::Center)
+    .into()
+}
+
+pub fn input_submenu<'a>(
+    state: &InputState,
+    profiles: &'a [InputProfile],
+    active_profile: Option<&str>,
+) -> Element<'a, Message> {
+    column!(
+        toggle_row(
+            tr("natural-scroll"),
+            state.natural_scroll,
+            InputMessage::SetNaturalScroll
+        ),
+        toggle_row(
+            tr("disable-while-typing"),
+            state.disable_while_typing,
+            InputMessage::SetDisableWhileTyping
+        ),
+    )
+    .push_maybe((!profiles.is_empty()).then(|| horizontal_rule(1)))
+    .push(Column::with_children(
+        profiles
+            .iter()
+            .map(|profile| {
+                let content = row!(icon(Icons::Mouse), text(profile.name.clone()))
+                    .align_y(Alignment::Center)
+                    .spacing(16);
+
+                if active_profile == Some(profile.name.as_str()) {
+                    container(content)
+                        .padding([4, 12])
+                        .style(|theme: &Theme| container::Style {
+                            text_color: Some(theme.palette().success),
+                            ..Default::default()
+                        })
+                        .into()
+                } else {
+                    button(content)
+                        .on_press(Message::Input(InputMessage::SetProfile(
+                            profile.name.clone(),
+                        )))
+                        .padding([4, 12])
+                        .width(Length::Fill)
+                        .style(GhostButtonStyle.into_style())
+                        .into()
+                }
+            })
+            .collect::<Vec<_>>(),
+    ))
+    .spacing(8)
+    .into()
+}
@@ -1,55 +1,144 @@
 use self::{
-    audio::AudioMessage, bluetooth::BluetoothMessage, network::NetworkMessage, power::PowerMessage,
+    audio::AudioMessage,
+    bluetooth::BluetoothMessage,
+    display::{
+        display_row, hdr_rule, hdr_state, modes_submenu, set_hdr, DisplayMessage, ModeRevert,
+        REVERT_TIMEOUT,
+    },
+    input::{
+        apply_profile, input_state, input_submenu, set_disable_while_typing, set_natural_scroll,
+        set_touchpad_enabled, InputMessage, InputState,
+    },
+    network::NetworkMessage,
+    power::PowerMessage,
 };
 use super::{Module, OnModulePress};
 use crate::{
     app,
-    components::icons::{icon, Icons},
-    config::SettingsModuleConfig,
+    components::{
+        icons::{icon, icon_glyph, Icons},
+        swipe_area::swipe_area,
+    },
+    config::{
+        AudioConfig, BatteryNotificationsConfig, BatterySaverConfig, CompositorCommand, DataQuota,
+        SettingsModuleConfig,
+    },
+    i18n::{tr, tr_args},
     menu::MenuType,
-    modules::settings::power::power_menu,
+    modules::settings::power::{power_menu, DisplaysOff},
+    osd::OsdKind,
     outputs::Outputs,
-    password_dialog,
+    password_dialog::{self, PasswordDialog},
     position_button::ButtonUIRef,
     services::{
-        audio::{AudioCommand, AudioService},
+        audio::{AudioCommand, AudioEvent, AudioService, Sinks},
         bluetooth::{BluetoothCommand, BluetoothService, BluetoothState},
         brightness::{BrightnessCommand, BrightnessService},
         idle_inhibitor::IdleInhibitorManager,
-        network::{NetworkCommand, NetworkEvent, NetworkService},
-        upower::{PowerProfileCommand, UPowerService},
+        idle_notify::IdleNotify,
+        logind::{inhibit, list_inhibitors, running_matches, Inhibitor, InhibitorInfo},
+        mic_level::MicLevel,
+        network::{
+            keyring_wifi_password, saved_wifi_password, set_metered, ActiveConnectionInfo,
+            NetworkCommand, NetworkEvent, NetworkService, WifiPassword,
+        },
+        notifications::{NotificationsCommand, NotificationsService},
+        output_management::OutputManager,
+        sensor_proxy::SensorProxyService,
+        upower::{BatteryData, BatteryStatus, PowerProfile, PowerProfileCommand, UPowerService},
         ReadOnlyService, Service, ServiceEvent,
     },
+    status_export::Volume,
     style::{QuickSettingsButtonStyle, QuickSettingsSubMenuButtonStyle, SettingsButtonStyle},
+    utils::{
+        format_size,
+        launcher::{
+            copy_to_clipboard, displays_off, displays_on, execute_command,
+            send_critical_notification, send_notification,
+        },
+        state::{read_state, write_state},
+    },
 };
 use brightness::BrightnessMessage;
+use data_usage::{wireless_bytes, DataUsage};
 use iced::{
     alignment::{Horizontal, Vertical},
+    time::every,
     widget::{
         button, column, container, horizontal_space, row, text, vertical_rule, Column, Row, Space,
     },
     window::Id,
     Alignment, Background, Border, Element, Length, Padding, Subscription, Task, Theme,
 };
-use log::info;
+use log::{debug, info, warn};
+use public_ip::PublicIp;
+use recent::RecentList;
+use speedtest::Speedtest;
+use std::time::{Duration, Instant};
 use upower::UPowerMessage;
 
 pub mod audio;
 pub mod bluetooth;
 pub mod brightness;
+mod data_usage;
+mod display;
+mod input;
 pub mod network;
 mod power;
+mod public_ip;
+mod recent;
+mod speedtest;
 mod upower;
 
+// without input for this long after the click the displays are turned off
+const DISPLAYS_OFF_DELAY: Duration = Duration::from_secs(1);
+
 pub struct Settings {
     audio: Option<AudioService>,
     brightness: Option<BrightnessService>,
     network: Option<NetworkService>,
     bluetooth: Option<BluetoothService>,
     idle_inhibitor: Option<IdleInhibitorManager>,
+    // bumped on each change, a temporary inhibit only expires if still current
+    idle_inhibit_generation: u32,
     sub_menu: Option<SubMenu>,
     upower: Option<UPowerService>,
-    pub password_dialog: Option<(String, String)>,
+    // power profile and brightness to restore when the battery saver turns off
+    battery_saver: Option<(PowerProfile, Option<u32>)>,
+    sleep_inhibitor: Option<(Vec<String>, Inhibitor)>,
+    inhibitors: Vec<InhibitorInfo>,
+    mic_level: f32,
+    recent_bluetooth: RecentList,
+    recent_wifi: RecentList,
+    pub password_dialog: Option<PasswordDialog>,
+    // network waiting for the outcome of the password just provided
+    wifi_password_attempt: Option<String>,
+    // saved password of a network shown in the wifi menu, None if it couldn't be read
+    shown_wifi_password: Option<(String, Option<String>)>,
+    metered: bool,
+    data_usage: DataUsage,
+    speedtest: Speedtest,
+    public_ip: Option<PublicIp>,
+    // the public ip is outside the VPN provider ranges while a VPN is active
+    vpn_leak: bool,
+    // pid of the on screen keyboard started from the quick settings
+    osk: Option<u32>,
+    sensor_proxy: Option<SensorProxyService>,
+    rotation_locked: bool,
+    notifications: Option<NotificationsService>,
+    // do not disturb state to restore when the game mode ends, None if
+    // the game mode didn't change it or the user changed it since
+    dnd_before_game_mode: Option<bool>,
+    displays_off: Option<DisplaysOff>,
+    output_manager: Option<OutputManager>,
+    // output of the bar the menu was opened from
+    menu_output: Option<String>,
+    mode_revert: Option<ModeRevert>,
+    // None when the compositor doesn't tell
+    hdr: Option<bool>,
+    // None when the compositor settings can't be changed at runtime
+    input: Option<InputState>,
+    input_profile: Option<String>,
 }
 
 impl Default for Settings {
@@ -60,26 +149,75 @@ impl Default for Settings {
             network: None,
             bluetooth: None,
             idle_inhibitor: IdleInhibitorManager::new(),
+            idle_inhibit_generation: 0,
             sub_menu: None,
             upower: None,
+            battery_saver: None,
+            sleep_inhibitor: None,
+            inhibitors: Vec::new(),
+            mic_level: 0.,
+            recent_bluetooth: RecentList::load("recent-bluetooth"),
+            recent_wifi: RecentList::load("recent-wifi"),
             password_dialog: None,
+            wifi_password_attempt: None,
+            metered: false,
+            data_usage: DataUsage::load(),
+            shown_wifi_password: None,
+            speedtest: Speedtest::default(),
+            public_ip: None,
+            vpn_leak: false,
+            osk: None,
+            sensor_proxy: None,
+            rotation_locked: read_state("rotation-lock").is_some_and(|state| state == "true"),
+            notifications: None,
+            dnd_before_game_mode: None,
+            displays_off: None,
+            output_manager: OutputManager::new(),
+            menu_output: None,
+            mode_revert: None,
+            hdr: None,
+            input: None,
+            input_profile: read_state("input-profile"),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    ToggleMenu(Id, ButtonUIRef),
+    // the standalone indicators open the menu with their submenu expanded
+    ToggleMenu(Id, ButtonUIRef, Option<SubMenu>),
     UPower(UPowerMessage),
     Network(NetworkMessage),
     Bluetooth(BluetoothMessage),
     Audio(AudioMessage),
     Brightness(BrightnessMessage),
+    Display(DisplayMessage),
+    Input(InputMessage),
+    CompositorCommand(String),
     ToggleInhibitIdle,
+    InhibitIdleFor(Duration),
+    IdleInhibitExpired(u32),
+    ToggleOsk,
+    OskExited(u32),
+    SensorProxy(ServiceEvent<SensorProxyService>),
+    ToggleRotationLock,
+    Notifications(ServiceEvent<NotificationsService>),
+    ToggleDnd,
+    // do not disturb while the game mode is active
+    GameModeDnd(bool),
+    CheckSleepInhibitRules,
+    SleepInhibitRulesChecked(Vec<String>),
+    SleepInhibited(Vec<String>, Option<Inhibitor>),
+    InhibitorsListed(Vec<InhibitorInfo>),
     Lock,
+    OpenConfigEditor(Id),
     Power(PowerMessage),
+    DisplaysIdle(bool),
     ToggleSubMenu(SubMenu),
     PasswordDialog(password_dialog::Message),
+    DataUsageTick,
+    CheckPublicIp,
+    PublicIpChecked(Option<PublicIp>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -90,6 +228,20 @@ pub enum SubMenu {
     Wifi,
     Vpn,
     Bluetooth,
+    Displays,
+    Input,
+}
+
+impl Message {
+    /// Battery, network and audio updates, the parts of the exported status owned by the settings
+    pub fn changes_status(&self) -> bool {
+        matches!(
+            self,
+            Message::UPower(UPowerMessage::Event(_))
+                | Message::Network(NetworkMessage::Event(_))
+                | Message::Audio(AudioMessage::Event(_))
+        )
+    }
 }
 
 impl Settings {
@@ -97,13 +249,53 @@ impl Settings {
         &mut self,
         message: Message,
         config: &SettingsModuleConfig,
+        audio_config: &AudioConfig,
         outputs: &mut Outputs,
     ) -> Task<crate::app::Message> {
         match message {
-            Message::ToggleMenu(id, button_ui_ref) => {
+            Message::ToggleMenu(id, button_ui_ref, sub_menu) => {
+                let menu_open = outputs.is_menu_open(MenuType::Settings);
+
+                // another indicator of the open menu only switches the submenu
+                if let Some(sub_menu) =
+                    sub_menu.filter(|sub_menu| menu_open && self.sub_menu != Some(*sub_menu))
+                {
+                    return self.update(
+                        Message::ToggleSubMenu(sub_menu),
+                        config,
+                        audio_config,
+                        outputs,
+                    );
+                }
+
                 self.sub_menu = None;
                 self.password_dialog = None;
-                outputs.toggle_menu(id, MenuType::Settings, button_ui_ref)
+                self.shown_wifi_password = None;
+                self.menu_output = outputs
+                    .get_monitor_name(id)
+                    .or_else(|| outputs.get_menu_monitor_name(id))
+                    .map(str::to_owned);
+                if let Some(output_manager) = self.output_manager.as_mut() {
+                    output_manager.refresh();
+                }
+
+                let sub_menu_task = match sub_menu.filter(|_| !menu_open) {
+                    Some(sub_menu) => self.update(
+                        Message::ToggleSubMenu(sub_menu),
+                        config,
+                        audio_config,
+                        outputs,
+                    ),
+                    None => Task::none(),
+                };
+
+                Task::batch(vec![
+                    outputs.toggle_menu(id, MenuType::Settings, button_ui_ref),
+                    Task::perform(input_state(), |state| {
+                        crate::app::Message::Settings(Message::Input(InputMessage::State(state)))
+                    }),
+                    sub_menu_task,
+                ])
             }
             Message::Audio(msg) => match msg {
                 AudioMessage::Event(event) => match event {
@@ -112,10 +304,26 @@ impl Settings {
                         Task::none()
                     }
                     ServiceEvent::Update(data) => {
+                        let volume = self.osd_level(OsdKind::Volume);
+                        let microphone = self.osd_level(OsdKind::Microphone);
+
                         if let Some(audio) = self.audio.as_mut() {
+                            let commands = match &data {
+                                AudioEvent::Sinks(sinks) => {
+                                    audio.routing_commands(sinks, &audio_config.rules)
+                                }
+                                _ => Vec::new(),
+                            };
                             audio.update(data);
+
+                            for command in commands {
+                                let _ = audio.command(command);
+                            }
                         }
-                        Task::none()
+                        Task::batch(vec![
+                            self.show_osd_if_changed(OsdKind::Volume, volume),
+                            self.show_osd_if_changed(OsdKind::Microphone, microphone),
+                        ])
                     }
                     ServiceEvent::Error(_) => Task::none(),
                 },
@@ -125,6 +333,24 @@ impl Settings {
                     }
                     Task::none()
                 }
+                AudioMessage::SinkVolumeScrolled(direction) => {
+                    if let Some(audio) = self.audio.as_mut() {
+                        let _ = audio.command(AudioCommand::SinkVolumeStep(
+                            direction * audio_config.volume_step as i32,
+                            audio_config.scroll_max_volume(),
+                        ));
+                    }
+                    Task::none()
+                }
+                AudioMessage::SourceVolumeScrolled(direction) => {
+                    if let Some(audio) = self.audio.as_mut() {
+                        let _ = audio.command(AudioCommand::SourceVolumeStep(
+                            direction * audio_config.volume_step as i32,
+                            audio_config.scroll_max_volume(),
+                        ));
+                    }
+                    Task::none()
+                }
                 AudioMessage::SinkVolumeChanged(value) => {
                     if let Some(audio) = self.audio.as_mut() {
                         let _ = audio.command(AudioCommand::SinkVolume(value));
@@ -171,18 +397,31 @@ impl Settings {
                         Task::none()
                     }
                 }
+                AudioMessage::SourceLevel(level) => {
+                    self.mic_level = level;
+                    Task::none()
+                }
             },
             Message::UPower(msg) => match msg {
                 UPowerMessage::Event(event) => match event {
                     ServiceEvent::Init(service) => {
                         self.upower = Some(service);
-                        Task::none()
+                        self.battery_saver_automation(config.battery_saver.as_ref())
                     }
                     ServiceEvent::Update(data) => {
                         if let Some(upower) = self.upower.as_mut() {
+                            let previous = upower.battery;
                             upower.update(data);
+
+                            if let (Some(previous), Some(current)) = (previous, upower.battery) {
+                                battery_notifications(
+                                    &previous,
+                                    &current,
+                                    &config.battery_notifications,
+                                );
+                            }
                         }
-                        Task::none()
+                        self.battery_saver_automation(config.battery_saver.as_ref())
                     }
                     ServiceEvent::Error(_) => Task::none(),
                 },
@@ -202,17 +441,43 @@ impl Settings {
                 NetworkMessage::Event(event) => match event {
                     ServiceEvent::Init(service) => {
                         self.network = Some(service);
-                        Task::none()
+                        self.remember_wifi_network(config.recent_devices);
+                        self.update_metered(&config.metered_ssids);
+                        check_public_ip(config)
                     }
                     ServiceEvent::Update(NetworkEvent::RequestPasswordForSSID(ssid)) => {
-                        self.password_dialog = Some((ssid, "".to_string()));
-                        Task::none()
+                        if self.wifi_password_attempt.as_ref() == Some(&ssid) {
+                            self.wifi_password_attempt = None;
+                            self.password_dialog = Some(PasswordDialog::new(ssid, true));
+                            Task::none()
+                        } else {
+                            Task::perform(keyring_wifi_password(ssid.clone()), move |psk| {
+                                crate::app::Message::Settings(Message::Network(
+                                    NetworkMessage::KeyringPassword(ssid, psk),
+                                ))
+                            })
+                        }
                     }
                     ServiceEvent::Update(data) => {
+                        let connections = self.connection_names();
                         if let Some(network) = self.network.as_mut() {
                             network.update(data);
+
+                            if let Some(ssid) = &self.wifi_password_attempt {
+                                if network.active_connections.iter().any(|c| c.name() == *ssid) {
+                                    self.wifi_password_attempt = None;
+                                }
+                            }
+                        }
+                        self.remember_wifi_network(config.recent_devices);
+                        self.update_metered(&config.metered_ssids);
+
+                        // the public ip may change with the connections, e.g. toggling a VPN
+                        if connections != self.connection_names() {
+                            check_public_ip(config)
+                        } else {
+                            Task::none()
                         }
-                        Task::none()
                     }
                     _ => Task::none(),
                 },
@@ -255,9 +520,58 @@ impl Settings {
                 }
                 NetworkMessage::RequestWiFiPassword(id, ssid) => {
                     info!("Requesting password for {}", ssid);
-                    self.password_dialog = Some((ssid, "".to_string()));
+                    self.password_dialog = Some(PasswordDialog::new(ssid, false));
                     outputs.request_keyboard(id)
                 }
+                NetworkMessage::KeyringPassword(ssid, psk) => {
+                    let ap = self.network.as_ref().and_then(|network| {
+                        network
+                            .wireless_access_points
+                            .iter()
+                            .find(|ap| ap.ssid == ssid)
+                            .cloned()
+                    });
+
+                    match (self.network.as_mut(), ap, psk) {
+                        (Some(network), Some(ap), Some(psk)) => {
+                            debug!("Using the keyring password for {}", ssid);
+                            self.wifi_password_attempt = Some(ssid);
+
+                            network
+                                .command(NetworkCommand::SelectAccessPoint((
+                                    ap,
+                                    Some(WifiPassword { psk, keyring: true }),
+                                )))
+                                .map(|event| {
+                                    crate::app::Message::Settings(Message::Network(
+                                        NetworkMessage::Event(event),
+                                    ))
+                                })
+                        }
+                        _ => {
+                            self.password_dialog = Some(PasswordDialog::new(ssid, false));
+                            Task::none()
+                        }
+                    }
+                }
+                NetworkMessage::ShowPassword(ssid) => {
+                    Task::perform(saved_wifi_password(ssid.clone()), move |psk| {
+                        crate::app::Message::Settings(Message::Network(NetworkMessage::Password(
+                            ssid, psk,
+                        )))
+                    })
+                }
+                NetworkMessage::Password(ssid, psk) => {
+                    self.shown_wifi_password = Some((ssid, psk));
+                    Task::none()
+                }
+                NetworkMessage::HidePassword => {
+                    self.shown_wifi_password = None;
+                    Task::none()
+                }
+                NetworkMessage::CopyPassword(psk) => {
+                    Task::perform(copy_to_clipboard(psk), |_| crate::app::Message::None)
+                }
                 NetworkMessage::ScanNearByWiFi => {
                     if let Some(network) = self.network.as_mut() {
                         network
@@ -287,6 +601,35 @@ impl Settings {
                         Task::none()
                     }
                 }
+                NetworkMessage::RunSpeedtest => {
+                    if !self.speedtest.can_run() {
+                        return Task::none();
+                    }
+                    self.speedtest = Speedtest::Running;
+
+                    Task::perform(speedtest::run(), |result| {
+                        crate::app::Message::Settings(Message::Network(
+                            NetworkMessage::SpeedtestDone(result),
+                        ))
+                    })
+                }
+                NetworkMessage::SpeedtestDone(result) => {
+                    if let Err(err) = &result {
+                        warn!("Speed test failed: {}", err);
+                    }
+                    self.speedtest = Speedtest::Done {
+                        finished: Instant::now(),
+                        result,
+                    };
+
+                    // enables the button again
+                    Task::perform(tokio::time::sleep(speedtest::COOLDOWN), |_| {
+                        crate::app::Message::Settings(Message::Network(
+                            NetworkMessage::SpeedtestCooldownOver,
+                        ))
+                    })
+                }
+                NetworkMessage::SpeedtestCooldownOver => Task::none(),
                 NetworkMessage::ToggleVpn(vpn) => {
                     if let Some(network) = self.network.as_mut() {
                         network
@@ -305,12 +648,14 @@ impl Settings {
                 BluetoothMessage::Event(event) => match event {
                     ServiceEvent::Init(service) => {
                         self.bluetooth = Some(service);
+                        self.remember_bluetooth_devices(config.recent_devices);
                         Task::none()
                     }
                     ServiceEvent::Update(data) => {
                         if let Some(bluetooth) = self.bluetooth.as_mut() {
                             bluetooth.update(data);
                         }
+                        self.remember_bluetooth_devices(config.recent_devices);
                         Task::none()
                     }
                     _ => Task::none(),
@@ -326,6 +671,19 @@ impl Settings {
                         Task::none()
                     }
                 }
+                BluetoothMessage::Connect(device) => {
+                    if let Some(bluetooth) = self.bluetooth.as_mut() {
+                        bluetooth
+                            .command(BluetoothCommand::Connect(device))
+                            .map(|event| {
+                                crate::app::Message::Settings(Message::Bluetooth(
+                                    BluetoothMessage::Event(event),
+                                ))
+                            })
+                    } else {
+                        Task::none()
+                    }
+                }
                 BluetoothMessage::More(id) => {
                     if let Some(cmd) = &config.bluetooth_more_cmd {
                         crate::utils::launcher::execute_command(cmd.to_string());
@@ -342,10 +700,12 @@ impl Settings {
                         Task::none()
                     }
                     ServiceEvent::Update(data) => {
+                        let level = self.osd_level(OsdKind::Brightness);
+
                         if let Some(brightness) = self.brightness.as_mut() {
                             brightness.update(data);
                         }
-                        Task::none()
+                        self.show_osd_if_changed(OsdKind::Brightness, level)
                     }
                     _ => Task::none(),
                 },
@@ -368,48 +728,474 @@ impl Settings {
                     self.sub_menu.take();
                 } else {
                     self.sub_menu.replace(menu_type);
+                    self.mic_level = 0.;
+
+                    if menu_type == SubMenu::Power {
+                        return Task::perform(
+                            async move {
+                                list_inhibitors().await.unwrap_or_else(|err| {
+                                    warn!("Failed to list the logind inhibitors: {}", err);
+                                    Vec::new()
+                                })
+                            },
+                            |inhibitors| {
+                                crate::app::Message::Settings(Message::InhibitorsListed(inhibitors))
+                            },
+                        );
+                    }
+
+                    if let Some(output) = self
+                        .menu_output
+                        .clone()
+                        .filter(|_| menu_type == SubMenu::Displays)
+                    {
+                        return Task::perform(hdr_state(output), |state| {
+                            crate::app::Message::Settings(Message::Display(
+                                DisplayMessage::HdrState(state),
+                            ))
+                        });
+                    }
 
                     if menu_type == SubMenu::Wifi {
-                        if let Some(network) = self.network.as_mut() {
-                            return network
-                                .command(NetworkCommand::ScanNearByWiFi)
-                                .map(|event| {
-                                    crate::app::Message::Settings(Message::Network(
-                                        NetworkMessage::Event(event),
-                                    ))
-                                });
-                        }
+                        return self.scan_wifi();
                     }
                 }
 
                 Task::none()
             }
+            Message::Notifications(event) => {
+                match event {
+                    ServiceEvent::Init(service) => {
+                        self.notifications = Some(service);
+                    }
+                    ServiceEvent::Update(data) => {
+                        if let Some(notifications) = self.notifications.as_mut() {
+                            notifications.update(data);
+                        }
+                    }
+                    ServiceEvent::Error(_) => {}
+                }
+                Task::none()
+            }
+            Message::ToggleDnd => match self.notifications.as_mut() {
+                Some(notifications) => {
+                    // the choice of the user outlives the game mode
+                    self.dnd_before_game_mode = None;
+                    let dnd = !notifications.dnd;
+                    notifications
+                        .command(NotificationsCommand::SetDnd(dnd))
+                        .map(|event| crate::app::Message::Settings(Message::Notifications(event)))
+                }
+                None => Task::none(),
+            },
+            Message::GameModeDnd(active) => {
+                let Some(notifications) = self.notifications.as_mut() else {
+                    return Task::none();
+                };
+                let dnd = if active {
+                    self.dnd_before_game_mode = Some(notifications.dnd);
+                    true
+                } else {
+                    match self.dnd_before_game_mode.take() {
+                        Some(dnd) => dnd,
+                        None => return Task::none(),
+                    }
+                };
+
+                if dnd == notifications.dnd {
+                    Task::none()
+                } else {
+                    notifications
+                        .command(NotificationsCommand::SetDnd(dnd))
+                        .map(|event| crate::app::Message::Settings(Message::Notifications(event)))
+                }
+            }
             Message::ToggleInhibitIdle => {
                 if let Some(idle_inhibitor) = &mut self.idle_inhibitor {
                     idle_inhibitor.toggle();
+                    self.idle_inhibit_generation = self.idle_inhibit_generation.wrapping_add(1);
                 }
                 Task::none()
             }
+            Message::InhibitIdleFor(duration) => match &mut self.idle_inhibitor {
+                Some(idle_inhibitor) => {
+                    if !idle_inhibitor.is_inhibited() {
+                        idle_inhibitor.toggle();
+                    }
+                    self.idle_inhibit_generation = self.idle_inhibit_generation.wrapping_add(1);
+                    let generation = self.idle_inhibit_generation;
+
+                    Task::perform(tokio::time::sleep(duration), move |_| {
+                        app::Message::Settings(Message::IdleInhibitExpired(generation))
+                    })
+                }
+                None => Task::none(),
+            },
+            Message::IdleInhibitExpired(generation) => {
+                if let Some(idle_inhibitor) = self
+                    .idle_inhibitor
+                    .as_mut()
+                    .filter(|i| i.is_inhibited() && generation == self.idle_inhibit_generation)
+                {
+                    idle_inhibitor.toggle();
+                }
+                Task::none()
+            }
+            Message::ToggleOsk => match (self.osk.take(), &config.osk_cmd) {
+                (Some(pid), _) => {
+                    tokio::spawn(async move {
+                        let _ = tokio::process::Command::new("kill")
+                            .arg(pid.to_string())
+                            .status()
+                            .await;
+                    });
+
+                    Task::none()
+                }
+                (None, Some(osk_cmd)) => {
+                    // exec so that the pid is the keyboard itself and not the shell
+                    match tokio::process::Command::new("bash")
+                        .arg("-c")
+                        .arg(format!("exec {}", osk_cmd))
+                        .spawn()
+                    {
+                        Ok(mut child) => {
+                            self.osk = child.id();
+
+                            match self.osk {
+                                Some(pid) => Task::perform(
+                                    async move {
+                                        let _ = child.wait().await;
+                                    },
+                                    move |_| crate::app::Message::Settings(Message::OskExited(pid)),
+                                ),
+                                None => Task::none(),
+                            }
+                        }
+                        Err(err) => {
+                            warn!("Failed to start the on screen keyboard: {}", err);
+
+                            Task::none()
+                        }
+                    }
+                }
+                (None, None) => Task::none(),
+            },
+            Message::SensorProxy(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.sensor_proxy = Some(service);
+                    Task::none()
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(sensor_proxy) = self.sensor_proxy.as_mut() {
+                        let changed = sensor_proxy.orientation != data.orientation;
+                        sensor_proxy.update(data);
+
+                        if changed && !self.rotation_locked {
+                            rotate(config, sensor_proxy);
+                        }
+                    }
+                    Task::none()
+                }
+                ServiceEvent::Error(_) => Task::none(),
+            },
+            Message::ToggleRotationLock => {
+                self.rotation_locked = !self.rotation_locked;
+                write_state("rotation-lock", &self.rotation_locked.to_string());
+
+                // catch up with the rotations ignored while locked
+                if let Some(sensor_proxy) =
+                    self.sensor_proxy.as_ref().filter(|_| !self.rotation_locked)
+                {
+                    rotate(config, sensor_proxy);
+                }
+                Task::none()
+            }
+            Message::OskExited(pid) => {
+                if self.osk == Some(pid) {
+                    self.osk = None;
+                }
+
+                Task::none()
+            }
+            Message::DataUsageTick => {
+                let quota = self
+                    .active_wifi_ssid()
+                    .and_then(|ssid| config.data_quotas.iter().find(|quota| quota.ssid == ssid))
+                    .cloned();
+                let month = chrono::Local::now().format("%Y-%m").to_string();
+
+                if let (Some((before, after)), Some(quota)) = (
+                    self.data_usage.sample(
+                        &month,
+                        quota.as_ref().map(|quota| quota.ssid.as_str()),
+                        wireless_bytes(),
+                    ),
+                    quota,
+                ) {
+                    if after > before {
+                        self.data_usage.save();
+                    }
+                    data_quota_notifications(before, after, &quota, config.data_quota_warning);
+                }
+                Task::none()
+            }
+            Message::CheckPublicIp => check_public_ip(config),
+            Message::PublicIpChecked(public_ip) => {
+                let vpn_active = self.network.as_ref().is_some_and(|network| {
+                    network
+                        .active_connections
+                        .iter()
+                        .any(|c| matches!(c, ActiveConnectionInfo::Vpn { .. }))
+                });
+                let vpn_leak = vpn_active
+                    && public_ip
+                        .as_ref()
+                        .is_some_and(|public_ip| public_ip.is_leaking(&config.vpn_ip_ranges));
+
+                if let Some(public_ip) = public_ip.as_ref().filter(|_| vpn_leak && !self.vpn_leak) {
+                    warn!("Public ip {} outside the VPN ranges", public_ip.address);
+                    send_critical_notification(
+                        tr("vpn-leak"),
+                        tr_args("vpn-leak-body", &[("ip", public_ip.address.to_string())]),
+                    );
+                }
+                self.vpn_leak = vpn_leak;
+                self.public_ip = public_ip;
+                Task::none()
+            }
+            Message::CheckSleepInhibitRules => {
+                let rules = config.inhibit_sleep_processes.clone();
+
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || running_matches(&rules))
+                            .await
+                            .unwrap_or_default()
+                    },
+                    |matches| {
+                        crate::app::Message::Settings(Message::SleepInhibitRulesChecked(matches))
+                    },
+                )
+            }
+            Message::SleepInhibitRulesChecked(matches) => {
+                if matches.is_empty() {
+                    if self.sleep_inhibitor.take().is_some() {
+                        info!("No matching process running, sleep allowed");
+                    }
+
+                    Task::none()
+                } else if let Some((reasons, _)) = self.sleep_inhibitor.as_mut() {
+                    *reasons = matches;
+
+                    Task::none()
+                } else {
+                    let why = matches.join(", ");
+
+                    Task::perform(
+                        async move {
+                            match inhibit("sleep", &why).await {
+                                Ok(inhibitor) => {
+                                    info!("Sleep inhibited by {}", why);
+                                    (matches, Some(inhibitor))
+                                }
+                                Err(err) => {
+                                    warn!("Failed to take a sleep inhibitor: {}", err);
+                                    (matches, None)
+                                }
+                            }
+                        },
+                        |(matches, inhibitor)| {
+                            crate::app::Message::Settings(Message::SleepInhibited(
+                                matches, inhibitor,
+                            ))
+                        },
+                    )
+                }
+            }
+            Message::InhibitorsListed(inhibitors) => {
+                self.inhibitors = inhibitors;
+
+                Task::none()
+            }
+            Message::SleepInhibited(matches, inhibitor) => {
+                self.sleep_inhibitor = inhibitor.map(|inhibitor| (matches, inhibitor));
+
+                Task::none()
+            }
             Message::Lock => {
                 if let Some(lock_cmd) = &config.lock_cmd {
                     crate::utils::launcher::execute_command(lock_cmd.to_string());
                 }
                 Task::none()
             }
+            Message::OpenConfigEditor(id) => Task::batch(vec![
+                outputs.close_menu(id),
+                Task::done(crate::app::Message::OpenConfigEditor),
+            ]),
+            Message::Power(PowerMessage::TurnOffDisplays { id, this_output }) => {
+                let output = this_output
+                    .then(|| outputs.get_menu_monitor_name(id).map(str::to_owned))
+                    .flatten();
+                self.displays_off = Some(DisplaysOff::Pending(output));
+
+                outputs.close_menu(id)
+            }
             Message::Power(msg) => {
                 msg.update();
                 Task::none()
             }
+            Message::Display(msg) => {
+                match msg {
+                    DisplayMessage::SetMode(mode) => {
+                        if let (Some(output_manager), Some(output)) =
+                            (self.output_manager.as_mut(), self.menu_output.clone())
+                        {
+                            let previous = output_manager.current_mode(&output);
+
+                            match output_manager.set_mode(&output, mode) {
+                                Ok(()) => {
+                                    self.mode_revert = previous.map(|previous| ModeRevert {
+                                        output,
+                                        previous,
+                                        remaining: REVERT_TIMEOUT,
+                                    });
+                                }
+                                Err(err) => {
+                                    warn!("Failed to set the mode of {}: {}", output, err);
+                                }
+                            }
+                        }
+                    }
+                    DisplayMessage::KeepMode => {
+                        self.mode_revert = None;
+                    }
+                    DisplayMessage::RevertMode => {
+                        self.revert_mode();
+                    }
+                    DisplayMessage::ToggleAdaptiveSync(enabled) => {
+                        if let (Some(output_manager), Some(output)) =
+                            (self.output_manager.as_mut(), self.menu_output.as_deref())
+                        {
+                            if let Err(err) = output_manager.set_adaptive_sync(output, enabled) {
+                                warn!("Failed to set the adaptive sync of {}: {}", output, err);
+                            }
+                        }
+                    }
+                    DisplayMessage::ToggleHdr(hdr) => {
+                        let rule = self
+                            .output_manager
+                            .as_ref()
+                            .zip(self.menu_output.as_deref())
+                            .and_then(|(output_manager, output)| {
+                                let mode = output_manager.current_mode(output)?;
+                                let (position, scale) = output_manager.layout(output)?;
+
+                                Some(hdr_rule(output, mode, position, scale, hdr))
+                            });
+
+                        if let (Some(rule), Some(output)) = (rule, self.menu_output.clone()) {
+                            return Task::perform(set_hdr(output, rule), |state| {
+                                crate::app::Message::Settings(Message::Display(
+                                    DisplayMessage::HdrState(state),
+                                ))
+                            });
+                        }
+                    }
+                    DisplayMessage::HdrState(state) => {
+                        self.hdr = state;
+                    }
+                    DisplayMessage::RevertTick => match self.mode_revert.as_mut() {
+                        Some(revert) if revert.remaining > 1 => {
+                            revert.remaining -= 1;
+                        }
+                        Some(_) => {
+                            self.revert_mode();
+                        }
+                        None => {}
+                    },
+                }
+                Task::none()
+            }
+            Message::Input(msg) => {
+                let on_state = |state| {
+                    crate::app::Message::Settings(Message::Input(InputMessage::State(state)))
+                };
+
+                match msg {
+                    InputMessage::State(state) => {
+                        self.input = state;
+                        Task::none()
+                    }
+                    InputMessage::ToggleTouchpad => match self.input.as_ref() {
+                        Some(InputState {
+                            touchpad: Some(touchpad),
+                            touchpad_enabled,
+                            ..
+                        }) => Task::perform(
+                            set_touchpad_enabled(touchpad.clone(), !touchpad_enabled),
+                            on_state,
+                        ),
+                        _ => Task::none(),
+                    },
+                    InputMessage::SetNaturalScroll(enabled) => {
+                        Task::perform(set_natural_scroll(enabled), on_state)
+                    }
+                    InputMessage::SetDisableWhileTyping(enabled) => {
+                        Task::perform(set_disable_while_typing(enabled), on_state)
+                    }
+                    InputMessage::SetProfile(name) => {
+                        match config.input_profiles.iter().find(|p| p.name == name) {
+                            Some(profile) => {
+                                write_state("input-profile", &name);
+                                self.input_profile = Some(name);
+                                Task::perform(apply_profile(profile.clone()), |_| {
+                                    crate::app::Message::None
+                                })
+                            }
+                            None => Task::none(),
+                        }
+                    }
+                }
+            }
+            Message::CompositorCommand(command) => {
+                execute_command(command);
+                Task::none()
+            }
+            Message::DisplaysIdle(idle) => {
+                match (idle, self.displays_off.take()) {
+                    (true, Some(DisplaysOff::Pending(output))) => {
+                        displays_off(output.as_deref());
+                        self.displays_off = Some(DisplaysOff::Off);
+                    }
+                    (false, Some(DisplaysOff::Off)) => {
+                        displays_on();
+                    }
+                    (_, state) => {
+                        self.displays_off = state;
+                    }
+                }
+                Task::none()
+            }
             Message::PasswordDialog(msg) => match msg {
-                password_dialog::Message::PasswordChanged(password) => {
-                    if let Some((_, current_password)) = &mut self.password_dialog {
-                        *current_password = password;
+                password_dialog::Message::PasswordChanged(_)
+                | password_dialog::Message::ToggleReveal
+                | password_dialog::Message::ToggleKeyring(_) => {
+                    if let Some(dialog) = &mut self.password_dialog {
+                        dialog.update(&msg);
                     }
 
                     Task::none()
                 }
                 password_dialog::Message::DialogConfirmed(id) => {
-                    if let Some((ssid, password)) = self.password_dialog.take() {
+                    if let Some(PasswordDialog {
+                        ssid,
+                        password,
+                        keyring,
+                        ..
+                    }) = self.password_dialog.take()
+                    {
+                        self.wifi_password_attempt = Some(ssid.clone());
                         let network_command = if let Some(network) = self.network.as_mut() {
                             let ap = network
                                 .wireless_access_points
@@ -420,7 +1206,10 @@ impl Settings {
                                 network
                                     .command(NetworkCommand::SelectAccessPoint((
                                         ap,
-                                        Some(password),
+                                        Some(WifiPassword {
+                                            psk: password,
+                                            keyring,
+                                        }),
                                     )))
                                     .map(|event| {
                                         crate::app::Message::Settings(Message::Network(
@@ -447,9 +1236,290 @@ impl Settings {
         }
     }
 
-    pub fn menu_view(&self, id: Id, config: &SettingsModuleConfig) -> Element<Message> {
-        if let Some((ssid, current_password)) = &self.password_dialog {
-            password_dialog::view(id, ssid, current_password).map(Message::PasswordDialog)
+    /// Icon and level of the device a media key acts on
+    pub fn battery(&self) -> Option<&BatteryData> {
+        self.upower.as_ref()?.battery.as_ref()
+    }
+
+    pub fn ssid(&self) -> Option<&str> {
+        self.network
+            .as_ref()?
+            .active_connections
+            .iter()
+            .find_map(|connection| match connection {
+                ActiveConnectionInfo::WiFi { ssid, .. } => Some(ssid.as_str()),
+                _ => None,
+            })
+    }
+
+    pub fn volume(&self) -> Option<Volume> {
+        let audio = self.audio.as_ref()?;
+        let sink = audio
+            .sinks
+            .iter()
+            .find(|sink| sink.name == audio.server_info.default_sink)?;
+
+        Some(Volume {
+            level: audio.cur_sink_volume,
+            muted: sink.is_mute,
+        })
+    }
+
+    pub fn osd_level(&self, kind: OsdKind) -> (Icons, Option<f32>) {
+        match kind {
+            OsdKind::Volume => self
+                .audio
+                .as_ref()
+                .and_then(|audio| {
+                    audio
+                        .sinks
+                        .iter()
+                        .find(|sink| sink.name == audio.server_info.default_sink)
+                        .map(|sink| {
+                            if sink.is_mute {
+                                (Icons::Speaker0, Some(0.))
+                            } else {
+                                (
+                                    audio.sinks.get_icon(&audio.server_info.default_sink),
+                                    Some(audio.cur_sink_volume as f32 / 100.),
+                                )
+                            }
+                        })
+                })
+                .unwrap_or((Icons::Speaker3, None)),
+            OsdKind::Microphone => self
+                .audio
+                .as_ref()
+                .and_then(|audio| {
+                    audio
+                        .sources
+                        .iter()
+                        .find(|source| source.name == audio.server_info.default_source)
+                        .map(|source| {
+                            if source.is_mute {
+                                (Icons::Mic0, Some(0.))
+                            } else {
+                                (Icons::Mic1, Some(audio.cur_source_volume as f32 / 100.))
+                            }
+                        })
+                })
+                .unwrap_or((Icons::Mic1, None)),
+            OsdKind::Brightness => (
+                Icons::Brightness,
+                self.brightness
+                    .as_ref()
+                    .filter(|brightness| brightness.max > 0)
+                    .map(|brightness| brightness.current as f32 / brightness.max as f32),
+            ),
+        }
+    }
+
+    /// Shows the overlay when a level, known before the update, changed
+    /// from the keys, the sliders or another application
+    fn show_osd_if_changed(
+        &self,
+        kind: OsdKind,
+        before: (Icons, Option<f32>),
+    ) -> Task<crate::app::Message> {
+        if before.1.is_some() && self.osd_level(kind) != before {
+            Task::done(crate::app::Message::ShowOsd(kind))
+        } else {
+            Task::none()
+        }
+    }
+
+    fn remember_bluetooth_devices(&mut self, max: usize) {
+        if let Some(bluetooth) = self.bluetooth.as_ref() {
+            self.recent_bluetooth.remember(
+                bluetooth
+                    .devices
+                    .iter()
+                    .map(|device| (device.path.as_str(), device.name.as_str())),
+                max,
+            );
+        }
+    }
+
+    fn remember_wifi_network(&mut self, max: usize) {
+        if let Some(ActiveConnectionInfo::WiFi { ssid, .. }) =
+            self.network.as_ref().and_then(|network| {
+                network
+                    .active_connections
+                    .iter()
+                    .find(|c| matches!(c, ActiveConnectionInfo::WiFi { .. }))
+            })
+        {
+            self.recent_wifi
+                .remember([(ssid.as_str(), ssid.as_str())].into_iter(), max);
+        }
+    }
+
+    fn revert_mode(&mut self) {
+        if let (Some(revert), Some(output_manager)) =
+            (self.mode_revert.take(), self.output_manager.as_mut())
+        {
+            if let Err(err) = output_manager.set_mode(&revert.output, revert.previous) {
+                warn!("Failed to restore the mode of {}: {}", revert.output, err);
+            }
+        }
+    }
+
+    fn scan_wifi(&mut self) -> Task<crate::app::Message> {
+        match self.network.as_mut() {
+            Some(network) => network
+                .command(NetworkCommand::ScanNearByWiFi)
+                .map(|event| {
+                    crate::app::Message::Settings(Message::Network(NetworkMessage::Event(event)))
+                }),
+            None => Task::none(),
+        }
+    }
+
+    /// Resets the state of a section opened as a menu of its own
+    pub fn open_section_menu(&mut self, menu_type: &MenuType) -> Task<crate::app::Message> {
+        self.password_dialog = None;
+        self.shown_wifi_password = None;
+
+        if *menu_type == MenuType::Network {
+            self.scan_wifi()
+        } else {
+            Task::none()
+        }
+    }
+
+    fn wifi_data_usage(&self, config: &SettingsModuleConfig) -> Option<(u64, u64)> {
+        self.active_wifi_ssid().and_then(|ssid| {
+            config
+                .data_quotas
+                .iter()
+                .find(|quota| quota.ssid == ssid)
+                .map(|quota| (self.data_usage.used(ssid), quota.bytes()))
+        })
+    }
+
+    fn active_wifi_ssid(&self) -> Option<&str> {
+        self.network.as_ref().and_then(|network| {
+            network.active_connections.iter().find_map(|c| match c {
+                ActiveConnectionInfo::WiFi { ssid, .. } => Some(ssid.as_str()),
+                _ => None,
+            })
+        })
+    }
+
+    fn connection_names(&self) -> Vec<String> {
+        self.network
+            .as_ref()
+            .map(|network| {
+                network
+                    .active_connections
+                    .iter()
+                    .map(|c| c.name())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn update_metered(&mut self, metered_ssids: &[String]) {
+        self.metered = self
+            .network
+            .as_ref()
+            .is_some_and(|network| network.is_metered(metered_ssids));
+        set_metered(self.metered);
+    }
+
+    fn battery_saver_automation(
+        &mut self,
+        config: Option<&BatterySaverConfig>,
+    ) -> Task<crate::app::Message> {
+        let (Some(config), Some(upower)) = (config, self.upower.as_mut()) else {
+            return Task::none();
+        };
+        let Some(battery) = upower.battery else {
+            return Task::none();
+        };
+
+        let discharging = matches!(battery.status, BatteryStatus::Discharging(_));
+        let mut tasks = Vec::new();
+
+        if discharging && battery.capacity <= config.threshold && self.battery_saver.is_none() {
+            info!(
+                "Battery below {}%, enabling battery saver",
+                config.threshold
+            );
+            self.battery_saver = Some((
+                upower.power_profile,
+                self.brightness.as_ref().map(|b| b.current),
+            ));
+
+            if upower.power_profile != PowerProfile::PowerSaver {
+                tasks.push(
+                    upower
+                        .command(PowerProfileCommand::Set(PowerProfile::PowerSaver))
+                        .map(|event| {
+                            crate::app::Message::Settings(Message::UPower(UPowerMessage::Event(
+                                event,
+                            )))
+                        }),
+                );
+            }
+
+            if let (Some(percentage), Some(brightness)) =
+                (config.brightness, self.brightness.as_mut())
+            {
+                let value = brightness.max * percentage.min(100) / 100;
+                if value < brightness.current {
+                    tasks.push(
+                        brightness
+                            .command(BrightnessCommand::Set(value))
+                            .map(|event| {
+                                crate::app::Message::Settings(Message::Brightness(
+                                    BrightnessMessage::Event(event),
+                                ))
+                            }),
+                    );
+                }
+            }
+        } else if !discharging {
+            if let Some((profile, brightness_value)) = self.battery_saver.take() {
+                info!("Charging, restoring the state before the battery saver");
+
+                tasks.push(
+                    upower
+                        .command(PowerProfileCommand::Set(profile))
+                        .map(|event| {
+                            crate::app::Message::Settings(Message::UPower(UPowerMessage::Event(
+                                event,
+                            )))
+                        }),
+                );
+
+                if let (Some(value), Some(brightness)) =
+                    (brightness_value, self.brightness.as_mut())
+                {
+                    tasks.push(
+                        brightness
+                            .command(BrightnessCommand::Set(value))
+                            .map(|event| {
+                                crate::app::Message::Settings(Message::Brightness(
+                                    BrightnessMessage::Event(event),
+                                ))
+                            }),
+                    );
+                }
+            }
+        }
+
+        Task::batch(tasks)
+    }
+
+    pub fn menu_view(
+        &self,
+        id: Id,
+        config: &SettingsModuleConfig,
+        audio_config: &AudioConfig,
+    ) -> Element<Message> {
+        if let Some(dialog) = &self.password_dialog {
+            password_dialog::view(id, dialog).map(Message::PasswordDialog)
         } else {
             let battery_data = self
                 .upower
@@ -463,6 +1533,12 @@ impl Settings {
                         .on_press(Message::Lock)
                         .style(SettingsButtonStyle.into_style())
                 }))
+                .push(
+                    button(icon(Icons::Configure))
+                        .padding([8, 13])
+                        .on_press(Message::OpenConfigEditor(id))
+                        .style(SettingsButtonStyle.into_style()),
+                )
                 .push(
                     button(icon(if self.sub_menu == Some(SubMenu::Power) {
                         Icons::Close
@@ -482,14 +1558,32 @@ impl Settings {
                 .spacing(8)
                 .width(Length::Fill);
 
+            let (current_mode, modes, adaptive_sync) =
+                match (self.output_manager.as_ref(), self.menu_output.as_deref()) {
+                    (Some(output_manager), Some(output)) => (
+                        output_manager.current_mode(output),
+                        output_manager.modes(output),
+                        output_manager.adaptive_sync(output),
+                    ),
+                    _ => (None, Vec::new(), None),
+                };
+
             let (sink_slider, source_slider) = self
                 .audio
                 .as_ref()
-                .map(|a| a.audio_sliders(self.sub_menu))
+                .map(|a| a.audio_sliders(self.sub_menu, audio_config))
                 .unwrap_or((None, None));
 
             let wifi_setting_button = self.network.as_ref().and_then(|n| {
-                n.get_wifi_quick_setting_button(id, self.sub_menu, config.wifi_more_cmd.is_some())
+                n.get_wifi_quick_setting_button(
+                    id,
+                    self.sub_menu,
+                    config.wifi_more_cmd.is_some(),
+                    self.recent_wifi.entries(config.recent_devices),
+                    self.wifi_data_usage(config),
+                    &self.speedtest,
+                    self.shown_wifi_password.as_ref(),
+                )
             });
             let quick_settings = quick_settings_section(
                 vec![
@@ -502,6 +1596,7 @@ impl Settings {
                                 id,
                                 self.sub_menu,
                                 config.bluetooth_more_cmd.is_some(),
+                                self.recent_bluetooth.entries(config.recent_devices),
                             )
                         }),
                     self.network.as_ref().map(|n| {
@@ -509,6 +1604,8 @@ impl Settings {
                             id,
                             self.sub_menu,
                             config.vpn_more_cmd.is_some(),
+                            self.public_ip.as_ref(),
+                            self.vpn_leak,
                         )
                     }),
                     self.network
@@ -522,7 +1619,7 @@ impl Settings {
                                 } else {
                                     Icons::EyeClosed
                                 },
-                                "Idle Inhibitor".to_string(),
+                                tr("idle-inhibitor"),
                                 None,
                                 idle_inhibitor.is_inhibited(),
                                 Message::ToggleInhibitIdle,
@@ -531,6 +1628,101 @@ impl Settings {
                             None,
                         )
                     }),
+                    self.notifications
+                        .as_ref()
+                        .filter(|notifications| notifications.dnd_backend.is_some())
+                        .map(|notifications| {
+                            (
+                                quick_setting_button(
+                                    if notifications.dnd {
+                                        Icons::BellOff
+                                    } else {
+                                        Icons::Bell
+                                    },
+                                    tr("do-not-disturb"),
+                                    None,
+                                    notifications.dnd,
+                                    Message::ToggleDnd,
+                                    None,
+                                ),
+                                None,
+                            )
+                        }),
+                    config.osk_cmd.as_ref().map(|_| {
+                        (
+                            quick_setting_button(
+                                if self.osk.is_some() {
+                                    Icons::Keyboard
+                                } else {
+                                    Icons::KeyboardOff
+                                },
+                                tr("screen-keyboard"),
+                                None,
+                                self.osk.is_some(),
+                                Message::ToggleOsk,
+                                None,
+                            ),
+                            None,
+                        )
+                    }),
+                    self.sensor_proxy.as_ref().map(|sensor_proxy| {
+                        (
+                            quick_setting_button(
+                                if self.rotation_locked {
+                                    Icons::ScreenRotationLock
+                                } else {
+                                    Icons::ScreenRotation
+                                },
+                                tr("auto-rotate"),
+                                Some(tr(&format!(
+                                    "orientation-{}",
+                                    sensor_proxy.orientation.name()
+                                ))),
+                                !self.rotation_locked,
+                                Message::ToggleRotationLock,
+                                None,
+                            ),
+                            None,
+                        )
+                    }),
+                    self.input
+                        .as_ref()
+                        .filter(|input| {
+                            input.touchpad.is_some() || !config.input_profiles.is_empty()
+                        })
+                        .map(|input| {
+                            (
+                                quick_setting_button(
+                                    if input.touchpad_enabled {
+                                        Icons::Touchpad
+                                    } else {
+                                        Icons::TouchpadOff
+                                    },
+                                    tr("touchpad"),
+                                    self.input_profile.clone(),
+                                    input.touchpad_enabled,
+                                    if input.touchpad.is_some() {
+                                        Message::Input(InputMessage::ToggleTouchpad)
+                                    } else {
+                                        Message::ToggleSubMenu(SubMenu::Input)
+                                    },
+                                    Some((
+                                        SubMenu::Input,
+                                        self.sub_menu,
+                                        Message::ToggleSubMenu(SubMenu::Input),
+                                    )),
+                                ),
+                                self.sub_menu
+                                    .filter(|menu_type| *menu_type == SubMenu::Input)
+                                    .map(|_| {
+                                        input_submenu(
+                                            input,
+                                            &config.input_profiles,
+                                            self.input_profile.as_deref(),
+                                        )
+                                    }),
+                            )
+                        }),
                     self.upower
                         .as_ref()
                         .and_then(|u| u.power_profile.get_quick_setting_button()),
@@ -542,10 +1734,24 @@ impl Settings {
 
             Column::new()
                 .push(header)
+                .push_maybe(self.sleep_inhibitor.as_ref().map(|(reasons, _)| {
+                    row!(
+                        icon(Icons::SleepOff),
+                        text(tr_args(
+                            "sleep-inhibited",
+                            &[("processes", reasons.join(", "))]
+                        ))
+                        .size(12)
+                    )
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                }))
                 .push_maybe(
                     self.sub_menu
                         .filter(|menu_type| *menu_type == SubMenu::Power)
-                        .map(|_| sub_menu_wrapper(power_menu().map(Message::Power))),
+                        .map(|_| {
+                            sub_menu_wrapper(power_menu(id, &self.inhibitors).map(Message::Power))
+                        }),
                 )
                 .push_maybe(sink_slider)
                 .push_maybe(
@@ -565,23 +1771,199 @@ impl Settings {
                         .filter(|menu_type| *menu_type == SubMenu::Sources)
                         .and_then(|_| {
                             self.audio.as_ref().map(|a| {
-                                sub_menu_wrapper(
-                                    a.sources_submenu(id, config.audio_sources_more_cmd.is_some()),
-                                )
+                                sub_menu_wrapper(a.sources_submenu(
+                                    id,
+                                    config.audio_sources_more_cmd.is_some(),
+                                    self.mic_level,
+                                ))
                             })
                         }),
                 )
                 .push_maybe(self.brightness.as_ref().map(|b| b.brightness_slider()))
+                .push_maybe(
+                    current_mode.map(|current| {
+                        display_row(current, self.sub_menu, self.mode_revert.as_ref())
+                    }),
+                )
+                .push_maybe(
+                    self.sub_menu
+                        .filter(|menu_type| *menu_type == SubMenu::Displays)
+                        .zip(current_mode)
+                        .map(|(_, current)| {
+                            sub_menu_wrapper(modes_submenu(modes, current, adaptive_sync, self.hdr))
+                        }),
+                )
                 .push(quick_settings)
+                .push_maybe(
+                    (!config.compositor_commands.is_empty())
+                        .then(|| compositor_commands(&config.compositor_commands)),
+                )
                 .spacing(16)
                 .into()
         }
     }
 }
 
+/// Indicators of the settings module placed on their own in the bar, with
+/// `sectionMenus` they open their section as a menu of its own
+impl Settings {
+    pub fn battery_view(&self) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        self.upower
+            .as_ref()
+            .and_then(|upower| upower.battery)
+            .map(|battery| {
+                (
+                    if self.battery_saver.is_some() {
+                        battery.low_battery_indicator()
+                    } else {
+                        battery.indicator()
+                    },
+                    Some(OnModulePress::ToggleSettings(None)),
+                )
+            })
+    }
+
+    pub fn network_view(
+        &self,
+        config: &SettingsModuleConfig,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let network = self.network.as_ref()?;
+        let connection = network.get_connection_indicator(self.metered);
+        let vpn = network.get_vpn_indicator();
+
+        if connection.is_none() && vpn.is_none() {
+            return None;
+        }
+
+        Some((
+            Row::new()
+                .push_maybe(connection)
+                .push_maybe(vpn)
+                .spacing(4)
+                .into(),
+            Some(match network.wifi_present {
+                true if config.section_menus => OnModulePress::ToggleMenu(MenuType::Network),
+                true => OnModulePress::ToggleSettings(Some(SubMenu::Wifi)),
+                false => OnModulePress::ToggleSettings(None),
+            }),
+        ))
+    }
+
+    pub fn bluetooth_view(
+        &self,
+        config: &SettingsModuleConfig,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let bluetooth = self.bluetooth.as_ref()?;
+
+        let icon_type = match bluetooth.state {
+            BluetoothState::Unavailable => return None,
+            BluetoothState::Inactive => Icons::BluetoothOff,
+            BluetoothState::Active if bluetooth.devices.is_empty() => Icons::Bluetooth,
+            BluetoothState::Active => Icons::BluetoothConnected,
+        };
+
+        Some((
+            icon(icon_type).into(),
+            Some(match bluetooth.state {
+                BluetoothState::Active if config.section_menus => {
+                    OnModulePress::ToggleMenu(MenuType::Bluetooth)
+                }
+                BluetoothState::Active => OnModulePress::ToggleSettings(Some(SubMenu::Bluetooth)),
+                _ => OnModulePress::ToggleSettings(None),
+            }),
+        ))
+    }
+
+    pub fn audio_view(
+        &self,
+        config: &SettingsModuleConfig,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let indicator = self.audio.as_ref()?.sink_indicator()?;
+
+        Some((
+            swipe_area(indicator)
+                .on_vertical(|direction| {
+                    app::Message::Settings(Message::Audio(AudioMessage::SinkVolumeScrolled(
+                        direction,
+                    )))
+                })
+                .into(),
+            Some(if config.section_menus {
+                OnModulePress::ToggleMenu(MenuType::Audio)
+            } else {
+                OnModulePress::ToggleSettings(None)
+            }),
+        ))
+    }
+
+    pub fn network_menu_view(
+        &self,
+        id: Id,
+        config: &SettingsModuleConfig,
+    ) -> Option<Element<Message>> {
+        if let Some(dialog) = &self.password_dialog {
+            return Some(password_dialog::view(id, dialog).map(Message::PasswordDialog));
+        }
+
+        self.network.as_ref().map(|network| {
+            network
+                .wifi_menu(
+                    id,
+                    network.active_connections.iter().find_map(|c| match c {
+                        ActiveConnectionInfo::WiFi { ssid, strength, .. } => {
+                            Some((ssid.as_str(), *strength))
+                        }
+                        _ => None,
+                    }),
+                    config.wifi_more_cmd.is_some(),
+                    self.recent_wifi.entries(config.recent_devices),
+                    self.wifi_data_usage(config),
+                    &self.speedtest,
+                    self.shown_wifi_password.as_ref(),
+                )
+                .map(Message::Network)
+        })
+    }
+
+    pub fn bluetooth_menu_view(
+        &self,
+        id: Id,
+        config: &SettingsModuleConfig,
+    ) -> Option<Element<Message>> {
+        self.bluetooth
+            .as_ref()
+            .filter(|bluetooth| bluetooth.state == BluetoothState::Active)
+            .map(|bluetooth| {
+                bluetooth.bluetooth_menu(
+                    id,
+                    config.bluetooth_more_cmd.is_some(),
+                    self.recent_bluetooth.entries(config.recent_devices),
+                )
+            })
+    }
+
+    pub fn audio_menu_view(
+        &self,
+        id: Id,
+        config: &SettingsModuleConfig,
+        audio_config: &AudioConfig,
+    ) -> Option<Element<Message>> {
+        self.audio.as_ref().map(|audio| {
+            let (sink_slider, source_slider) = audio.audio_sliders(None, audio_config);
+
+            Column::new()
+                .push_maybe(sink_slider)
+                .push(audio.sinks_submenu(id, config.audio_sinks_more_cmd.is_some()))
+                .push_maybe(source_slider)
+                .spacing(16)
+                .into()
+        })
+    }
+}
+
 impl Module for Settings {
     type ViewData<'a> = ();
-    type SubscriptionData<'a> = ();
+    type SubscriptionData<'a> = (&'a SettingsModuleConfig, bool);
 
     fn view(
         &self,
@@ -607,13 +1989,24 @@ impl Module for Settings {
                         .as_ref()
                         .and_then(|p| p.power_profile.indicator()),
                 )
-                .push_maybe(self.audio.as_ref().and_then(|a| a.sink_indicator()))
+                .push_maybe(
+                    self.audio
+                        .as_ref()
+                        .and_then(|a| a.sink_indicator())
+                        .map(|indicator| {
+                            swipe_area(indicator).on_vertical(|direction| {
+                                app::Message::Settings(Message::Audio(
+                                    AudioMessage::SinkVolumeScrolled(direction),
+                                ))
+                            })
+                        }),
+                )
                 .push(
                     Row::new()
                         .push_maybe(
                             self.network
                                 .as_ref()
-                                .and_then(|n| n.get_connection_indicator()),
+                                .and_then(|n| n.get_connection_indicator(self.metered)),
                         )
                         .push_maybe(self.network.as_ref().and_then(|n| n.get_vpn_indicator()))
                         .spacing(4),
@@ -622,7 +2015,13 @@ impl Module for Settings {
                     self.upower
                         .as_ref()
                         .and_then(|upower| upower.battery)
-                        .map(|battery| battery.indicator()),
+                        .map(|battery| {
+                            if self.battery_saver.is_some() {
+                                battery.low_battery_indicator()
+                            } else {
+                                battery.indicator()
+                            }
+                        }),
                 )
                 .spacing(8)
                 .into(),
@@ -630,55 +2029,179 @@ impl Module for Settings {
         ))
     }
 
-    fn subscription(&self, _: Self::SubscriptionData<'_>) -> Option<Subscription<app::Message>> {
+    fn subscription(
+        &self,
+        (config, menu_open): Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
         Some(
             Subscription::batch(vec![
+                if config.inhibit_sleep_processes.is_empty() {
+                    Subscription::none()
+                } else {
+                    every(Duration::from_secs(10)).map(|_| Message::CheckSleepInhibitRules)
+                },
+                match config.public_ip_interval {
+                    Some(interval) => {
+                        every(Duration::from_secs(interval)).map(|_| Message::CheckPublicIp)
+                    }
+                    None => Subscription::none(),
+                },
+                if config.data_quotas.is_empty() {
+                    Subscription::none()
+                } else {
+                    every(Duration::from_secs(30)).map(|_| Message::DataUsageTick)
+                },
                 UPowerService::subscribe()
                     .map(|event| Message::UPower(UPowerMessage::Event(event))),
                 AudioService::subscribe().map(|evenet| Message::Audio(AudioMessage::Event(evenet))),
+                // the capture stream is only alive while the sources submenu is open
+                if menu_open && self.sub_menu == Some(SubMenu::Sources) {
+                    MicLevel::subscribe()
+                        .map(|level| Message::Audio(AudioMessage::SourceLevel(level)))
+                } else {
+                    Subscription::none()
+                },
                 BrightnessService::subscribe()
                     .map(|event| Message::Brightness(BrightnessMessage::Event(event))),
                 NetworkService::subscribe()
                     .map(|event| Message::Network(NetworkMessage::Event(event))),
                 BluetoothService::subscribe()
                     .map(|event| Message::Bluetooth(BluetoothMessage::Event(event))),
+                SensorProxyService::subscribe().map(Message::SensorProxy),
+                NotificationsService::subscribe().map(Message::Notifications),
+                if self.mode_revert.is_some() {
+                    every(Duration::from_secs(1))
+                        .map(|_| Message::Display(DisplayMessage::RevertTick))
+                } else {
+                    Subscription::none()
+                },
+                if self.displays_off.is_some() {
+                    IdleNotify::subscribe(DISPLAYS_OFF_DELAY).map(Message::DisplaysIdle)
+                } else {
+                    Subscription::none()
+                },
             ])
             .map(app::Message::Settings),
         )
     }
 }
 
-fn quick_settings_section<'a>(
-    buttons: Vec<(Element<'a, Message>, Option<Element<'a, Message>>)>,
-) -> Element<'a, Message> {
-    let mut section = column!().spacing(8);
+/// Runs the rotate command configured for the compositor with the current orientation
+fn rotate(config: &SettingsModuleConfig, sensor_proxy: &SensorProxyService) {
+    if let Some(rotate_cmd) = &config.rotate_cmd {
+        execute_command(
+            rotate_cmd
+                .replace("{orientation}", sensor_proxy.orientation.name())
+                .replace(
+                    "{transform}",
+                    &sensor_proxy.orientation.transform().to_string(),
+                ),
+        );
+    }
+}
 
-    let mut before: Option<(Element<'a, Message>, Option<Element<'a, Message>>)> = None;
+fn check_public_ip(config: &SettingsModuleConfig) -> Task<crate::app::Message> {
+    if config.public_ip_interval.is_some() {
+        Task::perform(public_ip::lookup(), |public_ip| {
+            crate::app::Message::Settings(Message::PublicIpChecked(public_ip))
+        })
+    } else {
+        Task::none()
+    }
+}
 
-    for (button, menu) in buttons.into_iter() {
-        if let Some((before_button, before_menu)) = before.take() {
-            section = section.push(row![before_button, button].width(Length::Fill).spacing(8));
+/// Warns once when the usage on the network crosses the warning
+/// percentage of its quota and again when the quota is used up
+fn data_quota_notifications(before: u64, after: u64, quota: &DataQuota, warning: u8) {
+    let total = quota.bytes();
+    let warning_at = total / 100 * warning as u64;
 
-            if let Some(menu) = before_menu {
-                section = section.push(sub_menu_wrapper(menu));
-            }
+    let title = if before < total && after >= total {
+        tr("data-quota-exceeded")
+    } else if before < warning_at && after >= warning_at {
+        tr("data-quota-warning")
+    } else {
+        return;
+    };
 
-            if let Some(menu) = menu {
-                section = section.push(sub_menu_wrapper(menu));
-            }
-        } else {
-            before = Some((button, menu));
+    send_notification(
+        title,
+        tr_args(
+            "data-quota-used",
+            &[
+                ("ssid", quota.ssid.clone()),
+                ("used", format_size(after)),
+                ("quota", format_size(total)),
+            ],
+        ),
+    );
+}
+
+fn battery_notifications(
+    previous: &BatteryData,
+    current: &BatteryData,
+    config: &BatteryNotificationsConfig,
+) {
+    let was_discharging = matches!(previous.status, BatteryStatus::Discharging(_));
+    let discharging = matches!(current.status, BatteryStatus::Discharging(_));
+    let level = || {
+        tr_args(
+            "battery-level",
+            &[("capacity", current.capacity.to_string())],
+        )
+    };
+
+    if was_discharging && !discharging && config.plugged {
+        send_notification(tr("battery-plugged"), level());
+    } else if !was_discharging && discharging && config.unplugged {
+        send_notification(tr("battery-unplugged"), level());
+    }
+
+    if matches!(current.status, BatteryStatus::Full)
+        && !matches!(previous.status, BatteryStatus::Full)
+        && config.full
+    {
+        send_notification(tr("battery-full"), level());
+    }
+
+    if discharging {
+        if previous.capacity > 5 && current.capacity <= 5 && config.critical {
+            send_critical_notification(tr("battery-critical"), level());
+        } else if previous.capacity > 20 && current.capacity <= 20 && config.low {
+            send_notification(tr("battery-low"), level());
         }
     }
+}
+
+/// Pairs the quick settings two by two, the last row could be half empty
+fn quick_settings_rows<T>(items: Vec<T>) -> Vec<(T, Option<T>)> {
+    let mut items = items.into_iter();
+    let mut rows = Vec::new();
+
+    while let Some(first) = items.next() {
+        rows.push((first, items.next()));
+    }
+
+    rows
+}
+
+fn quick_settings_section<'a>(
+    buttons: Vec<(Element<'a, Message>, Option<Element<'a, Message>>)>,
+) -> Element<'a, Message> {
+    let mut section = column!().spacing(8);
+
+    // the sub menus of both buttons are shown below their row
+    for ((first_button, first_menu), second) in quick_settings_rows(buttons) {
+        let (second_button, second_menu) =
+            second.unwrap_or_else(|| (horizontal_space().into(), None));
 
-    if let Some((before_button, before_menu)) = before.take() {
         section = section.push(
-            row![before_button, horizontal_space()]
+            row![first_button, second_button]
                 .width(Length::Fill)
                 .spacing(8),
         );
 
-        if let Some(menu) = before_menu {
+        for menu in [first_menu, second_menu].into_iter().flatten() {
             section = section.push(sub_menu_wrapper(menu));
         }
     }
@@ -686,6 +2209,45 @@ fn quick_settings_section<'a>(
     section.into()
 }
 
+const COMPOSITOR_COMMANDS_PER_ROW: usize = 3;
+
+fn compositor_commands(commands: &[CompositorCommand]) -> Element<Message> {
+    Column::with_children(commands.chunks(COMPOSITOR_COMMANDS_PER_ROW).map(|row| {
+        Row::with_children(
+            row.iter()
+                .map(|command| {
+                    button(
+                        column!(
+                            command
+                                .icon
+                                .clone()
+                                .map_or_else(|| icon(Icons::Console), icon_glyph)
+                                .size(18),
+                            text(command.label.clone()).size(10),
+                        )
+                        .spacing(4)
+                        .align_x(Alignment::Center),
+                    )
+                    .padding([6, 8])
+                    .width(Length::FillPortion(1))
+                    .on_press(Message::CompositorCommand(command.command.clone()))
+                    .style(QuickSettingsButtonStyle(false).into_style())
+                    .into()
+                })
+                // keeps the buttons of the last row as wide as the others
+                .chain(
+                    (row.len()..COMPOSITOR_COMMANDS_PER_ROW)
+                        .map(|_| horizontal_space().width(Length::FillPortion(1)).into()),
+                )
+                .collect::<Vec<_>>(),
+        )
+        .spacing(8)
+        .into()
+    }))
+    .spacing(8)
+    .into()
+}
+
 fn sub_menu_wrapper<Msg: 'static>(content: Element<Msg>) -> Element<Msg> {
     container(content)
         .style(|theme: &Theme| container::Style {
@@ -751,3 +2313,29 @@ fn quick_setting_button<'a, Msg: Clone + 'static>(
     .height(Length::Fixed(50.))
     .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quick_settings_in_pairs() {
+        assert_eq!(
+            quick_settings_rows(vec![1, 2, 3, 4]),
+            vec![(1, Some(2)), (3, Some(4))]
+        );
+    }
+
+    #[test]
+    fn odd_quick_settings_leave_the_last_row_half_empty() {
+        assert_eq!(
+            quick_settings_rows(vec![1, 2, 3]),
+            vec![(1, Some(2)), (3, None)]
+        );
+    }
+
+    #[test]
+    fn no_quick_settings() {
+        assert!(quick_settings_rows::<u8>(Vec::new()).is_empty());
+    }
+}
@@ -1,6 +1,13 @@
-use super::{quick_setting_button, sub_menu_wrapper, Message, SubMenu};
+use super::{
+    public_ip::PublicIp,
+    quick_setting_button,
+    recent::{recent_chips, RecentEntry},
+    speedtest::{Speedtest, SpeedtestResult},
+    sub_menu_wrapper, Message, SubMenu,
+};
 use crate::{
     components::icons::{icon, Icons},
+    i18n::{tr, tr_args},
     services::{
         network::{
             dbus::ConnectivityState, AccessPoint, ActiveConnectionInfo, KnownConnection,
@@ -9,12 +16,15 @@ use crate::{
         ServiceEvent,
     },
     style::{GhostButtonStyle, SettingsButtonStyle},
-    utils::IndicatorState,
+    utils::{format_size, IndicatorState},
 };
 use iced::{
-    widget::{button, column, container, horizontal_rule, row, scrollable, text, toggler, Column},
+    widget::{
+        button, column, container, horizontal_rule, progress_bar, row, scrollable, text, toggler,
+        Column,
+    },
     window::Id,
-    Alignment, Element, Length, Theme,
+    Alignment, Border, Element, Length, Theme,
 };
 
 #[derive(Debug, Clone)]
@@ -26,8 +36,16 @@ pub enum NetworkMessage {
     VpnMore(Id),
     SelectAccessPoint(AccessPoint),
     RequestWiFiPassword(Id, String),
+    KeyringPassword(String, Option<String>),
+    ShowPassword(String),
+    Password(String, Option<String>),
+    HidePassword,
+    CopyPassword(String),
     ToggleVpn(Vpn),
     ToggleAirplaneMode,
+    RunSpeedtest,
+    SpeedtestDone(Result<SpeedtestResult, String>),
+    SpeedtestCooldownOver,
 }
 
 static WIFI_SIGNAL_ICONS: [Icons; 6] = [
@@ -75,35 +93,52 @@ impl ActiveConnectionInfo {
 }
 
 impl NetworkData {
-    pub fn get_connection_indicator<Message: 'static>(&self) -> Option<Element<Message>> {
+    pub fn get_connection_indicator<Message: 'static>(
+        &self,
+        metered: bool,
+    ) -> Option<Element<Message>> {
         if self.airplane_mode || !self.wifi_present {
             None
         } else {
             Some(
                 self.active_connections
                     .iter()
-                    .find(|c| {
-                        matches!(c, ActiveConnectionInfo::WiFi { .. })
-                            || matches!(c, ActiveConnectionInfo::Wired { .. })
+                    // the wired connection is the primary one when both are up,
+                    // the wifi icon shows up when it fails over
+                    .find(|c| matches!(c, ActiveConnectionInfo::Wired { .. }))
+                    .or_else(|| {
+                        self.active_connections
+                            .iter()
+                            .find(|c| matches!(c, ActiveConnectionInfo::WiFi { .. }))
                     })
                     .map_or_else(
                         || icon(Icons::Wifi0).into(),
                         |a| {
                             let icon_type = a.get_icon();
-                            let state = (self.connectivity, a.get_indicator_state());
-
-                            container(icon(icon_type))
-                                .style(move |theme: &Theme| container::Style {
-                                    text_color: match state {
-                                        (ConnectivityState::Full, IndicatorState::Warning) => {
-                                            Some(theme.extended_palette().danger.weak.color)
-                                        }
-                                        (ConnectivityState::Full, _) => None,
-                                        _ => Some(theme.palette().danger),
-                                    },
-                                    ..Default::default()
-                                })
-                                .into()
+                            // without full connectivity the connection is unusable
+                            let state = match (self.connectivity, a.get_indicator_state()) {
+                                (ConnectivityState::Full, state) => state,
+                                _ => IndicatorState::Danger,
+                            };
+
+                            container(
+                                row!(icon(icon_type))
+                                    .push_maybe(state.badge().map(|badge| icon(badge).size(10)))
+                                    .push_maybe(metered.then(|| icon(Icons::Cash).size(10)))
+                                    .spacing(2)
+                                    .align_y(Alignment::Center),
+                            )
+                            .style(move |theme: &Theme| container::Style {
+                                text_color: match state {
+                                    IndicatorState::Warning => {
+                                        Some(theme.extended_palette().danger.weak.color)
+                                    }
+                                    IndicatorState::Danger => Some(theme.palette().danger),
+                                    _ => None,
+                                },
+                                ..Default::default()
+                            })
+                            .into()
                         },
                     ),
             )
@@ -131,11 +166,15 @@ impl NetworkData {
         id: Id,
         sub_menu: Option<SubMenu>,
         show_more_button: bool,
+        recent: &[RecentEntry],
+        data_usage: Option<(u64, u64)>,
+        speedtest: &Speedtest,
+        shown_password: Option<&(String, Option<String>)>,
     ) -> Option<(Element<Message>, Option<Element<Message>>)> {
         if self.wifi_present {
             let active_connection = self.active_connections.iter().find_map(|c| match c {
-                ActiveConnectionInfo::WiFi { name, strength, .. } => {
-                    Some((name, strength, c.get_icon()))
+                ActiveConnectionInfo::WiFi { ssid, strength, .. } => {
+                    Some((ssid, strength, c.get_icon()))
                 }
                 _ => None,
             });
@@ -143,7 +182,7 @@ impl NetworkData {
             Some((
                 quick_setting_button(
                     active_connection.map_or_else(|| Icons::Wifi0, |(_, _, icon)| icon),
-                    "Wi-Fi".to_string(),
+                    tr("wifi"),
                     active_connection.map(|(name, _, _)| name.clone()),
                     self.wifi_enabled,
                     Message::Network(NetworkMessage::ToggleWiFi),
@@ -161,6 +200,10 @@ impl NetworkData {
                             id,
                             active_connection.map(|(name, strengh, _)| (name.as_str(), *strengh)),
                             show_more_button,
+                            recent,
+                            data_usage,
+                            speedtest,
+                            shown_password,
                         ))
                         .map(Message::Network)
                     }),
@@ -175,11 +218,13 @@ impl NetworkData {
         id: Id,
         sub_menu: Option<SubMenu>,
         show_more_button: bool,
+        public_ip: Option<&PublicIp>,
+        vpn_leak: bool,
     ) -> (Element<Message>, Option<Element<Message>>) {
         (
             quick_setting_button(
                 Icons::Vpn,
-                "Vpn".to_string(),
+                tr("vpn"),
                 None,
                 self.active_connections
                     .iter()
@@ -190,7 +235,8 @@ impl NetworkData {
             sub_menu
                 .filter(|menu_type| *menu_type == SubMenu::Vpn)
                 .map(|_| {
-                    sub_menu_wrapper(self.vpn_menu(id, show_more_button)).map(Message::Network)
+                    sub_menu_wrapper(self.vpn_menu(id, show_more_button, public_ip, vpn_leak))
+                        .map(Message::Network)
                 }),
         )
     }
@@ -200,14 +246,34 @@ impl NetworkData {
         id: Id,
         active_connection: Option<(&str, u8)>,
         show_more_button: bool,
+        recent: &[RecentEntry],
+        data_usage: Option<(u64, u64)>,
+        speedtest: &Speedtest,
+        shown_password: Option<&(String, Option<String>)>,
     ) -> Element<NetworkMessage> {
+        let recent = recent_chips(
+            recent
+                .iter()
+                .filter(|entry| active_connection.map_or(true, |(ssid, _)| ssid != entry.id))
+                .map(|entry| {
+                    (
+                        entry.name.clone(),
+                        self.wireless_access_points
+                            .iter()
+                            .find(|ac| ac.ssid == entry.id)
+                            .map(|ac| NetworkMessage::SelectAccessPoint(ac.clone())),
+                    )
+                })
+                .collect(),
+        );
+
         let main = column!(
             row!(
-                text("Nearby Wifi").width(Length::Fill),
+                text(tr("nearby-wifi")).width(Length::Fill),
                 text(if self.scanning_nearby_wifi {
-                    "Scanning..."
+                    tr("scanning")
                 } else {
-                    ""
+                    String::new()
                 })
                 .size(12),
                 button(icon(Icons::Refresh))
@@ -221,13 +287,11 @@ impl NetworkData {
             horizontal_rule(1),
             container(scrollable(
                 Column::with_children(
-                    self.wireless_access_points
-                    .iter()
-                    .filter_map(|ac| if active_connection.is_some_and(|(ssid, _)| ssid == ac.ssid) {Some((ac, true))} else {None })
-                    .chain(self.wireless_access_points
-                        .iter()
-                        .filter_map(|ac| if active_connection.is_some_and(|(ssid, _)| ssid == ac.ssid) {None} else {Some((ac, false))})
+                    sorted_access_points(
+                        &self.wireless_access_points,
+                        active_connection.map(|(ssid, _)| ssid),
                     )
+                        .into_iter()
                         .map(|(ac, is_active)| {
                             let is_known = self.known_connections.iter().any(|c| {
                                 matches!(
@@ -236,7 +300,7 @@ impl NetworkData {
                                 )
                             });
 
-                            button(
+                            let network = button(
                                 container(
                                     row!(
                                         icon(if ac.public {
@@ -272,8 +336,22 @@ impl NetworkData {
                             } else {
                                 None
                             })
-                            .width(Length::Fill)
-                            .into()
+                            .width(Length::Fill);
+
+                            // only saved networks have a password to show
+                            if (is_known || is_active) && !ac.public {
+                                row!(
+                                    network,
+                                    button(icon(Icons::EyeOpened))
+                                        .padding([8, 8])
+                                        .style(GhostButtonStyle.into_style())
+                                        .on_press(NetworkMessage::ShowPassword(ac.ssid.clone())),
+                                )
+                                .align_y(Alignment::Center)
+                                .into()
+                            } else {
+                                network.into()
+                            }
                         })
                         .collect::<Vec<Element<NetworkMessage>>>(),
                 )
@@ -281,13 +359,21 @@ impl NetworkData {
             ))
             .max_height(200),
         )
+        .push_maybe(shown_password.map(|(ssid, psk)| password_view(ssid, psk.as_deref())))
         .spacing(8);
+        let main = Column::new()
+            .push_maybe(data_usage.map(|(used, quota)| data_usage_view(used, quota)))
+            .push_maybe(recent)
+            .push(main)
+            .push(horizontal_rule(1))
+            .push(speedtest_view(speedtest))
+            .spacing(12);
 
         if show_more_button {
             column!(
                 main,
                 horizontal_rule(1),
-                button("More")
+                button(text(tr("more")))
                     .on_press(NetworkMessage::WiFiMore(id))
                     .padding([4, 12])
                     .width(Length::Fill)
@@ -300,7 +386,13 @@ impl NetworkData {
         }
     }
 
-    pub fn vpn_menu(&self, id: Id, show_more_button: bool) -> Element<NetworkMessage> {
+    pub fn vpn_menu(
+        &self,
+        id: Id,
+        show_more_button: bool,
+        public_ip: Option<&PublicIp>,
+        vpn_leak: bool,
+    ) -> Element<NetworkMessage> {
         let main = Column::with_children(
             self.known_connections
                 .iter()
@@ -324,12 +416,16 @@ impl NetworkData {
                 .collect::<Vec<Element<NetworkMessage>>>(),
         )
         .spacing(8);
+        let main = Column::new()
+            .push(main)
+            .push_maybe(public_ip.map(|public_ip| public_ip_view(public_ip, vpn_leak)))
+            .spacing(12);
 
         if show_more_button {
             column!(
                 main,
                 horizontal_rule(1),
-                button("More")
+                button(text(tr("more")))
                     .on_press(NetworkMessage::VpnMore(id))
                     .padding([4, 12])
                     .width(Length::Fill)
@@ -348,7 +444,7 @@ impl NetworkData {
         (
             quick_setting_button(
                 Icons::Airplane,
-                "Airplane Mode".to_string(),
+                tr("airplane-mode"),
                 None,
                 self.airplane_mode,
                 Message::Network(NetworkMessage::ToggleAirplaneMode),
@@ -358,3 +454,223 @@ impl NetworkData {
         )
     }
 }
+
+/// Data used on the current network against its monthly quota
+fn data_usage_view<'a>(used: u64, quota: u64) -> Element<'a, NetworkMessage> {
+    let exceeded = used >= quota;
+
+    column!(
+        row!(
+            text(tr("data-usage")).width(Length::Fill),
+            text(tr_args(
+                "data-usage-of-quota",
+                &[("used", format_size(used)), ("quota", format_size(quota))],
+            ))
+            .size(12),
+        )
+        .align_y(Alignment::Center),
+        progress_bar(0.0..=quota as f32, used.min(quota) as f32)
+            .height(Length::Fixed(4.))
+            .style(move |theme: &Theme| progress_bar::Style {
+                background: theme.extended_palette().background.weak.color.into(),
+                bar: if exceeded {
+                    theme.palette().danger
+                } else {
+                    theme.palette().primary
+                }
+                .into(),
+                border: Border::default().rounded(2),
+            }),
+    )
+    .spacing(4)
+    .into()
+}
+
+/// Saved password of a network, NetworkManager asks polkit before handing it out
+fn password_view<'a>(ssid: &str, psk: Option<&str>) -> Element<'a, NetworkMessage> {
+    let content: Element<'a, NetworkMessage> = match psk {
+        Some(psk) => column!(
+            text(tr_args("wifi-password-of", &[("ssid", ssid.to_owned())])).size(12),
+            text(psk.to_owned()),
+        )
+        .spacing(2)
+        .width(Length::Fill)
+        .into(),
+        None => text(tr_args(
+            "wifi-password-unavailable",
+            &[("ssid", ssid.to_owned())],
+        ))
+        .size(12)
+        .width(Length::Fill)
+        .into(),
+    };
+
+    row!(content)
+        .push_maybe(psk.map(|psk| {
+            button(icon(Icons::ContentCopy))
+                .padding([4, 8])
+                .style(GhostButtonStyle.into_style())
+                .on_press(NetworkMessage::CopyPassword(psk.to_owned()))
+        }))
+        .push(
+            button(icon(Icons::EyeClosed))
+                .padding([4, 8])
+                .style(GhostButtonStyle.into_style())
+                .on_press(NetworkMessage::HidePassword),
+        )
+        .align_y(Alignment::Center)
+        .spacing(4)
+        .into()
+}
+
+fn public_ip_view<'a>(public_ip: &PublicIp, vpn_leak: bool) -> Element<'a, NetworkMessage> {
+    let address = match &public_ip.country {
+        Some(country) => format!("{} ({})", public_ip.address, country),
+        None => public_ip.address.to_string(),
+    };
+
+    column!(row!(
+        text(tr("public-ip")).width(Length::Fill),
+        text(address).size(12),
+    )
+    .align_y(Alignment::Center),)
+    .push_maybe(vpn_leak.then(|| {
+        container(
+            row!(icon(Icons::AlertCircle), text(tr("vpn-leak-hint")).size(12))
+                .spacing(8)
+                .align_y(Alignment::Center),
+        )
+        .style(|theme: &Theme| container::Style {
+            text_color: Some(theme.palette().danger),
+            ..Default::default()
+        })
+    }))
+    .spacing(4)
+    .into()
+}
+
+fn speedtest_view(speedtest: &Speedtest) -> Element<NetworkMessage> {
+    let result: Option<Element<NetworkMessage>> = match speedtest {
+        Speedtest::Idle => None,
+        Speedtest::Running => Some(text(tr("speedtest-running")).size(12).into()),
+        Speedtest::Done {
+            result: Ok(result), ..
+        } => Some(
+            text(tr_args(
+                "speedtest-result",
+                &[
+                    ("download", format!("{:.1}", result.download_mbps())),
+                    ("upload", format!("{:.1}", result.upload_mbps())),
+                    ("latency", result.latency.as_millis().to_string()),
+                ],
+            ))
+            .size(12)
+            .into(),
+        ),
+        Speedtest::Done {
+            result: Err(err), ..
+        } => Some(
+            text(tr_args("speedtest-failed", &[("error", err.clone())]))
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+                .into(),
+        ),
+    };
+
+    column!(row!(
+        text(tr("speedtest")).width(Length::Fill),
+        button(text(tr("run-speedtest")))
+            .padding([4, 12])
+            .style(SettingsButtonStyle.into_style())
+            .on_press_maybe(speedtest.can_run().then_some(NetworkMessage::RunSpeedtest)),
+    )
+    .align_y(Alignment::Center),)
+    .push_maybe(result)
+    .spacing(4)
+    .into()
+}
+
+/// Nearby networks for the wifi menu, the active one first and then
+/// the others from the strongest signal
+fn sorted_access_points<'a>(
+    access_points: &'a [AccessPoint],
+    active_ssid: Option<&str>,
+) -> Vec<(&'a AccessPoint, bool)> {
+    let mut sorted = access_points
+        .iter()
+        .map(|ac| (ac, active_ssid == Some(ac.ssid.as_str())))
+        .collect::<Vec<_>>();
+    sorted.sort_by(|(a, a_active), (b, b_active)| {
+        b_active
+            .cmp(a_active)
+            .then_with(|| b.strength.cmp(&a.strength))
+    });
+
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::network::dbus::DeviceState;
+    use zbus::zvariant::ObjectPath;
+
+    fn access_point(ssid: &str, strength: u8) -> AccessPoint {
+        AccessPoint {
+            ssid: ssid.to_owned(),
+            strength,
+            state: DeviceState::Activated,
+            public: false,
+            working: false,
+            path: ObjectPath::from_static_str_unchecked("/"),
+            device_path: ObjectPath::from_static_str_unchecked("/"),
+        }
+    }
+
+    fn ssids(sorted: Vec<(&AccessPoint, bool)>) -> Vec<(&str, bool)> {
+        sorted
+            .into_iter()
+            .map(|(ac, active)| (ac.ssid.as_str(), active))
+            .collect()
+    }
+
+    #[test]
+    fn strongest_networks_first() {
+        let access_points = vec![
+            access_point("weak", 20),
+            access_point("strong", 90),
+            access_point("medium", 50),
+        ];
+
+        assert_eq!(
+            ssids(sorted_access_points(&access_points, None)),
+            vec![("strong", false), ("medium", false), ("weak", false)]
+        );
+    }
+
+    #[test]
+    fn active_network_on_top() {
+        let access_points = vec![
+            access_point("strong", 90),
+            access_point("home", 30),
+            access_point("medium", 50),
+        ];
+
+        assert_eq!(
+            ssids(sorted_access_points(&access_points, Some("home"))),
+            vec![("home", true), ("strong", false), ("medium", false)]
+        );
+    }
+
+    #[test]
+    fn same_network_on_multiple_bands() {
+        let access_points = vec![access_point("home", 40), access_point("home", 70)];
+
+        assert_eq!(
+            ssids(sorted_access_points(&access_points, Some("home"))),
+            vec![("home", true), ("home", true)]
+        );
+    }
+}
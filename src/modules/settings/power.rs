@@ -1,27 +1,47 @@
 use crate::{
     components::icons::{icon, Icons},
+    i18n::{tr, tr_args},
+    services::logind::InhibitorInfo,
     style::GhostButtonStyle,
     utils,
 };
 use iced::{
-    widget::{button, column, horizontal_rule, row, text},
+    widget::{button, column, horizontal_rule, row, text, Column},
+    window::Id,
     Element, Length,
 };
 
 #[derive(Debug, Clone)]
 pub enum PowerMessage {
+    // the menu to close, and whether only its output is turned off
+    TurnOffDisplays { id: Id, this_output: bool },
     Suspend,
+    ForceSuspend,
     Reboot,
     Shutdown,
     Logout,
 }
 
+/// Displays turned off from the power menu, they are woken up on input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisplaysOff {
+    // waiting for the user to leave the mouse and keyboard,
+    // or the click on the menu would wake them up again
+    Pending(Option<String>),
+    Off,
+}
+
 impl PowerMessage {
     pub fn update(self) {
         match self {
+            // needs the outputs, handled by the settings module
+            PowerMessage::TurnOffDisplays { .. } => {}
             PowerMessage::Suspend => {
                 utils::launcher::suspend();
             }
+            PowerMessage::ForceSuspend => {
+                utils::launcher::force_suspend();
+            }
             PowerMessage::Reboot => {
                 utils::launcher::reboot();
             }
@@ -35,31 +55,100 @@ impl PowerMessage {
     }
 }
 
-pub fn power_menu<'a>() -> Element<'a, PowerMessage> {
+fn inhibitors_section(inhibitors: &[InhibitorInfo]) -> Element<PowerMessage> {
+    Column::with_children(
+        inhibitors
+            .iter()
+            .map(|inhibitor| {
+                column!(
+                    text(tr_args(
+                        "inhibited-by",
+                        &[
+                            ("who", inhibitor.who.clone()),
+                            ("what", inhibitor.what.replace(':', ", ")),
+                        ]
+                    ))
+                    .size(12),
+                    text(format!("{} ({})", inhibitor.why, inhibitor.mode)).size(10),
+                )
+                .spacing(2)
+                .into()
+            })
+            .collect::<Vec<_>>(),
+    )
+    .push(
+        button(row!(icon(Icons::Suspend), text(tr("force-suspend"))).spacing(16))
+            .padding([4, 12])
+            .on_press(PowerMessage::ForceSuspend)
+            .width(Length::Fill)
+            .style(GhostButtonStyle.into_style()),
+    )
+    .push(horizontal_rule(1))
+    .padding([0, 12])
+    .spacing(8)
+    .into()
+}
+
+pub fn power_menu(id: Id, inhibitors: &[InhibitorInfo]) -> Element<PowerMessage> {
+    Column::new()
+        .push_maybe((!inhibitors.is_empty()).then(|| inhibitors_section(inhibitors)))
+        .push(power_actions(id))
+        .into()
+}
+
+fn power_actions<'a>(id: Id) -> Element<'a, PowerMessage> {
     column!(
-        button(row!(icon(Icons::Suspend), text("Suspend")).spacing(16))
+        button(row!(icon(Icons::MonitorOff), text(tr("turn-off-displays"))).spacing(16))
+            .padding([4, 12])
+            .on_press(PowerMessage::TurnOffDisplays {
+                id,
+                this_output: false
+            })
+            .width(Length::Fill)
+            .style(GhostButtonStyle.into_style()),
+    )
+    .push_maybe((!utils::launcher::is_niri()).then(|| {
+        button(row!(icon(Icons::MonitorOff), text(tr("turn-off-this-display"))).spacing(16))
+            .padding([4, 12])
+            .on_press(PowerMessage::TurnOffDisplays {
+                id,
+                this_output: true,
+            })
+            .width(Length::Fill)
+            .style(GhostButtonStyle.into_style())
+    }))
+    .push(horizontal_rule(1))
+    .push(power_buttons())
+    .padding(8)
+    .width(Length::Fill)
+    .spacing(8)
+    .into()
+}
+
+fn power_buttons<'a>() -> Element<'a, PowerMessage> {
+    column!(
+        button(row!(icon(Icons::Suspend), text(tr("suspend"))).spacing(16))
             .padding([4, 12])
             .on_press(PowerMessage::Suspend)
             .width(Length::Fill)
             .style(GhostButtonStyle.into_style()),
-        button(row!(icon(Icons::Reboot), text("Reboot")).spacing(16))
+        button(row!(icon(Icons::Reboot), text(tr("reboot"))).spacing(16))
             .padding([4, 12])
             .on_press(PowerMessage::Reboot)
             .width(Length::Fill)
             .style(GhostButtonStyle.into_style()),
-        button(row!(icon(Icons::Power), text("Shutdown")).spacing(16))
+        button(row!(icon(Icons::Power), text(tr("shutdown"))).spacing(16))
             .padding([4, 12])
             .on_press(PowerMessage::Shutdown)
             .width(Length::Fill)
             .style(GhostButtonStyle.into_style()),
         horizontal_rule(1),
-        button(row!(icon(Icons::Logout), text("Logout")).spacing(16))
+        button(row!(icon(Icons::Logout), text(tr("logout"))).spacing(16))
             .padding([4, 12])
             .on_press(PowerMessage::Logout)
             .width(Length::Fill)
             .style(GhostButtonStyle.into_style()),
     )
-    .padding(8)
     .width(Length::Fill)
     .spacing(8)
     .into()
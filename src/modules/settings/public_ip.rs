@@ -0,0 +1,124 @@
+use log::error;
+use std::{net::IpAddr, process::Stdio};
+use tokio::process;
+
+// plain key=value lines, e.g. ip=203.0.113.7 and loc=IT
+const TRACE_URL: &str = "https://www.cloudflare.com/cdn-cgi/trace";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicIp {
+    pub address: IpAddr,
+    pub country: Option<String>,
+}
+
+impl PublicIp {
+    /// Outside all the ranges of the VPN provider, the traffic is not going through the VPN.
+    /// Without any range configured there is nothing to compare with
+    pub fn is_leaking(&self, vpn_ranges: &[String]) -> bool {
+        !vpn_ranges.is_empty()
+            && !vpn_ranges.iter().any(|range| {
+                range_contains(range, self.address).unwrap_or_else(|| {
+                    error!("Invalid VPN address range: {}", range);
+                    false
+                })
+            })
+    }
+}
+
+pub async fn lookup() -> Option<PublicIp> {
+    let curl_cmd = process::Command::new("curl")
+        .args(["--silent", "--fail", "--max-time", "10", TRACE_URL])
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match curl_cmd {
+        Ok(curl_cmd) if curl_cmd.status.success() => {
+            parse_trace(&String::from_utf8_lossy(&curl_cmd.stdout))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            error!("Error: {:?}", e);
+            None
+        }
+    }
+}
+
+fn parse_trace(output: &str) -> Option<PublicIp> {
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+    };
+
+    Some(PublicIp {
+        address: value("ip")?.parse().ok()?,
+        country: value("loc")
+            // XX is used when the country is unknown
+            .filter(|country| *country != "XX")
+            .map(str::to_owned),
+    })
+}
+
+/// Whether the address is inside a CIDR range like 198.51.100.0/24,
+/// None if the range is not valid
+fn range_contains(range: &str, address: IpAddr) -> Option<bool> {
+    let (network, prefix) = range.split_once('/').unwrap_or((range, ""));
+    let network = network.trim().parse::<IpAddr>().ok()?;
+
+    let (network, address, bits) = match (network, address) {
+        (IpAddr::V4(network), IpAddr::V4(address)) => {
+            (u32::from(network) as u128, u32::from(address) as u128, 32)
+        }
+        (IpAddr::V6(network), IpAddr::V6(address)) => {
+            (u128::from(network), u128::from(address), 128)
+        }
+        _ => return Some(false),
+    };
+    let prefix = if prefix.is_empty() {
+        bits
+    } else {
+        prefix
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|prefix| *prefix <= bits)?
+    };
+    let shift = bits - prefix;
+
+    // a /0 prefix shifts out all the bits
+    Some(
+        network.checked_shr(shift).unwrap_or_default()
+            == address.checked_shr(shift).unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_trace() {
+        let trace = "fl=123\nh=www.cloudflare.com\nip=203.0.113.7\nts=1.2\nloc=IT\n";
+
+        assert_eq!(
+            parse_trace(trace),
+            Some(PublicIp {
+                address: "203.0.113.7".parse().unwrap(),
+                country: Some("IT".to_owned()),
+            })
+        );
+    }
+
+    #[test]
+    fn matches_the_address_ranges() {
+        let address = "198.51.100.42".parse().unwrap();
+
+        assert_eq!(range_contains("198.51.100.0/24", address), Some(true));
+        assert_eq!(range_contains("198.51.101.0/24", address), Some(false));
+        assert_eq!(range_contains("0.0.0.0/0", address), Some(true));
+        assert_eq!(range_contains("198.51.100.42", address), Some(true));
+        assert_eq!(range_contains("2001:db8::/32", address), Some(false));
+        assert_eq!(range_contains("198.51.100.0/33", address), None);
+    }
+}
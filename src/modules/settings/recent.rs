@@ -0,0 +1,112 @@
+use crate::{
+    style::OutlineButtonStyle,
+    utils::state::{read_state, write_state},
+};
+use iced::{
+    widget::{button, text, Row},
+    Element, Theme,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentEntry {
+    pub id: String,
+    pub name: String,
+}
+
+/// Most recently used first list of devices persisted across restarts
+#[derive(Debug)]
+pub struct RecentList {
+    state_name: &'static str,
+    entries: Vec<RecentEntry>,
+}
+
+impl RecentList {
+    pub fn load(state_name: &'static str) -> Self {
+        let entries = read_state(state_name)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| {
+                        line.split_once('\t').map(|(id, name)| RecentEntry {
+                            id: id.to_string(),
+                            name: name.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            state_name,
+            entries,
+        }
+    }
+
+    /// Moves the currently connected entries on top of the list
+    pub fn remember<'a>(
+        &mut self,
+        connected: impl Iterator<Item = (&'a str, &'a str)>,
+        max: usize,
+    ) {
+        let mut entries = connected
+            .map(|(id, name)| RecentEntry {
+                id: id.to_string(),
+                name: name.to_string(),
+            })
+            .collect::<Vec<_>>();
+        for entry in &self.entries {
+            if !entries.iter().any(|e| e.id == entry.id) {
+                entries.push(entry.clone());
+            }
+        }
+        entries.truncate(max);
+
+        if max == 0 || entries == self.entries {
+            return;
+        }
+        self.entries = entries;
+
+        write_state(
+            self.state_name,
+            &self
+                .entries
+                .iter()
+                .map(|entry| format!("{}\t{}", entry.id, entry.name))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+    }
+
+    pub fn entries(&self, max: usize) -> &[RecentEntry] {
+        &self.entries[..max.min(self.entries.len())]
+    }
+}
+
+/// Reconnect buttons, the ones without a message are shown greyed out
+pub fn recent_chips<'a, Message: 'a + Clone>(
+    chips: Vec<(String, Option<Message>)>,
+) -> Option<Element<'a, Message>> {
+    if chips.is_empty() {
+        return None;
+    }
+
+    Some(
+        Row::with_children(chips.into_iter().map(|(name, msg)| {
+            let available = msg.is_some();
+
+            button(text(name).size(12).style(move |theme: &Theme| text::Style {
+                color: if available {
+                    None
+                } else {
+                    Some(theme.palette().text.scale_alpha(0.4))
+                },
+            }))
+            .padding([4, 12])
+            .style(OutlineButtonStyle.into_style())
+            .on_press_maybe(msg)
+            .into()
+        }))
+        .spacing(8)
+        .into(),
+    )
+}
@@ -0,0 +1,147 @@
+use log::error;
+use std::{
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use tokio::{io::AsyncWriteExt, process};
+
+const DOWNLOAD_URL: &str = "https://speed.cloudflare.com/__down?bytes=25000000";
+const UPLOAD_URL: &str = "https://speed.cloudflare.com/__up";
+const UPLOAD_BYTES: usize = 10_000_000;
+const MAX_TIME: &str = "30";
+// a test transfers tens of megabytes, a second click right
+// after the first one is most likely an accident
+pub const COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedtestResult {
+    // bytes per second
+    pub download: f64,
+    pub upload: f64,
+    pub latency: Duration,
+}
+
+impl SpeedtestResult {
+    pub fn download_mbps(&self) -> f64 {
+        self.download * 8. / 1_000_000.
+    }
+
+    pub fn upload_mbps(&self) -> f64 {
+        self.upload * 8. / 1_000_000.
+    }
+}
+
+#[derive(Debug, Default)]
+pub enum Speedtest {
+    #[default]
+    Idle,
+    Running,
+    Done {
+        finished: Instant,
+        result: Result<SpeedtestResult, String>,
+    },
+}
+
+impl Speedtest {
+    pub fn can_run(&self) -> bool {
+        match self {
+            Speedtest::Idle => true,
+            Speedtest::Running => false,
+            Speedtest::Done { finished, .. } => finished.elapsed() >= COOLDOWN,
+        }
+    }
+}
+
+/// Runs curl with the write out format and returns what it printed
+async fn curl(args: &[&str], body: Option<Vec<u8>>) -> Result<String, String> {
+    let mut child = process::Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", MAX_TIME])
+        .args(["--output", "/dev/null"])
+        .args(args)
+        .stdin(if body.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| {
+            error!("Failed to run curl: {}", err);
+            err.to_string()
+        })?;
+
+    if let (Some(body), Some(mut stdin)) = (body, child.stdin.take()) {
+        // curl stops reading when it gives up, the error is in its output
+        let _ = stdin.write_all(&body).await;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_owned())
+    }
+}
+
+// speed in bytes per second followed by the timings in seconds
+fn parse_values(output: &str) -> Option<Vec<f64>> {
+    output
+        .split_whitespace()
+        .map(|value| value.parse().ok())
+        .collect()
+}
+
+pub async fn run() -> Result<SpeedtestResult, String> {
+    let download = curl(
+        &[
+            "--write-out",
+            "%{speed_download} %{time_namelookup} %{time_connect}",
+            DOWNLOAD_URL,
+        ],
+        None,
+    )
+    .await?;
+    let upload = curl(
+        &[
+            "--write-out",
+            "%{speed_upload}",
+            "--data-binary",
+            "@-",
+            UPLOAD_URL,
+        ],
+        Some(vec![0; UPLOAD_BYTES]),
+    )
+    .await?;
+
+    match (
+        parse_values(&download).as_deref(),
+        parse_values(&upload).as_deref(),
+    ) {
+        (Some([download, namelookup, connect]), Some([upload])) => Ok(SpeedtestResult {
+            download: *download,
+            upload: *upload,
+            // the tcp handshake takes a round trip
+            latency: Duration::from_secs_f64((connect - namelookup).max(0.)),
+        }),
+        _ => Err(format!("unexpected curl output: {} {}", download, upload)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_curl_write_out() {
+        assert_eq!(
+            parse_values("3125000.000 0.012 0.032"),
+            Some(vec![3125000., 0.012, 0.032])
+        );
+        assert_eq!(parse_values("3125000 n/a"), None);
+    }
+}
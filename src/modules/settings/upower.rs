@@ -1,5 +1,6 @@
 use crate::{
     components::icons::{icon, Icons},
+    i18n::tr,
     services::{
         upower::{BatteryData, BatteryStatus, PowerProfile, UPowerService},
         ServiceEvent,
@@ -26,6 +27,7 @@ impl BatteryData {
 
         container(
             row!(icon(icon_type), text(format!("{}%", self.capacity)))
+                .push_maybe(state.badge().map(|badge| icon(badge).size(10)))
                 .spacing(4)
                 .align_y(Alignment::Center),
         )
@@ -40,12 +42,36 @@ impl BatteryData {
         .into()
     }
 
+    pub fn low_battery_indicator<'a, Message: 'static>(&self) -> Element<'a, Message> {
+        container(
+            row!(icon(self.get_icon()), text(format!("{}%", self.capacity)))
+                .push_maybe(
+                    IndicatorState::Danger
+                        .badge()
+                        .map(|badge| icon(badge).size(10)),
+                )
+                .spacing(4)
+                .align_y(Alignment::Center),
+        )
+        .padding([0, 6])
+        .style(|theme: &Theme| container::Style {
+            background: Background::Color(theme.palette().danger).into(),
+            text_color: Some(theme.palette().background),
+            border: Border::default().rounded(8),
+            ..Default::default()
+        })
+        .into()
+    }
+
     pub fn settings_indicator<'a, Message: 'static>(&self) -> Container<'a, Message> {
         let state = self.get_indicator_state();
 
         container({
             let battery_info = container(
-                row!(icon(self.get_icon()), text(format!("{}%", self.capacity))).spacing(4),
+                row!(icon(self.get_icon()), text(format!("{}%", self.capacity)))
+                    .push_maybe(state.badge().map(|badge| icon(badge).size(10)))
+                    .spacing(4)
+                    .align_y(Alignment::Center),
             )
             .style(move |theme: &Theme| container::Style {
                 text_color: Some(match state {
@@ -108,12 +134,11 @@ impl PowerProfile {
                 quick_setting_button(
                     (*self).into(),
                     match self {
-                        PowerProfile::Balanced => "Balanced",
-                        PowerProfile::Performance => "Performance",
-                        PowerProfile::PowerSaver => "Power Saver",
-                        PowerProfile::Unknown => "",
-                    }
-                    .to_string(),
+                        PowerProfile::Balanced => tr("power-profile-balanced"),
+                        PowerProfile::Performance => tr("power-profile-performance"),
+                        PowerProfile::PowerSaver => tr("power-profile-power-saver"),
+                        PowerProfile::Unknown => String::new(),
+                    },
                     None,
                     true,
                     Message::UPower(UPowerMessage::TogglePowerProfile),
@@ -0,0 +1,268 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::{SolarMode, SolarModuleConfig},
+    i18n::{tr, tr_args},
+    services::{
+        location::{Coordinates, LocationService},
+        ReadOnlyService, ServiceEvent,
+    },
+    utils::launcher::send_notification,
+};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta};
+use iced::{
+    time::every,
+    widget::{row, text},
+    Alignment, Element, Subscription,
+};
+use std::{f64::consts::PI, time::Duration};
+
+const SUNRISE_ALTITUDE: f64 = -0.833;
+// Muslim World League angles
+const FAJR_ALTITUDE: f64 = -18.;
+const ISHA_ALTITUDE: f64 = -17.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolarEvent {
+    Sunrise,
+    Sunset,
+    Fajr,
+    Dhuhr,
+    Asr,
+    Maghrib,
+    Isha,
+}
+
+impl SolarEvent {
+    fn label(self) -> String {
+        tr(match self {
+            SolarEvent::Sunrise => "sunrise",
+            SolarEvent::Sunset => "sunset",
+            SolarEvent::Fajr => "fajr",
+            SolarEvent::Dhuhr => "dhuhr",
+            SolarEvent::Asr => "asr",
+            SolarEvent::Maghrib => "maghrib",
+            SolarEvent::Isha => "isha",
+        })
+    }
+
+    fn icon(self) -> Icons {
+        match self {
+            SolarEvent::Sunrise => Icons::Sunrise,
+            SolarEvent::Sunset => Icons::Sunset,
+            _ => Icons::Mosque,
+        }
+    }
+}
+
+struct SolarPosition {
+    // solar declination in radians
+    declination: f64,
+    // solar noon in minutes from UTC midnight
+    noon: f64,
+}
+
+// NOAA approximation, good to about a minute
+fn solar_position(date: NaiveDate, longitude: f64) -> SolarPosition {
+    let gamma = 2. * PI / 365. * (date.ordinal0() as f64 + 0.5);
+
+    let equation_of_time = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2. * gamma).cos()
+            - 0.040849 * (2. * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2. * gamma).cos()
+        + 0.000907 * (2. * gamma).sin()
+        - 0.002697 * (3. * gamma).cos()
+        + 0.00148 * (3. * gamma).sin();
+
+    SolarPosition {
+        declination,
+        noon: 720. - 4. * longitude - equation_of_time,
+    }
+}
+
+/// Minutes from solar noon when the sun reaches the given altitude,
+/// None during polar days and nights
+fn hour_angle_minutes(position: &SolarPosition, latitude: f64, altitude: f64) -> Option<f64> {
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = (altitude.to_radians().sin()
+        - latitude.sin() * position.declination.sin())
+        / (latitude.cos() * position.declination.cos());
+
+    (-1. ..=1.)
+        .contains(&cos_hour_angle)
+        .then(|| 4. * cos_hour_angle.acos().to_degrees())
+}
+
+fn to_local(date: NaiveDate, minutes: f64) -> Option<DateTime<Local>> {
+    let midnight = date.and_hms_opt(0, 0, 0)?.and_utc();
+
+    Some((midnight + TimeDelta::seconds((minutes * 60.).round() as i64)).with_timezone(&Local))
+}
+
+fn day_events(
+    date: NaiveDate,
+    coordinates: Coordinates,
+    mode: SolarMode,
+) -> Vec<(SolarEvent, DateTime<Local>)> {
+    let position = solar_position(date, coordinates.longitude);
+    let before_noon = |altitude| {
+        hour_angle_minutes(&position, coordinates.latitude, altitude)
+            .map(|minutes| position.noon - minutes)
+    };
+    let after_noon = |altitude| {
+        hour_angle_minutes(&position, coordinates.latitude, altitude)
+            .map(|minutes| position.noon + minutes)
+    };
+
+    let events = match mode {
+        SolarMode::SunriseSunset => vec![
+            (SolarEvent::Sunrise, before_noon(SUNRISE_ALTITUDE)),
+            (SolarEvent::Sunset, after_noon(SUNRISE_ALTITUDE)),
+        ],
+        SolarMode::Prayer => {
+            // the shadow of an object equals its length plus the shadow at noon
+            let noon_angle = (coordinates.latitude.to_radians() - position.declination).abs();
+            let asr_altitude = (1. / (1. + noon_angle.tan())).atan().to_degrees();
+
+            vec![
+                (SolarEvent::Fajr, before_noon(FAJR_ALTITUDE)),
+                (SolarEvent::Sunrise, before_noon(SUNRISE_ALTITUDE)),
+                (SolarEvent::Dhuhr, Some(position.noon + 1.)),
+                (SolarEvent::Asr, after_noon(asr_altitude)),
+                (SolarEvent::Maghrib, after_noon(SUNRISE_ALTITUDE)),
+                (SolarEvent::Isha, after_noon(ISHA_ALTITUDE)),
+            ]
+        }
+    };
+
+    events
+        .into_iter()
+        .filter_map(|(event, minutes)| Some((event, to_local(date, minutes?)?)))
+        .collect()
+}
+
+fn next_event(
+    now: DateTime<Local>,
+    coordinates: Coordinates,
+    mode: SolarMode,
+) -> Option<(SolarEvent, DateTime<Local>)> {
+    // the events of a day are computed for the calendar day of the user
+    let today = now.date_naive();
+
+    [today, today + Days::new(1), today + Days::new(2)]
+        .into_iter()
+        .flat_map(|date| day_events(date, coordinates, mode))
+        .find(|(_, time)| *time > now)
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+    Location(ServiceEvent<LocationService>),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Solar {
+    location: Option<LocationService>,
+    next: Option<(SolarEvent, DateTime<Local>)>,
+    notified: Option<DateTime<Local>>,
+}
+
+impl Solar {
+    fn coordinates(&self, config: &SolarModuleConfig) -> Option<Coordinates> {
+        match (config.latitude, config.longitude) {
+            (Some(latitude), Some(longitude)) => Some(Coordinates {
+                latitude,
+                longitude,
+            }),
+            _ => self
+                .location
+                .as_ref()
+                .and_then(|location| location.coordinates),
+        }
+    }
+
+    pub fn update(&mut self, message: Message, config: &SolarModuleConfig) {
+        match message {
+            Message::Tick => {}
+            Message::Location(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.location = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(location) = self.location.as_mut() {
+                        location.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+
+        let now = Local::now();
+        self.next = self
+            .coordinates(config)
+            .and_then(|coordinates| next_event(now, coordinates, config.mode));
+
+        if let (Some((event, time)), Some(notify_before)) = (self.next, config.notify_before) {
+            if time - now <= TimeDelta::minutes(notify_before as i64) && self.notified != Some(time)
+            {
+                self.notified = Some(time);
+                send_notification(
+                    event.label(),
+                    tr_args(
+                        "solar-event-in",
+                        &[
+                            ("event", event.label()),
+                            ("minutes", (time - now).num_minutes().to_string()),
+                        ],
+                    ),
+                );
+            }
+        }
+    }
+}
+
+impl Module for Solar {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = &'a SolarModuleConfig;
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        self.next.map(|(event, time)| {
+            (
+                row!(
+                    icon(event.icon()),
+                    text(format!("{} {}", event.label(), time.format("%R")))
+                )
+                .align_y(Alignment::Center)
+                .spacing(4)
+                .into(),
+                None,
+            )
+        })
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let tick = every(Duration::from_secs(30)).map(|_| Message::Tick);
+
+        let subscription = if config.latitude.is_some() && config.longitude.is_some() {
+            tick
+        } else {
+            Subscription::batch(vec![
+                tick,
+                LocationService::subscribe().map(Message::Location),
+            ])
+        };
+
+        Some(subscription.map(app::Message::Solar))
+    }
+}
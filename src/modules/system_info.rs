@@ -1,25 +1,49 @@
 use crate::{
     app,
     components::icons::{icon, Icons},
-    config::SystemModuleConfig,
+    config::{SystemModuleConfig, TemperatureConfig},
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    metrics::{self, SystemSample},
+    style::SettingsButtonStyle,
+    utils::{
+        format_size,
+        launcher::{send_critical_notification, send_notification},
+        IndicatorState,
+    },
 };
 use iced::{
     time::every,
-    widget::{container, row, text, Row},
-    Alignment, Element, Subscription, Theme,
+    widget::{button, column, container, horizontal_rule, row, text, Column, Row},
+    Alignment, Element, Length, Subscription, Task, Theme,
 };
-use std::time::Duration;
-use sysinfo::{Components, System};
+use std::{
+    collections::HashMap,
+    fs,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use sysinfo::{Components, Disks, ProcessesToUpdate, System};
 
 use super::{Module, OnModulePress};
 
 struct SystemInfoData {
     pub cpu_usage: u32,
     pub memory_usage: u32,
-    pub temperature: Option<i32>,
+    pub temperature: Option<f32>,
+    pub sensors: Vec<(String, f32)>,
 }
 
-fn get_system_info(system: &mut System, components: &mut Components) -> SystemInfoData {
+fn get_system_info(
+    system: &mut System,
+    components: &mut Components,
+    temperature_config: &TemperatureConfig,
+) -> SystemInfoData {
     system.refresh_memory();
     system.refresh_cpu_specifics(sysinfo::CpuRefreshKind::everything());
 
@@ -33,32 +57,214 @@ fn get_system_info(system: &mut System, components: &mut Components) -> SystemIn
 
     let temperature = components
         .iter()
-        .find(|c| c.label() == "acpitz temp1")
-        .map(|c| c.temperature() as i32);
+        .find(|c| c.label() == temperature_config.sensor)
+        .map(|c| c.temperature());
+
+    let mut sensors = components
+        .iter()
+        .filter_map(|c| {
+            temperature_config
+                .labels
+                .get(c.label())
+                .map(|label| (label.clone(), c.temperature()))
+        })
+        .collect::<Vec<_>>();
+    sensors.sort_by(|a, b| a.0.cmp(&b.0));
 
     SystemInfoData {
         cpu_usage,
         memory_usage,
         temperature,
+        sensors,
+    }
+}
+
+struct DiskUsage {
+    pub mount_point: String,
+    pub usage: u32,
+    pub available: u64,
+}
+
+fn get_disks_usage(disks: &mut Disks, mount_points: &[String]) -> Vec<DiskUsage> {
+    disks.refresh_list();
+
+    mount_points
+        .iter()
+        .filter_map(|mount_point| {
+            let disk = disks
+                .iter()
+                .find(|disk| disk.mount_point() == Path::new(mount_point))?;
+            let total = disk.total_space();
+
+            if total == 0 {
+                None
+            } else {
+                Some(DiskUsage {
+                    mount_point: mount_point.clone(),
+                    usage: ((total - disk.available_space()) as f64 / total as f64 * 100.) as u32,
+                    available: disk.available_space(),
+                })
+            }
+        })
+        .collect()
+}
+
+// A minimal `du`, it doesn't follow symlinks and doesn't cross filesystem boundaries
+fn dir_size(path: &Path, device: u64, cancel: &AtomicBool) -> u64 {
+    if cancel.load(Ordering::Relaxed) {
+        return 0;
+    }
+
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.dev() != device => 0,
+        Ok(metadata) if metadata.is_dir() => fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| dir_size(&entry.path(), device, cancel))
+                    .sum()
+            })
+            .unwrap_or_default(),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+fn top_space_consumers(mount_point: &str, cancel: &AtomicBool) -> Vec<(String, u64)> {
+    let root = Path::new(mount_point);
+    let device = match fs::metadata(root) {
+        Ok(metadata) => metadata.dev(),
+        Err(_) => return Vec::new(),
+    };
+
+    let mut entries = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    let path = entry.path();
+                    let size = dir_size(&path, device, cancel);
+
+                    (path.to_string_lossy().into_owned(), size)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    entries.sort_by(|a, b| b.1.cmp(&a.1));
+    entries.truncate(5);
+
+    entries
+}
+
+const RAPL_PACKAGE: &str = "/sys/class/powercap/intel-rapl:0";
+const POWER_SUPPLY_FOLDER: &str = "/sys/class/power_supply";
+
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Energy counter of the cpu package and its wrap around value, in microjoules.
+/// Since Linux 5.10 the counter can only be read by root
+fn rapl_energy() -> Option<(u64, u64)> {
+    let package = Path::new(RAPL_PACKAGE);
+
+    Some((
+        read_number(&package.join("energy_uj"))?,
+        read_number(&package.join("max_energy_range_uj"))?,
+    ))
+}
+
+/// Discharge rate of the batteries, in watts
+fn battery_power() -> Option<f32> {
+    fs::read_dir(POWER_SUPPLY_FOLDER)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+        .filter_map(|entry| {
+            let path = entry.path();
+
+            read_number(&path.join("power_now")).or_else(|| {
+                Some(
+                    read_number(&path.join("current_now"))?
+                        * read_number(&path.join("voltage_now"))?
+                        / 1_000_000,
+                )
+            })
+        })
+        .reduce(|a, b| a + b)
+        .map(|microwatts| microwatts as f32 / 1_000_000.)
+}
+
+/// Cpu usage of the busiest processes, grouped by name, and of all
+/// the processes. The power draw is split between them by this usage
+fn cpu_consumers(system: &mut System) -> (Vec<(String, f32)>, f32) {
+    system.refresh_processes(ProcessesToUpdate::All, true);
+
+    let mut consumers = HashMap::<String, f32>::new();
+    for process in system.processes().values() {
+        *consumers
+            .entry(process.name().to_string_lossy().into_owned())
+            .or_default() += process.cpu_usage();
     }
+
+    let total = consumers.values().sum::<f32>();
+    let mut consumers = consumers
+        .into_iter()
+        .filter(|(_, cpu_usage)| *cpu_usage > 0.)
+        .collect::<Vec<_>>();
+    consumers.sort_by(|a, b| b.1.total_cmp(&a.1));
+    consumers.truncate(5);
+
+    (consumers, total)
+}
+
+enum DiskScan {
+    Idle,
+    Running {
+        mount_point: String,
+        cancel: Arc<AtomicBool>,
+    },
+    Completed {
+        mount_point: String,
+        entries: Vec<(String, u64)>,
+    },
 }
 
 pub struct SystemInfo {
     system: System,
     components: Components,
+    disks: Disks,
     data: SystemInfoData,
+    disks_usage: Vec<DiskUsage>,
+    // disks over the warning level, true once over the alert level too
+    notified_disks: HashMap<String, bool>,
+    disk_scan: DiskScan,
+    last_energy: Option<(u64, Instant)>,
+    power: Option<f32>,
+    // refreshed off the main thread, only while the menu is open
+    processes: Arc<Mutex<System>>,
+    cpu_consumers: (Vec<(String, f32)>, f32),
 }
 
 impl Default for SystemInfo {
     fn default() -> Self {
         let mut system = System::new();
         let mut components = Components::new_with_refreshed_list();
-        let data = get_system_info(&mut system, &mut components);
+        let data = get_system_info(&mut system, &mut components, &TemperatureConfig::default());
 
         Self {
             system,
             components,
+            disks: Disks::new(),
             data,
+            disks_usage: Vec::new(),
+            notified_disks: HashMap::new(),
+            disk_scan: DiskScan::Idle,
+            last_energy: None,
+            power: None,
+            processes: Arc::new(Mutex::new(System::new())),
+            cpu_consumers: (Vec::new(), 0.),
         }
     }
 }
@@ -66,16 +272,341 @@ impl Default for SystemInfo {
 #[derive(Debug, Clone)]
 pub enum Message {
     Update,
+    ScanDisk(String),
+    CancelScan,
+    ScanCompleted(String, Vec<(String, u64)>),
+    ConsumersSampled((Vec<(String, f32)>, f32)),
 }
 
 impl SystemInfo {
-    pub fn update(&mut self, message: Message) {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &SystemModuleConfig,
+        menu_open: bool,
+    ) -> Task<app::Message> {
         match message {
             Message::Update => {
-                self.data = get_system_info(&mut self.system, &mut self.components);
+                self.data =
+                    get_system_info(&mut self.system, &mut self.components, &config.temperature);
+                metrics::set_system_sample(SystemSample {
+                    cpu_usage: self.data.cpu_usage,
+                    memory_usage: self.data.memory_usage,
+                    temperature: self.data.temperature,
+                    sensors: self.data.sensors.clone(),
+                });
+
+                if !config.disks.is_empty() {
+                    self.disks_usage = get_disks_usage(&mut self.disks, &config.disks);
+
+                    for disk in self.disks_usage.iter() {
+                        if disk.usage > config.disk_warn_threshold {
+                            let critical = disk.usage > config.disk_alert_threshold;
+                            let previous = self
+                                .notified_disks
+                                .insert(disk.mount_point.clone(), critical);
+
+                            // once when it fills up, again when it reaches the alert level
+                            if previous.is_none() || (critical && previous == Some(false)) {
+                                let summary = tr_args(
+                                    "low-disk-space",
+                                    &[("mount", disk.mount_point.clone())],
+                                );
+                                let body = tr_args(
+                                    "disk-usage-details",
+                                    &[
+                                        ("usage", disk.usage.to_string()),
+                                        ("available", format_size(disk.available)),
+                                    ],
+                                );
+
+                                if critical {
+                                    send_critical_notification(summary, body);
+                                } else {
+                                    send_notification(summary, body);
+                                }
+                            }
+                        } else {
+                            self.notified_disks.remove(&disk.mount_point);
+                        }
+                    }
+                }
+
+                if config.power_consumers && menu_open {
+                    self.power = self.sample_power();
+
+                    let processes = self.processes.clone();
+                    Task::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || {
+                                processes
+                                    .lock()
+                                    .map(|mut system| cpu_consumers(&mut system))
+                                    .unwrap_or_default()
+                            })
+                            .await
+                            .unwrap_or_default()
+                        },
+                        |consumers| app::Message::SystemInfo(Message::ConsumersSampled(consumers)),
+                    )
+                } else {
+                    // the next sample would average the energy over the closed menu time
+                    self.last_energy = None;
+
+                    Task::none()
+                }
+            }
+            Message::ConsumersSampled(consumers) => {
+                self.cpu_consumers = consumers;
+
+                Task::none()
+            }
+            Message::ScanDisk(mount_point) => {
+                if let DiskScan::Running { cancel, .. } = &self.disk_scan {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+
+                let cancel = Arc::new(AtomicBool::new(false));
+                self.disk_scan = DiskScan::Running {
+                    mount_point: mount_point.clone(),
+                    cancel: cancel.clone(),
+                };
+
+                Task::perform(
+                    async move {
+                        let scan_mount_point = mount_point.clone();
+                        let entries = tokio::task::spawn_blocking(move || {
+                            top_space_consumers(&scan_mount_point, &cancel)
+                        })
+                        .await
+                        .unwrap_or_default();
+
+                        (mount_point, entries)
+                    },
+                    |(mount_point, entries)| {
+                        app::Message::SystemInfo(Message::ScanCompleted(mount_point, entries))
+                    },
+                )
+            }
+            Message::CancelScan => {
+                if let DiskScan::Running { cancel, .. } = &self.disk_scan {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+                self.disk_scan = DiskScan::Idle;
+
+                Task::none()
             }
+            Message::ScanCompleted(mount_point, entries) => {
+                if let DiskScan::Running {
+                    mount_point: current,
+                    cancel,
+                } = &self.disk_scan
+                {
+                    if *current == mount_point && !cancel.load(Ordering::Relaxed) {
+                        self.disk_scan = DiskScan::Completed {
+                            mount_point,
+                            entries,
+                        };
+                    }
+                }
+
+                Task::none()
+            }
+        }
+    }
+
+    /// Power draw since the previous sample, in watts
+    fn sample_power(&mut self) -> Option<f32> {
+        match rapl_energy() {
+            Some((energy, max_energy)) => {
+                let now = Instant::now();
+                let power = self.last_energy.map(|(last_energy, last_sample)| {
+                    let delta = if energy >= last_energy {
+                        energy - last_energy
+                    } else {
+                        energy + max_energy - last_energy
+                    };
+
+                    delta as f32 / 1_000_000. / now.duration_since(last_sample).as_secs_f32()
+                });
+                self.last_energy = Some((energy, now));
+
+                power
+            }
+            None => battery_power(),
         }
     }
+
+    pub fn menu_view(&self, config: &SystemModuleConfig) -> Element<Message> {
+        let disks = Column::with_children(
+            self.disks_usage
+                .iter()
+                .map(|disk| {
+                    row!(
+                        column!(
+                            text(disk.mount_point.clone()),
+                            text(tr_args(
+                                "disk-usage",
+                                &[
+                                    ("usage", disk.usage.to_string()),
+                                    ("available", format_size(disk.available)),
+                                ],
+                            ))
+                            .size(10)
+                        )
+                        .spacing(4)
+                        .width(Length::Fill),
+                        button(text(tr("scan")))
+                            .padding([4, 12])
+                            .on_press(Message::ScanDisk(disk.mount_point.clone()))
+                            .style(SettingsButtonStyle.into_style()),
+                    )
+                    .align_y(Alignment::Center)
+                    .spacing(8)
+                    .into()
+                })
+                .collect::<Vec<Element<Message>>>(),
+        )
+        .spacing(8);
+
+        let scan: Option<Element<Message>> = match &self.disk_scan {
+            DiskScan::Idle => None,
+            DiskScan::Running { mount_point, .. } => Some(
+                row!(
+                    text(tr_args("scanning-mount", &[("mount", mount_point.clone())]))
+                        .width(Length::Fill),
+                    button(text(tr("cancel")))
+                        .padding([4, 12])
+                        .on_press(Message::CancelScan)
+                        .style(SettingsButtonStyle.into_style()),
+                )
+                .align_y(Alignment::Center)
+                .spacing(8)
+                .into(),
+            ),
+            DiskScan::Completed {
+                mount_point,
+                entries,
+            } => Some(
+                column!(
+                    text(tr_args(
+                        "top-space-consumers",
+                        &[("mount", mount_point.clone())],
+                    )),
+                    Column::with_children(
+                        entries
+                            .iter()
+                            .map(|(path, size)| {
+                                row!(
+                                    text(path.clone()).size(12).width(Length::Fill),
+                                    text(format_size(*size)).size(12)
+                                )
+                                .spacing(8)
+                                .into()
+                            })
+                            .collect::<Vec<Element<Message>>>(),
+                    )
+                    .spacing(4)
+                )
+                .spacing(8)
+                .into(),
+            ),
+        };
+
+        // without a power reading the processes are ranked by cpu usage
+        let (consumers, cpu_total) = &self.cpu_consumers;
+        let power = (config.power_consumers && !consumers.is_empty()).then(|| {
+            column!(
+                text(match self.power {
+                    Some(power) => tr_args("power-draw", &[("watts", format!("{:.1}", power))]),
+                    None => tr("top-cpu-consumers"),
+                }),
+                Column::with_children(
+                    consumers
+                        .iter()
+                        .map(|(name, cpu_usage)| {
+                            row!(
+                                text(name.clone()).size(12).width(Length::Fill),
+                                text(match self.power {
+                                    Some(power) if *cpu_total > 0. => {
+                                        format!("{:.2} W", power * cpu_usage / cpu_total)
+                                    }
+                                    _ => format!("{:.0}%", cpu_usage),
+                                })
+                                .size(12)
+                            )
+                            .spacing(8)
+                            .into()
+                        })
+                        .collect::<Vec<Element<Message>>>(),
+                )
+                .spacing(4)
+            )
+            .spacing(8)
+        });
+
+        let sensors = (!self.data.sensors.is_empty()).then(|| {
+            Column::with_children(
+                self.data
+                    .sensors
+                    .iter()
+                    .map(|(label, temperature)| {
+                        row!(
+                            icon(Icons::Temp),
+                            text(label.clone()).width(Length::Fill),
+                            text(config.temperature.unit.format(*temperature))
+                        )
+                        .spacing(8)
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(4)
+        });
+
+        let has_sensors = sensors.is_some();
+
+        column!(disks)
+            .push_maybe(scan.map(|scan| column!(horizontal_rule(1), scan).spacing(12)))
+            .push_maybe(sensors.map(|sensors| {
+                Column::new()
+                    .push_maybe((!self.disks_usage.is_empty()).then(|| horizontal_rule(1)))
+                    .push(sensors)
+                    .spacing(12)
+            }))
+            .push_maybe(power.map(|power| {
+                Column::new()
+                    .push_maybe(
+                        (!self.disks_usage.is_empty() || has_sensors).then(|| horizontal_rule(1)),
+                    )
+                    .push(power)
+                    .spacing(12)
+            }))
+            .spacing(12)
+            .into()
+    }
+}
+
+fn indicator<'a>(
+    icon_type: Icons,
+    value: String,
+    state: IndicatorState,
+) -> Element<'a, app::Message> {
+    container(
+        row!(icon(icon_type), text(value))
+            .push_maybe(state.badge().map(|badge| icon(badge).size(10)))
+            .spacing(4)
+            .align_y(Alignment::Center),
+    )
+    .style(move |theme: &Theme| container::Style {
+        text_color: match state {
+            IndicatorState::Warning => Some(theme.extended_palette().danger.weak.color),
+            IndicatorState::Danger => Some(theme.palette().danger),
+            _ => None,
+        },
+        ..Default::default()
+    })
+    .into()
 }
 
 impl Module for SystemInfo {
@@ -96,62 +627,73 @@ impl Module for SystemInfo {
         let mem_warn_threshold = config.mem_warn_threshold;
         let mem_alert_threshold = config.mem_alert_threshold;
 
-        let temp_warn_threshold = config.temp_warn_threshold;
-        let temp_alert_threshold = config.temp_alert_threshold;
+        let temp_warn_threshold = config.temp_warn_threshold as f32;
+        let temp_alert_threshold = config.temp_alert_threshold as f32;
+        let temp_unit = config.temperature.unit;
+
+        let disk_warn_threshold = config.disk_warn_threshold;
+        let disk_alert_threshold = config.disk_alert_threshold;
 
         Some((
             Row::new()
-                .push(
-                    container(row!(icon(Icons::Cpu), text(format!("{}%", cpu_usage))).spacing(4))
-                        .style(move |theme: &Theme| container::Style {
-                            text_color: if cpu_usage > cpu_warn_threshold
-                                && cpu_usage < cpu_alert_threshold
-                            {
-                                Some(theme.extended_palette().danger.weak.color)
-                            } else if cpu_usage >= cpu_alert_threshold {
-                                Some(theme.palette().danger)
-                            } else {
-                                None
-                            },
-                            ..Default::default()
+                .push(indicator(
+                    Icons::Cpu,
+                    format!("{}%", cpu_usage),
+                    IndicatorState::from_thresholds(
+                        cpu_usage,
+                        cpu_warn_threshold,
+                        cpu_alert_threshold,
+                    ),
+                ))
+                .push(indicator(
+                    Icons::Mem,
+                    format!("{}%", memory_usage),
+                    IndicatorState::from_thresholds(
+                        memory_usage,
+                        mem_warn_threshold,
+                        mem_alert_threshold,
+                    ),
+                ))
+                .push_maybe(
+                    self.disks_usage
+                        .iter()
+                        .map(|disk| disk.usage)
+                        .max()
+                        .filter(|usage| *usage > disk_warn_threshold)
+                        .map(|usage| {
+                            indicator(
+                                Icons::Disk,
+                                format!("{}%", usage),
+                                IndicatorState::from_thresholds(
+                                    usage,
+                                    disk_warn_threshold,
+                                    disk_alert_threshold,
+                                ),
+                            )
                         }),
                 )
-                .push(
-                    container(
-                        row!(icon(Icons::Mem), text(format!("{}%", memory_usage))).spacing(4),
-                    )
-                    .style(move |theme: &Theme| container::Style {
-                        text_color: if memory_usage > mem_warn_threshold
-                            && memory_usage < mem_alert_threshold
-                        {
-                            Some(theme.extended_palette().danger.weak.color)
-                        } else if memory_usage >= mem_alert_threshold {
-                            Some(theme.palette().danger)
-                        } else {
-                            None
-                        },
-                        ..Default::default()
-                    }),
-                )
                 .push_maybe(temperature.map(|temperature| {
-                    container(row!(icon(Icons::Temp), text(format!("{}°", temperature))).spacing(4))
-                        .style(move |theme: &Theme| container::Style {
-                            text_color: if temperature > temp_warn_threshold
-                                && temperature < temp_alert_threshold
-                            {
-                                Some(theme.extended_palette().danger.weak.color)
-                            } else if temperature >= temp_alert_threshold {
-                                Some(theme.palette().danger)
-                            } else {
-                                None
-                            },
-                            ..Default::default()
-                        })
+                    indicator(
+                        Icons::Temp,
+                        temp_unit.format(temperature),
+                        IndicatorState::from_thresholds(
+                            temperature,
+                            temp_warn_threshold,
+                            temp_alert_threshold,
+                        ),
+                    )
                 }))
                 .align_y(Alignment::Center)
                 .spacing(4)
                 .into(),
-            None,
+            if config.disks.is_empty()
+                && !config.power_consumers
+                && config.temperature.labels.is_empty()
+            {
+                None
+            } else {
+                Some(OnModulePress::ToggleMenu(MenuType::SystemInfo))
+            },
         ))
     }
 
@@ -2,53 +2,143 @@ use super::{Module, OnModulePress};
 use crate::{
     app,
     components::icons::{icon, Icons},
+    config::TrayModuleConfig,
     menu::MenuType,
     position_button::position_button,
     services::{
         tray::{
             dbus::{Layout, LayoutProps},
-            TrayCommand, TrayService,
+            Tooltip, TrayCommand, TrayService,
         },
         ReadOnlyService, Service, ServiceEvent,
     },
-    style::GhostButtonStyle,
+    style::{fonts, GhostButtonStyle},
+    utils::icons::{resolve_icon, AppIcon},
 };
 use iced::{
-    widget::{button, horizontal_rule, row, text, toggler, Column, Image, Row},
+    font::{Style, Weight},
+    widget::{
+        button, container, horizontal_rule, mouse_area, rich_text, row, text, text::Span, toggler,
+        tooltip, Column, Row,
+    },
     window::Id,
-    Alignment, Element, Length, Subscription, Task,
+    Alignment, Border, Element, Font, Length, Subscription, Task, Theme,
 };
 use log::debug;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub enum TrayMessage {
     Event(ServiceEvent<TrayService>),
     ToggleSubmenu(i32),
     MenuSelected(String, i32),
+    Hover(String),
+    IconResolved(String, Option<AppIcon>),
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct TrayModule {
     pub service: Option<TrayService>,
     pub submenus: Vec<i32>,
+    // icons of the overrides, resolved off the view
+    icons: HashMap<String, Option<AppIcon>>,
+}
+
+/// Piece of a tooltip description, the items can use a small subset of HTML
+#[derive(Debug, Default, Clone, PartialEq)]
+struct MarkupSpan {
+    text: String,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Splits the markup in styled spans, unknown tags and images are dropped
+fn parse_markup(markup: &str) -> Vec<MarkupSpan> {
+    let mut spans = Vec::new();
+    let mut style = MarkupSpan::default();
+    let mut rest = markup;
+
+    while !rest.is_empty() {
+        let (content, tag) = match rest.find('<') {
+            Some(start) => match rest[start..].find('>') {
+                Some(end) => (&rest[..start], Some(&rest[start + 1..start + end])),
+                None => (rest, None),
+            },
+            None => (rest, None),
+        };
+
+        if !content.is_empty() {
+            spans.push(MarkupSpan {
+                text: decode_entities(content),
+                ..style.clone()
+            });
+        }
+
+        let Some(tag) = tag else {
+            break;
+        };
+        rest = &rest[content.len() + tag.len() + 2..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match name.as_str() {
+            "b" | "strong" => style.bold = !closing,
+            "i" | "em" => style.italic = !closing,
+            "u" | "a" => style.underline = !closing,
+            "br" => spans.push(MarkupSpan {
+                text: "\n".to_owned(),
+                ..Default::default()
+            }),
+            "p" if closing => spans.push(MarkupSpan {
+                text: "\n".to_owned(),
+                ..Default::default()
+            }),
+            _ => {}
+        }
+    }
+
+    spans
 }
 
 impl TrayModule {
-    pub fn update(&mut self, message: TrayMessage) -> Task<crate::app::Message> {
+    pub fn update(
+        &mut self,
+        message: TrayMessage,
+        config: &TrayModuleConfig,
+    ) -> Task<crate::app::Message> {
         match message {
             TrayMessage::Event(event) => match event {
                 ServiceEvent::Init(service) => {
                     self.service = Some(service);
-                    Task::none()
+                    self.resolve_icons(config)
                 }
                 ServiceEvent::Update(data) => {
                     if let Some(service) = self.service.as_mut() {
                         service.update(data);
                     }
-                    Task::none()
+                    self.resolve_icons(config)
                 }
                 ServiceEvent::Error(_) => Task::none(),
             },
+            TrayMessage::IconResolved(name, icon) => {
+                self.icons.insert(name, icon);
+                Task::none()
+            }
             TrayMessage::ToggleSubmenu(index) => {
                 if self.submenus.contains(&index) {
                     self.submenus.retain(|i| i != &index);
@@ -67,7 +157,30 @@ impl TrayModule {
                     Task::none()
                 }
             }
+            // the tooltip is refreshed when the pointer enters the item
+            TrayMessage::Hover(name) => match self.service.as_mut() {
+                Some(service) => service
+                    .command(TrayCommand::FetchTooltip(name))
+                    .map(|event| crate::app::Message::Tray(TrayMessage::Event(event))),
+                None => Task::none(),
+            },
+        }
+    }
+
+    fn resolve_icons(&mut self, config: &TrayModuleConfig) -> Task<crate::app::Message> {
+        let mut lookups = Vec::new();
+        for name in config.overrides.iter().filter_map(|o| o.icon.as_ref()) {
+            if !self.icons.contains_key(name) {
+                // the icon of the item is shown until the lookup completes
+                self.icons.insert(name.clone(), None);
+                let name = name.clone();
+                lookups.push(Task::perform(resolve_icon(name.clone()), move |icon| {
+                    crate::app::Message::Tray(TrayMessage::IconResolved(name, icon))
+                }));
+            }
         }
+
+        Task::batch(lookups)
     }
 
     pub fn menu_view(&self, name: &'_ str) -> Element<TrayMessage> {
@@ -153,13 +266,61 @@ impl TrayModule {
     }
 }
 
+fn tooltip_content<'a, Message: 'a>(tooltip: &'a Tooltip) -> Element<'a, Message> {
+    let text_font = fonts().text;
+    let description = parse_markup(&tooltip.description)
+        .into_iter()
+        .map(|span| {
+            Span::new(span.text)
+                .font(Font {
+                    weight: if span.bold {
+                        Weight::Bold
+                    } else {
+                        text_font.weight
+                    },
+                    style: if span.italic {
+                        Style::Italic
+                    } else {
+                        text_font.style
+                    },
+                    ..text_font
+                })
+                .underline(span.underline)
+        })
+        .collect::<Vec<Span<'_, ()>>>();
+
+    container(
+        Row::new()
+            .push_maybe(tooltip.icon.as_ref().map(|icon| icon.view(32.)))
+            .push(
+                Column::new()
+                    .push_maybe((!tooltip.title.is_empty()).then(|| text(tooltip.title.clone())))
+                    .push_maybe((!description.is_empty()).then(|| rich_text(description).size(12)))
+                    .spacing(4),
+            )
+            .spacing(12)
+            .align_y(Alignment::Center),
+    )
+    .padding([8, 12])
+    .style(|theme: &Theme| container::Style {
+        background: Some(theme.palette().background.into()),
+        border: Border {
+            color: theme.extended_palette().secondary.base.color,
+            width: 1.,
+            radius: 8.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
 impl Module for TrayModule {
-    type ViewData<'a> = Id;
+    type ViewData<'a> = (Id, &'a TrayModuleConfig);
     type SubscriptionData<'a> = ();
 
     fn view(
         &self,
-        id: Self::ViewData<'_>,
+        (id, config): Self::ViewData<'_>,
     ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
         self.service
             .as_ref()
@@ -171,13 +332,24 @@ impl Module for TrayModule {
                             .data
                             .iter()
                             .map(|item| {
-                                position_button(if let Some(pixmap) = &item.icon_pixmap {
-                                    Into::<Element<_>>::into(
-                                        Image::new(pixmap.clone()).height(Length::Fixed(14.)),
-                                    )
-                                } else {
-                                    icon(Icons::Point).into()
-                                })
+                                let item_override =
+                                    config.overrides.iter().find(|o| o.id == item.id);
+                                let size = config.icon_size
+                                    * item_override.map_or(1., |item_override| item_override.scale);
+                                let app_icon = item_override
+                                    .and_then(|item_override| item_override.icon.as_ref())
+                                    .and_then(|name| self.icons.get(name).cloned().flatten())
+                                    .or_else(|| item.icon.clone());
+
+                                // every icon takes the same space whatever its scale
+                                let item_button = position_button(
+                                    container(if let Some(app_icon) = app_icon {
+                                        app_icon.view(size)
+                                    } else {
+                                        icon(Icons::Point).into()
+                                    })
+                                    .center(Length::Fixed(config.icon_size)),
+                                )
                                 .on_press_with_position(move |button_ui_ref| {
                                     app::Message::ToggleMenu(
                                         MenuType::Tray(item.name.to_owned()),
@@ -186,8 +358,22 @@ impl Module for TrayModule {
                                     )
                                 })
                                 .padding([2, 2])
-                                .style(GhostButtonStyle.into_style())
-                                .into()
+                                .style(GhostButtonStyle.into_style());
+                                let item_button = mouse_area(item_button).on_enter(
+                                    app::Message::Tray(TrayMessage::Hover(item.name.to_owned())),
+                                );
+
+                                // drawn inside the bar surface, it never takes the pointer
+                                // away from the menus
+                                match item.tooltip.as_ref() {
+                                    Some(item_tooltip) => tooltip(
+                                        item_button,
+                                        tooltip_content(item_tooltip),
+                                        tooltip::Position::Bottom,
+                                    )
+                                    .into(),
+                                    None => item_button.into(),
+                                }
                             })
                             .collect::<Vec<_>>(),
                     )
@@ -203,3 +389,47 @@ impl Module for TrayModule {
         Some(TrayService::subscribe().map(|e| app::Message::Tray(TrayMessage::Event(e))))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, bold: bool, italic: bool) -> MarkupSpan {
+        MarkupSpan {
+            text: text.to_owned(),
+            bold,
+            italic,
+            underline: false,
+        }
+    }
+
+    #[test]
+    fn plain_description() {
+        assert_eq!(
+            parse_markup("3 new messages"),
+            vec![span("3 new messages", false, false)]
+        );
+    }
+
+    #[test]
+    fn styled_description() {
+        assert_eq!(
+            parse_markup("<b>Syncing</b> <i>3 files</i><br/>a &amp; b"),
+            vec![
+                span("Syncing", true, false),
+                span(" ", false, false),
+                span("3 files", false, true),
+                span("\n", false, false),
+                span("a & b", false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn images_and_unknown_tags_are_dropped() {
+        assert_eq!(
+            parse_markup("<img src=\"icon.png\"/><span>ok</span>"),
+            vec![span("ok", false, false)]
+        );
+    }
+}
@@ -2,24 +2,42 @@ use crate::{
     app::{self},
     components::icons::{icon, Icons},
     config::UpdatesModuleConfig,
+    i18n::{tr, tr_args},
     menu::MenuType,
     outputs::Outputs,
+    services::{
+        fwupd::{install_firmware_update, pending_firmware_updates, FirmwareUpdate},
+        network::{self, dbus::NetworkDbus},
+        upower::dbus::UPowerDbus,
+    },
     style::GhostButtonStyle,
+    utils::{
+        self,
+        icons::{resolve_icon, AppIcon},
+    },
 };
 use iced::{
     alignment::Horizontal,
+    futures::SinkExt,
     stream::channel,
-    widget::{button, column, container, horizontal_rule, row, scrollable, text, Column},
+    widget::{
+        button, column, container, horizontal_rule, progress_bar, row, scrollable, text, Column,
+    },
     window::Id,
-    Alignment, Element, Length, Padding, Subscription, Task,
+    Alignment, Element, Length, Padding, Subscription, Task, Theme,
 };
-use log::error;
+use log::{debug, error};
+use rand::Rng;
 use serde::Deserialize;
-use std::{any::TypeId, convert, process::Stdio, time::Duration};
+use std::{
+    any::TypeId, collections::HashMap, convert, fs, path::Path, process::Stdio, time::Duration,
+};
 use tokio::{process, spawn, time::sleep};
 
 use super::{Module, OnModulePress};
 
+const SKIPPED_CHECK_RETRY: Duration = Duration::from_secs(600);
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Update {
     pub package: String,
@@ -64,6 +82,158 @@ async fn check_update_now(check_cmd: &str) -> Vec<Update> {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct FlatpakUpdate {
+    pub app_id: String,
+    pub name: String,
+    pub version: String,
+    pub download_size: String,
+}
+
+async fn check_flatpak_updates() -> Vec<FlatpakUpdate> {
+    let output = process::Command::new("flatpak")
+        .args([
+            "remote-ls",
+            "--updates",
+            "--app",
+            "--columns=application,name,version,download-size",
+        ])
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.split('\t');
+                let app_id = columns.next()?.trim();
+                if app_id.is_empty() {
+                    return None;
+                }
+
+                Some(FlatpakUpdate {
+                    app_id: app_id.to_string(),
+                    name: columns.next().unwrap_or(app_id).trim().to_string(),
+                    version: columns.next().unwrap_or_default().trim().to_string(),
+                    download_size: columns.next().unwrap_or_default().trim().to_string(),
+                })
+            })
+            .collect(),
+        Err(e) => {
+            error!("Failed to check the flatpak updates: {:?}", e);
+            vec![]
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlatpakProgress {
+    pub app_id: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Updates the given apps one at a time reporting which one is in progress
+fn update_flatpak_apps(apps: Vec<String>) -> Task<Message> {
+    Task::run(
+        channel(10, |mut output| async move {
+            let total = apps.len();
+            for (done, app_id) in apps.into_iter().enumerate() {
+                let _ = output
+                    .send(Message::FlatpakProgress(FlatpakProgress {
+                        app_id: app_id.clone(),
+                        done,
+                        total,
+                    }))
+                    .await;
+
+                let res = process::Command::new("flatpak")
+                    .args(["update", "-y", "--noninteractive", &app_id])
+                    .stdout(Stdio::null())
+                    .status()
+                    .await;
+                if let Err(e) = res {
+                    error!("Failed to update the flatpak app {}: {:?}", app_id, e);
+                }
+            }
+        }),
+        convert::identity,
+    )
+    .chain(Task::done(Message::FlatpakUpdateFinished))
+}
+
+async fn check_firmware_updates() -> Vec<FirmwareUpdate> {
+    pending_firmware_updates()
+        .await
+        .inspect_err(|e| error!("Failed to check the firmware updates: {}", e))
+        .unwrap_or_default()
+}
+
+/// Time between two automatic checks with a random jitter on top
+fn next_check_delay(config: &UpdatesModuleConfig) -> Duration {
+    let jitter = if config.jitter > 0 {
+        rand::thread_rng().gen_range(0..=config.jitter)
+    } else {
+        0
+    };
+
+    Duration::from_secs(config.interval.max(60) + jitter)
+}
+
+/// Reason to skip an automatic check, if any
+async fn skip_reason(config: &UpdatesModuleConfig) -> Option<&'static str> {
+    if !config.skip_on_metered && config.min_battery.is_none() {
+        return None;
+    }
+
+    // also covers the metered SSIDs configured in the settings module
+    if config.skip_on_metered && network::is_metered() {
+        return Some("metered connection");
+    }
+
+    let conn = zbus::Connection::system()
+        .await
+        .inspect_err(|err| error!("Failed to connect to the system bus: {}", err))
+        .ok()?;
+
+    if config.skip_on_metered {
+        if let Ok(nm) = NetworkDbus::new(&conn).await {
+            if nm.is_metered().await.unwrap_or_default() {
+                return Some("metered connection");
+            }
+        }
+    }
+
+    if let Some(min_battery) = config.min_battery {
+        let upower = UPowerDbus::new(&conn).await.ok()?;
+        if let Ok(Some(battery)) = upower.get_battery_device().await {
+            // 2 is discharging
+            let discharging = battery.state().await.is_ok_and(|state| state == 2);
+            let percentage = battery.percentage().await.unwrap_or(100.);
+
+            if discharging && percentage < min_battery as f64 {
+                return Some("low battery");
+            }
+        }
+    }
+
+    None
+}
+
+/// Debian based distributions flag a pending reboot with a file,
+/// most of the others remove the running kernel modules on update
+fn reboot_required() -> bool {
+    if Path::new("/var/run/reboot-required").exists() {
+        return true;
+    }
+
+    let modules = Path::new("/usr/lib/modules");
+    modules.is_dir()
+        && fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| !modules.join(release.trim()).exists())
+}
+
 async fn update(update_cmd: &str) {
     let _ = process::Command::new("bash")
         .arg("-c")
@@ -75,10 +245,21 @@ async fn update(update_cmd: &str) {
 #[derive(Debug, Clone)]
 pub enum Message {
     UpdatesCheckCompleted(Vec<Update>),
+    UpdatesCheckSkipped,
     UpdateFinished,
     ToggleUpdatesList,
     CheckNow,
     Update(Id),
+    Reboot,
+    FlatpakCheckCompleted(Vec<FlatpakUpdate>),
+    FlatpakIconResolved(String, Option<AppIcon>),
+    UpdateFlatpak(Option<String>),
+    FlatpakProgress(FlatpakProgress),
+    FlatpakUpdateFinished,
+    FirmwareCheckCompleted(Vec<FirmwareUpdate>),
+    ToggleFirmwareNotes(String),
+    InstallFirmware(String),
+    FirmwareInstallFinished(bool),
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
@@ -93,6 +274,13 @@ pub struct Updates {
     state: State,
     pub updates: Vec<Update>,
     pub is_updates_list_open: bool,
+    reboot_required: bool,
+    flatpak_updates: Vec<FlatpakUpdate>,
+    flatpak_icons: HashMap<String, Option<AppIcon>>,
+    flatpak_progress: Option<FlatpakProgress>,
+    firmware_updates: Vec<FirmwareUpdate>,
+    firmware_notes_open: Option<String>,
+    firmware_installing: Option<String>,
 }
 
 impl Updates {
@@ -106,12 +294,20 @@ impl Updates {
             Message::UpdatesCheckCompleted(updates) => {
                 self.updates = updates;
                 self.state = State::Ready;
+                self.reboot_required |= reboot_required();
+
+                Task::none()
+            }
+            Message::UpdatesCheckSkipped => {
+                self.state = State::Ready;
+                self.reboot_required |= reboot_required();
 
                 Task::none()
             }
             Message::UpdateFinished => {
                 self.updates.clear();
                 self.state = State::Ready;
+                self.reboot_required |= reboot_required();
 
                 Task::none()
             }
@@ -123,10 +319,24 @@ impl Updates {
             Message::CheckNow => {
                 self.state = State::Checking;
                 let check_command = config.check_cmd.clone();
-                Task::perform(
+                let mut cmds = vec![Task::perform(
                     async move { check_update_now(&check_command).await },
                     move |updates| app::Message::Updates(Message::UpdatesCheckCompleted(updates)),
-                )
+                )];
+
+                if config.flatpak {
+                    cmds.push(Task::perform(check_flatpak_updates(), |updates| {
+                        app::Message::Updates(Message::FlatpakCheckCompleted(updates))
+                    }));
+                }
+
+                if config.firmware {
+                    cmds.push(Task::perform(check_firmware_updates(), |updates| {
+                        app::Message::Updates(Message::FirmwareCheckCompleted(updates))
+                    }));
+                }
+
+                Task::batch(cmds)
             }
             Message::Update(id) => {
                 let update_command = config.update_cmd.clone();
@@ -146,95 +356,395 @@ impl Updates {
 
                 Task::batch(cmds)
             }
+            Message::Reboot => {
+                utils::launcher::reboot();
+
+                Task::none()
+            }
+            Message::FlatpakCheckCompleted(updates) => {
+                let mut lookups = Vec::new();
+                for update in &updates {
+                    if !self.flatpak_icons.contains_key(&update.app_id) {
+                        self.flatpak_icons.insert(update.app_id.clone(), None);
+                        let app_id = update.app_id.clone();
+                        lookups.push(Task::perform(resolve_icon(app_id.clone()), move |icon| {
+                            app::Message::Updates(Message::FlatpakIconResolved(app_id, icon))
+                        }));
+                    }
+                }
+                self.flatpak_updates = updates;
+
+                Task::batch(lookups)
+            }
+            Message::FlatpakIconResolved(app_id, icon) => {
+                self.flatpak_icons.insert(app_id, icon);
+
+                Task::none()
+            }
+            Message::UpdateFlatpak(app_id) => {
+                if self.flatpak_progress.is_some() {
+                    return Task::none();
+                }
+
+                let apps = match app_id {
+                    Some(app_id) => vec![app_id],
+                    None => self
+                        .flatpak_updates
+                        .iter()
+                        .map(|update| update.app_id.clone())
+                        .collect(),
+                };
+                self.flatpak_progress = apps.first().map(|app_id| FlatpakProgress {
+                    app_id: app_id.clone(),
+                    done: 0,
+                    total: apps.len(),
+                });
+
+                update_flatpak_apps(apps).map(app::Message::Updates)
+            }
+            Message::FlatpakProgress(progress) => {
+                self.flatpak_progress = Some(progress);
+
+                Task::none()
+            }
+            Message::FlatpakUpdateFinished => {
+                self.flatpak_progress = None;
+
+                Task::perform(check_flatpak_updates(), |updates| {
+                    app::Message::Updates(Message::FlatpakCheckCompleted(updates))
+                })
+            }
+            Message::FirmwareCheckCompleted(updates) => {
+                self.firmware_updates = updates;
+
+                Task::none()
+            }
+            Message::ToggleFirmwareNotes(device_id) => {
+                if self.firmware_notes_open.as_ref() == Some(&device_id) {
+                    self.firmware_notes_open = None;
+                } else {
+                    self.firmware_notes_open = Some(device_id);
+                }
+
+                Task::none()
+            }
+            Message::InstallFirmware(device_id) => {
+                if self.firmware_installing.is_some() {
+                    return Task::none();
+                }
+
+                let needs_reboot = self
+                    .firmware_updates
+                    .iter()
+                    .find(|update| update.device_id == device_id)
+                    .is_some_and(|update| update.needs_reboot);
+                self.firmware_installing = Some(device_id.clone());
+
+                Task::perform(
+                    async move { install_firmware_update(&device_id).await },
+                    move |res| {
+                        let installed = res
+                            .inspect_err(|e| error!("Failed to install the firmware update: {}", e))
+                            .is_ok();
+
+                        app::Message::Updates(Message::FirmwareInstallFinished(
+                            installed && needs_reboot,
+                        ))
+                    },
+                )
+            }
+            Message::FirmwareInstallFinished(needs_reboot) => {
+                self.firmware_installing = None;
+                self.reboot_required |= needs_reboot;
+
+                Task::perform(check_firmware_updates(), |updates| {
+                    app::Message::Updates(Message::FirmwareCheckCompleted(updates))
+                })
+            }
         }
     }
 
-    pub fn menu_view(&self, id: Id) -> Element<Message> {
-        column!(
-            if self.updates.is_empty() {
-                convert::Into::<Element<'_, _, _>>::into(
-                    container(text("Up to date ;)")).padding([8, 8]),
-                )
-            } else {
-                let mut elements = column!(button(row!(
-                    text(format!("{} Updates available", self.updates.len())).width(Length::Fill),
-                    icon(if self.is_updates_list_open {
-                        Icons::MenuClosed
-                    } else {
-                        Icons::MenuOpen
-                    })
+    fn flatpak_view(&self) -> Element<Message> {
+        let header: Element<Message> = match &self.flatpak_progress {
+            Some(progress) => column!(
+                text(tr_args(
+                    "updating-flatpak",
+                    &[
+                        ("app", progress.app_id.clone()),
+                        ("current", (progress.done + 1).to_string()),
+                        ("total", progress.total.to_string()),
+                    ],
+                ))
+                .size(12),
+                progress_bar(0.0..=progress.total as f32, progress.done as f32)
+                    .height(Length::Fixed(4.))
+            )
+            .spacing(4)
+            .padding([0, 8])
+            .into(),
+            None => row!(
+                text(tr_args(
+                    "flatpak-updates-available",
+                    &[("count", self.flatpak_updates.len().to_string())],
                 ))
-                .style(GhostButtonStyle.into_style())
-                .padding([8, 8])
-                .on_press(Message::ToggleUpdatesList)
-                .width(Length::Fill),);
-
-                if self.is_updates_list_open {
-                    elements = elements.push(
-                        container(scrollable(
-                            Column::with_children(
-                                self.updates
-                                    .iter()
-                                    .map(|update| {
-                                        column!(
-                                            text(update.package.clone())
-                                                .size(10)
-                                                .width(Length::Fill),
-                                            text(format!(
-                                                "{} -> {}",
-                                                {
-                                                    let mut res = update.from.clone();
-                                                    res.truncate(18);
-
-                                                    res
-                                                },
-                                                {
-                                                    let mut res = update.to.clone();
-                                                    res.truncate(18);
-
-                                                    res
-                                                },
-                                            ))
-                                            .width(Length::Fill)
-                                            .align_x(Horizontal::Right)
-                                            .size(10)
-                                        )
-                                        .into()
-                                    })
-                                    .collect::<Vec<Element<'_, _, _>>>(),
-                            )
-                            .padding(Padding::ZERO.right(16))
-                            .spacing(4),
-                        ))
-                        .padding([8, 0])
-                        .max_height(300),
-                    );
-                }
-                elements.into()
-            },
-            horizontal_rule(1),
-            button("Update")
-                .style(GhostButtonStyle.into_style())
-                .padding([8, 8])
-                .on_press(Message::Update(id))
                 .width(Length::Fill),
-            button({
-                let mut content = row!(text("Check now").width(Length::Fill),);
+                button(text(tr("update-all")))
+                    .style(GhostButtonStyle.into_style())
+                    .padding([4, 12])
+                    .on_press(Message::UpdateFlatpak(None))
+            )
+            .align_y(Alignment::Center)
+            .padding([0, 8])
+            .into(),
+        };
 
-                if self.state == State::Checking {
-                    content = content.push(icon(Icons::Refresh));
-                }
+        column!(
+            header,
+            container(scrollable(
+                Column::with_children(
+                    self.flatpak_updates
+                        .iter()
+                        .map(|update| {
+                            row!(
+                                container(
+                                    self.flatpak_icons
+                                        .get(&update.app_id)
+                                        .and_then(|icon| icon.as_ref())
+                                        .map_or_else(
+                                            || icon(Icons::UpdatesAvailable).into(),
+                                            |icon| icon.view(24.),
+                                        )
+                                )
+                                .center_x(Length::Fixed(24.)),
+                                column!(
+                                    text(update.name.clone()).size(12),
+                                    text(format!("{} {}", update.version, update.download_size))
+                                        .size(10)
+                                )
+                                .width(Length::Fill),
+                                button(icon(Icons::Download))
+                                    .style(GhostButtonStyle.into_style())
+                                    .padding([4, 8])
+                                    .on_press_maybe(self.flatpak_progress.is_none().then(|| {
+                                        Message::UpdateFlatpak(Some(update.app_id.clone()))
+                                    }))
+                            )
+                            .align_y(Alignment::Center)
+                            .spacing(8)
+                            .into()
+                        })
+                        .collect::<Vec<Element<'_, _, _>>>(),
+                )
+                .padding(Padding::ZERO.right(16))
+                .spacing(4),
+            ))
+            .padding([0, 8])
+            .max_height(240),
+            horizontal_rule(1)
+        )
+        .spacing(8)
+        .into()
+    }
 
-                content
-            })
-            .style(GhostButtonStyle.into_style())
-            .padding([8, 8])
-            .on_press(Message::CheckNow)
+    fn firmware_view(&self) -> Element<Message> {
+        column!(
+            text(tr_args(
+                "firmware-updates-available",
+                &[("count", self.firmware_updates.len().to_string())],
+            ))
             .width(Length::Fill),
+            Column::with_children(
+                self.firmware_updates
+                    .iter()
+                    .map(|update| {
+                        let security = update.security;
+                        let installing =
+                            self.firmware_installing.as_ref() == Some(&update.device_id);
+                        let notes_open =
+                            self.firmware_notes_open.as_ref() == Some(&update.device_id);
+
+                        column!(row!(
+                            container(icon(if security {
+                                Icons::FirmwareSecurity
+                            } else {
+                                Icons::FirmwareUpdate
+                            }))
+                            .style(move |theme: &Theme| {
+                                container::Style {
+                                    text_color: security.then_some(theme.palette().danger),
+                                    ..Default::default()
+                                }
+                            }),
+                            button(
+                                column!(
+                                    text(update.device_name.clone()).size(12),
+                                    text(format!(
+                                        "{} -> {}",
+                                        update.current_version, update.version
+                                    ))
+                                    .size(10)
+                                )
+                                .width(Length::Fill)
+                            )
+                            .style(GhostButtonStyle.into_style())
+                            .padding([2, 4])
+                            .on_press(Message::ToggleFirmwareNotes(update.device_id.clone())),
+                            button(icon(if installing {
+                                Icons::Refresh
+                            } else {
+                                Icons::Download
+                            }))
+                            .style(GhostButtonStyle.into_style())
+                            .padding([4, 8])
+                            .on_press_maybe(
+                                self.firmware_installing.is_none().then(|| {
+                                    Message::InstallFirmware(update.device_id.clone())
+                                })
+                            )
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(8))
+                        .push_maybe((notes_open && !update.release_notes.is_empty()).then(|| {
+                            container(text(update.release_notes.clone()).size(10))
+                                .padding(Padding::ZERO.left(32))
+                        }))
+                        .push_maybe(
+                            (notes_open && update.needs_reboot)
+                                .then(|| text(tr("firmware-needs-reboot")).size(10)),
+                        )
+                        .spacing(4)
+                        .into()
+                    })
+                    .collect::<Vec<Element<'_, _, _>>>(),
+            )
+            .spacing(4),
+            horizontal_rule(1)
         )
-        .spacing(4)
+        .padding([0, 8])
+        .spacing(8)
         .into()
     }
+
+    pub fn menu_view(&self, id: Id) -> Element<Message> {
+        let reboot = self.reboot_required.then(|| {
+            column!(
+                row!(
+                    icon(Icons::RebootRequired),
+                    text(tr("reboot-required")).width(Length::Fill),
+                    button(text(tr("reboot")))
+                        .style(GhostButtonStyle.into_style())
+                        .padding([4, 12])
+                        .on_press(Message::Reboot)
+                )
+                .align_y(Alignment::Center)
+                .spacing(8)
+                .padding([0, 8]),
+                horizontal_rule(1)
+            )
+            .spacing(4)
+        });
+
+        let flatpak = (!self.flatpak_updates.is_empty() || self.flatpak_progress.is_some())
+            .then(|| self.flatpak_view());
+
+        let firmware = (!self.firmware_updates.is_empty()).then(|| self.firmware_view());
+
+        Column::new()
+            .push_maybe(reboot)
+            .push_maybe(firmware)
+            .push_maybe(flatpak)
+            .push(
+                column!(
+                    if self.updates.is_empty() {
+                        convert::Into::<Element<'_, _, _>>::into(
+                            container(text(tr("up-to-date"))).padding([8, 8]),
+                        )
+                    } else {
+                        let mut elements = column!(button(row!(
+                            text(tr_args(
+                                "updates-available",
+                                &[("count", self.updates.len().to_string())],
+                            ))
+                            .width(Length::Fill),
+                            icon(if self.is_updates_list_open {
+                                Icons::MenuClosed
+                            } else {
+                                Icons::MenuOpen
+                            })
+                        ))
+                        .style(GhostButtonStyle.into_style())
+                        .padding([8, 8])
+                        .on_press(Message::ToggleUpdatesList)
+                        .width(Length::Fill),);
+
+                        if self.is_updates_list_open {
+                            elements = elements.push(
+                                container(scrollable(
+                                    Column::with_children(
+                                        self.updates
+                                            .iter()
+                                            .map(|update| {
+                                                column!(
+                                                    text(update.package.clone())
+                                                        .size(10)
+                                                        .width(Length::Fill),
+                                                    text(format!(
+                                                        "{} -> {}",
+                                                        {
+                                                            let mut res = update.from.clone();
+                                                            res.truncate(18);
+
+                                                            res
+                                                        },
+                                                        {
+                                                            let mut res = update.to.clone();
+                                                            res.truncate(18);
+
+                                                            res
+                                                        },
+                                                    ))
+                                                    .width(Length::Fill)
+                                                    .align_x(Horizontal::Right)
+                                                    .size(10)
+                                                )
+                                                .into()
+                                            })
+                                            .collect::<Vec<Element<'_, _, _>>>(),
+                                    )
+                                    .padding(Padding::ZERO.right(16))
+                                    .spacing(4),
+                                ))
+                                .padding([8, 0])
+                                .max_height(300),
+                            );
+                        }
+                        elements.into()
+                    },
+                    horizontal_rule(1),
+                    button(text(tr("update")))
+                        .style(GhostButtonStyle.into_style())
+                        .padding([8, 8])
+                        .on_press(Message::Update(id))
+                        .width(Length::Fill),
+                    button({
+                        let mut content = row!(text(tr("check-now")).width(Length::Fill),);
+
+                        if self.state == State::Checking {
+                            content = content.push(icon(Icons::Refresh));
+                        }
+
+                        content
+                    })
+                    .style(GhostButtonStyle.into_style())
+                    .padding([8, 8])
+                    .on_press(Message::CheckNow)
+                    .width(Length::Fill),
+                )
+                .spacing(4),
+            )
+            .spacing(4)
+            .into()
+    }
 }
 
 impl Module for Updates {
@@ -246,16 +756,31 @@ impl Module for Updates {
         config: Self::ViewData<'_>,
     ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
         if config.is_some() {
+            let count =
+                self.updates.len() + self.flatpak_updates.len() + self.firmware_updates.len();
             let mut content = row!(container(icon(match self.state {
                 State::Checking => Icons::Refresh,
-                State::Ready if self.updates.is_empty() => Icons::NoUpdatesAvailable,
+                State::Ready if count == 0 => Icons::NoUpdatesAvailable,
                 _ => Icons::UpdatesAvailable,
             })))
             .align_y(Alignment::Center)
             .spacing(4);
 
-            if !self.updates.is_empty() {
-                content = content.push(text(self.updates.len()));
+            if count > 0 {
+                content = content.push(text(count));
+            }
+
+            if self.reboot_required {
+                content = content.push(icon(Icons::RebootRequired));
+            }
+
+            if self.firmware_updates.iter().any(|update| update.security) {
+                content = content.push(container(icon(Icons::FirmwareSecurity)).style(
+                    |theme: &Theme| container::Style {
+                        text_color: Some(theme.palette().danger),
+                        ..Default::default()
+                    },
+                ));
             }
 
             Some((
@@ -271,7 +796,7 @@ impl Module for Updates {
         &self,
         config: Self::SubscriptionData<'_>,
     ) -> Option<Subscription<app::Message>> {
-        let check_cmd = config.check_cmd.clone();
+        let config = config.clone();
         let id = TypeId::of::<Self>();
 
         Some(
@@ -279,11 +804,32 @@ impl Module for Updates {
                 id,
                 channel(10, |mut output| async move {
                     loop {
-                        let updates = check_update_now(&check_cmd).await;
+                        if let Some(reason) = skip_reason(&config).await {
+                            debug!("Skipping the updates check: {}", reason);
+
+                            let _ = output.try_send(Message::UpdatesCheckSkipped);
+
+                            sleep(SKIPPED_CHECK_RETRY).await;
+                            continue;
+                        }
+
+                        let updates = check_update_now(&config.check_cmd).await;
 
                         let _ = output.try_send(Message::UpdatesCheckCompleted(updates));
 
-                        sleep(Duration::from_secs(3600)).await;
+                        if config.flatpak {
+                            let updates = check_flatpak_updates().await;
+
+                            let _ = output.try_send(Message::FlatpakCheckCompleted(updates));
+                        }
+
+                        if config.firmware {
+                            let updates = check_firmware_updates().await;
+
+                            let _ = output.try_send(Message::FirmwareCheckCompleted(updates));
+                        }
+
+                        sleep(next_check_delay(&config)).await;
                     }
                 }),
             )
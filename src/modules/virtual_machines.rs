@@ -0,0 +1,299 @@
+use super::{Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::VirtualMachinesModuleConfig,
+    i18n::tr,
+    menu::MenuType,
+    style::SettingsButtonStyle,
+};
+use iced::{
+    stream::channel,
+    widget::{button, column, row, text, Column},
+    Alignment, Element, Length, Subscription, Task,
+};
+use log::error;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    process::Stdio,
+    time::{Duration, Instant},
+};
+use tokio::{process, time::sleep};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DomainState {
+    Running,
+    Paused,
+    ShutOff,
+    Other,
+}
+
+impl From<u32> for DomainState {
+    fn from(state: u32) -> Self {
+        match state {
+            1 => DomainState::Running,
+            3 => DomainState::Paused,
+            5 => DomainState::ShutOff,
+            _ => DomainState::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Domain {
+    pub name: String,
+    pub state: DomainState,
+    pub cpu_time: Option<u64>,
+    pub memory: Option<u64>,
+}
+
+async fn get_domains(uri: &str) -> Vec<Domain> {
+    let domstats_cmd = process::Command::new("virsh")
+        .arg("-c")
+        .arg(uri)
+        .arg("domstats")
+        .arg("--state")
+        .arg("--cpu-total")
+        .arg("--balloon")
+        .stdout(Stdio::piped())
+        .output()
+        .await;
+
+    match domstats_cmd {
+        Ok(domstats_cmd) => {
+            let cmd_output = String::from_utf8_lossy(&domstats_cmd.stdout);
+            let mut domains: Vec<Domain> = Vec::new();
+            for line in cmd_output.lines() {
+                let line = line.trim();
+
+                if let Some(name) = line.strip_prefix("Domain: ") {
+                    domains.push(Domain {
+                        name: name.trim_matches('\'').to_string(),
+                        state: DomainState::Other,
+                        cpu_time: None,
+                        memory: None,
+                    });
+                } else if let (Some(domain), Some((key, value))) =
+                    (domains.last_mut(), line.split_once('='))
+                {
+                    match key {
+                        "state.state" => {
+                            domain.state = value.parse::<u32>().unwrap_or_default().into();
+                        }
+                        "cpu.time" => {
+                            domain.cpu_time = value.parse::<u64>().ok();
+                        }
+                        "balloon.rss" => {
+                            domain.memory = value.parse::<u64>().ok();
+                        }
+                        "balloon.current" if domain.memory.is_none() => {
+                            domain.memory = value.parse::<u64>().ok();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            domains
+        }
+        Err(e) => {
+            error!("Error: {:?}", e);
+            vec![]
+        }
+    }
+}
+
+async fn domain_action(uri: &str, action: &str, name: &str) {
+    let res = process::Command::new("virsh")
+        .arg("-c")
+        .arg(uri)
+        .arg(action)
+        .arg(name)
+        .output()
+        .await;
+
+    if let Err(e) = res {
+        error!("Failed to {} domain {}: {:?}", action, name, e);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    DomainsUpdated(Vec<Domain>),
+    Start(String),
+    Shutdown(String),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct VirtualMachines {
+    domains: Vec<Domain>,
+    cpu_usage: HashMap<String, u32>,
+    last_sample: Option<Instant>,
+}
+
+impl VirtualMachines {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &VirtualMachinesModuleConfig,
+    ) -> Task<crate::app::Message> {
+        match message {
+            Message::DomainsUpdated(domains) => {
+                let now = Instant::now();
+
+                if let Some(elapsed) = self.last_sample.map(|last| now.duration_since(last)) {
+                    self.cpu_usage = domains
+                        .iter()
+                        .filter_map(|domain| {
+                            let previous = self
+                                .domains
+                                .iter()
+                                .find(|d| d.name == domain.name)
+                                .and_then(|d| d.cpu_time)?;
+                            let current = domain.cpu_time?;
+
+                            Some((
+                                domain.name.clone(),
+                                (current.saturating_sub(previous) as f64
+                                    / elapsed.as_nanos() as f64
+                                    * 100.) as u32,
+                            ))
+                        })
+                        .collect();
+                }
+
+                self.domains = domains;
+                self.last_sample = Some(now);
+
+                Task::none()
+            }
+            Message::Start(name) => {
+                let uri = config.uri.clone();
+                Task::perform(
+                    async move {
+                        domain_action(&uri, "start", &name).await;
+                        get_domains(&uri).await
+                    },
+                    |domains| app::Message::VirtualMachines(Message::DomainsUpdated(domains)),
+                )
+            }
+            Message::Shutdown(name) => {
+                let uri = config.uri.clone();
+                Task::perform(
+                    async move {
+                        domain_action(&uri, "shutdown", &name).await;
+                        get_domains(&uri).await
+                    },
+                    |domains| app::Message::VirtualMachines(Message::DomainsUpdated(domains)),
+                )
+            }
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        if self.domains.is_empty() {
+            text(tr("no-virtual-machines")).into()
+        } else {
+            Column::with_children(
+                self.domains
+                    .iter()
+                    .map(|domain| {
+                        let details = match domain.state {
+                            DomainState::Running => {
+                                let mut details = tr("vm-running");
+                                if let Some(cpu_usage) = self.cpu_usage.get(&domain.name) {
+                                    details.push_str(&format!(" · CPU {}%", cpu_usage));
+                                }
+                                if let Some(memory) = domain.memory {
+                                    details.push_str(&format!(" · {} MiB", memory / 1024));
+                                }
+
+                                details
+                            }
+                            DomainState::Paused => tr("vm-paused"),
+                            DomainState::ShutOff => tr("vm-shut-off"),
+                            DomainState::Other => tr("vm-unknown"),
+                        };
+
+                        row!(
+                            column!(text(domain.name.clone()), text(details).size(10))
+                                .spacing(4)
+                                .width(Length::Fill),
+                            button(icon(if domain.state == DomainState::ShutOff {
+                                Icons::Play
+                            } else {
+                                Icons::Stop
+                            }))
+                            .padding([4, 10])
+                            .on_press(if domain.state == DomainState::ShutOff {
+                                Message::Start(domain.name.clone())
+                            } else {
+                                Message::Shutdown(domain.name.clone())
+                            })
+                            .style(SettingsButtonStyle.into_style()),
+                        )
+                        .align_y(Alignment::Center)
+                        .spacing(8)
+                        .into()
+                    })
+                    .collect::<Vec<Element<Message>>>(),
+            )
+            .spacing(8)
+            .into()
+        }
+    }
+}
+
+impl Module for VirtualMachines {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = &'a VirtualMachinesModuleConfig;
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        if self.domains.is_empty() {
+            None
+        } else {
+            let running = self
+                .domains
+                .iter()
+                .filter(|d| d.state == DomainState::Running)
+                .count();
+
+            Some((
+                row!(icon(Icons::VirtualMachine), text(running))
+                    .align_y(Alignment::Center)
+                    .spacing(4)
+                    .into(),
+                Some(OnModulePress::ToggleMenu(MenuType::VirtualMachines)),
+            ))
+        }
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        let id = TypeId::of::<Self>();
+        let uri = config.uri.clone();
+        let interval = config.interval;
+
+        Some(
+            Subscription::run_with_id(
+                format!("{:?}-{}-{}", id, uri, interval),
+                channel(10, move |mut output| async move {
+                    loop {
+                        let domains = get_domains(&uri).await;
+
+                        let _ = output.try_send(Message::DomainsUpdated(domains));
+
+                        sleep(Duration::from_secs(interval)).await;
+                    }
+                }),
+            )
+            .map(app::Message::VirtualMachines),
+        )
+    }
+}
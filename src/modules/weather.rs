@@ -0,0 +1,246 @@
+use super::{clock::time_locale, Module, OnModulePress};
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::{WeatherModuleConfig, WeatherUnits},
+    i18n::{tr, tr_args},
+    menu::MenuType,
+    services::{
+        location::{Coordinates, LocationService},
+        weather::{set_query, Condition, WeatherQuery, WeatherService},
+        ReadOnlyService, ServiceEvent,
+    },
+};
+use chrono::{Local, Locale};
+use iced::{
+    alignment::Horizontal,
+    widget::{column, container, horizontal_rule, row, text, Column},
+    Alignment, Element, Length, Subscription,
+};
+use std::time::Duration;
+
+fn condition_icon(condition: Condition, is_day: bool) -> Icons {
+    match condition {
+        Condition::Clear if is_day => Icons::WeatherSunny,
+        Condition::Clear => Icons::WeatherNight,
+        Condition::PartlyCloudy if is_day => Icons::WeatherPartlyCloudy,
+        Condition::PartlyCloudy => Icons::WeatherNightPartlyCloudy,
+        Condition::Cloudy => Icons::WeatherCloudy,
+        Condition::Fog => Icons::WeatherFog,
+        Condition::Drizzle => Icons::WeatherRainy,
+        Condition::Rain => Icons::WeatherPouring,
+        Condition::Snow => Icons::WeatherSnowy,
+        Condition::Thunderstorm => Icons::WeatherLightning,
+    }
+}
+
+fn condition_label(condition: Condition) -> String {
+    tr(match condition {
+        Condition::Clear => "weather-clear",
+        Condition::PartlyCloudy => "weather-partly-cloudy",
+        Condition::Cloudy => "weather-cloudy",
+        Condition::Fog => "weather-fog",
+        Condition::Drizzle => "weather-drizzle",
+        Condition::Rain => "weather-rain",
+        Condition::Snow => "weather-snow",
+        Condition::Thunderstorm => "weather-thunderstorm",
+    })
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(ServiceEvent<WeatherService>),
+    Location(ServiceEvent<LocationService>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Weather {
+    service: Option<WeatherService>,
+    location: Option<LocationService>,
+    locale: Locale,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            service: None,
+            location: None,
+            locale: time_locale(),
+        }
+    }
+}
+
+impl Weather {
+    fn coordinates(&self, config: &WeatherModuleConfig) -> Option<Coordinates> {
+        match (config.latitude, config.longitude) {
+            (Some(latitude), Some(longitude)) => Some(Coordinates {
+                latitude,
+                longitude,
+            }),
+            _ => self
+                .location
+                .as_ref()
+                .and_then(|location| location.coordinates),
+        }
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::Event(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.service = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(service) = self.service.as_mut() {
+                        service.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+            Message::Location(event) => match event {
+                ServiceEvent::Init(service) => {
+                    self.location = Some(service);
+                }
+                ServiceEvent::Update(data) => {
+                    if let Some(location) = self.location.as_mut() {
+                        location.update(data);
+                    }
+                }
+                ServiceEvent::Error(_) => {}
+            },
+        }
+    }
+
+    pub fn menu_view(&self) -> Element<Message> {
+        let Some((service, current)) = self
+            .service
+            .as_ref()
+            .and_then(|service| service.current.map(|current| (service, current)))
+        else {
+            return container(text(tr("weather-unavailable")))
+                .padding([8, 8])
+                .into();
+        };
+
+        let (temperature_unit, speed_unit) = if service.imperial {
+            ("°F", "mph")
+        } else {
+            ("°C", "km/h")
+        };
+        let today = Local::now().date_naive();
+
+        let header = row!(
+            icon(condition_icon(current.condition, current.is_day)).size(48),
+            column!(
+                text(format!("{:.0}{}", current.temperature, temperature_unit)).size(24),
+                text(condition_label(current.condition)),
+            )
+            .spacing(4),
+        )
+        .align_y(Alignment::Center)
+        .spacing(16);
+
+        let details = row!(
+            text(tr_args(
+                "weather-feels-like",
+                &[(
+                    "temperature",
+                    format!("{:.0}°", current.apparent_temperature)
+                )],
+            ))
+            .width(Length::Fill),
+            row!(
+                icon(Icons::WaterPercent),
+                text(format!("{:.0}%", current.humidity))
+            )
+            .align_y(Alignment::Center)
+            .spacing(4),
+            row!(
+                icon(Icons::WeatherWindy),
+                text(format!("{:.0} {}", current.wind_speed, speed_unit))
+            )
+            .align_y(Alignment::Center)
+            .spacing(4),
+        )
+        .align_y(Alignment::Center)
+        .spacing(12);
+
+        let daily = Column::with_children(service.daily.iter().map(|day| {
+            let name = if day.date == today {
+                tr("weather-today")
+            } else {
+                day.date.format_localized("%A", self.locale).to_string()
+            };
+
+            row!(
+                text(name).width(Length::Fill),
+                text(
+                    day.precipitation_probability
+                        .map(|probability| format!("{:.0}%", probability))
+                        .unwrap_or_default()
+                )
+                .size(12),
+                container(icon(condition_icon(day.condition, true))).center_x(Length::Fixed(32.)),
+                text(format!("{:.0}° / {:.0}°", day.max, day.min))
+                    .width(Length::Fixed(80.))
+                    .align_x(Horizontal::Right),
+            )
+            .align_y(Alignment::Center)
+            .spacing(8)
+            .into()
+        }))
+        .spacing(8);
+
+        column!(header, details, horizontal_rule(1), daily)
+            .spacing(12)
+            .padding([0, 8])
+            .into()
+    }
+}
+
+impl Module for Weather {
+    type ViewData<'a> = ();
+    type SubscriptionData<'a> = &'a WeatherModuleConfig;
+
+    fn view(
+        &self,
+        _: Self::ViewData<'_>,
+    ) -> Option<(Element<app::Message>, Option<OnModulePress>)> {
+        let current = self.service.as_ref()?.current?;
+
+        Some((
+            row!(
+                icon(condition_icon(current.condition, current.is_day)),
+                text(format!("{:.0}°", current.temperature))
+            )
+            .align_y(Alignment::Center)
+            .spacing(4)
+            .into(),
+            Some(OnModulePress::ToggleMenu(MenuType::Weather)),
+        ))
+    }
+
+    fn subscription(
+        &self,
+        config: Self::SubscriptionData<'_>,
+    ) -> Option<Subscription<app::Message>> {
+        set_query(self.coordinates(config).map(|coordinates| WeatherQuery {
+            coordinates,
+            imperial: config.units == WeatherUnits::Imperial,
+            interval: Duration::from_secs(config.interval),
+        }));
+
+        let weather = WeatherService::subscribe().map(Message::Event);
+
+        let subscription = if config.latitude.is_some() && config.longitude.is_some() {
+            weather
+        } else {
+            Subscription::batch(vec![
+                weather,
+                LocationService::subscribe().map(Message::Location),
+            ])
+        };
+
+        Some(subscription.map(app::Message::Weather))
+    }
+}
@@ -27,6 +27,10 @@ impl Default for WindowTitle {
 }
 
 impl WindowTitle {
+    pub fn title(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
     pub fn update(&mut self, message: Message, truncate_title_after_length: u32) {
         match message {
             Message::TitleChanged(value) => {
@@ -1,9 +1,15 @@
 use super::{Module, OnModulePress};
 use crate::{
     app,
-    config::{AppearanceColor, WorkspaceVisibilityMode, WorkspacesModuleConfig},
+    config::Position,
+    config::{
+        AppearanceColor, WorkspaceDisplayMode, WorkspaceVisibilityMode, WorkspacesModuleConfig,
+    },
+    i18n::{tr, tr_args},
     outputs::Outputs,
+    position_button::{position_button, ButtonUIRef},
     style::WorkspaceButtonStyle,
+    utils::screencopy::capture_output,
 };
 use hyprland::{
     dispatch::MonitorIdentifier,
@@ -12,17 +18,27 @@ use hyprland::{
 };
 use iced::{
     alignment,
+    platform_specific::shell::commands::layer_surface::{
+        destroy_layer_surface, get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{IcedMargin, SctkLayerSurfaceSettings},
     stream::channel,
-    widget::{button, container, text, Row},
+    widget::{column, container, image, text, Row},
     window::Id,
-    Element, Length, Subscription,
+    Border, Element, Length, Subscription, Task, Theme,
 };
-use log::{debug, error};
+use log::{debug, error, warn};
 use std::{
     any::TypeId,
+    collections::HashMap,
     sync::{Arc, RwLock},
 };
 
+// size of the preview surface and scale of the captured output
+const PREVIEW_WIDTH: u32 = 320;
+const PREVIEW_HEIGHT: u32 = 220;
+const THUMBNAIL_SCALE: f32 = 0.2;
+
 #[derive(Debug, Clone)]
 pub struct Workspace {
     pub id: i32,
@@ -95,30 +111,113 @@ fn get_workspaces(enable_workspace_filling: bool) -> Vec<Workspace> {
         .collect::<Vec<Workspace>>()
 }
 
+/// Captures a scaled down frame of the output showing the workspace.
+/// Hidden workspaces can't be captured so the caller keeps the last
+/// thumbnail taken while they were visible.
+async fn capture_thumbnail(workspace_id: i32) -> Option<image::Handle> {
+    tokio::task::spawn_blocking(move || {
+        let monitor = hyprland::data::Monitors::get()
+            .ok()?
+            .into_iter()
+            .find(|monitor| {
+                if monitor.special_workspace.id != 0 {
+                    monitor.special_workspace.id == workspace_id
+                } else {
+                    monitor.active_workspace.id == workspace_id
+                }
+            })?;
+
+        capture_output(&monitor.name, THUMBNAIL_SCALE)
+            .inspect_err(|e| warn!("failed to capture output {}: {}", monitor.name, e))
+            .ok()
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
 pub struct Workspaces {
     workspaces: Vec<Workspace>,
+    hovered: Option<i32>,
+    thumbnails: HashMap<i32, image::Handle>,
+    // surface of the preview, it doesn't take the pointer
+    preview: Option<Id>,
 }
 
 impl Workspaces {
     pub fn new(enable_workspace_filling: bool) -> Self {
         Self {
             workspaces: get_workspaces(enable_workspace_filling),
+            hovered: None,
+            thumbnails: HashMap::new(),
+            preview: None,
         }
     }
+
+    pub fn preview_id(&self) -> Option<Id> {
+        self.preview
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.active)
+            .map(|workspace| workspace.name.as_str())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     WorkspacesChanged(Vec<Workspace>),
     ChangeWorkspace(i32),
+    // swipe on the bar, 1 moves to the next workspace of the monitor
+    Cycle(i32),
     ToggleSpecialWorkspace(i32),
+    Hover(Id, i32, Option<ButtonUIRef>),
+    ThumbnailCaptured(i32, image::Handle),
 }
 
 impl Workspaces {
-    pub fn update(&mut self, message: Message) {
+    pub fn update(
+        &mut self,
+        message: Message,
+        config: &WorkspacesModuleConfig,
+        outputs: &mut Outputs,
+    ) -> Task<app::Message> {
         match message {
             Message::WorkspacesChanged(workspaces) => {
                 self.workspaces = workspaces;
+                self.thumbnails
+                    .retain(|id, _| self.workspaces.iter().any(|w| w.id == *id));
+            }
+            Message::Hover(id, workspace, Some(button_ui_ref)) => {
+                if config.thumbnails {
+                    self.hovered = Some(workspace);
+
+                    // a single capture when the pointer enters the button
+                    return Task::batch(vec![
+                        self.show_preview(id, button_ui_ref, outputs),
+                        Task::perform(capture_thumbnail(workspace), move |thumbnail| {
+                            thumbnail.map_or(app::Message::None, |thumbnail| {
+                                app::Message::Workspaces(Message::ThumbnailCaptured(
+                                    workspace, thumbnail,
+                                ))
+                            })
+                        }),
+                    ]);
+                }
+            }
+            Message::Hover(_, workspace, None) => {
+                if self.hovered == Some(workspace) {
+                    self.hovered = None;
+
+                    if let Some(preview) = self.preview.take() {
+                        return destroy_layer_surface(preview);
+                    }
+                }
+            }
+            Message::ThumbnailCaptured(workspace, thumbnail) => {
+                self.thumbnails.insert(workspace, thumbnail);
             }
             Message::ChangeWorkspace(id) => {
                 if id > 0 {
@@ -138,6 +237,20 @@ impl Workspaces {
                     }
                 }
             }
+            Message::Cycle(direction) => {
+                debug!("cycling workspaces: {}", direction);
+                let res = hyprland::dispatch::Dispatch::call(
+                    hyprland::dispatch::DispatchType::Workspace(
+                        hyprland::dispatch::WorkspaceIdentifierWithSpecial::RelativeMonitor(
+                            direction,
+                        ),
+                    ),
+                );
+
+                if let Err(e) = res {
+                    error!("failed to dispatch workspace cycle: {:?}", e);
+                }
+            }
             Message::ToggleSpecialWorkspace(id) => {
                 if let Some(special) = self.workspaces.iter().find(|w| w.id == id && w.id < 0) {
                     debug!("toggle special workspace: {}", id);
@@ -160,6 +273,143 @@ impl Workspaces {
                 }
             }
         }
+
+        Task::none()
+    }
+
+    /// Opens the preview next to the hovered button, like the pinned menus
+    /// the compositor keeps it out of the bar exclusive zone
+    fn show_preview(
+        &mut self,
+        id: Id,
+        button_ui_ref: ButtonUIRef,
+        outputs: &Outputs,
+    ) -> Task<app::Message> {
+        let Some((output, position)) = outputs.bar_placement(id) else {
+            return Task::none();
+        };
+        let left = (button_ui_ref.position.x - PREVIEW_WIDTH as f32 / 2.)
+            .min(button_ui_ref.viewport.0 - PREVIEW_WIDTH as f32)
+            .max(0.) as i32;
+
+        let preview = Id::unique();
+        let previous = self
+            .preview
+            .replace(preview)
+            .map_or_else(Task::none, destroy_layer_surface);
+
+        Task::batch(vec![
+            previous,
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id: preview,
+                size: Some((Some(PREVIEW_WIDTH), Some(PREVIEW_HEIGHT))),
+                layer: Layer::Overlay,
+                pointer_interactivity: false,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                output,
+                anchor: match position {
+                    Position::Top => Anchor::TOP,
+                    Position::Bottom => Anchor::BOTTOM,
+                } | Anchor::LEFT,
+                margin: IcedMargin {
+                    left,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        ])
+    }
+
+    pub fn preview_view(&self) -> Element<Message> {
+        let Some(workspace) = self
+            .hovered
+            .and_then(|id| self.workspaces.iter().find(|w| w.id == id))
+        else {
+            return Row::new().into();
+        };
+
+        container(
+            column!(
+                text(tr_args(
+                    "workspace-preview",
+                    &[(
+                        "workspace",
+                        if workspace.id < 0 {
+                            workspace.name.clone()
+                        } else {
+                            workspace.id.to_string()
+                        }
+                    )]
+                )),
+                match self.thumbnails.get(&workspace.id) {
+                    Some(handle) =>
+                        Into::<Element<Message>>::into(image(handle.clone()).width(Length::Fill),),
+                    None => text(tr("no-workspace-preview")).size(12).into(),
+                }
+            )
+            .spacing(8),
+        )
+        .padding(12)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
+
+impl Workspaces {
+    fn visible_workspaces(
+        &self,
+        outputs: &Outputs,
+        monitor_name: Option<&str>,
+        config: &WorkspacesModuleConfig,
+    ) -> Vec<Workspace> {
+        let mut workspaces = self
+            .workspaces
+            .iter()
+            .filter(|w| {
+                config.visibility_mode == WorkspaceVisibilityMode::All
+                    || w.monitor == monitor_name.unwrap_or(&w.monitor)
+                    || !outputs.has_name(&w.monitor)
+            })
+            .filter(|w| {
+                monitor_name
+                    .and_then(|name| config.monitor_workspaces.get(name))
+                    .map_or(true, |ids| w.id < 0 || ids.contains(&w.id))
+            })
+            .filter(|w| match config.display_mode {
+                WorkspaceDisplayMode::All => true,
+                WorkspaceDisplayMode::Occupied => w.id < 0 || w.windows > 0 || w.active,
+                WorkspaceDisplayMode::Fixed => w.id < 0 || w.id <= config.fixed_count,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if config.display_mode == WorkspaceDisplayMode::Fixed {
+            for id in 1..=config.fixed_count {
+                if !workspaces.iter().any(|w| w.id == id) {
+                    workspaces.push(Workspace {
+                        id,
+                        name: id.to_string(),
+                        monitor_id: None,
+                        monitor: "".to_string(),
+                        active: false,
+                        windows: 0,
+                    });
+                }
+            }
+            workspaces.sort_by_key(|w| if w.id < 0 { i32::MAX } else { w.id });
+        }
+
+        workspaces
     }
 }
 
@@ -182,68 +432,68 @@ impl Module for Workspaces {
         Some((
             Into::<Element<Message>>::into(
                 Row::with_children(
-                    self.workspaces
-                        .iter()
-                        .filter_map(|w| {
-                            if config.visibility_mode == WorkspaceVisibilityMode::All
-                                || w.monitor == monitor_name.unwrap_or_else(|| &w.monitor)
-                                || !outputs.has_name(&w.monitor)
-                            {
-                                let empty = w.windows == 0;
-                                let monitor = w.monitor_id;
-
-                                let color = monitor.map(|m| {
-                                    if w.id > 0 {
-                                        workspace_colors.get(m).copied()
-                                    } else {
-                                        special_workspace_colors
-                                            .unwrap_or(workspace_colors)
-                                            .get(m)
-                                            .copied()
-                                    }
-                                });
-
-                                Some(
-                                    button(
-                                        container(
-                                            if w.id < 0 {
-                                                text(w.name.as_str())
-                                            } else {
-                                                text(w.id)
-                                            }
-                                            .size(10),
-                                        )
-                                        .align_x(alignment::Horizontal::Center)
-                                        .align_y(alignment::Vertical::Center),
-                                    )
-                                    .style(WorkspaceButtonStyle(empty, color).into_style())
-                                    .padding(if w.id < 0 {
-                                        if w.active {
-                                            [0, 16]
-                                        } else {
-                                            [0, 8]
-                                        }
-                                    } else {
-                                        [0, 0]
+                    self.visible_workspaces(outputs, monitor_name, config)
+                        .into_iter()
+                        .map(|w| {
+                            let empty = w.windows == 0;
+                            let monitor = w.monitor_id;
+                            let workspace_id = w.id;
+
+                            let color = monitor.map(|m| {
+                                if w.id > 0 {
+                                    workspace_colors.get(m).copied()
+                                } else {
+                                    special_workspace_colors
+                                        .unwrap_or(workspace_colors)
+                                        .get(m)
+                                        .copied()
+                                }
+                            });
+
+                            let label = config
+                                .labels
+                                .get(&w.id.to_string())
+                                .or_else(|| config.labels.get(&w.name));
+
+                            position_button(
+                                container(
+                                    text(match label {
+                                        Some(label) => label.clone(),
+                                        None if w.id < 0 => w.name.clone(),
+                                        None => w.id.to_string(),
                                     })
-                                    .on_press(if w.id > 0 {
-                                        Message::ChangeWorkspace(w.id)
-                                    } else {
-                                        Message::ToggleSpecialWorkspace(w.id)
-                                    })
-                                    .width(if w.id < 0 {
-                                        Length::Shrink
-                                    } else if w.active {
-                                        Length::Fixed(32.)
-                                    } else {
-                                        Length::Fixed(16.)
-                                    })
-                                    .height(16)
-                                    .into(),
+                                    .size(10),
                                 )
+                                .align_x(alignment::Horizontal::Center)
+                                .align_y(alignment::Vertical::Center),
+                            )
+                            .style(WorkspaceButtonStyle(empty, color).into_style())
+                            .padding(if w.id < 0 || label.is_some() {
+                                if w.active {
+                                    [0, 16]
+                                } else {
+                                    [0, 8]
+                                }
                             } else {
-                                None
-                            }
+                                [0, 0]
+                            })
+                            .on_press(if w.id > 0 {
+                                Message::ChangeWorkspace(w.id)
+                            } else {
+                                Message::ToggleSpecialWorkspace(w.id)
+                            })
+                            .on_hover_with_position(move |button_ui_ref| {
+                                Message::Hover(id, workspace_id, button_ui_ref)
+                            })
+                            .width(if w.id < 0 || label.is_some() {
+                                Length::Shrink
+                            } else if w.active {
+                                Length::Fixed(32.)
+                            } else {
+                                Length::Fixed(16.)
+                            })
+                            .height(16)
+                            .into()
                         })
                         .collect::<Vec<Element<'_, _, _>>>(),
                 )
@@ -0,0 +1,412 @@
+use crate::{
+    components::icons::{icon, Icons},
+    config::{write_config, Position},
+    i18n::{tr, tr_args},
+    style::{ConfirmButtonStyle, OutlineButtonStyle, QuickSettingsButtonStyle},
+};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{IcedOutput, SctkLayerSurfaceSettings},
+    widget::{button, column, container, horizontal_space, row, text, toggler, Column},
+    window::Id,
+    Alignment, Border, Element, Length, Task, Theme,
+};
+use log::{error, warn};
+use std::{collections::HashSet, env};
+
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    pub compositor: Option<String>,
+    pub network_manager: bool,
+    pub iwd: bool,
+    pub upower: bool,
+    pub bluez: bool,
+}
+
+impl Environment {
+    fn is_hyprland(&self) -> bool {
+        self.compositor.as_deref() == Some("Hyprland")
+    }
+}
+
+async fn detect_environment() -> Environment {
+    let compositor = if env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
+        Some("Hyprland".to_string())
+    } else {
+        env::var("XDG_CURRENT_DESKTOP").ok()
+    };
+
+    let names = match system_bus_names().await {
+        Ok(names) => names,
+        Err(err) => {
+            warn!("Failed to list system bus names: {}", err);
+            HashSet::new()
+        }
+    };
+
+    Environment {
+        compositor,
+        network_manager: names.contains("org.freedesktop.NetworkManager"),
+        iwd: names.contains("net.connman.iwd"),
+        upower: names.contains("org.freedesktop.UPower"),
+        bluez: names.contains("org.bluez"),
+    }
+}
+
+async fn system_bus_names() -> anyhow::Result<HashSet<String>> {
+    let conn = zbus::Connection::system().await?;
+    let dbus = zbus::fdo::DBusProxy::new(&conn).await?;
+
+    Ok(dbus
+        .list_names()
+        .await?
+        .into_iter()
+        .chain(dbus.list_activatable_names().await?)
+        .map(|name| name.to_string())
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Environment,
+    Layout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppearancePreset {
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EnvironmentDetected(Environment),
+    Next,
+    Back,
+    PositionSelected(Position),
+    AppearanceSelected(AppearancePreset),
+    ToggleSystemInfo(bool),
+    ToggleTray(bool),
+    ToggleMediaPlayer(bool),
+    Apply,
+    Skip,
+    ConfigWritten,
+}
+
+pub struct Onboarding {
+    pub id: Id,
+    step: Step,
+    environment: Option<Environment>,
+    position: Position,
+    appearance: AppearancePreset,
+    system_info: bool,
+    tray: bool,
+    media_player: bool,
+}
+
+impl Onboarding {
+    pub fn new() -> (Self, Task<Message>) {
+        let id = Id::unique();
+
+        (
+            Self {
+                id,
+                step: Step::Environment,
+                environment: None,
+                position: Position::Top,
+                appearance: AppearancePreset::Dark,
+                system_info: true,
+                tray: true,
+                media_player: false,
+            },
+            Task::batch(vec![
+                get_layer_surface(SctkLayerSurfaceSettings {
+                    id,
+                    size: Some((Some(520), Some(460))),
+                    layer: Layer::Overlay,
+                    pointer_interactivity: true,
+                    keyboard_interactivity: KeyboardInteractivity::OnDemand,
+                    output: IcedOutput::Active,
+                    anchor: Anchor::empty(),
+                    ..Default::default()
+                }),
+                Task::perform(detect_environment(), Message::EnvironmentDetected),
+            ]),
+        )
+    }
+
+    /// Returns true when the wizard is completed and the surface can be destroyed
+    pub fn update(&mut self, message: Message) -> (Task<Message>, bool) {
+        match message {
+            Message::EnvironmentDetected(environment) => {
+                self.environment = Some(environment);
+            }
+            Message::Next => self.step = Step::Layout,
+            Message::Back => self.step = Step::Environment,
+            Message::PositionSelected(position) => self.position = position,
+            Message::AppearanceSelected(appearance) => self.appearance = appearance,
+            Message::ToggleSystemInfo(value) => self.system_info = value,
+            Message::ToggleTray(value) => self.tray = value,
+            Message::ToggleMediaPlayer(value) => self.media_player = value,
+            Message::Apply => {
+                return (self.write(self.config_content()), false);
+            }
+            Message::Skip => {
+                return (self.write("position: Top\n".to_string()), false);
+            }
+            Message::ConfigWritten => {
+                return (Task::none(), true);
+            }
+        }
+
+        (Task::none(), false)
+    }
+
+    fn write(&self, content: String) -> Task<Message> {
+        Task::perform(
+            async move {
+                if let Err(err) = write_config(content).await {
+                    error!("Failed to write the initial config file: {}", err);
+                }
+            },
+            |_| Message::ConfigWritten,
+        )
+    }
+
+    fn config_content(&self) -> String {
+        let environment = self.environment.clone().unwrap_or_default();
+
+        let mut content = format!(
+            "position: {}\nmodules:\n",
+            match self.position {
+                Position::Top => "Top",
+                Position::Bottom => "Bottom",
+            }
+        );
+
+        if environment.is_hyprland() {
+            content.push_str("  left:\n    - Workspaces\n  center:\n    - WindowTitle\n");
+        } else {
+            content.push_str("  left: []\n  center: []\n");
+        }
+
+        content.push_str("  right:\n");
+        if self.system_info {
+            content.push_str("    - SystemInfo\n");
+        }
+        if self.media_player {
+            content.push_str("    - MediaPlayer\n");
+        }
+        if self.tray {
+            content.push_str("    - Tray\n");
+        }
+        content.push_str("    - [Clock, Privacy, Settings]\n");
+
+        if environment.network_manager {
+            content.push_str(
+                "settings:\n  wifiMoreCmd: \"nm-connection-editor\"\n  vpnMoreCmd: \"nm-connection-editor\"\n",
+            );
+        }
+
+        if self.appearance == AppearancePreset::Light {
+            content.push_str(concat!(
+                "appearance:\n",
+                "  backgroundColor:\n",
+                "    base: \"#eff1f5\"\n",
+                "    strong: \"#ccd0da\"\n",
+                "    weak: \"#e6e9ef\"\n",
+                "  primaryColor:\n",
+                "    base: \"#fe640b\"\n",
+                "    text: \"#eff1f5\"\n",
+                "  secondaryColor:\n",
+                "    base: \"#dce0e8\"\n",
+                "    strong: \"#ccd0da\"\n",
+                "  successColor: \"#40a02b\"\n",
+                "  dangerColor:\n",
+                "    base: \"#d20f39\"\n",
+                "    weak: \"#df8e1d\"\n",
+                "  textColor: \"#4c4f69\"\n",
+                "  workspaceColors:\n",
+                "    - \"#fe640b\"\n",
+                "    - \"#7287fd\"\n",
+                "    - \"#8839ef\"\n",
+            ));
+        }
+
+        content
+    }
+
+    fn environment_view(&self) -> Element<Message> {
+        match &self.environment {
+            Some(environment) => {
+                let entry = |label: String, ok: bool| {
+                    row!(
+                        icon(if ok {
+                            Icons::CheckCircle
+                        } else {
+                            Icons::CloseCircle
+                        }),
+                        text(label)
+                    )
+                    .spacing(8)
+                    .align_y(Alignment::Center)
+                };
+
+                column!(
+                    text(tr("environment-found")),
+                    entry(
+                        tr_args(
+                            "environment-compositor",
+                            &[(
+                                "compositor",
+                                environment
+                                    .compositor
+                                    .clone()
+                                    .unwrap_or_else(|| tr("environment-compositor-unknown")),
+                            )]
+                        ),
+                        environment.is_hyprland()
+                    ),
+                    entry(
+                        if environment.iwd && !environment.network_manager {
+                            tr("environment-iwd-only")
+                        } else {
+                            "NetworkManager".to_string()
+                        },
+                        environment.network_manager
+                    ),
+                    entry(tr("environment-upower"), environment.upower),
+                    entry(tr("environment-bluez"), environment.bluez),
+                )
+                .spacing(12)
+                .into()
+            }
+            None => text(tr("detecting-environment")).into(),
+        }
+    }
+
+    fn layout_view(&self) -> Element<Message> {
+        let choice = |label: String, active: bool, message: Message| {
+            button(text(label).width(Length::Fill).align_x(Horizontal::Center))
+                .padding([8, 16])
+                .width(Length::Fill)
+                .style(QuickSettingsButtonStyle(active).into_style())
+                .on_press(message)
+        };
+
+        column!(
+            text(tr("bar-position")),
+            row!(
+                choice(
+                    tr("position-top"),
+                    self.position == Position::Top,
+                    Message::PositionSelected(Position::Top)
+                ),
+                choice(
+                    tr("position-bottom"),
+                    self.position == Position::Bottom,
+                    Message::PositionSelected(Position::Bottom)
+                ),
+            )
+            .spacing(8),
+            text(tr("editor-appearance")),
+            row!(
+                choice(
+                    tr("theme-dark"),
+                    self.appearance == AppearancePreset::Dark,
+                    Message::AppearanceSelected(AppearancePreset::Dark)
+                ),
+                choice(
+                    tr("theme-light"),
+                    self.appearance == AppearancePreset::Light,
+                    Message::AppearanceSelected(AppearancePreset::Light)
+                ),
+            )
+            .spacing(8),
+            text(tr("optional-modules")),
+            Column::new()
+                .push(
+                    toggler(self.system_info)
+                        .label(tr("module-system-info"))
+                        .on_toggle(Message::ToggleSystemInfo),
+                )
+                .push(
+                    toggler(self.tray)
+                        .label(tr("module-tray"))
+                        .on_toggle(Message::ToggleTray),
+                )
+                .push(
+                    toggler(self.media_player)
+                        .label(tr("module-media-player"))
+                        .on_toggle(Message::ToggleMediaPlayer),
+                )
+                .spacing(8),
+        )
+        .spacing(12)
+        .into()
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let (content, buttons) = match self.step {
+            Step::Environment => (
+                self.environment_view(),
+                row!(
+                    button(text(tr("use-defaults")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(OutlineButtonStyle.into_style())
+                        .on_press(Message::Skip),
+                    horizontal_space(),
+                    button(text(tr("next")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(ConfirmButtonStyle.into_style())
+                        .on_press_maybe(self.environment.as_ref().map(|_| Message::Next)),
+                ),
+            ),
+            Step::Layout => (
+                self.layout_view(),
+                row!(
+                    button(text(tr("back")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(OutlineButtonStyle.into_style())
+                        .on_press(Message::Back),
+                    horizontal_space(),
+                    button(text(tr("apply")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(ConfirmButtonStyle.into_style())
+                        .on_press(Message::Apply),
+                ),
+            ),
+        };
+
+        container(
+            column!(
+                row!(icon(Icons::Welcome).size(32), text(tr("welcome")).size(22))
+                    .spacing(16)
+                    .align_y(Alignment::Center),
+                container(content).height(Length::Fill),
+                buttons.spacing(8).width(Length::Fill),
+            )
+            .spacing(16),
+        )
+        .padding(24)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{
+    components::icons::{icon, Icons},
+    services::media_keys::MediaKey,
+};
+use iced::{
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    widget::{container, progress_bar, row},
+    window::Id,
+    Alignment, Border, Element, Length, Task, Theme,
+};
+use std::time::Duration;
+
+const WIDTH: u32 = 280;
+const HEIGHT: u32 = 56;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Hide(u32),
+}
+
+/// Level shown by the overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsdKind {
+    Volume,
+    Microphone,
+    Brightness,
+}
+
+impl From<MediaKey> for OsdKind {
+    fn from(key: MediaKey) -> Self {
+        match key {
+            MediaKey::VolumeUp | MediaKey::VolumeDown | MediaKey::VolumeMute => Self::Volume,
+            MediaKey::MicMute => Self::Microphone,
+            MediaKey::BrightnessUp | MediaKey::BrightnessDown => Self::Brightness,
+        }
+    }
+}
+
+/// Transient overlay showing the level changed by a media key or a slider
+pub struct Osd {
+    pub id: Id,
+    pub kind: OsdKind,
+    generation: u32,
+}
+
+impl Osd {
+    pub fn new(kind: OsdKind, timeout: Duration) -> (Self, Task<Message>) {
+        let id = Id::unique();
+        let mut osd = Self {
+            id,
+            kind,
+            generation: 0,
+        };
+        let hide_task = osd.show(kind, timeout);
+
+        (
+            osd,
+            Task::batch(vec![
+                get_layer_surface(SctkLayerSurfaceSettings {
+                    id,
+                    size: Some((Some(WIDTH), Some(HEIGHT))),
+                    layer: Layer::Overlay,
+                    pointer_interactivity: false,
+                    keyboard_interactivity: KeyboardInteractivity::None,
+                    output: IcedOutput::Active,
+                    anchor: Anchor::BOTTOM,
+                    margin: IcedMargin {
+                        bottom: 96,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                hide_task,
+            ]),
+        )
+    }
+
+    /// Keeps the overlay open for another timeout
+    pub fn show(&mut self, kind: OsdKind, timeout: Duration) -> Task<Message> {
+        self.kind = kind;
+        self.generation = self.generation.wrapping_add(1);
+        let generation = self.generation;
+
+        Task::perform(tokio::time::sleep(timeout), move |_| {
+            Message::Hide(generation)
+        })
+    }
+
+    /// Returns true when the overlay expired and the surface can be destroyed
+    pub fn update(&mut self, message: Message) -> bool {
+        match message {
+            Message::Hide(generation) => generation == self.generation,
+        }
+    }
+
+    pub fn view<'a>(&self, icon_type: Icons, value: Option<f32>) -> Element<'a, Message> {
+        container(
+            row!(icon(icon_type).size(22))
+                .push_maybe(value.map(|value| {
+                    progress_bar(0.0..=1., value.clamp(0., 1.))
+                        .height(Length::Fixed(6.))
+                        .style(|theme: &Theme| progress_bar::Style {
+                            background: theme.extended_palette().background.weak.color.into(),
+                            bar: theme.palette().primary.into(),
+                            border: Border::default().rounded(3),
+                        })
+                }))
+                .spacing(16)
+                .align_y(Alignment::Center),
+        )
+        .padding([8, 20])
+        .width(Length::Fill)
+        .center_y(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 28.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
@@ -1,48 +1,168 @@
 use iced::{
     platform_specific::shell::commands::layer_surface::{
-        destroy_layer_surface, get_layer_surface, set_anchor, Anchor, KeyboardInteractivity, Layer,
+        destroy_layer_surface, get_layer_surface, set_anchor, set_exclusive_zone, set_margin,
+        set_size, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
     },
-    runtime::platform_specific::wayland::layer_surface::{IcedOutput, SctkLayerSurfaceSettings},
     window::Id,
-    Task,
+    Point, Rectangle, Task,
 };
 use log::debug;
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
 use wayland_client::protocol::wl_output::WlOutput;
 
 use crate::{
-    config::{self, Position},
-    menu::{Menu, MenuType},
+    components::animation::Animation,
+    config::{self, BarMargin, Position},
+    menu::{Menu, MenuType, MENU_NAMESPACE},
     position_button::ButtonUIRef,
     HEIGHT,
 };
 
+// height of the bar surfaces, grows with the scale applied to touch devices
+// and with the space left around a floating bar
+static BAR_HEIGHT: AtomicU32 = AtomicU32::new(HEIGHT);
+// part of the bar height left for the shadow of a floating bar
+static BAR_INSET: AtomicU32 = AtomicU32::new(0);
+// distance of a floating bar from the anchored edge and from the sides
+static BAR_EDGE_MARGIN: AtomicU32 = AtomicU32::new(0);
+static BAR_SIDE_MARGIN: AtomicU32 = AtomicU32::new(0);
+const BAR_ANIMATION: Duration = Duration::from_millis(200);
+// wider than any output, the input region is clipped to the surface
+const INPUT_ZONE_WIDTH: f32 = i16::MAX as f32;
+
+/// Sets the height of the bar surfaces created from now on
+pub fn set_bar_height(scale: f64, inset: u32) {
+    let height = ((HEIGHT + inset) as f64 * scale).round() as u32;
+    BAR_HEIGHT.store(height, Ordering::Relaxed);
+    BAR_INSET.store((inset as f64 * scale).round() as u32, Ordering::Relaxed);
+}
+
+/// Sets the margins of the bar surfaces created from now on
+pub fn set_bar_margin(margin: BarMargin) {
+    BAR_EDGE_MARGIN.store(margin.edge, Ordering::Relaxed);
+    BAR_SIDE_MARGIN.store(margin.sides, Ordering::Relaxed);
+}
+
+/// Margins of the layer surface of a bar, the compositor keeps them
+/// outside of the surface so they don't take the pointer events
+fn bar_margin(position: Position) -> IcedMargin {
+    let edge = BAR_EDGE_MARGIN.load(Ordering::Relaxed) as i32;
+    let sides = BAR_SIDE_MARGIN.load(Ordering::Relaxed) as i32;
+    let (top, bottom) = match position {
+        Position::Top => (edge, 0),
+        Position::Bottom => (0, edge),
+    };
+
+    IcedMargin {
+        top,
+        right: sides,
+        bottom,
+        left: sides,
+    }
+}
+
+/// Input region of a bar, without the room left for the shadow
+fn bar_input_zone(position: Position) -> Option<Vec<Rectangle>> {
+    let height = BAR_HEIGHT.load(Ordering::Relaxed) as f32;
+    let inset = BAR_INSET.load(Ordering::Relaxed) as f32;
+
+    (inset > 0.).then(|| {
+        vec![Rectangle {
+            x: 0.,
+            y: match position {
+                Position::Top => 0.,
+                Position::Bottom => inset,
+            },
+            width: INPUT_ZONE_WIDTH,
+            height: height - inset,
+        }]
+    })
+}
+
 #[derive(Debug, Clone)]
 struct ShellInfo {
     id: Id,
     position: Position,
     menu: Menu,
+    // 1 when the bar is shown, 0 when hidden
+    visibility: Animation,
+    // height currently applied to the surface and to its exclusive zone
+    height: u32,
+    // size of the output, the menu surface covers all of it
+    output_size: Option<(f32, f32)>,
+}
+
+impl ShellInfo {
+    fn new(id: Id, menu_id: Id, position: Position) -> Self {
+        Self {
+            id,
+            position,
+            menu: Menu::new(menu_id),
+            visibility: Animation::new(1., BAR_ANIMATION),
+            height: BAR_HEIGHT.load(Ordering::Relaxed),
+            output_size: None,
+        }
+    }
+
+    fn target_height(&self) -> u32 {
+        (BAR_HEIGHT.load(Ordering::Relaxed) as f32 * self.visibility.target()).round() as u32
+    }
+
+    /// Resizes the surface to the height reached by the animation
+    fn apply_height<Message: 'static>(&mut self, now: Instant) -> Task<Message> {
+        let height =
+            (BAR_HEIGHT.load(Ordering::Relaxed) as f32 * self.visibility.value(now)).round() as u32;
+
+        if height == self.height {
+            return Task::none();
+        }
+        self.height = height;
+
+        // a layer surface can't have a zero height, a hidden bar keeps a transparent line
+        Task::batch(vec![
+            set_size(self.id, None, Some(height.max(1))),
+            set_exclusive_zone(self.id, height as i32),
+        ])
+    }
+}
+
+/// A wayland output the layer surfaces can be bound to
+pub trait OutputHandle: Clone + PartialEq + std::fmt::Debug {
+    fn iced_output(&self) -> IcedOutput;
 }
 
+impl OutputHandle for WlOutput {
+    fn iced_output(&self) -> IcedOutput {
+        IcedOutput::Output(self.clone())
+    }
+}
+
+/// Tracks the known outputs, by name, and the layer surfaces created on them.
+///
+/// An entry without a name is the fallback surface created on the active
+/// output when no requested output is available.
 #[derive(Debug, Clone)]
-pub struct Outputs(Vec<(Option<String>, Option<ShellInfo>, Option<WlOutput>)>);
+pub struct Outputs<O: OutputHandle = WlOutput>(Vec<(Option<String>, Option<ShellInfo>, Option<O>)>);
 
 pub enum HasOutput<'a> {
     Main,
     Menu(Option<&'a (MenuType, ButtonUIRef)>),
 }
 
-impl Outputs {
+impl<O: OutputHandle> Outputs<O> {
     pub fn new<Message: 'static>(position: Position) -> (Self, Task<Message>) {
         let (id, menu_id, task) = Self::create_output_layers(None, position);
 
         (
             Self(vec![(
                 None,
-                Some(ShellInfo {
-                    id,
-                    menu: Menu::new(menu_id),
-                    position,
-                }),
+                Some(ShellInfo::new(id, menu_id, position)),
                 None,
             )]),
             task,
@@ -50,20 +170,23 @@ impl Outputs {
     }
 
     fn create_output_layers<Message: 'static>(
-        wl_output: Option<WlOutput>,
+        wl_output: Option<O>,
         position: Position,
     ) -> (Id, Id, Task<Message>) {
         let id = Id::unique();
+        let height = BAR_HEIGHT.load(Ordering::Relaxed);
         let task = get_layer_surface(SctkLayerSurfaceSettings {
             id,
-            size: Some((None, Some(HEIGHT))),
+            size: Some((None, Some(height))),
             layer: Layer::Bottom,
             pointer_interactivity: true,
             keyboard_interactivity: KeyboardInteractivity::None,
-            exclusive_zone: HEIGHT as i32,
-            output: wl_output.clone().map_or(IcedOutput::Active, |wl_output| {
-                IcedOutput::Output(wl_output)
-            }),
+            exclusive_zone: height as i32,
+            margin: bar_margin(position),
+            input_zone: bar_input_zone(position),
+            output: wl_output
+                .as_ref()
+                .map_or(IcedOutput::Active, |wl_output| wl_output.iced_output()),
             anchor: match position {
                 Position::Top => Anchor::TOP,
                 Position::Bottom => Anchor::BOTTOM,
@@ -79,17 +202,29 @@ impl Outputs {
             layer: Layer::Background,
             pointer_interactivity: true,
             keyboard_interactivity: KeyboardInteractivity::None,
-            output: wl_output.map_or(IcedOutput::Active, |wl_output| {
-                IcedOutput::Output(wl_output)
-            }),
+            output: wl_output
+                .as_ref()
+                .map_or(IcedOutput::Active, |wl_output| wl_output.iced_output()),
             anchor: Anchor::TOP | Anchor::BOTTOM | Anchor::LEFT | Anchor::RIGHT,
+            namespace: MENU_NAMESPACE.to_owned(),
             ..Default::default()
         });
 
         (id, menu_id, Task::batch(vec![task, menu_task]))
     }
 
-    fn name_in_config(name: Option<&str>, outputs: &config::Outputs) -> bool {
+    fn name_in_config(
+        name: Option<&str>,
+        outputs: &config::Outputs,
+        outputs_exclude: &[String],
+    ) -> bool {
+        if outputs_exclude
+            .iter()
+            .any(|output| Some(output.as_str()) == name)
+        {
+            return false;
+        }
+
         match outputs {
             config::Outputs::All => true,
             config::Outputs::Active => false,
@@ -105,7 +240,7 @@ impl Outputs {
                 if info.id == id {
                     Some(HasOutput::Main)
                 } else if info.menu.id == id {
-                    Some(HasOutput::Menu(info.menu.menu_info.as_ref()))
+                    Some(HasOutput::Menu(info.menu.shown()))
                 } else {
                     None
                 }
@@ -129,6 +264,15 @@ impl Outputs {
         })
     }
 
+    /// Output of the bar owning the menu
+    pub fn get_menu_monitor_name(&self, id: Id) -> Option<&str> {
+        self.0.iter().find_map(|(name, info, _)| {
+            info.as_ref()
+                .filter(|info| info.menu.id == id)
+                .and_then(|_| name.as_deref())
+        })
+    }
+
     pub fn has_name(&self, name: &str) -> bool {
         self.0
             .iter()
@@ -138,137 +282,227 @@ impl Outputs {
     pub fn add<Message: 'static>(
         &mut self,
         request_outputs: &config::Outputs,
+        outputs_exclude: &[String],
         position: Position,
         name: &str,
-        wl_output: WlOutput,
+        wl_output: O,
     ) -> Task<Message> {
-        let target = Self::name_in_config(Some(name), request_outputs);
+        let target = Self::name_in_config(Some(name), request_outputs, outputs_exclude);
+
+        // an output with the same name is a stale entry left by an unclean removal
+        let destroy_task = if let Some(index) = self
+            .0
+            .iter()
+            .position(|(key, _, _)| key.as_ref().map(|k| k.as_str()) == Some(name))
+        {
+            let (_, shell_info, _) = self.0.swap_remove(index);
+
+            Self::destroy_surfaces(shell_info)
+        } else {
+            Task::none()
+        };
 
         if target {
             debug!("Found target output, creating a new layer surface");
 
             let (id, menu_id, task) = Self::create_output_layers(Some(wl_output.clone()), position);
 
-            let destroy_task = if let Some(index) = self
-                .0
-                .iter()
-                .position(|(key, _, _)| key.as_ref().map(|k| k.as_str()) == Some(name))
-            {
-                let old_output = self.0.swap_remove(index);
+            self.0.push((
+                Some(name.to_owned()),
+                Some(ShellInfo::new(id, menu_id, position)),
+                Some(wl_output),
+            ));
 
-                if let Some(shell_info) = old_output.1 {
-                    let destroy_main_task = destroy_layer_surface(shell_info.id);
-                    let destroy_menu_task = destroy_layer_surface(shell_info.menu.id);
+            Task::batch(vec![destroy_task, self.remove_fallback(), task])
+        } else {
+            self.0.push((Some(name.to_owned()), None, Some(wl_output)));
 
-                    Task::batch(vec![destroy_main_task, destroy_menu_task])
-                } else {
-                    Task::none()
-                }
+            // the fallback created before the outputs were known could be on this one
+            Task::batch(vec![
+                destroy_task,
+                self.place_fallback(position, outputs_exclude),
+            ])
+        }
+    }
+
+    /// Forgets an output that has been disconnected
+    pub fn remove<Message: 'static>(
+        &mut self,
+        position: Position,
+        outputs_exclude: &[String],
+        wl_output: O,
+    ) -> Task<Message> {
+        let destroy_task =
+            if let Some(index) = self.0.iter().position(|(_, _, assigned_wl_output)| {
+                assigned_wl_output.as_ref() == Some(&wl_output)
+            }) {
+                debug!("Removing output");
+
+                let (_, shell_info, _) = self.0.swap_remove(index);
+
+                Self::destroy_surfaces(shell_info)
             } else {
                 Task::none()
             };
 
-            self.0.push((
-                Some(name.to_owned()),
-                Some(ShellInfo {
-                    id,
-                    menu: Menu::new(menu_id),
-                    position,
-                }),
-                Some(wl_output),
-            ));
+        // the fallback surface could live on the removed output and
+        // the compositor closes it, move it to the current active output
+        let fallback_task = if self
+            .0
+            .iter()
+            .any(|(name, shell_info, _)| name.is_none() && shell_info.is_some())
+        {
+            debug!("Moving the fallback layer surface to the active output");
 
-            // remove fallback layer surface
-            let destroy_fallback_task =
-                if let Some(index) = self.0.iter().position(|(key, _, _)| key.is_none()) {
-                    let old_output = self.0.swap_remove(index);
+            Task::batch(vec![
+                self.remove_fallback(),
+                self.add_fallback(position, outputs_exclude),
+            ])
+        } else {
+            self.ensure_fallback(position, outputs_exclude)
+        };
 
-                    if let Some(shell_info) = old_output.1 {
-                        let destroy_fallback_main_task = destroy_layer_surface(shell_info.id);
-                        let destroy_fallback_menu_task = destroy_layer_surface(shell_info.menu.id);
+        Task::batch(vec![destroy_task, fallback_task])
+    }
 
-                        Task::batch(vec![destroy_fallback_main_task, destroy_fallback_menu_task])
-                    } else {
-                        Task::none()
-                    }
-                } else {
-                    Task::none()
-                };
+    /// Destroys the layer surfaces of an output that is no longer requested
+    fn detach<Message: 'static>(
+        &mut self,
+        position: Position,
+        outputs_exclude: &[String],
+        wl_output: O,
+    ) -> Task<Message> {
+        if let Some((_, shell_info, _)) = self
+            .0
+            .iter_mut()
+            .find(|(_, _, assigned_wl_output)| assigned_wl_output.as_ref() == Some(&wl_output))
+        {
+            debug!("Removing layer surface for output");
+
+            let destroy_task = Self::destroy_surfaces(shell_info.take());
 
-            Task::batch(vec![destroy_task, destroy_fallback_task, task])
+            Task::batch(vec![
+                destroy_task,
+                self.ensure_fallback(position, outputs_exclude),
+            ])
         } else {
-            self.0.push((Some(name.to_owned()), None, Some(wl_output)));
+            Task::none()
+        }
+    }
 
+    fn destroy_surfaces<Message: 'static>(shell_info: Option<ShellInfo>) -> Task<Message> {
+        if let Some(shell_info) = shell_info {
+            Task::batch(vec![
+                destroy_layer_surface(shell_info.id),
+                destroy_layer_surface(shell_info.menu.id),
+            ])
+        } else {
             Task::none()
         }
     }
 
-    pub fn remove<Message: 'static>(
+    /// Output of the fallback surface, the active one unless some outputs are excluded:
+    /// then the first known output that isn't, or none if they are all excluded
+    fn fallback_output(&self, outputs_exclude: &[String]) -> Option<Option<O>> {
+        let mut known = self
+            .0
+            .iter()
+            .filter_map(|(name, _, wl_output)| Some((name.as_deref()?, wl_output.as_ref()?)))
+            .peekable();
+
+        if outputs_exclude.is_empty() || known.peek().is_none() {
+            return Some(None);
+        }
+
+        known
+            .find(|(name, _)| !outputs_exclude.iter().any(|excluded| excluded == name))
+            .map(|(_, wl_output)| Some(wl_output.clone()))
+    }
+
+    fn add_fallback<Message: 'static>(
         &mut self,
         position: Position,
-        wl_output: WlOutput,
+        outputs_exclude: &[String],
     ) -> Task<Message> {
-        if let Some(index_to_remove) = self.0.iter().position(|(_, _, assigned_wl_output)| {
-            assigned_wl_output
-                .as_ref()
-                .map(|assigned_wl_output| *assigned_wl_output == wl_output)
-                .unwrap_or_default()
-        }) {
-            debug!("Removing layer surface for output");
+        let Some(wl_output) = self.fallback_output(outputs_exclude) else {
+            debug!("All the outputs are excluded, no fallback layer surface");
 
-            let (name, shell_info, wl_output) = self.0.swap_remove(index_to_remove);
+            return Task::none();
+        };
+        let (id, menu_id, task) = Self::create_output_layers(wl_output, position);
 
-            let destroy_task = if let Some(shell_info) = shell_info {
-                let destroy_main_task = destroy_layer_surface(shell_info.id);
-                let destroy_menu_task = destroy_layer_surface(shell_info.menu.id);
+        self.0
+            .push((None, Some(ShellInfo::new(id, menu_id, position)), None));
 
-                Task::batch(vec![destroy_main_task, destroy_menu_task])
-            } else {
-                Task::none()
-            };
+        task
+    }
+
+    fn remove_fallback<Message: 'static>(&mut self) -> Task<Message> {
+        if let Some(index) = self.0.iter().position(|(key, _, _)| key.is_none()) {
+            let (_, shell_info, _) = self.0.swap_remove(index);
 
-            self.0.push((name.to_owned(), None, wl_output));
+            Self::destroy_surfaces(shell_info)
+        } else {
+            Task::none()
+        }
+    }
 
-            if !self.0.iter().any(|(_, shell_info, _)| shell_info.is_some()) {
-                debug!("No outputs left, creating a fallback layer surface");
+    fn ensure_fallback<Message: 'static>(
+        &mut self,
+        position: Position,
+        outputs_exclude: &[String],
+    ) -> Task<Message> {
+        if self.0.iter().any(|(_, shell_info, _)| shell_info.is_some()) {
+            Task::none()
+        } else {
+            debug!("No outputs left, creating a fallback layer surface");
 
-                let (id, menu_id, task) = Self::create_output_layers(None, position);
+            self.add_fallback(position, outputs_exclude)
+        }
+    }
 
-                self.0.push((
-                    None,
-                    Some(ShellInfo {
-                        id,
-                        menu: Menu::new(menu_id),
-                        position,
-                    }),
-                    None,
-                ));
+    /// Moves the fallback surface off the excluded outputs, or creates it if
+    /// there are no bars
+    fn place_fallback<Message: 'static>(
+        &mut self,
+        position: Position,
+        outputs_exclude: &[String],
+    ) -> Task<Message> {
+        let has_fallback = self
+            .0
+            .iter()
+            .any(|(name, shell_info, _)| name.is_none() && shell_info.is_some());
 
-                Task::batch(vec![destroy_task, task])
-            } else {
-                Task::batch(vec![destroy_task])
-            }
+        if has_fallback && !outputs_exclude.is_empty() {
+            Task::batch(vec![
+                self.remove_fallback(),
+                self.add_fallback(position, outputs_exclude),
+            ])
         } else {
-            Task::none()
+            self.ensure_fallback(position, outputs_exclude)
         }
     }
 
     pub fn sync<Message: 'static>(
         &mut self,
         request_outputs: &config::Outputs,
+        outputs_exclude: &[String],
         position: Position,
     ) -> Task<Message> {
         debug!(
-            "Syncing outputs: {:?}, request_outputs: {:?}",
-            self, request_outputs
+            "Syncing outputs: {:?}, request_outputs: {:?}, outputs_exclude: {:?}",
+            self, request_outputs, outputs_exclude
         );
 
         let to_remove = self
             .0
             .iter()
             .filter_map(|(name, shell_info, wl_output)| {
-                if !Self::name_in_config(name.as_ref().map(|n| n.as_str()), request_outputs)
-                    && shell_info.is_some()
+                if !Self::name_in_config(
+                    name.as_ref().map(|n| n.as_str()),
+                    request_outputs,
+                    outputs_exclude,
+                ) && shell_info.is_some()
                 {
                     Some(wl_output.clone())
                 } else {
@@ -283,8 +517,11 @@ impl Outputs {
             .0
             .iter()
             .filter_map(|(name, shell_info, wl_output)| {
-                if Self::name_in_config(name.as_ref().map(|n| n.as_str()), request_outputs)
-                    && shell_info.is_none()
+                if Self::name_in_config(
+                    name.as_ref().map(|n| n.as_str()),
+                    request_outputs,
+                    outputs_exclude,
+                ) && shell_info.is_none()
                 {
                     Some((name.clone(), wl_output.clone()))
                 } else {
@@ -298,14 +535,21 @@ impl Outputs {
         for (name, wl_output) in to_add {
             if let Some(wl_output) = wl_output {
                 if let Some(name) = name {
-                    tasks.push(self.add(request_outputs, position, name.as_str(), wl_output));
+                    tasks.push(self.add(
+                        request_outputs,
+                        outputs_exclude,
+                        position,
+                        name.as_str(),
+                        wl_output,
+                    ));
                 }
             }
         }
 
         for wl_output in to_remove {
-            tasks.push(self.remove(position, wl_output));
+            tasks.push(self.detach(position, outputs_exclude, wl_output));
         }
+        tasks.push(self.place_fallback(position, outputs_exclude));
 
         for shell_info in self.0.iter_mut().filter_map(|(_, shell_info, _)| {
             if let Some(shell_info) = shell_info {
@@ -323,6 +567,7 @@ impl Outputs {
                 shell_info.id, position
             );
             shell_info.position = position;
+            tasks.push(Self::apply_margin(shell_info));
             tasks.push(set_anchor(
                 shell_info.id,
                 match position {
@@ -369,6 +614,82 @@ impl Outputs {
         }
     }
 
+    /// Output and edge of a bar, for the surfaces shown next to it
+    pub fn bar_placement(&self, id: Id) -> Option<(IcedOutput, Position)> {
+        self.0.iter().find_map(|(_, shell_info, wl_output)| {
+            shell_info
+                .as_ref()
+                .filter(|shell_info| shell_info.id == id)
+                .map(|shell_info| {
+                    (
+                        wl_output
+                            .as_ref()
+                            .map_or(IcedOutput::Active, |wl_output| wl_output.iced_output()),
+                        shell_info.position,
+                    )
+                })
+        })
+    }
+
+    /// Records the size of a surface from its configure event, the menu
+    /// surfaces give the size of their output
+    pub fn set_surface_size(&mut self, id: Id, size: (f32, f32)) {
+        if let Some(shell_info) = self
+            .0
+            .iter_mut()
+            .filter_map(|(_, shell_info, _)| shell_info.as_mut())
+            .find(|shell_info| shell_info.menu.id == id)
+        {
+            shell_info.output_size = Some(size);
+        }
+    }
+
+    /// Bar and button a menu opened without the mouse is anchored to, the bar
+    /// a menu was last opened from comes first. A bar that never opened a
+    /// menu gets it at the right edge of its output
+    pub fn menu_anchor(&self, menu_type: &MenuType) -> Option<(Id, ButtonUIRef)> {
+        let mut shells = self
+            .0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .collect::<Vec<_>>();
+        shells.sort_by_key(|shell_info| std::cmp::Reverse(shell_info.menu.last_opened));
+
+        let find_anchor = |exact| {
+            shells.iter().find_map(|shell_info| {
+                shell_info
+                    .menu
+                    .anchor(menu_type, exact)
+                    .map(|anchor| (shell_info.id, anchor))
+            })
+        };
+
+        find_anchor(true)
+            .or_else(|| find_anchor(false))
+            .or_else(|| {
+                shells.iter().find_map(|shell_info| {
+                    shell_info.output_size.map(|viewport| {
+                        (
+                            shell_info.id,
+                            ButtonUIRef {
+                                position: Point::new(viewport.0, 0.),
+                                viewport,
+                            },
+                        )
+                    })
+                })
+            })
+    }
+
+    pub fn is_menu_open(&self, menu_type: MenuType) -> bool {
+        self.0.iter().any(|(_, shell_info, _)| {
+            shell_info
+                .as_ref()
+                .and_then(|shell_info| shell_info.menu.menu_info.as_ref())
+                .is_some_and(|(current_type, _)| *current_type == menu_type)
+        })
+    }
+
     pub fn close_menu<Message: 'static>(&mut self, id: Id) -> Task<Message> {
         if let Some((_, Some(shell_info), _)) = self.0.iter_mut().find(|(_, shell_info, _)| {
             shell_info.as_ref().map(|shell_info| shell_info.id) == Some(id)
@@ -416,4 +737,345 @@ impl Outputs {
             Task::none()
         }
     }
+
+    /// Resizes the bars so that their content, and the space around it,
+    /// can be drawn with the given scale factor
+    pub fn resize_bars<Message: 'static>(
+        &mut self,
+        scale: f64,
+        inset: u32,
+        margin: BarMargin,
+    ) -> Task<Message> {
+        set_bar_height(scale, inset);
+        set_bar_margin(margin);
+
+        let margins = self
+            .0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .map(Self::apply_margin)
+            .collect::<Vec<_>>();
+
+        Task::batch(
+            margins
+                .into_iter()
+                .chain([self.animate_bars(Instant::now())]),
+        )
+    }
+
+    fn apply_margin<Message: 'static>(shell_info: &ShellInfo) -> Task<Message> {
+        let margin = bar_margin(shell_info.position);
+
+        set_margin(
+            shell_info.id,
+            margin.top,
+            margin.right,
+            margin.bottom,
+            margin.left,
+        )
+    }
+
+    /// Shows, hides or, without a value, toggles the bar on the named output or on all of them
+    pub fn set_bar_visible(&mut self, output: Option<&str>, visible: Option<bool>) {
+        let now = Instant::now();
+
+        for shell_info in self
+            .0
+            .iter_mut()
+            .filter(|(name, _, _)| output.map_or(true, |output| name.as_deref() == Some(output)))
+            .filter_map(|(_, shell_info, _)| shell_info.as_mut())
+        {
+            let visible = visible.unwrap_or(shell_info.visibility.target() == 0.);
+            shell_info
+                .visibility
+                .go_to(if visible { 1. } else { 0. }, now);
+        }
+    }
+
+    pub fn is_bar_hidden(&self, id: Id) -> bool {
+        self.0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .any(|shell_info| shell_info.id == id && shell_info.height == 0)
+    }
+
+    pub fn is_bar_animating(&self) -> bool {
+        self.0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .any(|shell_info| shell_info.height != shell_info.target_height())
+    }
+
+    pub fn animate_bars<Message: 'static>(&mut self, now: Instant) -> Task<Message> {
+        Task::batch(
+            self.0
+                .iter_mut()
+                .filter_map(|(_, shell_info, _)| shell_info.as_mut())
+                .map(|shell_info| shell_info.apply_height(now))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Progress of the opening or closing animation of the menu
+    pub fn menu_transition(&self, id: Id, now: Instant) -> f32 {
+        self.0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .find(|shell_info| shell_info.menu.id == id)
+            .map_or(1., |shell_info| shell_info.menu.transition(now))
+    }
+
+    pub fn is_menu_animating(&self, now: Instant) -> bool {
+        self.0
+            .iter()
+            .filter_map(|(_, shell_info, _)| shell_info.as_ref())
+            .any(|shell_info| shell_info.menu.is_animating(now))
+    }
+
+    pub fn animate_menus<Message: 'static>(&mut self, now: Instant) -> Task<Message> {
+        Task::batch(
+            self.0
+                .iter_mut()
+                .filter_map(|(_, shell_info, _)| shell_info.as_mut())
+                .map(|shell_info| shell_info.menu.animate(now))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct TestOutput(u32);
+
+    impl OutputHandle for TestOutput {
+        fn iced_output(&self) -> IcedOutput {
+            IcedOutput::Active
+        }
+    }
+
+    fn bars(outputs: &Outputs<TestOutput>) -> Vec<Option<&str>> {
+        let mut bars = outputs
+            .0
+            .iter()
+            .filter(|(_, shell_info, _)| shell_info.is_some())
+            .map(|(name, _, _)| name.as_deref())
+            .collect::<Vec<_>>();
+        bars.sort();
+
+        bars
+    }
+
+    fn targets(names: &[&str]) -> config::Outputs {
+        config::Outputs::Targets(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn starts_with_fallback() {
+        let (outputs, _) = Outputs::<TestOutput>::new::<()>(Position::Top);
+
+        assert_eq!(bars(&outputs), vec![None]);
+    }
+
+    #[test]
+    fn output_replaces_fallback() {
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &[],
+            Position::Top,
+            "eDP-1",
+            TestOutput(1),
+        );
+
+        assert_eq!(bars(&outputs), vec![Some("eDP-1")]);
+    }
+
+    #[test]
+    fn fallback_when_last_output_is_removed() {
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &[],
+            Position::Top,
+            "eDP-1",
+            TestOutput(1),
+        );
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &[],
+            Position::Top,
+            "DP-1",
+            TestOutput(2),
+        );
+
+        let _ = outputs.remove::<()>(Position::Top, &[], TestOutput(2));
+        assert_eq!(bars(&outputs), vec![Some("eDP-1")]);
+
+        let _ = outputs.remove::<()>(Position::Top, &[], TestOutput(1));
+        assert_eq!(bars(&outputs), vec![None]);
+        assert!(outputs.0.iter().all(|(name, _, _)| name.is_none()));
+    }
+
+    #[test]
+    fn target_unplug_and_replug() {
+        let config = targets(&["DP-1"]);
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let _ = outputs.add::<()>(&config, &[], Position::Top, "eDP-1", TestOutput(1));
+        let _ = outputs.add::<()>(&config, &[], Position::Top, "DP-1", TestOutput(2));
+        assert_eq!(bars(&outputs), vec![Some("DP-1")]);
+
+        // undocking migrates the bar to the remaining output
+        let _ = outputs.remove::<()>(Position::Top, &[], TestOutput(2));
+        assert_eq!(bars(&outputs), vec![None]);
+
+        // docking again moves it back to the target
+        let _ = outputs.add::<()>(&config, &[], Position::Top, "DP-1", TestOutput(3));
+        assert_eq!(bars(&outputs), vec![Some("DP-1")]);
+        assert_eq!(outputs.0.len(), 2);
+    }
+
+    #[test]
+    fn fallback_is_recreated_on_removal() {
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let fallback_id = outputs.0[0].1.as_ref().map(|shell_info| shell_info.id);
+        let _ = outputs.add::<()>(
+            &config::Outputs::Active,
+            &[],
+            Position::Top,
+            "eDP-1",
+            TestOutput(1),
+        );
+
+        let _ = outputs.remove::<()>(Position::Top, &[], TestOutput(1));
+
+        assert_eq!(bars(&outputs), vec![None]);
+        assert_ne!(
+            outputs.0[0].1.as_ref().map(|shell_info| shell_info.id),
+            fallback_id
+        );
+    }
+
+    #[test]
+    fn excluded_outputs_never_get_a_bar() {
+        let exclude = vec!["HDMI-A-1".to_string()];
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &exclude,
+            Position::Top,
+            "HDMI-A-1",
+            TestOutput(1),
+        );
+        // not even the fallback, it would land on the excluded output
+        assert_eq!(bars(&outputs), Vec::<Option<&str>>::new());
+
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &exclude,
+            Position::Top,
+            "eDP-1",
+            TestOutput(2),
+        );
+        assert_eq!(bars(&outputs), vec![Some("eDP-1")]);
+
+        let _ = outputs.remove::<()>(Position::Top, &exclude, TestOutput(2));
+        assert_eq!(bars(&outputs), Vec::<Option<&str>>::new());
+    }
+
+    #[test]
+    fn fallback_avoids_excluded_outputs() {
+        let exclude = vec!["HDMI-A-1".to_string()];
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        for (name, output) in [("HDMI-A-1", 1), ("eDP-1", 2)] {
+            let _ = outputs.add::<()>(
+                &config::Outputs::Active,
+                &exclude,
+                Position::Top,
+                name,
+                TestOutput(output),
+            );
+        }
+
+        assert_eq!(bars(&outputs), vec![None]);
+        assert_eq!(outputs.fallback_output(&exclude), Some(Some(TestOutput(2))));
+        assert_eq!(outputs.fallback_output(&[]), Some(None));
+    }
+
+    #[test]
+    fn sync_detaches_and_restores() {
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &[],
+            Position::Top,
+            "eDP-1",
+            TestOutput(1),
+        );
+        let _ = outputs.add::<()>(
+            &config::Outputs::All,
+            &[],
+            Position::Top,
+            "DP-1",
+            TestOutput(2),
+        );
+
+        let _ = outputs.sync::<()>(&targets(&["DP-1"]), &[], Position::Top);
+        assert_eq!(bars(&outputs), vec![Some("DP-1")]);
+
+        let _ = outputs.sync::<()>(&targets(&["HDMI-A-1"]), &[], Position::Top);
+        assert_eq!(bars(&outputs), vec![None]);
+
+        let _ = outputs.sync::<()>(&config::Outputs::All, &[], Position::Top);
+        assert_eq!(bars(&outputs), vec![Some("DP-1"), Some("eDP-1")]);
+    }
+
+    #[test]
+    fn shortcut_menus_open_on_the_last_used_bar() {
+        let (mut outputs, _) = Outputs::new::<()>(Position::Top);
+        for (name, output) in [("eDP-1", 1), ("DP-1", 2)] {
+            let _ = outputs.add::<()>(
+                &config::Outputs::All,
+                &[],
+                Position::Top,
+                name,
+                TestOutput(output),
+            );
+        }
+        let ids = |name: &str| {
+            outputs
+                .0
+                .iter()
+                .find(|(n, _, _)| n.as_deref() == Some(name))
+                .and_then(|(_, shell_info, _)| shell_info.as_ref())
+                .map(|shell_info| (shell_info.id, shell_info.menu.id))
+                .unwrap()
+        };
+        let (edp, edp_menu) = ids("eDP-1");
+        let (dp, dp_menu) = ids("DP-1");
+
+        // nothing is anchored before the size of the outputs is known
+        assert!(outputs.menu_anchor(&MenuType::Settings).is_none());
+
+        outputs.set_surface_size(edp_menu, (1920., 1080.));
+        outputs.set_surface_size(dp_menu, (2560., 1440.));
+        let (id, anchor) = outputs.menu_anchor(&MenuType::Settings).unwrap();
+        assert!(id == edp || id == dp);
+        assert_eq!(anchor.position.x, anchor.viewport.0);
+
+        let button = ButtonUIRef {
+            position: Point::new(100., 0.),
+            viewport: (2560., 1440.),
+        };
+        let _ = outputs.toggle_menu::<()>(dp, MenuType::MediaPlayer, button);
+        let (id, anchor) = outputs.menu_anchor(&MenuType::MediaPlayer).unwrap();
+        assert_eq!(id, dp);
+        assert_eq!(anchor.position, button.position);
+
+        let (id, anchor) = outputs.menu_anchor(&MenuType::Settings).unwrap();
+        assert_eq!(id, dp);
+        assert_eq!(anchor.position, Point::new(2560., 0.));
+    }
 }
@@ -1,46 +1,108 @@
 use iced::{
     alignment::Vertical,
-    widget::{button, column, horizontal_space, row, text, text_input},
+    widget::{button, column, horizontal_space, row, text, text_input, toggler},
     window::Id,
-    Alignment, Element, Length,
+    Alignment, Element, Length, Theme,
 };
 
 use crate::{
     components::icons::{icon, Icons},
-    style::{ConfirmButtonStyle, OutlineButtonStyle, TextInputStyle},
+    i18n::{tr, tr_args},
+    style::{ConfirmButtonStyle, GhostButtonStyle, OutlineButtonStyle, TextInputStyle},
 };
 
 #[derive(Debug, Clone)]
 pub enum Message {
     PasswordChanged(String),
+    ToggleReveal,
+    ToggleKeyring(bool),
     DialogConfirmed(Id),
     DialogCancelled(Id),
 }
 
-pub fn view<'a>(id: Id, wifi_ssid: &str, current_password: &str) -> Element<'a, Message> {
+#[derive(Debug, Clone, Default)]
+pub struct PasswordDialog {
+    pub ssid: String,
+    pub password: String,
+    pub reveal: bool,
+    /// The previous attempt with this network failed
+    pub wrong_password: bool,
+    pub keyring: bool,
+}
+
+impl PasswordDialog {
+    pub fn new(ssid: String, wrong_password: bool) -> Self {
+        Self {
+            ssid,
+            wrong_password,
+            ..Default::default()
+        }
+    }
+
+    pub fn update(&mut self, message: &Message) {
+        match message {
+            Message::PasswordChanged(password) => {
+                self.password = password.clone();
+                self.wrong_password = false;
+            }
+            Message::ToggleReveal => {
+                self.reveal = !self.reveal;
+            }
+            Message::ToggleKeyring(keyring) => {
+                self.keyring = *keyring;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn view<'a>(id: Id, dialog: &PasswordDialog) -> Element<'a, Message> {
     column!(
         row!(
             icon(Icons::WifiLock4).size(32),
-            text("Authentication required").size(22),
+            text(tr("authentication-required")).size(22),
         )
         .spacing(16)
         .align_y(Alignment::Center),
-        text(format!("Insert password to connect to: {}", wifi_ssid)),
-        text_input("", current_password)
-            .secure(true)
-            .size(16)
-            .padding([8, 16])
-            .style(TextInputStyle.into_style())
-            .on_input(Message::PasswordChanged)
-            .on_submit(Message::DialogConfirmed(id)),
+        text(tr_args("insert-password", &[("ssid", dialog.ssid.clone())])),
+        column!(row!(
+            text_input("", &dialog.password)
+                .secure(!dialog.reveal)
+                .size(16)
+                .padding([8, 16])
+                .style(TextInputStyle.into_style())
+                .on_input(Message::PasswordChanged)
+                .on_submit(Message::DialogConfirmed(id)),
+            button(icon(if dialog.reveal {
+                Icons::EyeClosed
+            } else {
+                Icons::EyeOpened
+            }))
+            .padding([8, 12])
+            .style(GhostButtonStyle.into_style())
+            .on_press(Message::ToggleReveal)
+        )
+        .spacing(8)
+        .align_y(Alignment::Center))
+        .push_maybe(dialog.wrong_password.then(|| {
+            text(tr("wrong-password"))
+                .size(12)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().danger),
+                })
+        }))
+        .spacing(4),
+        toggler(dialog.keyring)
+            .label(tr("remember-in-keyring"))
+            .on_toggle(Message::ToggleKeyring),
         row!(
             horizontal_space(),
-            button(text("Cancel").align_y(Vertical::Center))
+            button(text(tr("cancel")).align_y(Vertical::Center))
                 .padding([4, 32])
                 .style(OutlineButtonStyle.into_style())
                 .height(Length::Fixed(50.))
                 .on_press(Message::DialogCancelled(id)),
-            button(text("Confirm").align_y(Vertical::Center))
+            button(text(tr("confirm")).align_y(Vertical::Center))
                 .padding([4, 32])
                 .height(Length::Fixed(50.))
                 .style(ConfirmButtonStyle.into_style())
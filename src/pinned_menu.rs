@@ -0,0 +1,127 @@
+use crate::{
+    app,
+    components::icons::{icon, Icons},
+    config::Position,
+    menu::MenuType,
+    position_button::ButtonUIRef,
+    style::GhostButtonStyle,
+};
+use iced::{
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{
+        IcedMargin, IcedOutput, SctkLayerSurfaceSettings,
+    },
+    widget::{button, column, container, horizontal_space, row},
+    window::Id,
+    Border, Element, Length, Limits, Task, Theme,
+};
+
+const MARGIN: i32 = 8;
+const PADDING: f32 = 16.;
+
+/// A menu detached from the bar, kept open as a floating widget until unpinned
+#[derive(Debug, Clone)]
+pub struct PinnedMenu {
+    pub id: Id,
+    pub menu_type: MenuType,
+    width: f32,
+}
+
+impl PinnedMenu {
+    /// Opens the widget where the menu was shown, the surface follows the size of the content
+    pub fn new<Message: 'static>(
+        menu_type: MenuType,
+        button_ui_ref: ButtonUIRef,
+        position: Position,
+        width: f32,
+    ) -> (Self, Task<Message>) {
+        let id = Id::unique();
+        let outer_width = width + PADDING * 2.;
+        let left = (button_ui_ref.position.x - outer_width / 2.)
+            .min(button_ui_ref.viewport.0 - outer_width)
+            .max(0.) as i32;
+
+        (
+            Self {
+                id,
+                menu_type,
+                width,
+            },
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id,
+                size: None,
+                size_limits: Limits::NONE
+                    .min_width(1.)
+                    .min_height(1.)
+                    .max_width(outer_width),
+                layer: Layer::Top,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::None,
+                output: IcedOutput::Active,
+                anchor: match position {
+                    Position::Top => Anchor::TOP,
+                    Position::Bottom => Anchor::BOTTOM,
+                } | Anchor::LEFT,
+                margin: IcedMargin {
+                    top: MARGIN,
+                    bottom: MARGIN,
+                    left: left.max(MARGIN),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+    }
+
+    pub fn view<'a>(&self, content: Element<'a, app::Message>) -> Element<'a, app::Message> {
+        container(column!(
+            row!(
+                horizontal_space(),
+                button(icon(Icons::PinOff))
+                    .padding([4, 6])
+                    .style(GhostButtonStyle.into_style())
+                    .on_press(app::Message::UnpinMenu(self.id))
+            ),
+            content
+        ))
+        .max_width(self.width + PADDING * 2.)
+        .width(Length::Shrink)
+        .padding(PADDING)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
+
+/// Header of the pinnable menus, with the button detaching them from the bar
+pub fn pin_header<'a>(
+    menu_id: Id,
+    menu_type: &MenuType,
+    button_ui_ref: ButtonUIRef,
+    content: Element<'a, app::Message>,
+) -> Element<'a, app::Message> {
+    column!(
+        row!(
+            horizontal_space(),
+            button(icon(Icons::Pin))
+                .padding([4, 6])
+                .style(GhostButtonStyle.into_style())
+                .on_press(app::Message::PinMenu(
+                    menu_id,
+                    menu_type.clone(),
+                    button_ui_ref
+                ))
+        ),
+        content
+    )
+    .into()
+}
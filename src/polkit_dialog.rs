@@ -0,0 +1,196 @@
+use crate::{
+    components::icons::{icon, Icons},
+    i18n::{tr, tr_args},
+    services::polkit::AuthRequest,
+    style::{ConfirmButtonStyle, OutlineButtonStyle, TextInputStyle},
+};
+use iced::{
+    alignment::Vertical,
+    platform_specific::shell::commands::layer_surface::{
+        get_layer_surface, Anchor, KeyboardInteractivity, Layer,
+    },
+    runtime::platform_specific::wayland::layer_surface::{IcedOutput, SctkLayerSurfaceSettings},
+    widget::{button, column, container, horizontal_space, row, text, text_input},
+    window::Id,
+    Alignment, Border, Element, Length, Task, Theme,
+};
+use std::collections::VecDeque;
+
+const WIDTH: u32 = 420;
+const HEIGHT: u32 = 260;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PasswordChanged(String),
+    Confirm,
+    Cancel,
+}
+
+pub enum Action {
+    None,
+    Authenticate { cookie: String, password: String },
+    Cancel(String),
+}
+
+/// Authentication requests coming from the polkit agent, shown one at a time
+pub struct PolkitDialog {
+    pub id: Id,
+    requests: VecDeque<AuthRequest>,
+    password: String,
+    wrong_password: bool,
+    authenticating: bool,
+}
+
+impl PolkitDialog {
+    pub fn new(request: AuthRequest) -> (Self, Task<Message>) {
+        let id = Id::unique();
+
+        (
+            Self {
+                id,
+                requests: VecDeque::from([request]),
+                password: String::new(),
+                wrong_password: false,
+                authenticating: false,
+            },
+            get_layer_surface(SctkLayerSurfaceSettings {
+                id,
+                size: Some((Some(WIDTH), Some(HEIGHT))),
+                layer: Layer::Overlay,
+                pointer_interactivity: true,
+                keyboard_interactivity: KeyboardInteractivity::Exclusive,
+                output: IcedOutput::Active,
+                anchor: Anchor::empty(),
+                ..Default::default()
+            }),
+        )
+    }
+
+    pub fn push(&mut self, request: AuthRequest) {
+        self.requests.push_back(request);
+    }
+
+    /// The password was rejected, let the user try again
+    pub fn failed(&mut self, cookie: &str) {
+        if self.is_current(cookie) {
+            self.password.clear();
+            self.wrong_password = true;
+            self.authenticating = false;
+        }
+    }
+
+    /// Removes a completed request, returns true when there is nothing left to show
+    pub fn completed(&mut self, cookie: &str) -> bool {
+        if self.is_current(cookie) {
+            self.password.clear();
+            self.wrong_password = false;
+            self.authenticating = false;
+        }
+        self.requests.retain(|request| request.cookie != cookie);
+
+        self.requests.is_empty()
+    }
+
+    fn is_current(&self, cookie: &str) -> bool {
+        self.requests
+            .front()
+            .is_some_and(|request| request.cookie == cookie)
+    }
+
+    pub fn update(&mut self, message: Message) -> Action {
+        let Some(request) = self.requests.front() else {
+            return Action::None;
+        };
+
+        match message {
+            Message::PasswordChanged(password) => {
+                self.password = password;
+                self.wrong_password = false;
+
+                Action::None
+            }
+            Message::Confirm if !self.authenticating => {
+                self.authenticating = true;
+
+                Action::Authenticate {
+                    cookie: request.cookie.clone(),
+                    password: self.password.clone(),
+                }
+            }
+            Message::Confirm => Action::None,
+            Message::Cancel => Action::Cancel(request.cookie.clone()),
+        }
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let Some(request) = self.requests.front() else {
+            return horizontal_space().into();
+        };
+
+        let request_icon = request
+            .icon
+            .as_ref()
+            .map(|app_icon| app_icon.view(32.))
+            .unwrap_or_else(|| icon(Icons::Lock).size(32).into());
+
+        container(
+            column!(
+                row!(request_icon, text(tr("authentication-required")).size(22))
+                    .spacing(16)
+                    .align_y(Alignment::Center),
+                text(request.message.clone()),
+                column!(
+                    text(tr_args(
+                        "polkit-password",
+                        &[("user", request.user.clone())]
+                    ))
+                    .size(12),
+                    text_input("", &self.password)
+                        .secure(true)
+                        .size(16)
+                        .padding([8, 16])
+                        .style(TextInputStyle.into_style())
+                        .on_input_maybe((!self.authenticating).then_some(Message::PasswordChanged))
+                        .on_submit(Message::Confirm),
+                )
+                .push_maybe(self.wrong_password.then(|| {
+                    text(tr("wrong-password"))
+                        .size(12)
+                        .style(|theme: &Theme| text::Style {
+                            color: Some(theme.palette().danger),
+                        })
+                }))
+                .spacing(4),
+                row!(
+                    horizontal_space(),
+                    button(text(tr("cancel")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .style(OutlineButtonStyle.into_style())
+                        .height(Length::Fixed(50.))
+                        .on_press(Message::Cancel),
+                    button(text(tr("confirm")).align_y(Vertical::Center))
+                        .padding([4, 32])
+                        .height(Length::Fixed(50.))
+                        .style(ConfirmButtonStyle.into_style())
+                        .on_press_maybe((!self.authenticating).then_some(Message::Confirm))
+                )
+                .spacing(8)
+                .width(Length::Fill)
+            )
+            .spacing(16)
+            .padding(16),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .style(|theme: &Theme| container::Style {
+            background: Some(theme.palette().background.into()),
+            border: Border {
+                color: theme.extended_palette().secondary.base.color,
+                width: 1.,
+                radius: 16.0.into(),
+            },
+            ..Default::default()
+        })
+        .into()
+    }
+}
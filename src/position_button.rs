@@ -28,6 +28,7 @@ where
 {
     content: Element<'a, Message, Theme, Renderer>,
     on_press: Option<OnPress<'a, Message>>,
+    on_hover: Option<Box<dyn Fn(Option<ButtonUIRef>) -> Message + 'a>>,
     id: Id,
     width: Length,
     height: Length,
@@ -49,6 +50,7 @@ where
             content,
             id: Id::unique(),
             on_press: None,
+            on_hover: None,
             width: size.width.fluid(),
             height: size.height.fluid(),
             padding: DEFAULT_PADDING,
@@ -91,6 +93,16 @@ where
         self
     }
 
+    /// Sets the message that will be produced when the cursor enters, with the
+    /// button position, or leaves the [`Button`].
+    pub fn on_hover_with_position(
+        mut self,
+        on_hover: impl Fn(Option<ButtonUIRef>) -> Message + 'a,
+    ) -> Self {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
     /// Sets whether the contents of the [`Button`] should be clipped on
     /// overflow.
     pub fn clip(mut self, clip: bool) -> Self {
@@ -207,6 +219,24 @@ where
         }
 
         match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some(on_hover) = self.on_hover.as_ref() {
+                    let state = tree.state.downcast_mut::<State>();
+                    let is_hovered = cursor.is_over(layout.bounds());
+
+                    if state.is_hovered != is_hovered {
+                        state.is_hovered = is_hovered;
+
+                        shell.publish(on_hover(is_hovered.then(|| ButtonUIRef {
+                            position: Point::new(
+                                layout.bounds().width / 2. + layout.position().x,
+                                layout.bounds().height / 2. + layout.position().y,
+                            ),
+                            viewport: (viewport.width, viewport.height),
+                        })));
+                    }
+                }
+            }
             Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
             | Event::Touch(touch::Event::FingerPressed { .. }) => {
                 if self.on_press.is_some() {
@@ -282,6 +312,13 @@ where
             Event::Touch(touch::Event::FingerLost { .. })
             | Event::Mouse(mouse::Event::CursorLeft) => {
                 let state = tree.state.downcast_mut::<State>();
+
+                if state.is_hovered {
+                    if let Some(on_hover) = self.on_hover.as_ref() {
+                        shell.publish(on_hover(None));
+                    }
+                }
+
                 state.is_hovered = false;
                 state.is_pressed = false;
             }
@@ -0,0 +1,792 @@
+use super::{ReadOnlyService, ServiceEvent};
+use chrono::{
+    DateTime, Datelike, Days, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use iced::{
+    futures::{channel::mpsc::Sender, SinkExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+const SOURCE_INTERFACE: &str = "org.gnome.evolution.dataserver.Source";
+
+/// Where the events are read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgendaSources {
+    /// Calendars configured in Evolution Data Server, e.g. through GNOME Online Accounts
+    Eds,
+    /// vdir collections, a directory of ics files per calendar, as synced by vdirsyncer for khal
+    Vdir(Vec<PathBuf>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaQuery {
+    pub sources: AgendaSources,
+    pub interval: Duration,
+}
+
+static QUERY: Mutex<Option<AgendaQuery>> = Mutex::new(None);
+
+/// Calendars read by the service, kept up to date by the clock module
+pub fn set_query(query: Option<AgendaQuery>) {
+    if let Ok(mut current) = QUERY.lock() {
+        *current = query;
+    }
+}
+
+fn query() -> Option<AgendaQuery> {
+    QUERY.lock().ok().and_then(|query| query.clone())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaEvent {
+    pub summary: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub all_day: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AgendaData {
+    // today's events, the all day ones first, then by start time
+    pub events: Vec<AgendaEvent>,
+}
+
+impl AgendaData {
+    /// The event in progress or the next one, all day events aside
+    pub fn next(&self, now: DateTime<Local>) -> Option<&AgendaEvent> {
+        self.events
+            .iter()
+            .find(|event| !event.all_day && event.end > now)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    weekdays: Vec<Weekday>,
+    until: Option<NaiveDate>,
+    count: Option<usize>,
+}
+
+impl Recurrence {
+    /// Only the simple rules, e.g. "every other Monday", "the 3rd of every month"
+    fn parse(value: &str) -> Option<Self> {
+        let mut recurrence = Recurrence {
+            frequency: Frequency::Daily,
+            interval: 1,
+            weekdays: Vec::new(),
+            until: None,
+            count: None,
+        };
+
+        for part in value.split(';') {
+            let (key, value) = part.split_once('=')?;
+
+            match key {
+                "FREQ" => {
+                    recurrence.frequency = match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return None,
+                    }
+                }
+                "INTERVAL" => recurrence.interval = value.parse().ok()?,
+                // ordinal days, like 1MO for the first Monday, aren't parsed
+                "BYDAY" => {
+                    recurrence.weekdays =
+                        value.split(',').map(parse_weekday).collect::<Option<_>>()?;
+                }
+                "UNTIL" => recurrence.until = Some(parse_date(value)?),
+                "COUNT" => recurrence.count = Some(value.parse().ok()?),
+                "WKST" => {}
+                _ => return None,
+            }
+        }
+
+        Some(recurrence)
+    }
+
+    fn matches(&self, first: NaiveDate, date: NaiveDate) -> bool {
+        if date < first || self.until.is_some_and(|until| date > until) {
+            return false;
+        }
+
+        let interval = self.interval.max(1) as i64;
+        let weekday = if self.weekdays.is_empty() {
+            self.frequency != Frequency::Weekly || date.weekday() == first.weekday()
+        } else {
+            self.weekdays.contains(&date.weekday())
+        };
+
+        match self.frequency {
+            Frequency::Daily => weekday && (date - first).num_days() % interval == 0,
+            Frequency::Weekly => {
+                let week = |date: NaiveDate| {
+                    date - Days::new(date.weekday().num_days_from_monday() as u64)
+                };
+
+                weekday && (week(date) - week(first)).num_days() / 7 % interval == 0
+            }
+            Frequency::Monthly => {
+                let months =
+                    (date.year() - first.year()) * 12 + date.month() as i32 - first.month() as i32;
+
+                date.day() == first.day() && months as i64 % interval == 0
+            }
+            Frequency::Yearly => {
+                date.month() == first.month()
+                    && date.day() == first.day()
+                    && (date.year() - first.year()) as i64 % interval == 0
+            }
+        }
+    }
+
+    fn occurs_on(&self, first: NaiveDate, date: NaiveDate) -> bool {
+        if !self.matches(first, date) {
+            return false;
+        }
+
+        match self.count {
+            Some(count) => {
+                first
+                    .iter_days()
+                    .take_while(|day| *day <= date)
+                    .filter(|day| self.matches(first, *day))
+                    .count()
+                    <= count
+            }
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct VEvent {
+    uid: Option<String>,
+    summary: String,
+    start: DateTime<Local>,
+    duration: TimeDelta,
+    all_day: bool,
+    recurrence: Option<Recurrence>,
+    exceptions: Vec<NaiveDate>,
+    // set on the instances of a recurring event moved to another time
+    recurrence_id: Option<NaiveDate>,
+    cancelled: bool,
+}
+
+impl VEvent {
+    fn from_properties(properties: &[(&str, Option<&str>, &str)]) -> Option<Self> {
+        let property = |key: &str| {
+            properties
+                .iter()
+                .find(|(name, _, _)| *name == key)
+                .map(|(_, tzid, value)| (*tzid, *value))
+        };
+        let date_time =
+            |key: &str| property(key).and_then(|(tzid, value)| parse_date_time(value, tzid));
+
+        let (start, all_day) = date_time("DTSTART")?;
+        let duration = property("DURATION")
+            .and_then(|(_, value)| parse_duration(value))
+            .or_else(|| date_time("DTEND").map(|(end, _)| end - start))
+            .unwrap_or_else(|| {
+                if all_day {
+                    TimeDelta::days(1)
+                } else {
+                    TimeDelta::zero()
+                }
+            });
+        let recurrence = property("RRULE").and_then(|(_, value)| {
+            let recurrence = Recurrence::parse(value);
+            if recurrence.is_none() {
+                debug!("Unsupported recurrence rule: {}", value);
+            }
+
+            recurrence
+        });
+
+        Some(Self {
+            uid: property("UID").map(|(_, value)| value.to_owned()),
+            summary: property("SUMMARY")
+                .map(|(_, value)| unescape(value))
+                .unwrap_or_default(),
+            start,
+            duration,
+            all_day,
+            recurrence,
+            exceptions: properties
+                .iter()
+                .filter(|(name, _, _)| *name == "EXDATE")
+                .flat_map(|(_, tzid, value)| {
+                    value
+                        .split(',')
+                        .filter_map(move |value| parse_date_time(value, *tzid))
+                })
+                .map(|(date, _)| date.date_naive())
+                .collect(),
+            recurrence_id: date_time("RECURRENCE-ID").map(|(date, _)| date.date_naive()),
+            cancelled: property("STATUS").is_some_and(|(_, value)| value == "CANCELLED"),
+        })
+    }
+
+    /// Start of the occurrence overlapping the day
+    fn occurrence_on(
+        &self,
+        date: NaiveDate,
+        overridden: &[(&str, NaiveDate)],
+    ) -> Option<DateTime<Local>> {
+        let day_start = local_midnight(date)?;
+        let day_end = local_midnight(date.succ_opt()?)?;
+        let first = self.start.date_naive();
+
+        // multi-day occurrences started on the previous days are still shown
+        let span = self.duration.num_days().max(0) as u64;
+        date.checked_sub_days(Days::new(span))?
+            .iter_days()
+            .take_while(|day| *day <= date)
+            .filter(|day| match &self.recurrence {
+                Some(recurrence) => {
+                    recurrence.occurs_on(first, *day)
+                        && !self.exceptions.contains(day)
+                        && !overridden.iter().any(|(uid, overridden)| {
+                            self.uid.as_deref() == Some(*uid) && overridden == day
+                        })
+                }
+                None => *day == first,
+            })
+            .filter_map(|day| {
+                Local
+                    .from_local_datetime(&day.and_time(self.start.time()))
+                    .earliest()
+            })
+            .find(|start| {
+                *start < day_end && (*start + self.duration > day_start || *start >= day_start)
+            })
+    }
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(0..8)?, "%Y%m%d").ok()
+}
+
+/// EDS prefixes the time zones, e.g. "/freeassociation.sourceforge.net/Europe/Rome"
+fn parse_tz(tzid: &str) -> Option<Tz> {
+    let tzid = tzid.trim_matches('"');
+
+    tzid.parse().ok().or_else(|| {
+        tzid.match_indices('/')
+            .find_map(|(index, _)| tzid[index + 1..].parse().ok())
+    })
+}
+
+/// Returns the start and whether it's a whole day
+fn parse_date_time(value: &str, tzid: Option<&str>) -> Option<(DateTime<Local>, bool)> {
+    if value.len() == 8 {
+        return Some((local_midnight(parse_date(value)?)?, true));
+    }
+
+    let date_time =
+        NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S").ok()?;
+    let date_time = if value.ends_with('Z') {
+        Utc.from_utc_datetime(&date_time).with_timezone(&Local)
+    } else if let Some(tz) = tzid.and_then(parse_tz) {
+        tz.from_local_datetime(&date_time)
+            .earliest()?
+            .with_timezone(&Local)
+    } else {
+        // floating time
+        Local.from_local_datetime(&date_time).earliest()?
+    };
+
+    Some((date_time, false))
+}
+
+/// Parse durations like "PT1H30M" or "P1D"
+fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let (sign, value) = match value.strip_prefix('-') {
+        Some(value) => (-1, value),
+        None => (1, value.trim_start_matches('+')),
+    };
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in value.strip_prefix('P')?.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            _ => {
+                let unit = match c {
+                    'W' => 604800,
+                    'D' => 86400,
+                    'H' => 3600,
+                    'M' => 60,
+                    'S' => 1,
+                    _ => return None,
+                };
+                seconds += number.parse::<i64>().ok()? * unit;
+                number.clear();
+            }
+        }
+    }
+
+    TimeDelta::try_seconds(sign * seconds)
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+fn parse_ics(content: &str) -> Vec<VEvent> {
+    // unfold the lines continued with a leading space
+    let unfolded = content.replace("\r\n ", "").replace("\n ", "");
+
+    let mut events = Vec::new();
+    let mut properties = None;
+    // alarms nested in the events have their own summaries
+    let mut nested = 0_usize;
+
+    for line in unfolded.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // the parameters are dropped, except the time zone
+        let mut params = name.split(';');
+        let name = params.next().unwrap_or_default();
+        let tzid = params.find_map(|param| param.strip_prefix("TZID="));
+
+        match (name, value) {
+            ("BEGIN", "VEVENT") => {
+                properties = Some(Vec::new());
+                nested = 0;
+            }
+            ("END", "VEVENT") => events.extend(
+                properties
+                    .take()
+                    .and_then(|properties| VEvent::from_properties(&properties)),
+            ),
+            _ => {
+                if let Some(properties) = properties.as_mut() {
+                    match name {
+                        "BEGIN" => nested += 1,
+                        "END" => nested = nested.saturating_sub(1),
+                        _ if nested == 0 => properties.push((name, tzid, value)),
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    events
+}
+
+fn events_on(events: &[VEvent], date: NaiveDate) -> Vec<AgendaEvent> {
+    let overridden = events
+        .iter()
+        .filter_map(|event| Some((event.uid.as_deref()?, event.recurrence_id?)))
+        .collect::<Vec<_>>();
+
+    let mut agenda = events
+        .iter()
+        .filter(|event| !event.cancelled)
+        .filter_map(|event| {
+            let start = event.occurrence_on(date, &overridden)?;
+
+            Some(AgendaEvent {
+                summary: event.summary.clone(),
+                start,
+                end: start + event.duration,
+                all_day: event.all_day,
+            })
+        })
+        .collect::<Vec<_>>();
+    agenda.sort_by_key(|event| (!event.all_day, event.start));
+
+    agenda
+}
+
+#[derive(Debug, Clone)]
+pub struct AgendaService {
+    data: AgendaData,
+}
+
+impl Deref for AgendaService {
+    type Target = AgendaData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    // last query read, when and for which day
+    Active(Option<(AgendaQuery, Instant, NaiveDate)>),
+}
+
+impl AgendaService {
+    async fn read_vdir(paths: &[PathBuf]) -> Vec<VEvent> {
+        let mut events = Vec::new();
+
+        // the collections or a directory containing them
+        let mut dirs = paths.to_vec();
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(err) => {
+                    warn!("Failed to read the calendar {:?}: {}", dir, err);
+                    continue;
+                }
+            };
+
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+
+                if path.extension().is_some_and(|extension| extension == "ics") {
+                    match tokio::fs::read_to_string(&path).await {
+                        Ok(content) => events.extend(parse_ics(&content)),
+                        Err(err) => debug!("Failed to read the event {:?}: {}", path, err),
+                    }
+                } else if paths.contains(&dir) && path.is_dir() && !is_hidden(&path) {
+                    dirs.push(path);
+                }
+            }
+        }
+
+        events
+    }
+
+    async fn read_eds(date: NaiveDate) -> zbus::Result<Vec<VEvent>> {
+        let conn = zbus::Connection::session().await?;
+
+        let sources = EdsSourcesProxy::new(&conn)
+            .await?
+            .get_managed_objects()
+            .await?
+            .into_values()
+            .filter_map(|mut interfaces| {
+                let source = interfaces.remove(SOURCE_INTERFACE)?;
+                let uid = source.get("UID")?.downcast_ref::<&str>().ok()?.to_owned();
+                let data = source.get("Data")?.downcast_ref::<&str>().ok()?;
+
+                is_enabled_calendar(data).then_some(uid)
+            })
+            .collect::<Vec<_>>();
+
+        let factory = EdsCalendarFactoryProxy::new(&conn).await?;
+        // a day more on each side as the range is in UTC, the recurring events
+        // are returned once and their occurrences are computed later
+        let sexp = format!(
+            "(occur-in-time-range? (make-time \"{}\") (make-time \"{}\"))",
+            date.pred_opt().unwrap_or(date).format("%Y%m%dT000000Z"),
+            date.succ_opt().unwrap_or(date).format("%Y%m%dT235959Z"),
+        );
+
+        let mut events = Vec::new();
+        for uid in sources {
+            let (path, bus_name) = match factory.open_calendar(&uid).await {
+                Ok(calendar) => calendar,
+                Err(err) => {
+                    debug!("Failed to open the calendar {}: {}", uid, err);
+                    continue;
+                }
+            };
+            let calendar = EdsCalendarProxy::builder(&conn)
+                .destination(bus_name)?
+                .path(path)?
+                .build()
+                .await?;
+
+            let objects = match calendar.open().await {
+                Ok(_) => calendar.get_object_list(&sexp).await,
+                Err(err) => Err(err),
+            };
+            match objects {
+                Ok(objects) => {
+                    for object in objects {
+                        events.extend(parse_ics(&object));
+                    }
+                }
+                Err(err) => debug!("Failed to read the calendar {}: {}", uid, err),
+            }
+            let _ = calendar.close().await;
+        }
+
+        Ok(events)
+    }
+
+    async fn read(sources: &AgendaSources, date: NaiveDate) -> Vec<AgendaEvent> {
+        let events = match sources {
+            AgendaSources::Eds => Self::read_eds(date).await.unwrap_or_else(|err| {
+                warn!("Failed to read the Evolution calendars: {}", err);
+                Vec::new()
+            }),
+            AgendaSources::Vdir(paths) => Self::read_vdir(paths).await,
+        };
+
+        events_on(&events, date)
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => {
+                info!("Agenda service initialized");
+
+                let _ = output
+                    .send(ServiceEvent::Init(AgendaService {
+                        data: AgendaData::default(),
+                    }))
+                    .await;
+
+                State::Active(None)
+            }
+            State::Active(last) => {
+                let Some(query) = query() else {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    return State::Active(last);
+                };
+                let today = Local::now().date_naive();
+
+                let due = last.as_ref().map_or(true, |(last_query, read, date)| {
+                    *last_query != query || *date != today || read.elapsed() >= query.interval
+                });
+                if !due {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    return State::Active(last);
+                }
+
+                debug!("Reading the events of {} from {:?}", today, query.sources);
+                let events = AgendaService::read(&query.sources, today).await;
+                let _ = output
+                    .send(ServiceEvent::Update(AgendaData { events }))
+                    .await;
+
+                State::Active(Some((query, Instant::now(), today)))
+            }
+        }
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// The sources are key files, only the enabled calendars are read
+fn is_enabled_calendar(data: &str) -> bool {
+    let mut group = "";
+    let mut calendar = false;
+    let mut enabled = true;
+
+    for line in data.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            group = name;
+            calendar |= group == "Calendar";
+        } else if group == "Data Source" && line.replace(' ', "") == "Enabled=false" {
+            enabled = false;
+        }
+    }
+
+    calendar && enabled
+}
+
+impl ReadOnlyService for AgendaService {
+    type UpdateEvent = AgendaData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = AgendaService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.gnome.evolution.dataserver.Sources5",
+    default_path = "/org/gnome/evolution/dataserver/SourceManager",
+    interface = "org.freedesktop.DBus.ObjectManager"
+)]
+trait EdsSources {
+    fn get_managed_objects(&self) -> zbus::Result<ManagedObjects>;
+}
+
+#[proxy(
+    default_service = "org.gnome.evolution.dataserver.Calendar8",
+    default_path = "/org/gnome/evolution/dataserver/CalendarFactory",
+    interface = "org.gnome.evolution.dataserver.CalendarFactory"
+)]
+trait EdsCalendarFactory {
+    // object path and bus name of the calendar
+    fn open_calendar(&self, source_uid: &str) -> zbus::Result<(String, String)>;
+}
+
+#[proxy(interface = "org.gnome.evolution.dataserver.Calendar")]
+trait EdsCalendar {
+    fn open(&self) -> zbus::Result<Vec<String>>;
+
+    fn get_object_list(&self, query: &str) -> zbus::Result<Vec<String>>;
+
+    fn close(&self) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn todays_events() {
+        let events = parse_ics(
+            "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\n\
+            UID:standup\n\
+            SUMMARY:Standup\n\
+            DTSTART:20250602T093000\n\
+            DURATION:PT15M\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR\n\
+            EXDATE:20250604T093000\n\
+            BEGIN:VALARM\n\
+            SUMMARY:Reminder\n\
+            END:VALARM\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            UID:standup\n\
+            RECURRENCE-ID:20250606T093000\n\
+            SUMMARY:Late standup\n\
+            DTSTART:20250606T113000\n\
+            DTEND:20250606T114500\n\
+            END:VEVENT\n\
+            BEGIN:VEVENT\n\
+            SUMMARY:Holiday\n\
+            DTSTART;VALUE=DATE:20250606\n\
+            END:VEVENT\n\
+            END:VCALENDAR\n",
+        );
+        assert_eq!(events.len(), 3);
+
+        let monday = events_on(&events, date("20250609"));
+        assert_eq!(monday.len(), 1);
+        assert_eq!(monday[0].summary, "Standup");
+        assert_eq!(monday[0].end - monday[0].start, TimeDelta::minutes(15));
+
+        assert!(events_on(&events, date("20250604")).is_empty());
+        assert!(events_on(&events, date("20250605")).is_empty());
+
+        let friday = events_on(&events, date("20250606"))
+            .into_iter()
+            .map(|event| event.summary)
+            .collect::<Vec<_>>();
+        assert_eq!(friday, vec!["Holiday", "Late standup"]);
+    }
+
+    #[test]
+    fn recurrence_rules() {
+        let first = date("20250602");
+
+        let biweekly = Recurrence::parse("FREQ=WEEKLY;INTERVAL=2").unwrap();
+        assert!(!biweekly.occurs_on(first, date("20250609")));
+        assert!(biweekly.occurs_on(first, date("20250616")));
+
+        let limited = Recurrence::parse("FREQ=DAILY;COUNT=3").unwrap();
+        assert!(limited.occurs_on(first, date("20250604")));
+        assert!(!limited.occurs_on(first, date("20250605")));
+
+        let monthly = Recurrence::parse("FREQ=MONTHLY;UNTIL=20250901T000000Z").unwrap();
+        assert!(monthly.occurs_on(first, date("20250802")));
+        assert!(!monthly.occurs_on(first, date("20251002")));
+
+        assert!(Recurrence::parse("FREQ=MONTHLY;BYDAY=1MO").is_none());
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("PT1H30M"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("P1D"), Some(TimeDelta::days(1)));
+        assert_eq!(parse_duration("-PT15M"), Some(TimeDelta::minutes(-15)));
+        assert_eq!(parse_duration("1H"), None);
+    }
+
+    #[test]
+    fn eds_time_zones() {
+        assert_eq!(
+            parse_tz("/freeassociation.sourceforge.net/Europe/Rome"),
+            Some(chrono_tz::Europe::Rome)
+        );
+        assert_eq!(
+            parse_tz("\"America/New_York\""),
+            Some(chrono_tz::America::New_York)
+        );
+        assert_eq!(parse_tz("W. Europe Standard Time"), None);
+    }
+}
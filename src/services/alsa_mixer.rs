@@ -0,0 +1,72 @@
+use alsa::{
+    card,
+    mixer::{Mixer, SelemChannelId, SelemId},
+    poll::{poll, Descriptors},
+};
+use log::{debug, warn};
+use std::thread::{self, JoinHandle};
+
+/// Mixer of the first sound card with a capture switch
+fn capture_mixer() -> Option<(i32, Mixer)> {
+    card::Iter::new().filter_map(Result::ok).find_map(|card| {
+        let index = card.get_index();
+        let mixer = Mixer::new(&format!("hw:{}", index), false).ok()?;
+
+        let has_capture_switch = mixer
+            .find_selem(&SelemId::new("Capture", 0))
+            .is_some_and(|selem| selem.has_capture_switch());
+
+        has_capture_switch.then_some((index, mixer))
+    })
+}
+
+fn capture_muted(mixer: &Mixer) -> Option<bool> {
+    mixer
+        .find_selem(&SelemId::new("Capture", 0))
+        .and_then(|selem| selem.get_capture_switch(SelemChannelId::mono()).ok())
+        .map(|switch| switch == 0)
+}
+
+/// Watch the ALSA capture switch, some laptops toggle it directly
+/// from the mic mute key without going through the sound server.
+///
+/// Calls `on_change` with the card index and the mute state every time
+/// it changes, the monitor stops when it returns false.
+pub fn monitor_capture_switch(
+    on_change: impl Fn(i32, bool) -> bool + Send + 'static,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let Some((card, mixer)) = capture_mixer() else {
+            debug!("No sound card with a capture switch");
+            return;
+        };
+        debug!("Monitoring the capture switch of card {}", card);
+
+        let mut muted = capture_muted(&mixer);
+        if let Some(muted) = muted {
+            on_change(card, muted);
+        }
+
+        loop {
+            let res = Descriptors::get(&mixer)
+                .and_then(|mut fds| poll(&mut fds, -1))
+                .and_then(|_| mixer.handle_events());
+
+            if let Err(err) = res {
+                warn!("Failed to read the card {} mixer events: {}", card, err);
+                break;
+            }
+
+            let current = capture_muted(&mixer);
+            if current != muted {
+                muted = current;
+
+                if let Some(muted) = muted {
+                    if !on_change(card, muted) {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
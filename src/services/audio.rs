@@ -1,4 +1,7 @@
-use super::{ReadOnlyService, Service, ServiceEvent};
+use super::{
+    alsa_mixer::monitor_capture_switch, brightness::BrightnessCtrlProxy, ReadOnlyService, Service,
+    ServiceEvent,
+};
 use crate::components::icons::Icons;
 use iced::{
     futures::{channel::mpsc::Sender, executor::block_on, stream::pending, SinkExt, StreamExt},
@@ -19,10 +22,11 @@ use libpulse_binding::{
     proplist::{properties::APPLICATION_NAME, Proplist},
     volume::ChannelVolumes,
 };
-use log::{debug, error, trace};
+use log::{debug, error, trace, warn};
 use std::{
     any::TypeId,
     cell::RefCell,
+    fs,
     ops::{Deref, DerefMut},
     rc::Rc,
     thread::{self, JoinHandle},
@@ -37,6 +41,7 @@ pub struct Device {
     pub is_mute: bool,
     pub in_use: bool,
     pub ports: Vec<Port>,
+    pub alsa_card: Option<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,7 +52,7 @@ pub struct Port {
     pub active: bool,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DeviceType {
     Headphones,
     Speaker,
@@ -128,6 +133,11 @@ pub struct AudioData {
     pub sources: Vec<Device>,
     pub cur_sink_volume: i32,
     pub cur_source_volume: i32,
+    /// Sound card index and state of its capture switch, when it has one
+    pub hardware_mic_mute: Option<(i32, bool)>,
+    /// False until the first sink list is received, the devices found
+    /// at startup are not new devices
+    pub sinks_loaded: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -153,6 +163,9 @@ impl DerefMut for AudioService {
 struct PulseAudioServerHandle {
     _listener: JoinHandle<()>,
     _commander: JoinHandle<()>,
+    _mixer: JoinHandle<()>,
+    default_source: String,
+    mic_mute_led: Option<bool>,
     receiver: UnboundedReceiver<PulseAudioServerEvent>,
     sender: UnboundedSender<PulseAudioCommand>,
 }
@@ -174,6 +187,8 @@ impl AudioService {
                                 sources: Vec::new(),
                                 cur_sink_volume: 0,
                                 cur_source_volume: 0,
+                                hardware_mic_mute: None,
+                                sinks_loaded: false,
                             },
                             commander: handle.sender.clone(),
                         }))
@@ -198,6 +213,17 @@ impl AudioService {
                     State::Active(handle)
                 }
                 Some(PulseAudioServerEvent::Sources(sources)) => {
+                    let muted = sources
+                        .iter()
+                        .find(|source| source.name == handle.default_source)
+                        .map(|source| source.is_mute);
+                    if muted.is_some() && muted != handle.mic_mute_led {
+                        handle.mic_mute_led = muted;
+                        if let Err(err) = set_mic_mute_led(muted.unwrap_or_default()).await {
+                            warn!("Failed to set the mic mute led: {}", err);
+                        }
+                    }
+
                     let _ = output
                         .send(ServiceEvent::Update(AudioEvent::Sources(sources)))
                         .await;
@@ -205,12 +231,21 @@ impl AudioService {
                     State::Active(handle)
                 }
                 Some(PulseAudioServerEvent::ServerInfo(info)) => {
+                    handle.default_source = info.default_source.clone();
+
                     let _ = output
                         .send(ServiceEvent::Update(AudioEvent::ServerInfo(info)))
                         .await;
 
                     State::Active(handle)
                 }
+                Some(PulseAudioServerEvent::CaptureSwitch(card, muted)) => {
+                    let _ = output
+                        .send(ServiceEvent::Update(AudioEvent::CaptureSwitch(card, muted)))
+                        .await;
+
+                    State::Active(handle)
+                }
                 None => State::Active(handle),
             },
             State::Error => {
@@ -228,6 +263,28 @@ pub enum AudioEvent {
     Sinks(Vec<Device>),
     Sources(Vec<Device>),
     ServerInfo(ServerInfo),
+    CaptureSwitch(i32, bool),
+}
+
+const LEDS_FOLDER: &str = "/sys/class/leds";
+
+/// Keep the mic mute led in sync on devices where the kernel
+/// doesn't bind it to the sound card
+async fn set_mic_mute_led(muted: bool) -> anyhow::Result<()> {
+    let led = fs::read_dir(LEDS_FOLDER)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .find(|name| name.ends_with("::micmute"));
+
+    if let Some(led) = led {
+        let conn = zbus::Connection::system().await?;
+        BrightnessCtrlProxy::new(&conn)
+            .await?
+            .set_brightness("leds", &led, muted as u32)
+            .await?;
+    }
+
+    Ok(())
 }
 
 enum State {
@@ -244,6 +301,7 @@ impl ReadOnlyService for AudioService {
         match event {
             AudioEvent::Sinks(sinks) => {
                 self.data.sinks = sinks;
+                self.data.sinks_loaded = true;
                 self.data.cur_sink_volume = (self
                     .sinks
                     .iter()
@@ -331,6 +389,22 @@ impl ReadOnlyService for AudioService {
                     .unwrap_or_default()
                     * 100.) as i32;
             }
+            AudioEvent::CaptureSwitch(card, muted) => {
+                self.data.hardware_mic_mute = Some((card, muted));
+
+                // the mic mute key toggled the card directly, follow it
+                if let Some(source) = self
+                    .data
+                    .sources
+                    .iter()
+                    .find(|source| source.name == self.data.server_info.default_source)
+                    .filter(|source| source.alsa_card == Some(card) && source.is_mute != muted)
+                {
+                    let _ = self
+                        .commander
+                        .send(PulseAudioCommand::SourceMute(source.name.clone(), muted));
+                }
+            }
         }
     }
 
@@ -355,8 +429,22 @@ pub enum AudioCommand {
     ToggleSourceMute,
     SinkVolume(i32),
     SourceVolume(i32),
+    /// Change the volume by a step without going over a maximum
+    SinkVolumeStep(i32, i32),
+    SourceVolumeStep(i32, i32),
     DefaultSink(String, String),
     DefaultSource(String, String),
+    /// Volume of a sink by name, it doesn't need to be the default one
+    DeviceSinkVolume(String, i32),
+}
+
+fn step_volume(current: i32, step: i32, max: i32) -> i32 {
+    if step > 0 {
+        // a volume already over the maximum is never raised, nor lowered to the maximum
+        (current + step).min(max.max(current))
+    } else {
+        (current + step).max(0)
+    }
 }
 
 impl Service for AudioService {
@@ -419,6 +507,16 @@ impl Service for AudioService {
                     }
                 }
             }
+            AudioCommand::SinkVolumeStep(step, max) => {
+                let volume = step_volume(self.data.cur_sink_volume, step, max);
+
+                return self.command(AudioCommand::SinkVolume(volume));
+            }
+            AudioCommand::SourceVolumeStep(step, max) => {
+                let volume = step_volume(self.data.cur_source_volume, step, max);
+
+                return self.command(AudioCommand::SourceVolume(volume));
+            }
             AudioCommand::DefaultSink(name, port) => {
                 let _ = self
                     .commander
@@ -429,6 +527,15 @@ impl Service for AudioService {
                     .commander
                     .send(PulseAudioCommand::DefaultSource(name, port));
             }
+            AudioCommand::DeviceSinkVolume(name, volume) => {
+                if let Some(sink) = self.data.sinks.iter_mut().find(|sink| sink.name == name) {
+                    if let Some(volume) = sink.volume.scale_volume(volume as f64 / 100.) {
+                        let _ = self
+                            .commander
+                            .send(PulseAudioCommand::SinkVolume(name, *volume));
+                    }
+                }
+            }
         }
 
         iced::Task::none()
@@ -437,6 +544,7 @@ impl Service for AudioService {
 
 enum PulseAudioServerEvent {
     Error,
+    CaptureSwitch(i32, bool),
     Sinks(Vec<Device>),
     Sources(Vec<Device>),
     ServerInfo(ServerInfo),
@@ -507,10 +615,18 @@ impl PulseAudioServer {
 
         let listener = Self::start_listener(from_server_tx.clone()).await?;
         let commander = Self::start_commander(from_server_tx.clone(), to_server_rx).await?;
+        let mixer = monitor_capture_switch(move |card, muted| {
+            from_server_tx
+                .send(PulseAudioServerEvent::CaptureSwitch(card, muted))
+                .is_ok()
+        });
 
         Ok(PulseAudioServerHandle {
             _listener: listener,
             _commander: commander,
+            _mixer: mixer,
+            default_source: String::new(),
+            mic_mute_led: None,
             receiver: from_server_rx,
             sender: to_server_tx,
         })
@@ -843,6 +959,10 @@ impl From<&SinkInfo<'_>> for Device {
             volume: value.volume,
             is_mute: value.mute,
             in_use: value.state == SinkState::Running,
+            alsa_card: value
+                .proplist
+                .get_str("alsa.card")
+                .and_then(|card| card.parse().ok()),
             ports: value
                 .ports
                 .iter()
@@ -887,6 +1007,10 @@ impl From<&SourceInfo<'_>> for Device {
             volume: value.volume,
             is_mute: value.mute,
             in_use: value.state == SourceState::Running,
+            alsa_card: value
+                .proplist
+                .get_str("alsa.card")
+                .and_then(|card| card.parse().ok()),
             ports: value
                 .ports
                 .iter()
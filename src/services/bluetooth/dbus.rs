@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
+use log::debug;
 use zbus::{
     proxy,
     zvariant::{OwnedObjectPath, OwnedValue},
 };
 
 use super::{BluetoothDevice, BluetoothState};
+use crate::services::upower::dbus::{DeviceProxy as UPowerDeviceProxy, UPowerProxy};
 
 type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
 
@@ -59,6 +61,27 @@ impl BluetoothDbus<'_> {
         }
     }
 
+    pub async fn known_devices(&self) -> anyhow::Result<Vec<OwnedObjectPath>> {
+        Ok(self
+            .bluez
+            .get_managed_objects()
+            .await?
+            .into_iter()
+            .filter_map(|(key, item)| item.contains_key("org.bluez.Device1").then_some(key))
+            .collect())
+    }
+
+    pub async fn connect(&self, device_path: OwnedObjectPath) -> anyhow::Result<()> {
+        let device = DeviceProxy::builder(self.bluez.inner().connection())
+            .path(device_path)?
+            .build()
+            .await?;
+
+        device.connect().await?;
+
+        Ok(())
+    }
+
     pub async fn devices(&self) -> anyhow::Result<Vec<BluetoothDevice>> {
         let devices_proxy = self
             .bluez
@@ -74,6 +97,7 @@ impl BluetoothDbus<'_> {
             })
             .collect::<Vec<_>>();
 
+        let mut upower = None;
         let mut devices = Vec::new();
         for device_path in devices_proxy {
             let device = DeviceProxy::builder(self.bluez.inner().connection())
@@ -85,15 +109,29 @@ impl BluetoothDbus<'_> {
             let connected = device.connected().await?;
 
             if connected {
-                let battery = BatteryProxy::builder(self.bluez.inner().connection())
-                    .path(&device_path)?
-                    .build()
-                    .await?;
-                let battery = battery.percentage().await?;
+                let battery = match self.bluez_battery(&device_path).await {
+                    Some(battery) => Some(battery),
+                    None => {
+                        // the UPower devices are listed once per refresh, and only
+                        // when a device doesn't report its charge through bluez
+                        if upower.is_none() {
+                            upower = Some(
+                                upower_devices(self.bluez.inner().connection())
+                                    .await
+                                    .inspect_err(|err| {
+                                        debug!("Failed to query UPower bluetooth devices: {}", err)
+                                    })
+                                    .unwrap_or_default(),
+                            );
+                        }
+
+                        upower_battery(upower.as_ref(), &device_path).await
+                    }
+                };
 
                 devices.push(BluetoothDevice {
                     name,
-                    battery: Some(battery),
+                    battery,
                     path: device_path,
                 });
             }
@@ -101,6 +139,47 @@ impl BluetoothDbus<'_> {
 
         Ok(devices)
     }
+
+    async fn bluez_battery(&self, device_path: &OwnedObjectPath) -> Option<u8> {
+        let battery = BatteryProxy::builder(self.bluez.inner().connection())
+            .path(device_path)
+            .ok()?
+            .build()
+            .await
+            .ok()?;
+
+        battery.percentage().await.ok()
+    }
+}
+
+/// Some devices only expose their charge through UPower, which tracks
+/// bluetooth devices using the bluez object path as native path
+async fn upower_battery(
+    upower: Option<&HashMap<String, UPowerDeviceProxy<'static>>>,
+    device_path: &OwnedObjectPath,
+) -> Option<u8> {
+    upower?
+        .get(device_path.as_str())?
+        .percentage()
+        .await
+        .ok()
+        .map(|percentage| percentage.round() as u8)
+}
+
+/// UPower devices by native path
+pub async fn upower_devices(
+    conn: &zbus::Connection,
+) -> anyhow::Result<HashMap<String, UPowerDeviceProxy<'static>>> {
+    let upower = UPowerProxy::new(conn).await?;
+
+    let mut devices = HashMap::new();
+    for path in upower.enumerate_devices().await? {
+        let device = UPowerDeviceProxy::builder(conn).path(path)?.build().await?;
+
+        devices.insert(device.native_path().await?, device);
+    }
+
+    Ok(devices)
 }
 
 #[proxy(
@@ -133,6 +212,8 @@ pub trait Adapter {
 
 #[proxy(default_service = "org.bluez", interface = "org.bluez.Device1")]
 trait Device {
+    fn connect(&self) -> zbus::Result<()>;
+
     #[zbus(property)]
     fn name(&self) -> zbus::Result<String>;
 
@@ -1,5 +1,5 @@
 use super::{ReadOnlyService, Service, ServiceEvent};
-use dbus::{BatteryProxy, BluetoothDbus};
+use dbus::{upower_devices, BatteryProxy, BluetoothDbus};
 use iced::{
     futures::{
         channel::mpsc::Sender,
@@ -35,6 +35,7 @@ pub struct BluetoothDevice {
 pub struct BluetoothData {
     pub state: BluetoothState,
     pub devices: Vec<BluetoothDevice>,
+    pub known_devices: Vec<OwnedObjectPath>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +55,7 @@ impl Deref for BluetoothService {
 #[derive(Debug, Clone)]
 pub enum BluetoothCommand {
     Toggle,
+    Connect(OwnedObjectPath),
 }
 
 enum State {
@@ -75,8 +77,13 @@ impl BluetoothService {
             state => state,
         };
         let devices = bluetooth.devices().await?;
+        let known_devices = bluetooth.known_devices().await?;
 
-        Ok(BluetoothData { state, devices })
+        Ok(BluetoothData {
+            state,
+            devices,
+            known_devices,
+        })
     }
 
     async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()>> {
@@ -101,13 +108,31 @@ impl BluetoothService {
             let rfkill = BluetoothService::listen_rfkill_soft_block_changes().await?;
             let devices = bluetooth.devices().await?;
 
+            let upower = upower_devices(conn).await.unwrap_or_default();
+
             let mut batteries = Vec::with_capacity(devices.len());
             for device in devices {
+                if let Some(upower_device) = upower.get(device.path.as_str()) {
+                    batteries.push(
+                        upower_device
+                            .receive_percentage_changed()
+                            .await
+                            .map(|_| {})
+                            .boxed(),
+                    );
+                }
+
                 let battery = BatteryProxy::builder(bluetooth.bluez.inner().connection())
                     .path(device.path)?
                     .build()
                     .await?;
-                batteries.push(battery.receive_percentage_changed().await.map(|_| {}));
+                batteries.push(
+                    battery
+                        .receive_percentage_changed()
+                        .await
+                        .map(|_| {})
+                        .boxed(),
+                );
             }
 
             stream_select!(interface_changed, powered, rfkill, select_all(batteries)).boxed()
@@ -206,6 +231,14 @@ impl BluetoothService {
 
         Ok(())
     }
+
+    async fn connect(conn: &zbus::Connection, device: OwnedObjectPath) -> anyhow::Result<()> {
+        let bluetooth = BluetoothDbus::new(conn).await?;
+
+        bluetooth.connect(device).await?;
+
+        Ok(())
+    }
 }
 
 impl ReadOnlyService for BluetoothService {
@@ -265,6 +298,24 @@ impl Service for BluetoothService {
                     )
                 }
             }
+            BluetoothCommand::Connect(device) => {
+                let conn = self.conn.clone();
+                let data = self.data.clone();
+
+                Task::perform(
+                    async move {
+                        debug!("Connecting bluetooth device: {}", device.as_str());
+                        if let Err(err) = BluetoothService::connect(&conn, device).await {
+                            error!("Failed to connect bluetooth device: {}", err);
+                        }
+
+                        BluetoothService::initialize_data(&conn)
+                            .await
+                            .unwrap_or(data)
+                    },
+                    ServiceEvent::Update,
+                )
+            }
         }
     }
 }
@@ -258,6 +258,6 @@ impl Service for BrightnessService {
     default_path = "/org/freedesktop/login1/session/auto",
     interface = "org.freedesktop.login1.Session"
 )]
-trait BrightnessCtrl {
+pub trait BrightnessCtrl {
     fn set_brightness(&self, subsystem: &str, name: &str, value: u32) -> zbus::Result<()>;
 }
@@ -0,0 +1,327 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, stream_select, SinkExt, Stream, StreamExt},
+    stream::channel,
+    Subscription, Task,
+};
+use log::{debug, error, info, warn};
+use std::{any::TypeId, ops::Deref, path::Path};
+use tokio::process::Command;
+use zbus::proxy;
+
+const UFW_CONF: &str = "/etc/ufw/ufw.conf";
+const FIREWALLD_CONF: &str = "/etc/firewalld";
+const FIREWALLD_NAME: &str = "org.fedoraproject.FirewallD1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirewallBackend {
+    Firewalld,
+    Ufw,
+}
+
+#[derive(Debug, Clone)]
+pub struct FirewallData {
+    pub backend: FirewallBackend,
+    pub active: bool,
+    // firewalld only, ufw has no zones
+    pub zones: Vec<String>,
+    pub default_zone: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FirewallService {
+    data: FirewallData,
+    conn: Option<zbus::Connection>,
+}
+
+impl Deref for FirewallService {
+    type Target = FirewallData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(FirewallBackend, Option<zbus::Connection>),
+    Error,
+}
+
+impl FirewallService {
+    async fn firewalld_data(conn: &zbus::Connection) -> FirewallData {
+        let data = async {
+            // any method call would start firewalld again through the bus activation
+            let running = zbus::fdo::DBusProxy::new(conn)
+                .await?
+                .name_has_owner(FIREWALLD_NAME.try_into()?)
+                .await?;
+            if !running {
+                return Err(zbus::Error::Failure("firewalld is stopped".to_owned()));
+            }
+
+            let firewalld = FirewallD1Proxy::new(conn).await?;
+            let zones = FirewallD1ZoneProxy::new(conn).await?;
+
+            zbus::Result::Ok((
+                zones.get_zones().await?,
+                firewalld.get_default_zone().await?,
+            ))
+        }
+        .await;
+
+        match data {
+            Ok((zones, default_zone)) => FirewallData {
+                backend: FirewallBackend::Firewalld,
+                active: true,
+                zones,
+                default_zone: Some(default_zone),
+            },
+            Err(err) => {
+                debug!("firewalld not available: {}", err);
+
+                FirewallData {
+                    backend: FirewallBackend::Firewalld,
+                    active: false,
+                    zones: Vec::new(),
+                    default_zone: None,
+                }
+            }
+        }
+    }
+
+    async fn ufw_data() -> FirewallData {
+        let active = tokio::fs::read_to_string(UFW_CONF)
+            .await
+            .map(|conf| {
+                conf.lines()
+                    .any(|line| line.trim().eq_ignore_ascii_case("ENABLED=yes"))
+            })
+            .unwrap_or_default();
+
+        FirewallData {
+            backend: FirewallBackend::Ufw,
+            active,
+            zones: Vec::new(),
+            default_zone: None,
+        }
+    }
+
+    async fn data(backend: FirewallBackend, conn: Option<&zbus::Connection>) -> FirewallData {
+        match (backend, conn) {
+            (FirewallBackend::Firewalld, Some(conn)) => Self::firewalld_data(conn).await,
+            _ => Self::ufw_data().await,
+        }
+    }
+
+    /// firewalld when it's running or installed, ufw otherwise
+    async fn initialize_data(conn: Option<&zbus::Connection>) -> Option<FirewallData> {
+        if let Some(conn) = conn {
+            let data = Self::firewalld_data(conn).await;
+            if data.active || Path::new(FIREWALLD_CONF).exists() {
+                return Some(data);
+            }
+        }
+
+        if Path::new(UFW_CONF).exists() {
+            Some(Self::ufw_data().await)
+        } else {
+            None
+        }
+    }
+
+    async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()>> {
+        let firewalld = FirewallD1Proxy::new(conn).await?;
+
+        Ok(stream_select!(
+            firewalld.receive_default_zone_changed().await?.map(|_| {}),
+            firewalld.receive_reloaded().await?.map(|_| {}),
+            // started or stopped
+            firewalld.inner().receive_owner_changed().await?.map(|_| {}),
+        )
+        .boxed())
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => {
+                let conn = zbus::Connection::system()
+                    .await
+                    .inspect_err(|err| warn!("Failed to connect to system bus: {}", err))
+                    .ok();
+
+                match FirewallService::initialize_data(conn.as_ref()).await {
+                    Some(data) => {
+                        info!("Firewall service initialized with {:?}", data.backend);
+                        let backend = data.backend;
+
+                        let _ = output
+                            .send(ServiceEvent::Init(FirewallService {
+                                data,
+                                conn: conn.clone(),
+                            }))
+                            .await;
+
+                        State::Active(backend, conn)
+                    }
+                    None => {
+                        info!("Neither firewalld nor ufw found");
+
+                        State::Error
+                    }
+                }
+            }
+            State::Active(FirewallBackend::Firewalld, Some(conn)) => {
+                info!("Listening for firewalld events");
+
+                match FirewallService::events(&conn).await {
+                    Ok(mut events) => {
+                        while events.next().await.is_some() {
+                            let data = FirewallService::firewalld_data(&conn).await;
+                            let _ = output.send(ServiceEvent::Update(data)).await;
+                        }
+
+                        State::Active(FirewallBackend::Firewalld, Some(conn))
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for firewalld events: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            // ufw has no events, it changes through the commands
+            State::Active(..) | State::Error => {
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for FirewallService {
+    type UpdateEvent = FirewallData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = FirewallService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FirewallCommand {
+    SetActive(bool),
+    SetZone(String),
+}
+
+async fn run(program: &str, args: &[&str]) {
+    match Command::new(program).args(args).output().await {
+        Ok(output) if !output.status.success() => {
+            warn!(
+                "{} {} failed: {}",
+                program,
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(_) => {}
+        Err(err) => error!("Failed to run {}: {}", program, err),
+    }
+}
+
+impl Service for FirewallService {
+    type Command = FirewallCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let backend = self.backend;
+        let conn = self.conn.clone();
+
+        Task::perform(
+            async move {
+                debug!("Firewall command {:?}", command);
+
+                match (command, backend) {
+                    // systemd asks polkit to authorize it
+                    (FirewallCommand::SetActive(active), FirewallBackend::Firewalld) => {
+                        run(
+                            "systemctl",
+                            &[if active { "start" } else { "stop" }, "firewalld.service"],
+                        )
+                        .await;
+                    }
+                    // ufw checks the user id, it needs to be root
+                    (FirewallCommand::SetActive(active), FirewallBackend::Ufw) => {
+                        run(
+                            "pkexec",
+                            &["ufw", if active { "enable" } else { "disable" }],
+                        )
+                        .await;
+                    }
+                    (FirewallCommand::SetZone(zone), FirewallBackend::Firewalld) => {
+                        if let Some(conn) = conn.as_ref() {
+                            let res = async {
+                                FirewallD1Proxy::new(conn)
+                                    .await?
+                                    .set_default_zone(&zone)
+                                    .await
+                            }
+                            .await;
+
+                            if let Err(err) = res {
+                                error!("Failed to set the firewall zone {}: {}", zone, err);
+                            }
+                        }
+                    }
+                    (FirewallCommand::SetZone(_), FirewallBackend::Ufw) => {}
+                }
+
+                FirewallService::data(backend, conn.as_ref()).await
+            },
+            ServiceEvent::Update,
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.fedoraproject.FirewallD1",
+    default_path = "/org/fedoraproject/FirewallD1",
+    interface = "org.fedoraproject.FirewallD1"
+)]
+trait FirewallD1 {
+    #[zbus(name = "getDefaultZone")]
+    fn get_default_zone(&self) -> zbus::Result<String>;
+
+    #[zbus(name = "setDefaultZone", allow_interactive_auth)]
+    fn set_default_zone(&self, zone: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn default_zone_changed(&self, zone: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn reloaded(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.fedoraproject.FirewallD1",
+    default_path = "/org/fedoraproject/FirewallD1",
+    interface = "org.fedoraproject.FirewallD1.zone"
+)]
+trait FirewallD1Zone {
+    #[zbus(name = "getZones")]
+    fn get_zones(&self) -> zbus::Result<Vec<String>>;
+}
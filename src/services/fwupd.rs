@@ -0,0 +1,137 @@
+use log::debug;
+use std::collections::HashMap;
+use tokio::process;
+use zbus::{proxy, zvariant::OwnedValue};
+
+// FWUPD_DEVICE_FLAG_UPDATABLE
+const DEVICE_FLAG_UPDATABLE: u64 = 1 << 1;
+// FWUPD_DEVICE_FLAG_NEEDS_REBOOT
+const DEVICE_FLAG_NEEDS_REBOOT: u64 = 1 << 8;
+// FWUPD_RELEASE_URGENCY_HIGH
+const RELEASE_URGENCY_HIGH: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct FirmwareUpdate {
+    pub device_id: String,
+    pub device_name: String,
+    pub current_version: String,
+    pub version: String,
+    pub release_notes: String,
+    /// The release fixes known vulnerabilities or is flagged as urgent
+    pub security: bool,
+    /// The update is applied on the next boot
+    pub needs_reboot: bool,
+}
+
+type Dict = HashMap<String, OwnedValue>;
+
+fn string(dict: &Dict, key: &str) -> String {
+    dict.get(key)
+        .and_then(|value| value.downcast_ref::<&str>().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn flags(dict: &Dict) -> u64 {
+    dict.get("Flags")
+        .and_then(|value| value.downcast_ref::<u64>().ok())
+        .unwrap_or_default()
+}
+
+/// Release descriptions use the AppStream markup, keep only the text
+fn strip_markup(description: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in description.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+            }
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Updatable devices with a newer firmware available on the configured remotes
+pub async fn pending_firmware_updates() -> anyhow::Result<Vec<FirmwareUpdate>> {
+    let conn = zbus::Connection::system().await?;
+    let fwupd = FwupdProxy::new(&conn).await?;
+
+    let mut updates = Vec::new();
+    for device in fwupd.get_devices().await? {
+        let device_flags = flags(&device);
+        if device_flags & DEVICE_FLAG_UPDATABLE == 0 {
+            continue;
+        }
+
+        let device_id = string(&device, "DeviceId");
+        // fwupd replies with an error when there is nothing to do
+        let Ok(releases) = fwupd.get_upgrades(&device_id).await else {
+            continue;
+        };
+        let Some(release) = releases.first() else {
+            continue;
+        };
+
+        let security = release
+            .get("Issues")
+            .and_then(|issues| issues.try_clone().ok())
+            .and_then(|issues| Vec::<String>::try_from(issues).ok())
+            .is_some_and(|issues| !issues.is_empty())
+            || release
+                .get("Urgency")
+                .and_then(|urgency| urgency.downcast_ref::<u32>().ok())
+                .is_some_and(|urgency| urgency >= RELEASE_URGENCY_HIGH);
+
+        updates.push(FirmwareUpdate {
+            device_name: string(&device, "Name"),
+            current_version: string(&device, "Version"),
+            version: string(release, "Version"),
+            release_notes: strip_markup(&string(release, "Description")),
+            security,
+            needs_reboot: device_flags & DEVICE_FLAG_NEEDS_REBOOT != 0,
+            device_id,
+        });
+    }
+
+    debug!("Pending firmware updates: {:?}", updates);
+
+    Ok(updates)
+}
+
+/// Installing requires downloading the cabinet archive first,
+/// fwupdmgr takes care of it and of scheduling the offline updates.
+pub async fn install_firmware_update(device_id: &str) -> anyhow::Result<()> {
+    let status = process::Command::new("fwupdmgr")
+        .args(["update", device_id, "--assume-yes", "--no-reboot-check"])
+        .status()
+        .await?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("fwupdmgr exited with {}", status))
+    }
+}
+
+#[proxy(
+    interface = "org.freedesktop.fwupd",
+    default_service = "org.freedesktop.fwupd",
+    default_path = "/"
+)]
+trait Fwupd {
+    fn get_devices(&self) -> zbus::Result<Vec<Dict>>;
+
+    fn get_upgrades(&self, device_id: &str) -> zbus::Result<Vec<Dict>>;
+}
@@ -0,0 +1,157 @@
+use super::{ReadOnlyService, ServiceEvent};
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, stream_select, SinkExt, Stream, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{error, info};
+use std::{any::TypeId, ops::Deref};
+use zbus::proxy;
+
+#[derive(Debug, Clone, Default)]
+pub struct GameModeData {
+    pub client_count: i32,
+}
+
+impl GameModeData {
+    pub fn is_active(&self) -> bool {
+        self.client_count > 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameModeService {
+    data: GameModeData,
+}
+
+impl Deref for GameModeService {
+    type Target = GameModeData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl GameModeService {
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<GameModeData> {
+        let game_mode = GameModeProxy::new(conn).await?;
+
+        Ok(GameModeData {
+            client_count: game_mode.client_count().await?,
+        })
+    }
+
+    async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()>> {
+        let game_mode = GameModeProxy::new(conn).await?;
+
+        Ok(stream_select!(
+            game_mode.receive_game_registered().await?.map(|_| {}),
+            game_mode.receive_game_unregistered().await?.map(|_| {}),
+        )
+        .boxed())
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::session().await {
+                Ok(conn) => match GameModeService::initialize_data(&conn).await {
+                    Ok(data) => {
+                        info!("GameMode service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(GameModeService { data }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize gamemode service: {}", err);
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to session bus: {}", err);
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for gamemode events");
+
+                match GameModeService::events(&conn).await {
+                    Ok(mut events) => {
+                        while events.next().await.is_some() {
+                            if let Ok(data) = GameModeService::initialize_data(&conn).await {
+                                let _ = output.send(ServiceEvent::Update(data)).await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for gamemode events: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("GameMode service error");
+
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for GameModeService {
+    type UpdateEvent = GameModeData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = GameModeService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "com.feralinteractive.GameMode",
+    default_path = "/com/feralinteractive/GameMode",
+    interface = "com.feralinteractive.GameMode"
+)]
+trait GameMode {
+    #[zbus(property)]
+    fn client_count(&self) -> zbus::Result<i32>;
+
+    #[zbus(signal)]
+    fn game_registered(&self, pid: i32, object_path: zbus::zvariant::ObjectPath<'_>) -> Result<()>;
+
+    #[zbus(signal)]
+    fn game_unregistered(
+        &self,
+        pid: i32,
+        object_path: zbus::zvariant::ObjectPath<'_>,
+    ) -> Result<()>;
+}
@@ -0,0 +1,148 @@
+use super::portal;
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::{any::TypeId, collections::HashMap};
+use zbus::{
+    proxy,
+    zvariant::{ObjectPath, OwnedObjectPath, Value},
+};
+
+/// A shortcut registered through the portal, the compositor (or the user
+/// from the desktop settings) chooses the actual trigger
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub id: &'static str,
+    pub description: String,
+    // e.g. "CTRL+ALT+P", only a hint for the portal
+    pub preferred_trigger: Option<String>,
+}
+
+/// Global shortcuts registered with the xdg GlobalShortcuts portal,
+/// the subscription yields the id of the activated shortcut
+pub struct GlobalShortcuts;
+
+impl GlobalShortcuts {
+    async fn listen(
+        session: &str,
+        shortcuts: &[Shortcut],
+        output: &mut Sender<&'static str>,
+    ) -> anyhow::Result<()> {
+        let conn = zbus::Connection::session().await?;
+        let shortcuts_portal = GlobalShortcutsPortalProxy::new(&conn).await?;
+
+        let session_handle = ObjectPath::try_from(format!(
+            "/org/freedesktop/portal/desktop/session/{}/{}",
+            portal::sender(&conn),
+            session
+        ))?;
+
+        let create_token = format!("{}_create", session);
+        portal::request(
+            &conn,
+            &create_token,
+            shortcuts_portal.create_session(HashMap::from([
+                ("handle_token", Value::from(create_token.as_str())),
+                ("session_handle_token", Value::from(session)),
+            ])),
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("the portal request was cancelled"))?;
+
+        let mut activations = shortcuts_portal.receive_activated().await?;
+
+        let bind_token = format!("{}_bind", session);
+        let requested = shortcuts
+            .iter()
+            .map(|shortcut| {
+                let mut options =
+                    HashMap::from([("description", Value::from(shortcut.description.as_str()))]);
+                if let Some(trigger) = &shortcut.preferred_trigger {
+                    options.insert("preferred_trigger", Value::from(trigger.as_str()));
+                }
+
+                (shortcut.id, options)
+            })
+            .collect::<Vec<_>>();
+        portal::request(
+            &conn,
+            &bind_token,
+            shortcuts_portal.bind_shortcuts(
+                &session_handle,
+                &requested,
+                "",
+                HashMap::from([("handle_token", Value::from(bind_token.as_str()))]),
+            ),
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("the portal request was cancelled"))?;
+        info!("Global shortcuts bound for {}", session);
+
+        while let Some(activation) = activations.next().await {
+            let Ok(args) = activation.args() else {
+                continue;
+            };
+            if args.session_handle != session_handle {
+                continue;
+            }
+
+            if let Some(shortcut) = shortcuts
+                .iter()
+                .find(|shortcut| shortcut.id == args.shortcut_id)
+            {
+                debug!("Global shortcut activated: {}", shortcut.id);
+                let _ = output.send(shortcut.id).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `session` names the portal session, it must be a valid object path element
+    pub fn subscribe(
+        session: &'static str,
+        shortcuts: Vec<Shortcut>,
+    ) -> Subscription<&'static str> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            (id, session, shortcuts.clone()),
+            channel(10, move |mut output| async move {
+                if let Err(err) = GlobalShortcuts::listen(session, &shortcuts, &mut output).await {
+                    warn!("Global shortcuts not available: {}", err);
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    interface = "org.freedesktop.portal.GlobalShortcuts"
+)]
+trait GlobalShortcutsPortal {
+    fn create_session(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<OwnedObjectPath>;
+
+    fn bind_shortcuts(
+        &self,
+        session_handle: &ObjectPath<'_>,
+        shortcuts: &[(&str, HashMap<&str, Value<'_>>)],
+        parent_window: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(signal)]
+    fn activated(
+        &self,
+        session_handle: ObjectPath<'_>,
+        shortcut_id: &str,
+        timestamp: u64,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+}
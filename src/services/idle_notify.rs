@@ -0,0 +1,163 @@
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::{any::TypeId, time::Duration};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use wayland_client::{
+    protocol::{
+        wl_registry::{self, WlRegistry},
+        wl_seat::WlSeat,
+    },
+    Connection, Dispatch, Proxy, QueueHandle,
+};
+use wayland_protocols::ext::idle_notify::v1::client::{
+    ext_idle_notification_v1::{self, ExtIdleNotificationV1},
+    ext_idle_notifier_v1::ExtIdleNotifierV1,
+};
+
+/// Idle state of the seat reported through the ext-idle-notify protocol,
+/// true once the user has been idle for the timeout and false as soon as
+/// there is some activity again.
+///
+/// Idle inhibitors are respected, no event is sent while one is active.
+pub struct IdleNotify;
+
+impl IdleNotify {
+    fn listen(timeout: Duration, tx: UnboundedSender<bool>) -> anyhow::Result<()> {
+        let connection = Connection::connect_to_env()?;
+        let mut event_queue = connection.new_event_queue();
+        let handle = event_queue.handle();
+        let _registry = connection.display().get_registry(&handle, ());
+
+        let mut data = IdleNotifyData {
+            seat: None,
+            notifier: None,
+            tx,
+            closed: false,
+        };
+        event_queue.roundtrip(&mut data)?;
+
+        let (Some(seat), Some(notifier)) = (data.seat.clone(), data.notifier.clone()) else {
+            anyhow::bail!("the compositor doesn't support ext-idle-notify");
+        };
+        let _notification = notifier.get_idle_notification(
+            timeout.as_millis().min(u32::MAX as u128) as u32,
+            &seat,
+            &handle,
+            (),
+        );
+
+        // stops when the subscription is dropped and the receiver is gone
+        while !data.closed {
+            event_queue.blocking_dispatch(&mut data)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn subscribe(timeout: Duration) -> Subscription<bool> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            (id, timeout),
+            channel(10, move |mut output| async move {
+                let (tx, mut rx) = unbounded_channel();
+
+                // the wayland event queue is blocking, it gets its own thread
+                std::thread::spawn(move || {
+                    if let Err(err) = IdleNotify::listen(timeout, tx) {
+                        warn!("Failed to listen for idle notifications: {}", err);
+                    }
+                });
+
+                while let Some(idle) = rx.recv().await {
+                    debug!("Idle state changed: {}", idle);
+                    let _ = output.send(idle).await;
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+struct IdleNotifyData {
+    seat: Option<WlSeat>,
+    notifier: Option<ExtIdleNotifierV1>,
+    tx: UnboundedSender<bool>,
+    closed: bool,
+}
+
+impl Dispatch<WlRegistry, ()> for IdleNotifyData {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == WlSeat::interface().name && state.seat.is_none() {
+                debug!(target: "IdleNotify::WlRegistry::Event::Global", "Adding Seat with name {name} and version {version}");
+                state.seat = Some(proxy.bind(name, version.min(7), handle, ()));
+            } else if interface == ExtIdleNotifierV1::interface().name && state.notifier.is_none() {
+                info!(target: "IdleNotify::WlRegistry::Event::Global", "Adding IdleNotifier with name {name} and version {version}");
+                state.notifier = Some(proxy.bind(name, 1, handle, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for IdleNotifyData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlSeat,
+        _event: <WlSeat as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, ()> for IdleNotifyData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ExtIdleNotifierV1,
+        _event: <ExtIdleNotifierV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // This interface has no events.
+}
+
+impl Dispatch<ExtIdleNotificationV1, ()> for IdleNotifyData {
+    fn event(
+        state: &mut Self,
+        _proxy: &ExtIdleNotificationV1,
+        event: <ExtIdleNotificationV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        let idle = match event {
+            ext_idle_notification_v1::Event::Idled => true,
+            ext_idle_notification_v1::Event::Resumed => false,
+            _ => return,
+        };
+
+        if state.tx.send(idle).is_err() {
+            state.closed = true;
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use iced::{
+    futures::{
+        channel::mpsc::Sender,
+        stream::{pending, select_all, BoxStream},
+        SinkExt, StreamExt,
+    },
+    stream::channel,
+    Subscription, Task,
+};
+use log::{debug, error, info, warn};
+use std::{any::TypeId, ops::Deref};
+use zbus::proxy;
+
+const DEVICES_PATH: &str = "/modules/kdeconnect/devices";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhoneBattery {
+    pub charge: i32,
+    pub charging: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneDevice {
+    pub id: String,
+    pub name: String,
+    pub reachable: bool,
+    pub battery: Option<PhoneBattery>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhoneNotification {
+    pub device: String,
+    pub app: String,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum KdeConnectEvent {
+    Devices(Vec<PhoneDevice>),
+    Notification(PhoneNotification),
+}
+
+#[derive(Debug, Clone)]
+pub struct KdeConnectService {
+    conn: zbus::Connection,
+    devices: Vec<PhoneDevice>,
+}
+
+impl Deref for KdeConnectService {
+    type Target = Vec<PhoneDevice>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.devices
+    }
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+enum Signal {
+    // a device was paired, unpaired or discovered
+    DeviceList,
+    Refresh,
+    Notification(String, String),
+}
+
+impl KdeConnectService {
+    async fn device(conn: &zbus::Connection, id: String) -> zbus::Result<PhoneDevice> {
+        let device = DeviceProxy::builder(conn)
+            .path(format!("{}/{}", DEVICES_PATH, id))?
+            .build()
+            .await?;
+        let reachable = device.is_reachable().await?;
+
+        // the battery plugin answers only while the phone is connected
+        let battery = if reachable {
+            let battery = async {
+                let battery = BatteryProxy::builder(conn)
+                    .path(format!("{}/{}/battery", DEVICES_PATH, id))?
+                    .build()
+                    .await?;
+
+                zbus::Result::Ok(PhoneBattery {
+                    charge: battery.charge().await?,
+                    charging: battery.is_charging().await?,
+                })
+            }
+            .await;
+
+            battery
+                .inspect_err(|err| debug!("No battery for device {}: {}", id, err))
+                .ok()
+                .filter(|battery| battery.charge >= 0)
+        } else {
+            None
+        };
+
+        Ok(PhoneDevice {
+            name: device.name().await?,
+            id,
+            reachable,
+            battery,
+        })
+    }
+
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<Vec<PhoneDevice>> {
+        let daemon = DaemonProxy::new(conn).await?;
+        let mut devices = Vec::new();
+
+        for id in daemon.devices(false, true).await? {
+            match KdeConnectService::device(conn, id.clone()).await {
+                Ok(device) => devices.push(device),
+                Err(err) => warn!("Failed to read KDE Connect device {}: {}", id, err),
+            }
+        }
+
+        Ok(devices)
+    }
+
+    async fn notification(
+        conn: &zbus::Connection,
+        device: &str,
+        id: &str,
+    ) -> zbus::Result<PhoneNotification> {
+        let notification = NotificationProxy::builder(conn)
+            .path(format!("{}/{}/notifications/{}", DEVICES_PATH, device, id))?
+            .build()
+            .await?;
+        let device = DeviceProxy::builder(conn)
+            .path(format!("{}/{}", DEVICES_PATH, device))?
+            .build()
+            .await?;
+
+        Ok(PhoneNotification {
+            device: device.name().await?,
+            app: notification.app_name().await?,
+            title: notification.title().await?,
+            text: notification.text().await?,
+        })
+    }
+
+    async fn events(
+        conn: &zbus::Connection,
+        devices: &[PhoneDevice],
+    ) -> anyhow::Result<BoxStream<'static, Signal>> {
+        let daemon = DaemonProxy::new(conn).await?;
+        let mut streams = vec![
+            daemon
+                .receive_device_list_changed()
+                .await?
+                .map(|_| Signal::DeviceList)
+                .boxed(),
+            daemon
+                .receive_device_visibility_changed()
+                .await?
+                .map(|_| Signal::DeviceList)
+                .boxed(),
+        ];
+
+        for device in devices {
+            let path = format!("{}/{}", DEVICES_PATH, device.id);
+            let proxy = DeviceProxy::builder(conn)
+                .path(path.clone())?
+                .build()
+                .await?;
+            streams.push(
+                proxy
+                    .receive_reachable_changed()
+                    .await?
+                    .map(|_| Signal::Refresh)
+                    .boxed(),
+            );
+            streams.push(
+                proxy
+                    .receive_name_changed()
+                    .await?
+                    .map(|_| Signal::Refresh)
+                    .boxed(),
+            );
+
+            let battery = BatteryProxy::builder(conn)
+                .path(format!("{}/battery", path))?
+                .build()
+                .await?;
+            streams.push(
+                battery
+                    .receive_refreshed()
+                    .await?
+                    .map(|_| Signal::Refresh)
+                    .boxed(),
+            );
+
+            let notifications = NotificationsProxy::builder(conn)
+                .path(format!("{}/notifications", path))?
+                .build()
+                .await?;
+            let id = device.id.clone();
+            streams.push(
+                notifications
+                    .receive_notification_posted()
+                    .await?
+                    .filter_map(move |signal| {
+                        let id = id.clone();
+                        async move {
+                            let public_id = signal.args().ok()?.public_id.to_string();
+                            Some(Signal::Notification(id, public_id))
+                        }
+                    })
+                    .boxed(),
+            );
+        }
+
+        Ok(select_all(streams).boxed())
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::session().await {
+                Ok(conn) => match KdeConnectService::initialize_data(&conn).await {
+                    Ok(devices) => {
+                        info!("KDE Connect service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(KdeConnectService {
+                                conn: conn.clone(),
+                                devices,
+                            }))
+                            .await;
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        info!("KDE Connect not available: {}", err);
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to session bus: {}", err);
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for KDE Connect events");
+
+                let devices = KdeConnectService::initialize_data(&conn)
+                    .await
+                    .unwrap_or_default();
+                match KdeConnectService::events(&conn, &devices).await {
+                    Ok(mut events) => {
+                        while let Some(signal) = events.next().await {
+                            match signal {
+                                Signal::DeviceList | Signal::Refresh => {
+                                    if let Ok(devices) =
+                                        KdeConnectService::initialize_data(&conn).await
+                                    {
+                                        let _ = output
+                                            .send(ServiceEvent::Update(KdeConnectEvent::Devices(
+                                                devices,
+                                            )))
+                                            .await;
+                                    }
+
+                                    // the signals of the new devices need new streams
+                                    if matches!(signal, Signal::DeviceList) {
+                                        break;
+                                    }
+                                }
+                                Signal::Notification(device, id) => {
+                                    match KdeConnectService::notification(&conn, &device, &id).await
+                                    {
+                                        Ok(notification) => {
+                                            let _ = output
+                                                .send(ServiceEvent::Update(
+                                                    KdeConnectEvent::Notification(notification),
+                                                ))
+                                                .await;
+                                        }
+                                        Err(err) => {
+                                            debug!("Failed to read notification {}: {}", id, err)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for KDE Connect events: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for KdeConnectService {
+    type UpdateEvent = KdeConnectEvent;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        if let KdeConnectEvent::Devices(devices) = event {
+            self.devices = devices;
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = KdeConnectService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum KdeConnectCommand {
+    Ring(String),
+    // file uri
+    Share(String, String),
+}
+
+impl Service for KdeConnectService {
+    type Command = KdeConnectCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let conn = self.conn.clone();
+
+        Task::perform(
+            async move {
+                let res = match &command {
+                    KdeConnectCommand::Ring(device) => {
+                        async {
+                            FindMyPhoneProxy::builder(&conn)
+                                .path(format!("{}/{}/findmyphone", DEVICES_PATH, device))?
+                                .build()
+                                .await?
+                                .ring()
+                                .await
+                        }
+                        .await
+                    }
+                    KdeConnectCommand::Share(device, uri) => {
+                        async {
+                            ShareProxy::builder(&conn)
+                                .path(format!("{}/{}/share", DEVICES_PATH, device))?
+                                .build()
+                                .await?
+                                .share_url(uri)
+                                .await
+                        }
+                        .await
+                    }
+                };
+
+                if let Err(err) = res {
+                    error!("KDE Connect command {:?} failed: {}", command, err);
+                }
+
+                KdeConnectService::initialize_data(&conn)
+                    .await
+                    .unwrap_or_default()
+            },
+            |devices| ServiceEvent::Update(KdeConnectEvent::Devices(devices)),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    default_path = "/modules/kdeconnect",
+    interface = "org.kde.kdeconnect.daemon"
+)]
+trait Daemon {
+    #[zbus(name = "devices")]
+    fn devices(&self, only_reachable: bool, only_paired: bool) -> zbus::Result<Vec<String>>;
+
+    #[zbus(signal, name = "deviceListChanged")]
+    fn device_list_changed(&self) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "deviceVisibilityChanged")]
+    fn device_visibility_changed(&self, id: &str, is_visible: bool) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device"
+)]
+trait Device {
+    #[zbus(property, name = "name")]
+    fn name(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "isReachable")]
+    fn is_reachable(&self) -> zbus::Result<bool>;
+
+    #[zbus(signal, name = "reachableChanged")]
+    fn reachable_changed(&self, reachable: bool) -> zbus::Result<()>;
+
+    #[zbus(signal, name = "nameChanged")]
+    fn name_changed(&self, name: &str) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device.battery"
+)]
+trait Battery {
+    #[zbus(property, name = "charge")]
+    fn charge(&self) -> zbus::Result<i32>;
+
+    #[zbus(property, name = "isCharging")]
+    fn is_charging(&self) -> zbus::Result<bool>;
+
+    #[zbus(signal, name = "refreshed")]
+    fn refreshed(&self, is_charging: bool, charge: i32) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device.notifications"
+)]
+trait Notifications {
+    #[zbus(signal, name = "notificationPosted")]
+    fn notification_posted(&self, public_id: &str) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device.notifications.notification"
+)]
+trait Notification {
+    #[zbus(property, name = "appName")]
+    fn app_name(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "title")]
+    fn title(&self) -> zbus::Result<String>;
+
+    #[zbus(property, name = "text")]
+    fn text(&self) -> zbus::Result<String>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device.findmyphone"
+)]
+trait FindMyPhone {
+    #[zbus(name = "ring")]
+    fn ring(&self) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.kde.kdeconnect",
+    interface = "org.kde.kdeconnect.device.share"
+)]
+trait Share {
+    #[zbus(name = "shareUrl")]
+    fn share_url(&self, url: &str) -> zbus::Result<()>;
+}
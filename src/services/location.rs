@@ -0,0 +1,246 @@
+use super::{ReadOnlyService, ServiceEvent};
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{error, info};
+use std::{any::TypeId, ops::Deref};
+use zbus::{proxy, zvariant::OwnedObjectPath};
+
+// GeoClue accuracy level, city level is enough for the solar events and the weather
+const ACCURACY_LEVEL_CITY: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LocationData {
+    pub coordinates: Option<Coordinates>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocationService {
+    data: LocationData,
+}
+
+impl Deref for LocationService {
+    type Target = LocationData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection, OwnedObjectPath),
+    Error,
+}
+
+impl LocationService {
+    async fn start_client(conn: &zbus::Connection) -> anyhow::Result<OwnedObjectPath> {
+        let manager = ManagerProxy::new(conn).await?;
+        let client_path = manager.get_client().await?;
+
+        let client = ClientProxy::builder(conn)
+            .path(client_path.clone())?
+            .build()
+            .await?;
+        client.set_desktop_id("ashell").await?;
+        client
+            .set_requested_accuracy_level(ACCURACY_LEVEL_CITY)
+            .await?;
+        client.start().await?;
+
+        Ok(client_path)
+    }
+
+    async fn coordinates(
+        conn: &zbus::Connection,
+        location_path: OwnedObjectPath,
+    ) -> anyhow::Result<Option<Coordinates>> {
+        // GeoClue exposes "/" until the first location is available
+        if location_path.as_str() == "/" {
+            return Ok(None);
+        }
+
+        let location = LocationProxy::builder(conn)
+            .path(location_path)?
+            .build()
+            .await?;
+
+        Ok(Some(Coordinates {
+            latitude: location.latitude().await?,
+            longitude: location.longitude().await?,
+        }))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => match LocationService::start_client(&conn).await {
+                    Ok(client_path) => {
+                        info!("Location service initialized");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(LocationService {
+                                data: LocationData::default(),
+                            }))
+                            .await;
+
+                        State::Active(conn, client_path)
+                    }
+                    Err(err) => {
+                        error!("Failed to initialize location service: {}", err);
+
+                        State::Error
+                    }
+                },
+                Err(err) => {
+                    error!("Failed to connect to system bus: {}", err);
+
+                    State::Error
+                }
+            },
+            State::Active(conn, client_path) => {
+                info!("Listening for location updates");
+
+                let client = match ClientProxy::builder(&conn).path(client_path.clone()) {
+                    Ok(builder) => builder.build().await,
+                    Err(err) => Err(err),
+                };
+
+                match client {
+                    Ok(client) => {
+                        if let Ok(location_path) = client.location().await {
+                            if let Ok(coordinates) =
+                                LocationService::coordinates(&conn, location_path).await
+                            {
+                                let _ = output
+                                    .send(ServiceEvent::Update(LocationData { coordinates }))
+                                    .await;
+                            }
+                        }
+
+                        match client.receive_location_updated().await {
+                            Ok(mut updates) => {
+                                while let Some(update) = updates.next().await {
+                                    let Ok(args) = update.args() else {
+                                        continue;
+                                    };
+
+                                    match LocationService::coordinates(
+                                        &conn,
+                                        args.new().clone().into(),
+                                    )
+                                    .await
+                                    {
+                                        Ok(coordinates) => {
+                                            let _ = output
+                                                .send(ServiceEvent::Update(LocationData {
+                                                    coordinates,
+                                                }))
+                                                .await;
+                                        }
+                                        Err(err) => error!("Failed to read the location: {}", err),
+                                    }
+                                }
+
+                                State::Active(conn, client_path)
+                            }
+                            Err(err) => {
+                                error!("Failed to listen for location updates: {}", err);
+
+                                State::Error
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        error!("Failed to create the location client: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("Location service error");
+
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for LocationService {
+    type UpdateEvent = LocationData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = LocationService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.GeoClue2",
+    default_path = "/org/freedesktop/GeoClue2/Manager",
+    interface = "org.freedesktop.GeoClue2.Manager"
+)]
+trait Manager {
+    fn get_client(&self) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.GeoClue2",
+    interface = "org.freedesktop.GeoClue2.Client"
+)]
+trait Client {
+    fn start(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_desktop_id(&self, value: &str) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn set_requested_accuracy_level(&self, value: u32) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn location(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(signal)]
+    fn location_updated(
+        &self,
+        old: zbus::zvariant::ObjectPath<'_>,
+        new: zbus::zvariant::ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.GeoClue2",
+    interface = "org.freedesktop.GeoClue2.Location"
+)]
+trait Location {
+    #[zbus(property)]
+    fn latitude(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn longitude(&self) -> zbus::Result<f64>;
+}
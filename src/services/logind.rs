@@ -0,0 +1,113 @@
+use std::{env, ffi::OsStr, process, sync::Arc};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use zbus::{
+    proxy,
+    zvariant::{OwnedFd, OwnedObjectPath},
+};
+
+/// A logind inhibitor lock, released when the last clone is dropped
+#[derive(Debug, Clone)]
+pub struct Inhibitor {
+    _fd: Arc<OwnedFd>,
+}
+
+pub async fn inhibit(what: &str, why: &str) -> anyhow::Result<Inhibitor> {
+    let conn = zbus::Connection::system().await?;
+    let manager = Login1ManagerProxy::new(&conn).await?;
+
+    let fd = manager.inhibit(what, "ashell", why, "block").await?;
+
+    Ok(Inhibitor { _fd: Arc::new(fd) })
+}
+
+#[derive(Debug, Clone)]
+pub struct InhibitorInfo {
+    pub what: String,
+    pub who: String,
+    pub why: String,
+    pub mode: String,
+}
+
+/// Lists the inhibitors blocking sleep or idle
+pub async fn list_inhibitors() -> anyhow::Result<Vec<InhibitorInfo>> {
+    let conn = zbus::Connection::system().await?;
+    let manager = Login1ManagerProxy::new(&conn).await?;
+
+    Ok(manager
+        .list_inhibitors()
+        .await?
+        .into_iter()
+        .filter(|(what, ..)| what.split(':').any(|w| w == "sleep" || w == "idle"))
+        .map(|(what, who, why, mode, _, _)| InhibitorInfo {
+            what,
+            who,
+            why,
+            mode,
+        })
+        .collect())
+}
+
+/// Id of the logind session ashell is running in
+pub async fn current_session_id(conn: &zbus::Connection) -> anyhow::Result<String> {
+    if let Ok(id) = env::var("XDG_SESSION_ID") {
+        return Ok(id);
+    }
+
+    let manager = Login1ManagerProxy::new(conn).await?;
+    let session = Login1SessionProxy::builder(conn)
+        .path(manager.get_session_by_pid(process::id()).await?)?
+        .build()
+        .await?;
+
+    Ok(session.id().await?)
+}
+
+/// Returns the rules matched by a running process, a rule matches
+/// the process name or a substring of its command line
+pub fn running_matches(rules: &[String]) -> Vec<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet),
+    );
+
+    rules
+        .iter()
+        .filter(|rule| {
+            system.processes().values().any(|process| {
+                process.name() == OsStr::new(rule.as_str())
+                    || process
+                        .cmd()
+                        .iter()
+                        .map(|arg| arg.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .contains(rule.as_str())
+            })
+        })
+        .cloned()
+        .collect()
+}
+
+#[proxy(
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1",
+    interface = "org.freedesktop.login1.Manager"
+)]
+trait Login1Manager {
+    fn inhibit(&self, what: &str, who: &str, why: &str, mode: &str) -> zbus::Result<OwnedFd>;
+
+    fn list_inhibitors(&self) -> zbus::Result<Vec<(String, String, String, String, u32, u32)>>;
+
+    fn get_session_by_pid(&self, pid: u32) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.login1",
+    interface = "org.freedesktop.login1.Session"
+)]
+trait Login1Session {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+}
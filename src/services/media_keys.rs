@@ -0,0 +1,113 @@
+use evdev::{Device, InputEventKind, Key};
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::any::TypeId;
+use tokio::sync::mpsc::unbounded_channel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKey {
+    VolumeUp,
+    VolumeDown,
+    VolumeMute,
+    MicMute,
+    BrightnessUp,
+    BrightnessDown,
+}
+
+impl MediaKey {
+    fn from_key(key: Key) -> Option<Self> {
+        match key {
+            Key::KEY_VOLUMEUP => Some(Self::VolumeUp),
+            Key::KEY_VOLUMEDOWN => Some(Self::VolumeDown),
+            Key::KEY_MUTE => Some(Self::VolumeMute),
+            Key::KEY_MICMUTE => Some(Self::MicMute),
+            Key::KEY_BRIGHTNESSUP => Some(Self::BrightnessUp),
+            Key::KEY_BRIGHTNESSDOWN => Some(Self::BrightnessDown),
+            _ => None,
+        }
+    }
+}
+
+const MEDIA_KEYS: [Key; 6] = [
+    Key::KEY_VOLUMEUP,
+    Key::KEY_VOLUMEDOWN,
+    Key::KEY_MUTE,
+    Key::KEY_MICMUTE,
+    Key::KEY_BRIGHTNESSUP,
+    Key::KEY_BRIGHTNESSDOWN,
+];
+
+/// Media keys pressed on any input device, even when the key binding
+/// is handled by the compositor or by another hotkey daemon.
+///
+/// Reading the input devices requires the user to be in the `input` group.
+pub struct MediaKeys;
+
+impl MediaKeys {
+    fn media_key_devices() -> Vec<Device> {
+        evdev::enumerate()
+            .map(|(_, device)| device)
+            .filter(|device| {
+                device
+                    .supported_keys()
+                    .is_some_and(|keys| MEDIA_KEYS.iter().any(|key| keys.contains(*key)))
+            })
+            .collect()
+    }
+
+    pub fn subscribe() -> Subscription<MediaKey> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                let devices = MediaKeys::media_key_devices();
+
+                if devices.is_empty() {
+                    info!("No readable input device with media keys, external key presses will be ignored");
+                } else {
+                    let (tx, mut rx) = unbounded_channel();
+
+                    for device in devices {
+                        let name = device.name().unwrap_or_default().to_string();
+                        debug!("Listening for media keys on {}", name);
+
+                        match device.into_event_stream() {
+                            Ok(mut events) => {
+                                let tx = tx.clone();
+                                tokio::spawn(async move {
+                                    while let Ok(event) = events.next_event().await {
+                                        // 1 is a key press, 2 an autorepeat
+                                        if let InputEventKind::Key(key) = event.kind() {
+                                            if let Some(media_key) = MediaKey::from_key(key)
+                                                .filter(|_| event.value() > 0)
+                                            {
+                                                if tx.send(media_key).is_err() {
+                                                    break;
+                                                }
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+                            Err(err) => {
+                                warn!("Failed to listen for media keys on {}: {}", name, err);
+                            }
+                        }
+                    }
+                    drop(tx);
+
+                    while let Some(media_key) = rx.recv().await {
+                        let _ = output.send(media_key).await;
+                    }
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
@@ -0,0 +1,156 @@
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{error, info};
+use pipewire::{
+    context::Context,
+    keys,
+    main_loop::MainLoop,
+    properties::properties,
+    spa::{
+        param::{
+            audio::{AudioFormat, AudioInfoRaw},
+            ParamType,
+        },
+        pod::{serialize::PodSerializer, Object, Pod, Value},
+        utils::{Direction, SpaTypes},
+    },
+    stream::{Stream, StreamFlags},
+};
+use std::{
+    any::TypeId,
+    io::Cursor,
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+const UPDATE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Peak level, from 0 to 1, of the default audio source.
+///
+/// The capture stream only lives while the subscription is active.
+pub struct MicLevel;
+
+impl MicLevel {
+    fn capture(tx: UnboundedSender<f32>) -> anyhow::Result<()> {
+        let mainloop = MainLoop::new(None)?;
+        let context = Context::new(&mainloop)?;
+        let core = context.connect(None)?;
+
+        let stream = Stream::new(
+            &core,
+            "ashell-mic-level",
+            properties! {
+                *keys::MEDIA_TYPE => "Audio",
+                *keys::MEDIA_CATEGORY => "Capture",
+                // a level meter, "Communication" would trigger the policies meant for calls
+                *keys::MEDIA_ROLE => "Production",
+                *keys::APP_NAME => "ashell",
+            },
+        )?;
+
+        let _listener = stream
+            .add_local_listener_with_user_data((0f32, Instant::now()))
+            .process({
+                let tx = tx.clone();
+                move |stream, (peak, last_update)| {
+                    let Some(mut buffer) = stream.dequeue_buffer() else {
+                        return;
+                    };
+                    let Some(data) = buffer.datas_mut().first_mut() else {
+                        return;
+                    };
+
+                    let size = data.chunk().size() as usize;
+                    if let Some(samples) = data.data() {
+                        *peak = samples[..size.min(samples.len())]
+                            .chunks_exact(4)
+                            .map(|sample| {
+                                f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]])
+                                    .abs()
+                            })
+                            .fold(*peak, f32::max);
+                    }
+
+                    if last_update.elapsed() >= UPDATE_INTERVAL {
+                        let _ = tx.send(peak.min(1.));
+                        *peak = 0.;
+                        *last_update = Instant::now();
+                    }
+                }
+            })
+            .register()?;
+
+        let mut audio_info = AudioInfoRaw::new();
+        audio_info.set_format(AudioFormat::F32LE);
+        let values = PodSerializer::serialize(
+            Cursor::new(Vec::new()),
+            &Value::Object(Object {
+                type_: SpaTypes::ObjectParamFormat.as_raw(),
+                id: ParamType::EnumFormat.as_raw(),
+                properties: audio_info.into(),
+            }),
+        )
+        .map_err(|err| anyhow::anyhow!("Failed to serialize the audio format: {:?}", err))?
+        .0
+        .into_inner();
+        let mut params =
+            [Pod::from_bytes(&values)
+                .ok_or_else(|| anyhow::anyhow!("Invalid audio format pod"))?];
+
+        stream.connect(
+            Direction::Input,
+            None,
+            StreamFlags::AUTOCONNECT | StreamFlags::MAP_BUFFERS,
+            &mut params,
+        )?;
+
+        // stop capturing as soon as nobody is listening anymore
+        let timer = mainloop.loop_().add_timer({
+            let mainloop = mainloop.clone();
+            move |_| {
+                if tx.is_closed() {
+                    mainloop.quit();
+                }
+            }
+        });
+        timer
+            .update_timer(
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(500)),
+            )
+            .into_result()?;
+
+        info!("Microphone level capture started");
+        mainloop.run();
+        info!("Microphone level capture stopped");
+
+        Ok(())
+    }
+
+    pub fn subscribe() -> Subscription<f32> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                let (tx, mut rx) = unbounded_channel::<f32>();
+
+                thread::spawn(move || {
+                    if let Err(err) = MicLevel::capture(tx) {
+                        error!("Failed to capture the microphone level: {}", err);
+                    }
+                });
+
+                while let Some(level) = rx.recv().await {
+                    let _ = output.send(level).await;
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
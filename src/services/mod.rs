@@ -1,13 +1,38 @@
 use iced::{Subscription, Task};
 
+pub mod agenda;
+pub mod alsa_mixer;
 pub mod audio;
 pub mod bluetooth;
 pub mod brightness;
+pub mod firewall;
+pub mod fwupd;
+pub mod game_mode;
+pub mod global_shortcuts;
 pub mod idle_inhibitor;
+pub mod idle_notify;
+pub mod kde_connect;
+pub mod location;
+pub mod logind;
+pub mod media_keys;
+pub mod mic_level;
 pub mod network;
+pub mod notifications;
+pub mod output_management;
+pub mod polkit;
+pub mod portal;
+pub mod portal_settings;
 pub mod privacy;
+pub mod secret_service;
+pub mod sensor_proxy;
+#[cfg(test)]
+pub mod test_bus;
+pub mod touch;
 pub mod tray;
+pub mod udisks;
 pub mod upower;
+pub mod wallpaper;
+pub mod weather;
 
 #[derive(Debug, Clone)]
 pub enum ServiceEvent<S: ReadOnlyService> {
@@ -1,4 +1,4 @@
-use super::{AccessPoint, ActiveConnectionInfo, KnownConnection, Vpn};
+use super::{AccessPoint, ActiveConnectionInfo, KnownConnection, Vpn, WifiPassword};
 use iced::futures::StreamExt;
 use itertools::Itertools;
 use log::debug;
@@ -9,6 +9,40 @@ use zbus::{
     Result,
 };
 
+const WIRELESS: &str = "802-11-wireless";
+const WIRELESS_SECURITY: &str = "802-11-wireless-security";
+
+/// SSID of a Wi-Fi connection profile, from its 802-11-wireless setting
+pub(super) fn wifi_ssid(
+    connection: &HashMap<String, HashMap<String, OwnedValue>>,
+) -> Option<String> {
+    match connection.get(WIRELESS)?.get("ssid")?.deref() {
+        Value::Array(ssid) => {
+            let ssid = ssid
+                .iter()
+                .map(|byte| match byte {
+                    Value::U8(byte) => Some(*byte),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(String::from_utf8_lossy(&ssid).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// NetworkManager doesn't write the agent owned secrets in the connection profile,
+/// it asks the registered secret agents, ashell included, when it needs them
+fn psk_flags(password: &WifiPassword) -> u32 {
+    // NM_SETTING_SECRET_FLAG_AGENT_OWNED
+    if password.keyring {
+        1
+    } else {
+        0
+    }
+}
+
 pub struct NetworkDbus<'a>(NetworkManagerProxy<'a>);
 
 impl<'a> Deref for NetworkDbus<'a> {
@@ -30,6 +64,15 @@ impl NetworkDbus<'_> {
         self.0.connectivity().await.map(ConnectivityState::from)
     }
 
+    /// True when the primary connection is metered, or guessed to be
+    pub async fn is_metered(&self) -> Result<bool> {
+        // NM_METERED_YES and NM_METERED_GUESS_YES
+        self.0
+            .metered()
+            .await
+            .map(|metered| matches!(metered, 1 | 3))
+    }
+
     pub async fn wifi_device_present(&self) -> anyhow::Result<bool> {
         let devices = self.devices().await?;
         for d in devices {
@@ -109,8 +152,7 @@ impl NetworkDbus<'_> {
                                     .await?;
 
                             info.push(ActiveConnectionInfo::WiFi {
-                                id: connection.id().await?,
-                                name: String::from_utf8_lossy(&access_point.ssid().await?)
+                                ssid: String::from_utf8_lossy(&access_point.ssid().await?)
                                     .into_owned(),
                                 strength: access_point.strength().await.unwrap_or_default(),
                             });
@@ -131,7 +173,7 @@ impl NetworkDbus<'_> {
             let helper = |conn: &ActiveConnectionInfo| match conn {
                 ActiveConnectionInfo::Vpn { name, .. } => format!("0{name}"),
                 ActiveConnectionInfo::Wired { name, .. } => format!("1{name}"),
-                ActiveConnectionInfo::WiFi { name, .. } => format!("2{name}"),
+                ActiveConnectionInfo::WiFi { ssid, .. } => format!("2{ssid}"),
             };
             helper(a).cmp(&helper(b))
         });
@@ -155,18 +197,11 @@ impl NetworkDbus<'_> {
                 .build()
                 .await?;
             let s = cs.get_settings().await.unwrap();
-            let wifi = s.get("802-11-wireless");
+            let wifi = s.get(WIRELESS);
 
             if wifi.is_some() {
-                let ssid = s
-                    .get("connection")
-                    .and_then(|c| c.get("id"))
-                    .map(|s| match s.deref() {
-                        Value::Str(v) => v.to_string(),
-                        _ => "".to_string(),
-                    });
-
-                if let Some(cur_ssid) = ssid {
+                // the id of the profile can be anything, the access points are matched by SSID
+                if let Some(cur_ssid) = wifi_ssid(&s) {
                     known_ssid.push(cur_ssid);
                 }
             } else if s.contains_key("vpn") {
@@ -301,7 +336,7 @@ impl NetworkDbus<'_> {
     pub async fn select_access_point(
         &self,
         access_point: &AccessPoint,
-        password: Option<String>,
+        password: Option<&WifiPassword>,
     ) -> anyhow::Result<()> {
         let settings = NetworkSettingsDbus::new(self.0.inner().connection()).await?;
         let connection = settings.find_connection(&access_point.ssid).await?;
@@ -315,8 +350,13 @@ impl NetworkDbus<'_> {
 
                 let mut s = connection.get_settings().await?;
                 if let Some(wifi_settings) = s.get_mut("802-11-wireless-security") {
-                    let new_password = zvariant::Value::from(password.clone()).try_to_owned()?;
+                    let new_password =
+                        zvariant::Value::from(password.psk.clone()).try_to_owned()?;
                     wifi_settings.insert("psk".to_string(), new_password);
+                    wifi_settings.insert(
+                        "psk-flags".to_string(),
+                        zvariant::Value::from(psk_flags(password)).try_to_owned()?,
+                    );
                 }
 
                 connection.update(s).await?;
@@ -350,7 +390,8 @@ impl NetworkDbus<'_> {
                 conn_settings.insert(
                     "802-11-wireless-security",
                     HashMap::from([
-                        ("psk", Value::Str(pass.into())),
+                        ("psk", Value::Str(pass.psk.clone().into())),
+                        ("psk-flags", Value::U32(psk_flags(pass))),
                         ("key-mgmt", Value::Str("wpa-psk".into())),
                     ]),
                 );
@@ -389,7 +430,8 @@ impl NetworkSettingsDbus<'_> {
         Ok(self.list_connections().await?)
     }
 
-    pub async fn find_connection(&self, name: &str) -> anyhow::Result<Option<OwnedObjectPath>> {
+    /// Wi-Fi profile of the given SSID
+    pub async fn find_connection(&self, ssid: &str) -> anyhow::Result<Option<OwnedObjectPath>> {
         let connections = self.list_connections().await?;
 
         for connection in connections {
@@ -399,22 +441,36 @@ impl NetworkSettingsDbus<'_> {
                 .await?;
 
             let s = connection.get_settings().await?;
-            let id = s
-                .get("connection")
-                .unwrap()
-                .get("id")
-                .map(|v| match v.deref() {
-                    Value::Str(v) => v.to_string(),
-                    _ => "".to_string(),
-                })
-                .unwrap();
-            if id == name {
+            if wifi_ssid(&s).is_some_and(|s| s == ssid) {
                 return Ok(Some(connection.inner().path().to_owned().into()));
             }
         }
 
         Ok(None)
     }
+
+    /// Password saved in the connection profile, NetworkManager checks
+    /// with polkit before handing out the secrets
+    pub async fn wifi_psk(&self, ssid: &str) -> anyhow::Result<Option<String>> {
+        let path = self
+            .find_connection(ssid)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No connection profile for {}", ssid))?;
+
+        let connection = ConnectionSettingsProxy::builder(self.inner().connection())
+            .path(path)?
+            .build()
+            .await?;
+        let secrets = connection.get_secrets(WIRELESS_SECURITY).await?;
+
+        Ok(secrets
+            .get(WIRELESS_SECURITY)
+            .and_then(|security| security.get("psk"))
+            .and_then(|psk| match psk.deref() {
+                Value::Str(psk) => Some(psk.to_string()),
+                _ => None,
+            }))
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -561,6 +617,9 @@ pub trait NetworkManager {
 
     #[zbus(property)]
     fn connectivity(&self) -> Result<u32>;
+
+    #[zbus(property)]
+    fn metered(&self) -> Result<u32>;
 }
 
 #[proxy(
@@ -698,4 +757,9 @@ trait ConnectionSettings {
     fn update(&self, settings: HashMap<String, HashMap<String, OwnedValue>>) -> Result<()>;
 
     fn get_settings(&self) -> Result<HashMap<String, HashMap<String, OwnedValue>>>;
+
+    fn get_secrets(
+        &self,
+        setting_name: &str,
+    ) -> Result<HashMap<String, HashMap<String, OwnedValue>>>;
 }
@@ -1,5 +1,9 @@
 use super::{Service, ServiceEvent};
-use crate::services::{bluetooth::BluetoothService, ReadOnlyService};
+use crate::services::{
+    bluetooth::BluetoothService,
+    secret_service::{lookup_secret, store_secret},
+    ReadOnlyService,
+};
 use dbus::{
     AccessPointProxy, ConnectivityState, DeviceProxy, DeviceState, NetworkDbus,
     NetworkSettingsDbus, WirelessDeviceProxy,
@@ -13,16 +17,77 @@ use iced::{
     stream::channel,
     Subscription, Task,
 };
-use log::{debug, error, info};
-use std::{any::TypeId, collections::HashMap, ops::Deref};
+use log::{debug, error, info, warn};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
 use tokio::process::Command;
 use zbus::zvariant::{ObjectPath, OwnedObjectPath};
 
 pub mod dbus;
+mod secret_agent;
+
+pub fn wifi_secret_attributes(ssid: &str) -> HashMap<&str, &str> {
+    HashMap::from([("application", "ashell"), ("ssid", ssid)])
+}
+
+/// Wi-Fi password previously remembered in the Secret Service
+pub async fn keyring_wifi_password(ssid: String) -> Option<String> {
+    lookup_secret(wifi_secret_attributes(&ssid))
+        .await
+        .inspect_err(|err| debug!("No Wi-Fi password in the keyring for {}: {}", ssid, err))
+        .ok()
+        .flatten()
+}
+
+/// SSID and password of the connected Wi-Fi network, without a password
+/// for open networks
+pub async fn connected_wifi_credentials() -> Option<(String, Option<String>)> {
+    let conn = zbus::Connection::system().await.ok()?;
+    let ssid = NetworkDbus::new(&conn)
+        .await
+        .ok()?
+        .active_connections_info()
+        .await
+        .ok()?
+        .into_iter()
+        .find_map(|connection| match connection {
+            ActiveConnectionInfo::WiFi { ssid, .. } => Some(ssid),
+            _ => None,
+        })?;
+    let password = saved_wifi_password(ssid.clone()).await;
+
+    Some((ssid, password))
+}
+
+/// Password of a saved Wi-Fi network, from its connection profile or the keyring.
+/// NetworkManager checks with polkit before handing out the secrets, the keyring
+/// is only read once it agreed and the profile has no password of its own
+pub async fn saved_wifi_password(ssid: String) -> Option<String> {
+    let conn = zbus::Connection::system().await.ok()?;
+
+    match NetworkSettingsDbus::new(&conn)
+        .await
+        .ok()?
+        .wifi_psk(&ssid)
+        .await
+    {
+        Ok(Some(psk)) => Some(psk),
+        Ok(None) => keyring_wifi_password(ssid).await,
+        Err(err) => {
+            debug!("Failed to read the secrets of {}: {}", ssid, err);
+            None
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum NetworkEvent {
     WiFiEnabled(bool),
+    Metered(bool),
     AirplaneMode(bool),
     Connectivity(ConnectivityState),
     WirelessDevice {
@@ -42,10 +107,17 @@ pub enum NetworkCommand {
     ScanNearByWiFi,
     ToggleWiFi,
     ToggleAirplaneMode,
-    SelectAccessPoint((AccessPoint, Option<String>)),
+    SelectAccessPoint((AccessPoint, Option<WifiPassword>)),
     ToggleVpn(Vpn),
 }
 
+#[derive(Debug, Clone)]
+pub struct WifiPassword {
+    pub psk: String,
+    /// Keep the password in the Secret Service instead of the connection profile
+    pub keyring: bool,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct AccessPoint {
     pub ssid: String,
@@ -76,8 +148,7 @@ pub enum ActiveConnectionInfo {
         speed: u32,
     },
     WiFi {
-        id: String,
-        name: String,
+        ssid: String,
         strength: u8,
     },
     Vpn {
@@ -90,7 +161,7 @@ impl ActiveConnectionInfo {
     pub fn name(&self) -> String {
         match &self {
             Self::Wired { name, .. } => name.clone(),
-            Self::WiFi { name, .. } => name.clone(),
+            Self::WiFi { ssid, .. } => ssid.clone(),
             Self::Vpn { name, .. } => name.clone(),
         }
     }
@@ -106,6 +177,30 @@ pub struct NetworkData {
     pub airplane_mode: bool,
     pub connectivity: ConnectivityState,
     pub scanning_nearby_wifi: bool,
+    /// Metered flag of the primary connection, set by the user or guessed by NetworkManager
+    pub metered: bool,
+}
+
+impl NetworkData {
+    /// Metered for NetworkManager, or connected to one of the given SSIDs (e.g. a phone hotspot)
+    pub fn is_metered(&self, metered_ssids: &[String]) -> bool {
+        self.metered
+            || self.active_connections.iter().any(|connection| {
+                matches!(connection, ActiveConnectionInfo::WiFi { ssid, .. } if metered_ssids.contains(ssid))
+            })
+    }
+}
+
+static METERED: AtomicBool = AtomicBool::new(false);
+
+/// Metered state of the current connection shared with the modules doing
+/// background polling, kept up to date by the settings module
+pub fn is_metered() -> bool {
+    METERED.load(Ordering::Relaxed)
+}
+
+pub fn set_metered(metered: bool) {
+    METERED.store(metered, Ordering::Relaxed);
 }
 
 #[derive(Debug, Clone)]
@@ -141,6 +236,9 @@ impl ReadOnlyService for NetworkService {
                 debug!("WiFi enabled: {}", wifi_enabled);
                 self.data.wifi_enabled = wifi_enabled;
             }
+            NetworkEvent::Metered(metered) => {
+                self.data.metered = metered;
+            }
             NetworkEvent::ScanningNearbyWifi => {
                 self.data.scanning_nearby_wifi = true;
             }
@@ -238,6 +336,7 @@ impl NetworkService {
             wireless_access_points,
             known_connections,
             scanning_nearby_wifi: false,
+            metered: nm.is_metered().await.unwrap_or_default(),
         })
     }
 
@@ -251,6 +350,10 @@ impl NetworkService {
                         Ok(data) => {
                             info!("Network service initialized");
 
+                            if let Err(err) = secret_agent::register(&conn).await {
+                                warn!("Failed to register the Wi-Fi secret agent: {}", err);
+                            }
+
                             let _ = output
                                 .send(ServiceEvent::Init(NetworkService {
                                     data,
@@ -326,6 +429,18 @@ impl NetworkService {
             })
             .boxed();
 
+        let metered_changed = nm
+            .receive_metered_changed()
+            .await
+            .then(|val| async move {
+                // NM_METERED_YES and NM_METERED_GUESS_YES
+                let value = matches!(val.get().await.unwrap_or_default(), 1 | 3);
+
+                debug!("Metered changed: {}", value);
+                NetworkEvent::Metered(value)
+            })
+            .boxed();
+
         let connectivity_changed = nm
             .receive_connectivity_changed()
             .await
@@ -507,6 +622,7 @@ impl NetworkService {
             wireless_enabled,
             wireless_devices_changed,
             connectivity_changed,
+            metered_changed,
             active_connections_changes,
             access_points,
             strength_changes,
@@ -555,10 +671,25 @@ impl NetworkService {
     async fn select_access_point(
         conn: &zbus::Connection,
         access_point: &AccessPoint,
-        password: Option<String>,
+        password: Option<WifiPassword>,
     ) -> anyhow::Result<Vec<KnownConnection>> {
         let nm = NetworkDbus::new(conn).await?;
-        nm.select_access_point(access_point, password).await?;
+
+        // stored first, the secret agent serves it to NetworkManager during the activation
+        if let Some(password) = password.as_ref().filter(|password| password.keyring) {
+            if let Err(err) = store_secret(
+                &format!("Wi-Fi password for {}", access_point.ssid),
+                wifi_secret_attributes(&access_point.ssid),
+                &password.psk,
+            )
+            .await
+            {
+                error!("Failed to store the Wi-Fi password in the keyring: {}", err);
+            }
+        }
+
+        nm.select_access_point(access_point, password.as_ref())
+            .await?;
 
         let wireless_ac = nm.wireless_access_points().await?;
         let known_connections = nm.known_connections(&wireless_ac).await?;
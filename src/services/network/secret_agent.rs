@@ -0,0 +1,143 @@
+use super::{dbus::wifi_ssid, keyring_wifi_password, wifi_secret_attributes};
+use crate::services::secret_service::{delete_secret, store_secret};
+use log::{debug, warn};
+use std::{collections::HashMap, ops::Deref};
+use zbus::{
+    fdo, interface, proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+    DBusError,
+};
+
+pub const AGENT_PATH: &str = "/org/freedesktop/NetworkManager/SecretAgent";
+const AGENT_IDENTIFIER: &str = "org.ashell.network";
+
+const WIRELESS_SECURITY: &str = "802-11-wireless-security";
+// NM_SETTING_SECRET_FLAG_AGENT_OWNED
+const AGENT_OWNED: u32 = 1;
+
+type Settings = HashMap<String, HashMap<String, OwnedValue>>;
+
+#[derive(Debug, DBusError)]
+#[zbus(prefix = "org.freedesktop.NetworkManager.SecretAgent")]
+pub enum SecretAgentError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NoSecrets(String),
+}
+
+/// Password of a profile whose secret is kept by the agents
+fn agent_owned_psk(connection: &Settings) -> Option<String> {
+    let security = connection.get(WIRELESS_SECURITY)?;
+    let flags = security
+        .get("psk-flags")
+        .and_then(|flags| match flags.deref() {
+            Value::U32(flags) => Some(*flags),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    match security.get("psk")?.deref() {
+        Value::Str(psk) if flags & AGENT_OWNED != 0 => Some(psk.to_string()),
+        _ => None,
+    }
+}
+
+/// Hands NetworkManager the Wi-Fi passwords remembered in the keyring, the
+/// profiles keep their psk-flags as agent owned so NetworkManager asks the
+/// agents on every activation, autoconnect and roaming included
+pub struct SecretAgent;
+
+#[interface(name = "org.freedesktop.NetworkManager.SecretAgent")]
+impl SecretAgent {
+    async fn get_secrets(
+        &self,
+        connection: Settings,
+        _connection_path: OwnedObjectPath,
+        setting_name: &str,
+        _hints: Vec<String>,
+        flags: u32,
+    ) -> Result<HashMap<String, HashMap<String, OwnedValue>>, SecretAgentError> {
+        // NM_SECRET_AGENT_GET_SECRETS_FLAG_REQUEST_NEW, the saved password was refused
+        let request_new = flags & 0x2 != 0;
+
+        let psk = match wifi_ssid(&connection) {
+            Some(ssid) if setting_name == WIRELESS_SECURITY && !request_new => {
+                debug!("Secrets requested for {}", ssid);
+                keyring_wifi_password(ssid).await
+            }
+            _ => None,
+        };
+
+        match psk {
+            Some(psk) => Ok(HashMap::from([(
+                WIRELESS_SECURITY.to_string(),
+                HashMap::from([(
+                    "psk".to_string(),
+                    Value::from(psk).try_to_owned().map_err(zbus::Error::from)?,
+                )]),
+            )])),
+            None => Err(SecretAgentError::NoSecrets(format!(
+                "No secrets for {}",
+                setting_name
+            ))),
+        }
+    }
+
+    async fn cancel_get_secrets(&self, _connection_path: OwnedObjectPath, _setting_name: &str) {}
+
+    async fn save_secrets(
+        &self,
+        connection: Settings,
+        _connection_path: OwnedObjectPath,
+    ) -> fdo::Result<()> {
+        let (Some(ssid), Some(psk)) = (wifi_ssid(&connection), agent_owned_psk(&connection)) else {
+            return Ok(());
+        };
+
+        store_secret(
+            &format!("Wi-Fi password for {}", ssid),
+            wifi_secret_attributes(&ssid),
+            &psk,
+        )
+        .await
+        .map_err(|err| {
+            warn!("Failed to store the Wi-Fi password in the keyring: {}", err);
+            fdo::Error::Failed(err.to_string())
+        })
+    }
+
+    async fn delete_secrets(
+        &self,
+        connection: Settings,
+        _connection_path: OwnedObjectPath,
+    ) -> fdo::Result<()> {
+        let Some(ssid) = wifi_ssid(&connection) else {
+            return Ok(());
+        };
+
+        delete_secret(wifi_secret_attributes(&ssid))
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+}
+
+/// Serves the agent on the connection, it stays registered as long as the connection is open
+pub async fn register(conn: &zbus::Connection) -> anyhow::Result<()> {
+    conn.object_server().at(AGENT_PATH, SecretAgent).await?;
+
+    AgentManagerProxy::new(conn)
+        .await?
+        .register(AGENT_IDENTIFIER)
+        .await?;
+
+    Ok(())
+}
+
+#[proxy(
+    interface = "org.freedesktop.NetworkManager.AgentManager",
+    default_service = "org.freedesktop.NetworkManager",
+    default_path = "/org/freedesktop/NetworkManager/AgentManager"
+)]
+trait AgentManager {
+    fn register(&self, identifier: &str) -> zbus::Result<()>;
+}
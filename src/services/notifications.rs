@@ -0,0 +1,564 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use crate::utils::state::{read_state, write_state};
+use chrono::{DateTime, Local};
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription, Task,
+};
+use log::{debug, error, info, warn};
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use tokio::process::Command;
+use zbus::{message::Type, proxy, zvariant::OwnedValue, MessageStream};
+
+const NOTIFICATIONS_INTERFACE: &str = "org.freedesktop.Notifications";
+const DND_STATE: &str = "do-not-disturb";
+// mode to define in the mako config, e.g. [mode=do-not-disturb] invisible=1
+const MAKO_DND_MODE: &str = "do-not-disturb";
+
+static DND: AtomicBool = AtomicBool::new(false);
+
+pub fn is_dnd() -> bool {
+    DND.load(Ordering::Relaxed)
+}
+
+/// Notification daemon whose do not disturb can be driven by ashell,
+/// the daemon decides what is still shown, e.g. the critical notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DndBackend {
+    Mako,
+    Dunst,
+    Swaync,
+}
+
+impl DndBackend {
+    fn from_server_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mako" => Some(Self::Mako),
+            "dunst" => Some(Self::Dunst),
+            "swaync" | "swaynotificationcenter" => Some(Self::Swaync),
+            _ => None,
+        }
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Mako => "makoctl",
+            Self::Dunst => "dunstctl",
+            Self::Swaync => "swaync-client",
+        }
+    }
+
+    async fn run(self, args: &[&str]) -> Option<String> {
+        match Command::new(self.program()).args(args).output().await {
+            Ok(output) if output.status.success() => {
+                Some(String::from_utf8_lossy(&output.stdout).into_owned())
+            }
+            Ok(output) => {
+                warn!(
+                    "{} failed: {}",
+                    self.program(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+            Err(err) => {
+                warn!("Failed to run {}: {}", self.program(), err);
+                None
+            }
+        }
+    }
+
+    async fn get(self) -> Option<bool> {
+        let output = match self {
+            Self::Mako => self.run(&["mode"]).await?,
+            Self::Dunst => self.run(&["is-paused"]).await?,
+            Self::Swaync => self.run(&["--get-dnd"]).await?,
+        };
+
+        Some(match self {
+            Self::Mako => output.lines().any(|mode| mode.trim() == MAKO_DND_MODE),
+            Self::Dunst | Self::Swaync => output.trim() == "true",
+        })
+    }
+
+    async fn set(self, dnd: bool) -> bool {
+        let args: &[&str] = match (self, dnd) {
+            (Self::Mako, true) => &["mode", "-a", MAKO_DND_MODE],
+            (Self::Mako, false) => &["mode", "-r", MAKO_DND_MODE],
+            (Self::Dunst, true) => &["set-paused", "true"],
+            (Self::Dunst, false) => &["set-paused", "false"],
+            (Self::Swaync, true) => &["--dnd-on"],
+            (Self::Swaync, false) => &["--dnd-off"],
+        };
+
+        self.run(args).await.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl From<u8> for Urgency {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Urgency::Low,
+            2 => Urgency::Critical,
+            _ => Urgency::Normal,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub app_name: String,
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+    pub received: DateTime<Local>,
+    pub unread: bool,
+}
+
+/// Notifications received since ashell started, by a local id
+#[derive(Debug, Clone, Default)]
+pub struct NotificationsData {
+    pub notifications: HashMap<u32, Notification>,
+    // do not disturb of the daemon, the notifications are still
+    // collected in the history
+    pub dnd: bool,
+    // None when the daemon can't be driven
+    pub dnd_backend: Option<DndBackend>,
+}
+
+impl NotificationsData {
+    pub fn unread(&self) -> usize {
+        self.notifications
+            .values()
+            .filter(|notification| notification.unread)
+            .count()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationsEvent {
+    // a new notification or one replacing the notification with the same id
+    Received(u32, Notification),
+    Dismissed(u32),
+    Cleared,
+    // the history has been seen
+    Read,
+    Dnd(bool),
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationsCommand {
+    Dismiss(u32),
+    ClearAll,
+    SetDnd(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct NotificationsService {
+    data: NotificationsData,
+}
+
+impl Deref for NotificationsService {
+    type Target = NotificationsData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+type NotifyArgs = (
+    String,
+    u32,
+    String,
+    String,
+    String,
+    Vec<String>,
+    HashMap<String, OwnedValue>,
+    i32,
+);
+
+/// Ids of the notification daemon mapped to the local ids, the daemon
+/// picks its id in the reply to the Notify call
+#[derive(Default)]
+struct Ids {
+    next: u32,
+    // Notify calls waiting for a reply, by caller and serial, with the local id
+    pending: HashMap<(String, u32), u32>,
+    daemon: HashMap<u32, u32>,
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl NotificationsService {
+    /// Turns the connection into a monitor of the notifications sent to the daemon,
+    /// the daemon keeps showing them as usual
+    async fn monitor() -> zbus::Result<zbus::Connection> {
+        let conn = zbus::Connection::session().await?;
+
+        conn.call_method(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            Some("org.freedesktop.DBus.Monitoring"),
+            "BecomeMonitor",
+            &(
+                &[
+                    format!(
+                        "type='method_call',interface='{}',member='Notify'",
+                        NOTIFICATIONS_INTERFACE
+                    ),
+                    format!("type='method_return',sender='{}'", NOTIFICATIONS_INTERFACE),
+                ][..],
+                0u32,
+            ),
+        )
+        .await?;
+
+        Ok(conn)
+    }
+
+    /// Daemon able to do not disturb, and its state restored from the last run
+    async fn init_dnd(conn: &zbus::Connection) -> (Option<DndBackend>, bool) {
+        let server = async {
+            NotificationsProxy::new(conn)
+                .await?
+                .get_server_information()
+                .await
+        }
+        .await;
+        let Some(backend) = server
+            .inspect_err(|err| warn!("Failed to get the notification daemon: {}", err))
+            .ok()
+            .and_then(|(name, ..)| {
+                let backend = DndBackend::from_server_name(&name);
+                if backend.is_none() {
+                    info!("Do not disturb isn't supported by {}", name);
+                }
+                backend
+            })
+        else {
+            return (None, false);
+        };
+
+        let current = backend.get().await.unwrap_or_default();
+        let dnd = match read_state(DND_STATE).map(|state| state == "true") {
+            Some(dnd) if dnd != current && backend.set(dnd).await => dnd,
+            _ => current,
+        };
+
+        (Some(backend), dnd)
+    }
+
+    fn handle_message(message: &zbus::Message, ids: &mut Ids) -> Option<NotificationsEvent> {
+        let header = message.header();
+
+        match message.message_type() {
+            Type::MethodCall => {
+                let (app_name, replaces_id, _, summary, body, _, hints, _): NotifyArgs = message
+                    .body()
+                    .deserialize()
+                    .inspect_err(|err| warn!("Invalid notification: {}", err))
+                    .ok()?;
+
+                let id = ids
+                    .daemon
+                    .get(&replaces_id)
+                    .copied()
+                    .filter(|_| replaces_id != 0)
+                    .unwrap_or_else(|| {
+                        ids.next += 1;
+                        ids.next
+                    });
+                let urgency = hints
+                    .get("urgency")
+                    .and_then(|value| u8::try_from(value.clone()).ok())
+                    .map_or(Urgency::Normal, Urgency::from);
+
+                if let Some(sender) = header.sender() {
+                    ids.pending.insert(
+                        (sender.to_string(), header.primary().serial_num().get()),
+                        id,
+                    );
+                }
+
+                Some(NotificationsEvent::Received(
+                    id,
+                    Notification {
+                        app_name,
+                        summary,
+                        body,
+                        urgency,
+                        received: Local::now(),
+                        unread: true,
+                    },
+                ))
+            }
+            Type::MethodReturn => {
+                let key = (
+                    header.destination()?.to_string(),
+                    header.reply_serial()?.get(),
+                );
+                let id = ids.pending.remove(&key)?;
+                let daemon_id = message.body().deserialize::<u32>().ok()?;
+                debug!("Notification {} has daemon id {}", id, daemon_id);
+                ids.daemon.insert(daemon_id, id);
+
+                None
+            }
+            _ => None,
+        }
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => {
+                let conns = async {
+                    zbus::Result::Ok((
+                        NotificationsService::monitor().await?,
+                        zbus::Connection::session().await?,
+                    ))
+                }
+                .await;
+
+                match conns {
+                    Ok((monitor, conn)) => {
+                        info!("Notifications service initialized");
+
+                        // the monitoring connection can't be used for calls
+                        let (dnd_backend, dnd) = NotificationsService::init_dnd(&conn).await;
+                        DND.store(dnd, Ordering::Relaxed);
+
+                        let _ = output
+                            .send(ServiceEvent::Init(NotificationsService {
+                                data: NotificationsData {
+                                    dnd,
+                                    dnd_backend,
+                                    ..Default::default()
+                                },
+                            }))
+                            .await;
+
+                        State::Active(monitor)
+                    }
+                    Err(err) => {
+                        error!("Failed to monitor the notifications: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            State::Active(monitor) => {
+                info!("Listening for notifications");
+
+                let mut ids = Ids::default();
+                let mut messages = MessageStream::from(monitor);
+                while let Some(message) = messages.next().await {
+                    let Ok(message) = message else {
+                        continue;
+                    };
+
+                    if let Some(event) = NotificationsService::handle_message(&message, &mut ids) {
+                        let _ = output.send(ServiceEvent::Update(event)).await;
+                    }
+                }
+
+                error!("Notifications monitor disconnected");
+
+                State::Error
+            }
+            State::Error => {
+                error!("Notifications service error");
+
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for NotificationsService {
+    type UpdateEvent = NotificationsEvent;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        match event {
+            NotificationsEvent::Received(id, notification) => {
+                self.data.notifications.insert(id, notification);
+            }
+            NotificationsEvent::Dismissed(id) => {
+                self.data.notifications.remove(&id);
+            }
+            NotificationsEvent::Cleared => {
+                self.data.notifications.clear();
+            }
+            NotificationsEvent::Read => {
+                for notification in self.data.notifications.values_mut() {
+                    notification.unread = false;
+                }
+            }
+            NotificationsEvent::Dnd(dnd) => {
+                DND.store(dnd, Ordering::Relaxed);
+                self.data.dnd = dnd;
+            }
+        }
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = NotificationsService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for NotificationsService {
+    type Command = NotificationsCommand;
+
+    // the history is kept by ashell alone, the daemon only gets do not disturb
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        match command {
+            NotificationsCommand::Dismiss(id) => {
+                Task::done(ServiceEvent::Update(NotificationsEvent::Dismissed(id)))
+            }
+            NotificationsCommand::ClearAll => {
+                Task::done(ServiceEvent::Update(NotificationsEvent::Cleared))
+            }
+            NotificationsCommand::SetDnd(dnd) => {
+                let Some(backend) = self.data.dnd_backend else {
+                    warn!("Do not disturb isn't supported by the notification daemon");
+                    return Task::none();
+                };
+
+                Task::perform(
+                    async move {
+                        if backend.set(dnd).await {
+                            info!("Do not disturb: {}", dnd);
+                            write_state(DND_STATE, &dnd.to_string());
+
+                            Some(dnd)
+                        } else {
+                            None
+                        }
+                    },
+                    |dnd| match dnd {
+                        Some(dnd) => ServiceEvent::Update(NotificationsEvent::Dnd(dnd)),
+                        None => ServiceEvent::Error(()),
+                    },
+                )
+            }
+        }
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications",
+    interface = "org.freedesktop.Notifications"
+)]
+trait Notifications {
+    fn get_server_information(&self) -> zbus::Result<(String, String, String, String)>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zbus::zvariant::Value;
+
+    fn notify(replaces_id: u32, urgency: u8) -> zbus::Message {
+        let hints = HashMap::from([("urgency", Value::U8(urgency))]);
+
+        zbus::Message::method_call("/org/freedesktop/Notifications", "Notify")
+            .unwrap()
+            .interface(NOTIFICATIONS_INTERFACE)
+            .unwrap()
+            .sender(":1.5")
+            .unwrap()
+            .build(&(
+                "app",
+                replaces_id,
+                "",
+                "summary",
+                "body",
+                Vec::<&str>::new(),
+                hints,
+                -1,
+            ))
+            .unwrap()
+    }
+
+    fn received_id(event: Option<NotificationsEvent>) -> Option<u32> {
+        match event {
+            Some(NotificationsEvent::Received(id, _)) => Some(id),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn replaced_notifications_keep_their_id() {
+        let mut ids = Ids::default();
+
+        let call = notify(0, 2);
+        match NotificationsService::handle_message(&call, &mut ids) {
+            Some(NotificationsEvent::Received(id, notification)) => {
+                assert_eq!(id, 1);
+                assert_eq!(notification.urgency, Urgency::Critical);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        let reply = zbus::Message::method_return(&call.header())
+            .unwrap()
+            .build(&42u32)
+            .unwrap();
+        NotificationsService::handle_message(&reply, &mut ids);
+
+        assert_eq!(
+            received_id(NotificationsService::handle_message(
+                &notify(42, 1),
+                &mut ids
+            )),
+            Some(1)
+        );
+        assert_eq!(
+            received_id(NotificationsService::handle_message(
+                &notify(0, 1),
+                &mut ids
+            )),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn dnd_backend_of_the_daemon() {
+        assert_eq!(DndBackend::from_server_name("mako"), Some(DndBackend::Mako));
+        assert_eq!(
+            DndBackend::from_server_name("SwayNotificationCenter"),
+            Some(DndBackend::Swaync)
+        );
+        assert_eq!(DndBackend::from_server_name("Notification Daemon"), None);
+    }
+}
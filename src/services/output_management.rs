@@ -0,0 +1,436 @@
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use wayland_client::{
+    backend::ObjectId,
+    event_created_child,
+    protocol::{
+        wl_output::Transform,
+        wl_registry::{self, WlRegistry},
+    },
+    Connection, Dispatch, DispatchError, EventQueue, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols_wlr::output_management::v1::client::{
+    zwlr_output_configuration_head_v1::ZwlrOutputConfigurationHeadV1,
+    zwlr_output_configuration_v1::{self, ZwlrOutputConfigurationV1},
+    zwlr_output_head_v1::{self, AdaptiveSyncState, ZwlrOutputHeadV1},
+    zwlr_output_manager_v1::{self, ZwlrOutputManagerV1},
+    zwlr_output_mode_v1::{self, ZwlrOutputModeV1},
+};
+
+/// A mode supported by an output, the refresh rate is in mHz
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OutputMode {
+    pub width: i32,
+    pub height: i32,
+    pub refresh: i32,
+    pub preferred: bool,
+}
+
+impl OutputMode {
+    pub fn label(&self) -> String {
+        let refresh = if self.refresh % 1000 == 0 {
+            format!("{}", self.refresh / 1000)
+        } else {
+            format!("{:.2}", self.refresh as f32 / 1000.)
+        };
+
+        format!("{}×{} @ {} Hz", self.width, self.height, refresh)
+    }
+}
+
+/// Output configuration through the wlr-output-management protocol.
+///
+/// Like the idle inhibitor the events are only read when needed,
+/// call `refresh` before showing the outputs state.
+pub struct OutputManager {
+    _connection: Connection,
+    _registry: WlRegistry,
+    event_queue: EventQueue<OutputManagerData>,
+    handle: QueueHandle<OutputManagerData>,
+    data: OutputManagerData,
+}
+
+impl OutputManager {
+    pub fn new() -> Option<Self> {
+        let init = || -> anyhow::Result<Self> {
+            let connection = Connection::connect_to_env()?;
+            let event_queue = connection.new_event_queue();
+            let handle = event_queue.handle();
+            let registry = connection.display().get_registry(&handle, ());
+
+            let mut obj = Self {
+                _connection: connection,
+                _registry: registry,
+                event_queue,
+                handle,
+                data: OutputManagerData::default(),
+            };
+
+            // the first one binds the manager, the second one gets the heads
+            obj.roundtrip()?;
+            obj.roundtrip()?;
+
+            if obj.data.manager.is_none() {
+                anyhow::bail!("the compositor doesn't support wlr-output-management");
+            }
+
+            Ok(obj)
+        };
+
+        match init() {
+            Ok(obj) => Some(obj),
+            Err(err) => {
+                warn!("Failed to initialize the output manager: {}", err);
+                None
+            }
+        }
+    }
+
+    fn roundtrip(&mut self) -> Result<usize, DispatchError> {
+        self.event_queue.roundtrip(&mut self.data)
+    }
+
+    pub fn refresh(&mut self) {
+        if let Err(err) = self.roundtrip() {
+            warn!("Failed to read the outputs state: {}", err);
+        }
+    }
+
+    fn head(&self, output: &str) -> Option<&Head> {
+        self.data.heads.iter().find(|head| head.name == output)
+    }
+
+    pub fn current_mode(&self, output: &str) -> Option<OutputMode> {
+        self.head(output)?
+            .current_mode
+            .as_ref()
+            .and_then(|mode| self.data.modes.get(&mode.id()))
+            .copied()
+    }
+
+    /// Modes of the output, from the highest resolution and refresh rate
+    pub fn modes(&self, output: &str) -> Vec<OutputMode> {
+        let mut modes = self
+            .head(output)
+            .map(|head| {
+                head.modes
+                    .iter()
+                    .filter_map(|mode| self.data.modes.get(&mode.id()))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        modes.sort_by_key(|mode| {
+            std::cmp::Reverse((mode.width * mode.height, mode.width, mode.refresh))
+        });
+        modes.dedup_by_key(|mode| (mode.width, mode.height, mode.refresh));
+
+        modes
+    }
+
+    /// Variable refresh rate state, None if the output doesn't report it
+    pub fn adaptive_sync(&self, output: &str) -> Option<bool> {
+        self.head(output)?.adaptive_sync
+    }
+
+    /// Position in the compositor layout and scale of the output
+    pub fn layout(&self, output: &str) -> Option<((i32, i32), f64)> {
+        self.head(output).map(|head| (head.position, head.scale))
+    }
+
+    pub fn set_mode(&mut self, output: &str, mode: OutputMode) -> anyhow::Result<()> {
+        self.apply(output, OutputChange::Mode(mode))?;
+        info!("Output {} mode set to {}", output, mode.label());
+
+        Ok(())
+    }
+
+    pub fn set_adaptive_sync(&mut self, output: &str, enabled: bool) -> anyhow::Result<()> {
+        self.apply(output, OutputChange::AdaptiveSync(enabled))?;
+        info!("Output {} adaptive sync set to {}", output, enabled);
+
+        Ok(())
+    }
+
+    fn apply(&mut self, output: &str, change: OutputChange) -> anyhow::Result<()> {
+        // the configuration is refused if it's not based on the latest state
+        self.roundtrip()?;
+
+        let Some(manager) = self.data.manager.as_ref() else {
+            anyhow::bail!("the output manager is gone");
+        };
+        let configuration = manager.create_configuration(self.data.serial, &self.handle, ());
+
+        // every head has to be part of the configuration,
+        // the other ones keep their current state
+        for head in &self.data.heads {
+            if !head.enabled {
+                configuration.disable_head(&head.proxy);
+                continue;
+            }
+
+            let config_head = configuration.enable_head(&head.proxy, &self.handle, ());
+            let new_mode = match change {
+                OutputChange::Mode(mode) if head.name == output => head
+                    .modes
+                    .iter()
+                    .find(|m| self.data.modes.get(&m.id()) == Some(&mode)),
+                _ => None,
+            };
+            if let Some(mode) = new_mode.or(head.current_mode.as_ref()) {
+                config_head.set_mode(mode);
+            }
+            config_head.set_position(head.position.0, head.position.1);
+            config_head.set_scale(head.scale);
+            if let Some(transform) = head.transform {
+                config_head.set_transform(transform);
+            }
+            if let OutputChange::AdaptiveSync(enabled) = change {
+                if head.name == output {
+                    config_head.set_adaptive_sync(if enabled {
+                        AdaptiveSyncState::Enabled
+                    } else {
+                        AdaptiveSyncState::Disabled
+                    });
+                }
+            }
+        }
+
+        self.data.result = None;
+        configuration.apply();
+        while self.data.result.is_none() {
+            self.event_queue.blocking_dispatch(&mut self.data)?;
+        }
+        configuration.destroy();
+
+        if self.data.result == Some(true) {
+            Ok(())
+        } else {
+            anyhow::bail!("the compositor refused the configuration")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OutputChange {
+    Mode(OutputMode),
+    AdaptiveSync(bool),
+}
+
+struct Head {
+    proxy: ZwlrOutputHeadV1,
+    name: String,
+    enabled: bool,
+    modes: Vec<ZwlrOutputModeV1>,
+    current_mode: Option<ZwlrOutputModeV1>,
+    position: (i32, i32),
+    transform: Option<Transform>,
+    scale: f64,
+    // only reported since version 4 of the protocol
+    adaptive_sync: Option<bool>,
+}
+
+#[derive(Default)]
+struct OutputManagerData {
+    manager: Option<ZwlrOutputManagerV1>,
+    heads: Vec<Head>,
+    modes: HashMap<ObjectId, OutputMode>,
+    serial: u32,
+    // outcome of the last configuration applied
+    result: Option<bool>,
+}
+
+impl OutputManagerData {
+    fn head_mut(&mut self, proxy: &ZwlrOutputHeadV1) -> Option<&mut Head> {
+        self.heads.iter_mut().find(|head| head.proxy == *proxy)
+    }
+}
+
+impl Dispatch<WlRegistry, ()> for OutputManagerData {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == ZwlrOutputManagerV1::interface().name && state.manager.is_none() {
+                debug!(target: "OutputManager::WlRegistry::Event::Global", "Adding OutputManager with name {name} and version {version}");
+                state.manager = Some(proxy.bind(name, version.min(4), handle, ()));
+            }
+        }
+    }
+}
+
+impl Dispatch<ZwlrOutputManagerV1, ()> for OutputManagerData {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrOutputManagerV1,
+        event: <ZwlrOutputManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_output_manager_v1::Event::Head { head } => {
+                state.heads.push(Head {
+                    proxy: head,
+                    name: String::new(),
+                    enabled: false,
+                    modes: Vec::new(),
+                    current_mode: None,
+                    position: (0, 0),
+                    transform: None,
+                    scale: 1.,
+                    adaptive_sync: None,
+                });
+            }
+            zwlr_output_manager_v1::Event::Done { serial } => {
+                state.serial = serial;
+            }
+            zwlr_output_manager_v1::Event::Finished => {
+                state.manager = None;
+            }
+            _ => {}
+        }
+    }
+
+    event_created_child!(OutputManagerData, ZwlrOutputManagerV1, [
+        zwlr_output_manager_v1::EVT_HEAD_OPCODE => (ZwlrOutputHeadV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrOutputHeadV1, ()> for OutputManagerData {
+    fn event(
+        state: &mut Self,
+        proxy: &ZwlrOutputHeadV1,
+        event: <ZwlrOutputHeadV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let zwlr_output_head_v1::Event::Finished = event {
+            state.heads.retain(|head| head.proxy != *proxy);
+            return;
+        }
+        if let zwlr_output_head_v1::Event::Mode { mode } = &event {
+            state.modes.insert(mode.id(), OutputMode::default());
+        }
+
+        let Some(head) = state.head_mut(proxy) else {
+            return;
+        };
+        match event {
+            zwlr_output_head_v1::Event::Name { name } => head.name = name,
+            zwlr_output_head_v1::Event::Mode { mode } => head.modes.push(mode),
+            zwlr_output_head_v1::Event::Enabled { enabled } => head.enabled = enabled != 0,
+            zwlr_output_head_v1::Event::CurrentMode { mode } => head.current_mode = Some(mode),
+            zwlr_output_head_v1::Event::Position { x, y } => head.position = (x, y),
+            zwlr_output_head_v1::Event::Transform {
+                transform: WEnum::Value(transform),
+            } => head.transform = Some(transform),
+            zwlr_output_head_v1::Event::Scale { scale } => head.scale = scale,
+            zwlr_output_head_v1::Event::AdaptiveSync {
+                state: WEnum::Value(state),
+            } => head.adaptive_sync = Some(state == AdaptiveSyncState::Enabled),
+            _ => {}
+        }
+    }
+
+    event_created_child!(OutputManagerData, ZwlrOutputHeadV1, [
+        zwlr_output_head_v1::EVT_MODE_OPCODE => (ZwlrOutputModeV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrOutputModeV1, ()> for OutputManagerData {
+    fn event(
+        state: &mut Self,
+        proxy: &ZwlrOutputModeV1,
+        event: <ZwlrOutputModeV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let zwlr_output_mode_v1::Event::Finished = event {
+            state.modes.remove(&proxy.id());
+            for head in state.heads.iter_mut() {
+                head.modes.retain(|mode| mode != proxy);
+            }
+            return;
+        }
+
+        let Some(mode) = state.modes.get_mut(&proxy.id()) else {
+            return;
+        };
+        match event {
+            zwlr_output_mode_v1::Event::Size { width, height } => {
+                mode.width = width;
+                mode.height = height;
+            }
+            zwlr_output_mode_v1::Event::Refresh { refresh } => mode.refresh = refresh,
+            zwlr_output_mode_v1::Event::Preferred => mode.preferred = true,
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrOutputConfigurationV1, ()> for OutputManagerData {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrOutputConfigurationV1,
+        event: <ZwlrOutputConfigurationV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        state.result = match event {
+            zwlr_output_configuration_v1::Event::Succeeded => Some(true),
+            // cancelled when the outputs changed in the meantime
+            zwlr_output_configuration_v1::Event::Failed
+            | zwlr_output_configuration_v1::Event::Cancelled => Some(false),
+            _ => return,
+        };
+    }
+}
+
+impl Dispatch<ZwlrOutputConfigurationHeadV1, ()> for OutputManagerData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwlrOutputConfigurationHeadV1,
+        _event: <ZwlrOutputConfigurationHeadV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // This interface has no events.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mode_label() {
+        let mode = OutputMode {
+            width: 2560,
+            height: 1440,
+            refresh: 144000,
+            preferred: true,
+        };
+        assert_eq!(mode.label(), "2560×1440 @ 144 Hz");
+
+        let mode = OutputMode {
+            refresh: 59951,
+            ..mode
+        };
+        assert_eq!(mode.label(), "2560×1440 @ 59.95 Hz");
+    }
+}
@@ -0,0 +1,154 @@
+use super::{AuthRequest, PolkitEvent};
+use crate::utils::icons::resolve_icon;
+use log::{debug, warn};
+use std::{
+    collections::HashMap,
+    fs,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use zbus::{
+    fdo, interface, proxy,
+    zvariant::{OwnedValue, Value},
+};
+
+pub const AGENT_PATH: &str = "/org/ashell/PolicyKit1/AuthenticationAgent";
+
+/// Kind and details of a polkit identity
+type Identity = (String, HashMap<String, OwnedValue>);
+
+/// Authentication waiting for the user, resolved with the outcome
+#[derive(Debug)]
+pub struct PendingAuth {
+    pub user: String,
+    pub done: oneshot::Sender<bool>,
+}
+
+pub type PendingAuths = Arc<Mutex<HashMap<String, PendingAuth>>>;
+
+/// Name of the user with the given uid
+fn user_name(uid: u32) -> Option<String> {
+    fs::read_to_string("/etc/passwd")
+        .ok()?
+        .lines()
+        .find_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid_field = fields.nth(1)?;
+
+            (uid_field.parse::<u32>() == Ok(uid)).then(|| name.to_string())
+        })
+}
+
+pub struct AuthenticationAgent {
+    pub events: UnboundedSender<PolkitEvent>,
+    pub pending: PendingAuths,
+}
+
+#[interface(name = "org.freedesktop.PolicyKit1.AuthenticationAgent")]
+impl AuthenticationAgent {
+    async fn begin_authentication(
+        &self,
+        action_id: &str,
+        message: &str,
+        icon_name: &str,
+        _details: HashMap<String, String>,
+        cookie: &str,
+        identities: Vec<Identity>,
+    ) -> fdo::Result<()> {
+        debug!("Authentication requested for {}", action_id);
+
+        // prefer authenticating as the current user, otherwise as the first admin
+        let current_uid = current_uid();
+        let mut uids = identities
+            .iter()
+            .filter(|(kind, _)| kind == "unix-user")
+            .filter_map(|(_, details)| {
+                details
+                    .get("uid")
+                    .and_then(|uid| uid.downcast_ref::<u32>().ok())
+            })
+            .collect::<Vec<_>>();
+        uids.sort_by_key(|uid| Some(*uid) != current_uid);
+
+        let Some(user) = uids.first().and_then(|uid| user_name(*uid)) else {
+            warn!("No supported identity to authenticate {}", action_id);
+            return Err(fdo::Error::Failed("No supported identity".to_string()));
+        };
+
+        let (done, outcome) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(
+                cookie.to_string(),
+                PendingAuth {
+                    user: user.clone(),
+                    done,
+                },
+            );
+        }
+
+        let icon = if icon_name.is_empty() {
+            None
+        } else {
+            resolve_icon(icon_name.to_string()).await
+        };
+        let _ = self.events.send(PolkitEvent::Request(AuthRequest {
+            cookie: cookie.to_string(),
+            message: message.to_string(),
+            icon,
+            user,
+        }));
+
+        match outcome.await {
+            Ok(true) => Ok(()),
+            _ => Err(fdo::Error::Failed("Authentication cancelled".to_string())),
+        }
+    }
+
+    async fn cancel_authentication(&self, cookie: &str) {
+        debug!("Authentication cancelled by polkit");
+
+        if let Some(auth) = self
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(cookie))
+        {
+            let _ = auth.done.send(false);
+        }
+
+        let _ = self.events.send(PolkitEvent::Completed(cookie.to_string()));
+    }
+}
+
+/// Uid of the ashell process, read from procfs
+fn current_uid() -> Option<u32> {
+    fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))
+        .and_then(|uids| uids.split_whitespace().next())
+        .and_then(|uid| uid.parse().ok())
+}
+
+/// Subject identifying the session the agent is registered for
+pub fn session_subject(session_id: &str) -> (&str, HashMap<&str, Value<'_>>) {
+    (
+        "unix-session",
+        HashMap::from([("session-id", Value::from(session_id))]),
+    )
+}
+
+#[proxy(
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority",
+    interface = "org.freedesktop.PolicyKit1.Authority"
+)]
+pub trait Authority {
+    fn register_authentication_agent(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        locale: &str,
+        object_path: &str,
+    ) -> zbus::Result<()>;
+}
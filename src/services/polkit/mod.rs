@@ -0,0 +1,222 @@
+use super::{logind::current_session_id, ReadOnlyService, Service, ServiceEvent};
+use crate::utils::icons::AppIcon;
+use dbus::{session_subject, AuthenticationAgent, AuthorityProxy, PendingAuths, AGENT_PATH};
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription, Task,
+};
+use log::{debug, error, info, warn};
+use std::{any::TypeId, env, path::Path, process::Stdio};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::Command,
+    sync::mpsc::{unbounded_channel, UnboundedSender},
+};
+
+mod dbus;
+
+const HELPER_PATHS: [&str; 3] = [
+    "/usr/lib/polkit-1/polkit-agent-helper-1",
+    "/usr/libexec/polkit-agent-helper-1",
+    "/usr/lib/policykit-1/polkit-agent-helper-1",
+];
+
+#[derive(Debug, Clone)]
+pub struct AuthRequest {
+    pub cookie: String,
+    pub message: String,
+    // resolved before the request is shown, the dialog only draws it
+    pub icon: Option<AppIcon>,
+    pub user: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PolkitEvent {
+    Request(AuthRequest),
+    /// The password was wrong, the request is still pending
+    Failed(String),
+    Completed(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum PolkitCommand {
+    Authenticate { cookie: String, password: String },
+    Cancel(String),
+}
+
+/// Authentication agent answering the polkit requests of the current session
+#[derive(Debug, Clone)]
+pub struct PolkitService {
+    pending: PendingAuths,
+}
+
+impl PolkitService {
+    async fn register(
+        events: UnboundedSender<PolkitEvent>,
+        pending: PendingAuths,
+    ) -> anyhow::Result<zbus::Connection> {
+        let conn = zbus::Connection::system().await?;
+        conn.object_server()
+            .at(AGENT_PATH, AuthenticationAgent { events, pending })
+            .await?;
+
+        let session_id = current_session_id(&conn).await?;
+        let locale = env::var("LANG").unwrap_or_else(|_| "C".to_string());
+
+        AuthorityProxy::new(&conn)
+            .await?
+            .register_authentication_agent(&session_subject(&session_id), &locale, AGENT_PATH)
+            .await?;
+
+        Ok(conn)
+    }
+
+    /// Runs the setuid polkit helper that checks the password through PAM
+    async fn authenticate(user: &str, cookie: &str, password: &str) -> anyhow::Result<bool> {
+        let helper = HELPER_PATHS
+            .iter()
+            .find(|path| Path::new(path).exists())
+            .ok_or_else(|| anyhow::anyhow!("polkit-agent-helper-1 not found"))?;
+
+        let mut child = Command::new(helper)
+            .arg(user)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Missing helper stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Missing helper stdout"))?;
+
+        stdin.write_all(format!("{}\n", cookie).as_bytes()).await?;
+
+        let mut lines = BufReader::new(stdout).lines();
+        let mut success = false;
+        while let Some(line) = lines.next_line().await? {
+            if line.starts_with("PAM_PROMPT_ECHO") {
+                stdin
+                    .write_all(format!("{}\n", password).as_bytes())
+                    .await?;
+            } else if line.starts_with("PAM_ERROR_MSG") || line.starts_with("PAM_TEXT_INFO") {
+                debug!("polkit helper: {}", line);
+            } else if line == "SUCCESS" {
+                success = true;
+                break;
+            } else if line == "FAILURE" {
+                break;
+            }
+        }
+
+        let _ = child.wait().await;
+
+        Ok(success)
+    }
+}
+
+impl ReadOnlyService for PolkitService {
+    type UpdateEvent = PolkitEvent;
+    type Error = ();
+
+    fn update(&mut self, _event: Self::UpdateEvent) {}
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let (tx, mut rx) = unbounded_channel();
+                let auths = PendingAuths::default();
+
+                match PolkitService::register(tx, auths.clone()).await {
+                    // the connection serves the agent object as long as it lives
+                    Ok(_conn) => {
+                        info!("Polkit authentication agent registered");
+
+                        let _ = output
+                            .send(ServiceEvent::Init(PolkitService { pending: auths }))
+                            .await;
+
+                        while let Some(event) = rx.recv().await {
+                            let _ = output.send(ServiceEvent::Update(event)).await;
+                        }
+                    }
+                    Err(err) => {
+                        error!(
+                            "Failed to register the polkit authentication agent: {}",
+                            err
+                        );
+
+                        let _ = output.send(ServiceEvent::Error(())).await;
+                    }
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+impl Service for PolkitService {
+    type Command = PolkitCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        match command {
+            PolkitCommand::Authenticate { cookie, password } => {
+                let Some(user) = self
+                    .pending
+                    .lock()
+                    .ok()
+                    .and_then(|pending| pending.get(&cookie).map(|auth| auth.user.clone()))
+                else {
+                    return Task::done(ServiceEvent::Update(PolkitEvent::Completed(cookie)));
+                };
+                let pending = self.pending.clone();
+
+                Task::perform(
+                    async move {
+                        let success = PolkitService::authenticate(&user, &cookie, &password)
+                            .await
+                            .unwrap_or_else(|err| {
+                                warn!("Polkit authentication error: {}", err);
+                                false
+                            });
+
+                        if success {
+                            if let Some(auth) = pending
+                                .lock()
+                                .ok()
+                                .and_then(|mut pending| pending.remove(&cookie))
+                            {
+                                let _ = auth.done.send(true);
+                            }
+
+                            PolkitEvent::Completed(cookie)
+                        } else {
+                            PolkitEvent::Failed(cookie)
+                        }
+                    },
+                    ServiceEvent::Update,
+                )
+            }
+            PolkitCommand::Cancel(cookie) => {
+                if let Some(auth) = self
+                    .pending
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.remove(&cookie))
+                {
+                    let _ = auth.done.send(false);
+                }
+
+                Task::done(ServiceEvent::Update(PolkitEvent::Completed(cookie)))
+            }
+        }
+    }
+}
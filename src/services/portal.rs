@@ -0,0 +1,110 @@
+use iced::futures::StreamExt;
+use log::warn;
+use std::collections::HashMap;
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue, Value},
+};
+
+// the request and session paths contain the unique name of the caller
+pub fn sender(conn: &zbus::Connection) -> String {
+    conn.unique_name()
+        .map(|name| name.trim_start_matches(':').replace('.', "_"))
+        .unwrap_or_default()
+}
+
+/// The portal replies to each call with a Response signal on a request object,
+/// its path is known in advance so the signal can't be missed.
+/// Returns the results of the request, None if the user cancelled it
+pub async fn request<F>(
+    conn: &zbus::Connection,
+    token: &str,
+    call: F,
+) -> anyhow::Result<Option<HashMap<String, OwnedValue>>>
+where
+    F: std::future::Future<Output = zbus::Result<OwnedObjectPath>>,
+{
+    let request = RequestProxy::builder(conn)
+        .path(format!(
+            "/org/freedesktop/portal/desktop/request/{}/{}",
+            sender(conn),
+            token
+        ))?
+        .build()
+        .await?;
+    let mut responses = request.receive_response().await?;
+
+    call.await?;
+
+    let response = responses
+        .next()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("no response from the portal"))?;
+    let args = response.args()?;
+    match args.response {
+        0 => Ok(Some(
+            args.results
+                .iter()
+                .filter_map(|(key, value)| Some((key.to_string(), value.try_to_owned().ok()?)))
+                .collect(),
+        )),
+        1 => Ok(None),
+        code => anyhow::bail!("the portal request failed ({})", code),
+    }
+}
+
+/// Asks the user for a file with the file chooser portal, returns its uri
+pub async fn pick_file(title: &str) -> Option<String> {
+    let pick = async {
+        let conn = zbus::Connection::session().await?;
+        let chooser = FileChooserProxy::new(&conn).await?;
+        let token = format!("ashell_open_file_{}", rand::random::<u32>());
+
+        let results = request(
+            &conn,
+            &token,
+            chooser.open_file(
+                "",
+                title,
+                HashMap::from([("handle_token", Value::from(token.as_str()))]),
+            ),
+        )
+        .await?;
+
+        anyhow::Ok(
+            results
+                .and_then(|results| {
+                    Vec::<String>::try_from(results.get("uris")?.try_clone().ok()?).ok()
+                })
+                .and_then(|uris| uris.into_iter().next()),
+        )
+    };
+
+    pick.await.unwrap_or_else(|err| {
+        warn!("Failed to pick a file: {}", err);
+        None
+    })
+}
+
+#[proxy(
+    default_service = "org.freedesktop.portal.Desktop",
+    interface = "org.freedesktop.portal.Request"
+)]
+trait Request {
+    #[zbus(signal)]
+    fn response(&self, response: u32, results: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    interface = "org.freedesktop.portal.FileChooser"
+)]
+trait FileChooser {
+    fn open_file(
+        &self,
+        parent_window: &str,
+        title: &str,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<OwnedObjectPath>;
+}
@@ -0,0 +1,77 @@
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info};
+use std::any::TypeId;
+use zbus::{proxy, zvariant::OwnedValue};
+
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const CONTRAST_KEY: &str = "contrast";
+
+/// High contrast preference of the desktop, read from the settings portal
+pub struct ContrastPreference;
+
+impl ContrastPreference {
+    // 0 no preference, 1 higher contrast
+    fn is_high(value: &OwnedValue) -> bool {
+        u32::try_from(value).is_ok_and(|value| value == 1)
+    }
+
+    async fn listen(output: &mut Sender<bool>) -> zbus::Result<()> {
+        let conn = zbus::Connection::session().await?;
+        let proxy = PortalSettingsProxy::new(&conn).await?;
+
+        let mut changes = proxy.receive_setting_changed().await?;
+
+        let value = proxy.read_one(APPEARANCE_NAMESPACE, CONTRAST_KEY).await?;
+        info!("Portal contrast preference: {:?}", value);
+        let _ = output.send(Self::is_high(&value)).await;
+
+        while let Some(change) = changes.next().await {
+            if let Ok(args) = change.args() {
+                if args.namespace == APPEARANCE_NAMESPACE && args.key == CONTRAST_KEY {
+                    if let Ok(value) = OwnedValue::try_from(args.value) {
+                        info!("Portal contrast preference changed: {:?}", value);
+                        let _ = output.send(Self::is_high(&value)).await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn subscribe() -> Subscription<bool> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                if let Err(err) = ContrastPreference::listen(&mut output).await {
+                    debug!("Contrast preference not available: {}", err);
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop",
+    interface = "org.freedesktop.portal.Settings"
+)]
+trait PortalSettings {
+    fn read_one(&self, namespace: &str, key: &str) -> zbus::Result<OwnedValue>;
+
+    #[zbus(signal)]
+    fn setting_changed(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: zbus::zvariant::Value<'_>,
+    ) -> zbus::Result<()>;
+}
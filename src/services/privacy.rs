@@ -1,4 +1,5 @@
 use super::{ReadOnlyService, ServiceEvent};
+use chrono::{DateTime, Local};
 use iced::{
     futures::{
         channel::mpsc::Sender, select, stream::pending, FutureExt, SinkExt, Stream, StreamExt,
@@ -8,11 +9,22 @@ use iced::{
 };
 use inotify::{EventMask, Inotify, WatchMask};
 use log::{debug, error, info, warn};
-use pipewire::{context::Context, main_loop::MainLoop};
-use std::{any::TypeId, fs, ops::Deref, path::Path, thread};
+use pipewire::{context::Context, main_loop::MainLoop, types::ObjectType};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    fs,
+    ops::{Deref, DerefMut},
+    path::Path,
+    rc::Rc,
+    thread,
+};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
 const WEBCAM_DEVICE_PATH: &str = "/dev/video0";
+// capture sessions kept in the privacy menu
+const MAX_SESSIONS: usize = 20;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Media {
@@ -20,23 +32,210 @@ pub enum Media {
     Audio,
 }
 
+impl Media {
+    // only the video input streams reading a screen cast are tracked
+    fn capture_device(self) -> CaptureDevice {
+        match self {
+            Media::Video => CaptureDevice::Screen,
+            Media::Audio => CaptureDevice::Microphone,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ApplicationNode {
     pub id: u32,
     pub media: Media,
+    pub app: String,
+}
+
+/// Video input streams are told apart by what they read, only the ones linked to
+/// a screen cast source of the portal are screen shares, the others read a camera
+#[derive(Debug, Default)]
+struct VideoStreams {
+    // screen cast sources with their object serial
+    sources: HashMap<u32, Option<String>>,
+    // streams not linked to a screen cast yet, with their target
+    pending: HashMap<u32, (String, Option<String>)>,
+}
+
+impl VideoStreams {
+    fn is_source(&self, target: &str) -> bool {
+        self.sources
+            .iter()
+            .any(|(id, serial)| id.to_string() == target || serial.as_deref() == Some(target))
+    }
+
+    fn screen_share(id: u32, app: String) -> ApplicationNode {
+        ApplicationNode {
+            id,
+            media: Media::Video,
+            app,
+        }
+    }
+
+    fn add_source(&mut self, id: u32, serial: Option<String>) -> Vec<ApplicationNode> {
+        self.sources.insert(id, serial);
+
+        let streams = self
+            .pending
+            .iter()
+            .filter(|(_, (_, target))| target.as_deref().is_some_and(|t| self.is_source(t)))
+            .map(|(stream, _)| *stream)
+            .collect::<Vec<_>>();
+
+        streams
+            .into_iter()
+            .filter_map(|stream| self.pending.remove_entry(&stream))
+            .map(|(stream, (app, _))| Self::screen_share(stream, app))
+            .collect()
+    }
+
+    fn add_stream(
+        &mut self,
+        id: u32,
+        app: String,
+        target: Option<String>,
+    ) -> Option<ApplicationNode> {
+        if target
+            .as_deref()
+            .is_some_and(|target| self.is_source(target))
+        {
+            Some(Self::screen_share(id, app))
+        } else {
+            self.pending.insert(id, (app, target));
+            None
+        }
+    }
+
+    fn link(&mut self, output: u32, input: u32) -> Option<ApplicationNode> {
+        if !self.sources.contains_key(&output) {
+            return None;
+        }
+
+        self.pending
+            .remove(&input)
+            .map(|(app, _)| Self::screen_share(input, app))
+    }
+
+    fn remove(&mut self, id: u32) {
+        self.sources.remove(&id);
+        self.pending.remove(&id);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CaptureDevice {
+    Microphone,
+    Camera,
+    Screen,
+}
+
+/// An application capturing from a device, from when it started to when it stopped
+#[derive(Debug, Clone)]
+pub struct CaptureSession {
+    // pipewire node of the stream, None for the webcam
+    node: Option<u32>,
+    pub app: String,
+    pub device: CaptureDevice,
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct PrivacyData {
     nodes: Vec<ApplicationNode>,
     webcam_access: i32,
+    sessions: VecDeque<CaptureSession>,
 }
 
 impl PrivacyData {
     fn new() -> Self {
         Self {
             nodes: Vec::new(),
-            webcam_access: is_device_in_use(WEBCAM_DEVICE_PATH),
+            webcam_access: device_users(WEBCAM_DEVICE_PATH).len() as i32,
+            sessions: VecDeque::new(),
+        }
+    }
+
+    /// The capture sessions, the most recent first
+    pub fn sessions(&self) -> impl Iterator<Item = &CaptureSession> {
+        self.sessions.iter().rev()
+    }
+
+    /// Forgets the sessions that are over
+    pub fn clear_history(&mut self) {
+        self.sessions.retain(|session| session.end.is_none());
+    }
+
+    fn start_session(
+        &mut self,
+        node: Option<u32>,
+        app: String,
+        device: CaptureDevice,
+        now: DateTime<Local>,
+    ) {
+        self.sessions.push_back(CaptureSession {
+            node,
+            app,
+            device,
+            start: now,
+            end: None,
+        });
+
+        while self.sessions.len() > MAX_SESSIONS {
+            match self
+                .sessions
+                .iter()
+                .position(|session| session.end.is_some())
+            {
+                Some(index) => {
+                    self.sessions.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn end_session(&mut self, node: Option<u32>, device: CaptureDevice, now: DateTime<Local>) {
+        if let Some(session) = self.sessions.iter_mut().find(|session| {
+            session.node == node && session.device == device && session.end.is_none()
+        }) {
+            session.end = Some(now);
+        }
+    }
+
+    fn record(&mut self, event: PrivacyEvent, now: DateTime<Local>) {
+        match event {
+            PrivacyEvent::AddNode(node) => {
+                self.start_session(
+                    Some(node.id),
+                    node.app.clone(),
+                    node.media.capture_device(),
+                    now,
+                );
+                self.nodes.push(node);
+            }
+            PrivacyEvent::RemoveNode(id) => {
+                if let Some(node) = self.nodes.iter().find(|n| n.id == id) {
+                    self.end_session(Some(id), node.media.capture_device(), now);
+                }
+                self.nodes.retain(|n| n.id != id);
+            }
+            PrivacyEvent::WebcamOpen(app) => {
+                if self.webcam_access == 0 {
+                    self.start_session(None, app, CaptureDevice::Camera, now);
+                }
+                self.webcam_access += 1;
+                debug!("Webcam opened {}", self.webcam_access);
+            }
+            PrivacyEvent::WebcamClose => {
+                self.webcam_access = i32::max(self.webcam_access - 1, 0);
+                if self.webcam_access == 0 {
+                    self.end_session(None, CaptureDevice::Camera, now);
+                }
+                debug!("Webcam closed {}", self.webcam_access);
+            }
         }
     }
 
@@ -55,6 +254,23 @@ impl PrivacyData {
     pub fn screenshare_access(&self) -> bool {
         self.nodes.iter().any(|n| n.media == Media::Video)
     }
+
+    /// Pipewire streams of the applications receiving a screen cast
+    pub fn screenshare_nodes(&self) -> Vec<u32> {
+        self.nodes
+            .iter()
+            .filter(|n| n.media == Media::Video)
+            .map(|n| n.id)
+            .collect()
+    }
+
+    pub fn screenshare_apps(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .filter(|n| n.media == Media::Video)
+            .map(|n| n.app.as_str())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -70,6 +286,12 @@ impl Deref for PrivacyService {
     }
 }
 
+impl DerefMut for PrivacyService {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
 impl PrivacyService {
     async fn create_pipewire_listener() -> anyhow::Result<UnboundedReceiver<PrivacyEvent>> {
         let (tx, rx) = unbounded_channel::<PrivacyEvent>();
@@ -80,25 +302,70 @@ impl PrivacyService {
             let core = context.connect(None).unwrap();
             let registry = core.get_registry().unwrap();
 
+            let video = Rc::new(RefCell::new(VideoStreams::default()));
+
             let _listener = registry
                 .add_listener_local()
                 .global({
                     let tx = tx.clone();
+                    let video = video.clone();
                     move |global| {
-                        if let Some(props) = global.props {
-                            if let Some(media) = props.get("media.class").filter(|v| {
-                                v == &"Stream/Input/Video" || v == &"Stream/Input/Audio"
-                            }) {
-                                debug!("New global: {:?}", global);
-                                let _ = tx.send(PrivacyEvent::AddNode(ApplicationNode {
+                        let Some(props) = global.props else {
+                            return;
+                        };
+                        let app = || {
+                            props
+                                .get("application.name")
+                                .or_else(|| props.get("node.name"))
+                                .unwrap_or_default()
+                                .to_string()
+                        };
+
+                        let nodes = match (&global.type_, props.get("media.class")) {
+                            (ObjectType::Node, Some("Stream/Input/Audio")) => {
+                                vec![ApplicationNode {
                                     id: global.id,
-                                    media: if media == "Stream/Input/Video" {
-                                        Media::Video
-                                    } else {
-                                        Media::Audio
-                                    },
-                                }));
+                                    media: Media::Audio,
+                                    app: app(),
+                                }]
+                            }
+                            (ObjectType::Node, Some("Stream/Input/Video")) => {
+                                let target = props
+                                    .get("target.object")
+                                    .or_else(|| props.get("node.target"))
+                                    .map(str::to_owned);
+
+                                video
+                                    .borrow_mut()
+                                    .add_stream(global.id, app(), target)
+                                    .into_iter()
+                                    .collect()
+                            }
+                            // cameras are devices, the portal screen casts are client streams
+                            (ObjectType::Node, Some("Video/Source"))
+                                if props.get("device.id").is_none() =>
+                            {
+                                video.borrow_mut().add_source(
+                                    global.id,
+                                    props.get("object.serial").map(str::to_owned),
+                                )
+                            }
+                            (ObjectType::Link, _) => {
+                                let node = |key| props.get(key).and_then(|id| id.parse().ok());
+
+                                match (node("link.output.node"), node("link.input.node")) {
+                                    (Some(output), Some(input)) => {
+                                        video.borrow_mut().link(output, input).into_iter().collect()
+                                    }
+                                    _ => Vec::new(),
+                                }
                             }
+                            _ => Vec::new(),
+                        };
+
+                        for node in nodes {
+                            debug!("New capture stream: {:?}", node);
+                            let _ = tx.send(PrivacyEvent::AddNode(node));
                         }
                     }
                 })
@@ -106,6 +373,7 @@ impl PrivacyService {
                     let tx = tx.clone();
                     move |id| {
                         debug!("Remove global: {}", id);
+                        video.borrow_mut().remove(id);
                         let _ = tx.send(PrivacyEvent::RemoveNode(id));
                     }
                 })
@@ -141,7 +409,9 @@ impl PrivacyService {
                         if let Ok(event) = event {
                             debug!("Webcam event: {:?}", event);
                             match event.mask {
-                                EventMask::OPEN => Some(PrivacyEvent::WebcamOpen),
+                                EventMask::OPEN => Some(PrivacyEvent::WebcamOpen(
+                                    device_users(WEBCAM_DEVICE_PATH).pop().unwrap_or_default(),
+                                )),
                                 EventMask::CLOSE_WRITE | EventMask::CLOSE_NOWRITE => {
                                     Some(PrivacyEvent::WebcamClose)
                                 }
@@ -242,7 +512,8 @@ enum State {
 pub enum PrivacyEvent {
     AddNode(ApplicationNode),
     RemoveNode(u32),
-    WebcamOpen,
+    // the name of the process that opened it
+    WebcamOpen(String),
     WebcamClose,
 }
 
@@ -251,22 +522,7 @@ impl ReadOnlyService for PrivacyService {
     type Error = ();
 
     fn update(&mut self, event: Self::UpdateEvent) {
-        match event {
-            PrivacyEvent::AddNode(node) => {
-                self.data.nodes.push(node);
-            }
-            PrivacyEvent::RemoveNode(id) => {
-                self.data.nodes.retain(|n| n.id != id);
-            }
-            PrivacyEvent::WebcamOpen => {
-                self.data.webcam_access += 1;
-                debug!("Webcam opened {}", self.data.webcam_access);
-            }
-            PrivacyEvent::WebcamClose => {
-                self.data.webcam_access = i32::max(self.data.webcam_access - 1, 0);
-                debug!("Webcam closed {}", self.data.webcam_access);
-            }
-        }
+        self.data.record(event, Local::now());
     }
 
     fn subscribe() -> Subscription<ServiceEvent<Self>> {
@@ -285,8 +541,9 @@ impl ReadOnlyService for PrivacyService {
     }
 }
 
-fn is_device_in_use(target: &str) -> i32 {
-    let mut used_by = 0;
+/// Names of the processes with the device open
+fn device_users(target: &str) -> Vec<String> {
+    let mut used_by = Vec::new();
     if let Ok(entries) = fs::read_dir("/proc") {
         for entry in entries.flatten() {
             let pid_path = entry.path();
@@ -301,7 +558,11 @@ fn is_device_in_use(target: &str) -> i32 {
                 for fd_entry in fd_entries.flatten() {
                     if let Ok(link_path) = fs::read_link(fd_entry.path()) {
                         if link_path == Path::new(target) {
-                            used_by += 1;
+                            used_by.push(
+                                fs::read_to_string(pid_path.join("comm"))
+                                    .map(|comm| comm.trim().to_string())
+                                    .unwrap_or_default(),
+                            );
                         }
                     }
                 }
@@ -311,3 +572,72 @@ fn is_device_in_use(target: &str) -> i32 {
 
     used_by
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn data() -> PrivacyData {
+        PrivacyData {
+            nodes: Vec::new(),
+            webcam_access: 0,
+            sessions: VecDeque::new(),
+        }
+    }
+
+    #[test]
+    fn records_the_capture_sessions() {
+        let mut data = data();
+        let now = Local::now();
+
+        data.record(
+            PrivacyEvent::AddNode(ApplicationNode {
+                id: 42,
+                media: Media::Audio,
+                app: "Firefox".to_string(),
+            }),
+            now,
+        );
+        data.record(PrivacyEvent::WebcamOpen("zoom".to_string()), now);
+        data.record(PrivacyEvent::WebcamOpen("zoom".to_string()), now);
+        data.record(PrivacyEvent::WebcamClose, now + TimeDelta::seconds(5));
+        data.record(PrivacyEvent::RemoveNode(42), now + TimeDelta::seconds(10));
+
+        let sessions = data.sessions().collect::<Vec<_>>();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].device, CaptureDevice::Camera);
+        // the webcam is still open once
+        assert_eq!(sessions[0].end, None);
+        assert_eq!(sessions[1].app, "Firefox");
+        assert_eq!(sessions[1].end, Some(now + TimeDelta::seconds(10)));
+
+        data.clear_history();
+        assert_eq!(data.sessions().count(), 1);
+    }
+
+    #[test]
+    fn only_the_streams_of_a_screen_cast_are_screen_shares() {
+        let mut video = VideoStreams::default();
+
+        // a camera stream is never linked to a screen cast source
+        assert!(video.add_stream(10, "zoom".to_string(), None).is_none());
+        assert!(video.link(3, 10).is_none());
+
+        assert!(video.add_source(20, Some("77".to_string())).is_empty());
+        let share = video.link(20, 10).unwrap();
+        assert_eq!((share.id, share.app.as_str()), (10, "zoom"));
+
+        let share = video
+            .add_stream(11, "obs".to_string(), Some("77".to_string()))
+            .unwrap();
+        assert_eq!(share.id, 11);
+
+        assert!(video
+            .add_stream(12, "firefox".to_string(), Some("30".to_string()))
+            .is_none());
+        let shares = video.add_source(30, None);
+        assert_eq!(shares.len(), 1);
+        assert_eq!(shares[0].id, 12);
+    }
+}
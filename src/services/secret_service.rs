@@ -0,0 +1,207 @@
+use iced::futures::StreamExt;
+use log::debug;
+use std::{collections::HashMap, time::Duration};
+use tokio::time::timeout;
+use zbus::{
+    proxy,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value},
+};
+
+// the user can take a while to type the keyring password
+const UNLOCK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Session, parameters, value and content type
+type Secret = (OwnedObjectPath, Vec<u8>, Vec<u8>, String);
+
+async fn open_session(
+    conn: &zbus::Connection,
+) -> anyhow::Result<(SecretServiceProxy<'static>, OwnedObjectPath)> {
+    let service = SecretServiceProxy::new(conn).await?;
+    // the secrets never leave the session bus
+    let (_, session) = service
+        .open_session("plain", &Value::from("").try_to_owned()?)
+        .await?;
+
+    Ok((service, session))
+}
+
+/// Runs the prompt returned by the service, if any, and waits for its completion
+async fn complete_prompt(conn: &zbus::Connection, prompt: OwnedObjectPath) -> anyhow::Result<()> {
+    if prompt.as_str() == "/" {
+        return Ok(());
+    }
+
+    let prompt = PromptProxy::builder(conn).path(prompt)?.build().await?;
+    let mut completed = prompt.receive_completed().await?;
+    prompt.prompt("").await?;
+
+    let dismissed = timeout(UNLOCK_TIMEOUT, completed.next())
+        .await?
+        .map(|signal| signal.args().map(|args| args.dismissed).unwrap_or(true))
+        .unwrap_or(true);
+
+    if dismissed {
+        Err(anyhow::anyhow!("The keyring prompt was dismissed"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Stores a secret in the default collection, replacing the one with the same attributes
+pub async fn store_secret(
+    label: &str,
+    attributes: HashMap<&str, &str>,
+    secret: &str,
+) -> anyhow::Result<()> {
+    let conn = zbus::Connection::session().await?;
+    let (service, session) = open_session(&conn).await?;
+
+    let collection = service.read_alias("default").await?;
+    let (unlocked, prompt) = service.unlock(&[collection.as_ref()]).await?;
+    if unlocked.is_empty() {
+        complete_prompt(&conn, prompt).await?;
+    }
+
+    let collection = CollectionProxy::builder(&conn)
+        .path(collection)?
+        .build()
+        .await?;
+    let properties: HashMap<&str, OwnedValue> = HashMap::from([
+        (
+            "org.freedesktop.Secret.Item.Label",
+            Value::from(label).try_to_owned()?,
+        ),
+        (
+            "org.freedesktop.Secret.Item.Attributes",
+            Value::from(attributes).try_to_owned()?,
+        ),
+    ]);
+    let (_, prompt) = collection
+        .create_item(
+            properties,
+            &(
+                session,
+                vec![],
+                secret.as_bytes().to_vec(),
+                "text/plain".to_string(),
+            ),
+            true,
+        )
+        .await?;
+    complete_prompt(&conn, prompt).await?;
+
+    Ok(())
+}
+
+/// Looks up the secret matching the attributes, unlocking it if needed
+pub async fn lookup_secret(attributes: HashMap<&str, &str>) -> anyhow::Result<Option<String>> {
+    let conn = zbus::Connection::session().await?;
+    let (service, session) = open_session(&conn).await?;
+
+    let (mut unlocked, locked) = service.search_items(attributes).await?;
+    if unlocked.is_empty() && !locked.is_empty() {
+        debug!("Unlocking the keyring items {:?}", locked);
+
+        let locked = locked.iter().map(|item| item.as_ref()).collect::<Vec<_>>();
+        let (items, prompt) = service.unlock(&locked).await?;
+        if items.is_empty() {
+            complete_prompt(&conn, prompt).await?;
+            unlocked = locked.into_iter().map(OwnedObjectPath::from).collect();
+        } else {
+            unlocked = items;
+        }
+    }
+
+    let Some(item) = unlocked.first() else {
+        return Ok(None);
+    };
+
+    let secrets = service.get_secrets(&[item.as_ref()], &session).await?;
+
+    Ok(secrets
+        .into_values()
+        .next()
+        .and_then(|(_, _, value, _)| String::from_utf8(value).ok()))
+}
+
+/// Deletes the secrets matching the attributes
+pub async fn delete_secret(attributes: HashMap<&str, &str>) -> anyhow::Result<()> {
+    let conn = zbus::Connection::session().await?;
+    let (service, _) = open_session(&conn).await?;
+
+    let (unlocked, locked) = service.search_items(attributes).await?;
+    for item in unlocked.into_iter().chain(locked) {
+        let prompt = ItemProxy::builder(&conn)
+            .path(item)?
+            .build()
+            .await?
+            .delete()
+            .await?;
+        complete_prompt(&conn, prompt).await?;
+    }
+
+    Ok(())
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    default_path = "/org/freedesktop/secrets",
+    interface = "org.freedesktop.Secret.Service"
+)]
+trait SecretService {
+    fn open_session(
+        &self,
+        algorithm: &str,
+        input: &OwnedValue,
+    ) -> zbus::Result<(OwnedValue, OwnedObjectPath)>;
+
+    fn read_alias(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+
+    fn search_items(
+        &self,
+        attributes: HashMap<&str, &str>,
+    ) -> zbus::Result<(Vec<OwnedObjectPath>, Vec<OwnedObjectPath>)>;
+
+    fn unlock(
+        &self,
+        objects: &[ObjectPath<'_>],
+    ) -> zbus::Result<(Vec<OwnedObjectPath>, OwnedObjectPath)>;
+
+    fn get_secrets(
+        &self,
+        items: &[ObjectPath<'_>],
+        session: &ObjectPath<'_>,
+    ) -> zbus::Result<HashMap<OwnedObjectPath, Secret>>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    interface = "org.freedesktop.Secret.Collection"
+)]
+trait Collection {
+    fn create_item(
+        &self,
+        properties: HashMap<&str, OwnedValue>,
+        secret: &Secret,
+        replace: bool,
+    ) -> zbus::Result<(OwnedObjectPath, OwnedObjectPath)>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    interface = "org.freedesktop.Secret.Prompt"
+)]
+trait Prompt {
+    fn prompt(&self, window_id: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn completed(&self, dismissed: bool, result: Value<'_>) -> zbus::Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.secrets",
+    interface = "org.freedesktop.Secret.Item"
+)]
+trait Item {
+    fn delete(&self) -> zbus::Result<OwnedObjectPath>;
+}
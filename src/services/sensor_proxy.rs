@@ -0,0 +1,309 @@
+use super::{ReadOnlyService, ServiceEvent};
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{error, info};
+use std::{any::TypeId, ops::Deref};
+use zbus::proxy;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Normal,
+    BottomUp,
+    LeftUp,
+    RightUp,
+}
+
+impl Orientation {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "normal" => Some(Self::Normal),
+            "bottom-up" => Some(Self::BottomUp),
+            "left-up" => Some(Self::LeftUp),
+            "right-up" => Some(Self::RightUp),
+            // "undefined" while the device lies flat
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::BottomUp => "bottom-up",
+            Self::LeftUp => "left-up",
+            Self::RightUp => "right-up",
+        }
+    }
+
+    /// The wl_output transform matching the orientation
+    pub fn transform(self) -> u8 {
+        match self {
+            Self::Normal => 0,
+            Self::LeftUp => 1,
+            Self::BottomUp => 2,
+            Self::RightUp => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SensorProxyData {
+    pub orientation: Orientation,
+}
+
+/// Accelerometer orientation reported by iio-sensor-proxy
+#[derive(Debug, Clone)]
+pub struct SensorProxyService {
+    data: SensorProxyData,
+}
+
+impl Deref for SensorProxyService {
+    type Target = SensorProxyData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl SensorProxyService {
+    async fn initialize(conn: zbus::Connection, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        let proxy = match SensorProxyProxy::new(&conn).await {
+            Ok(proxy) => proxy,
+            Err(err) => {
+                error!("Failed to connect to iio-sensor-proxy: {}", err);
+
+                return State::Error;
+            }
+        };
+
+        if !proxy.has_accelerometer().await.unwrap_or_default() {
+            info!("No accelerometer available");
+
+            return State::Error;
+        }
+
+        // the readings are only updated while the accelerometer is claimed
+        if let Err(err) = proxy.claim_accelerometer().await {
+            error!("Failed to claim the accelerometer: {}", err);
+
+            return State::Error;
+        }
+
+        let orientation = proxy
+            .accelerometer_orientation()
+            .await
+            .ok()
+            .and_then(|value| Orientation::parse(&value))
+            .unwrap_or_default();
+
+        info!("Sensor proxy service initialized");
+
+        let _ = output
+            .send(ServiceEvent::Init(SensorProxyService {
+                data: SensorProxyData { orientation },
+            }))
+            .await;
+
+        State::Active(conn)
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => SensorProxyService::initialize(conn, output).await,
+                Err(err) => {
+                    error!("Failed to connect to system bus: {}", err);
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for orientation changes");
+
+                match SensorProxyProxy::new(&conn).await {
+                    Ok(proxy) => {
+                        let mut changes = proxy.receive_accelerometer_orientation_changed().await;
+
+                        while let Some(change) = changes.next().await {
+                            if let Some(orientation) = change
+                                .get()
+                                .await
+                                .ok()
+                                .and_then(|value| Orientation::parse(&value))
+                            {
+                                let _ = output
+                                    .send(ServiceEvent::Update(SensorProxyData { orientation }))
+                                    .await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for orientation changes: {}", err);
+
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for SensorProxyService {
+    type UpdateEvent = SensorProxyData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = SensorProxyService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[proxy(
+    default_service = "net.hadess.SensorProxy",
+    default_path = "/net/hadess/SensorProxy",
+    interface = "net.hadess.SensorProxy"
+)]
+trait SensorProxy {
+    fn claim_accelerometer(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn has_accelerometer(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn accelerometer_orientation(&self) -> zbus::Result<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_bus::{next_event, wait_for, TestBus};
+    use iced::futures::channel::mpsc;
+
+    const PATH: &str = "/net/hadess/SensorProxy";
+
+    struct FakeSensorProxy {
+        has_accelerometer: bool,
+        claimed: bool,
+        orientation: String,
+    }
+
+    #[zbus::interface(name = "net.hadess.SensorProxy")]
+    impl FakeSensorProxy {
+        fn claim_accelerometer(&mut self) {
+            self.claimed = true;
+        }
+
+        #[zbus(property)]
+        fn has_accelerometer(&self) -> bool {
+            self.has_accelerometer
+        }
+
+        #[zbus(property)]
+        fn accelerometer_orientation(&self) -> String {
+            self.orientation.clone()
+        }
+    }
+
+    async fn serve_sensor_proxy(bus: &TestBus, has_accelerometer: bool) -> zbus::Connection {
+        bus.server()
+            .name("net.hadess.SensorProxy")
+            .unwrap()
+            .serve_at(
+                PATH,
+                FakeSensorProxy {
+                    has_accelerometer,
+                    claimed: false,
+                    orientation: "left-up".to_owned(),
+                },
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn orientation_changes_are_streamed() {
+        let bus = TestBus::start();
+        let server = serve_sensor_proxy(&bus, true).await;
+        let (mut tx, mut rx) = mpsc::channel(10);
+
+        let state = SensorProxyService::initialize(bus.client().await, &mut tx).await;
+        let ServiceEvent::Init(service) = next_event(&mut rx).await else {
+            panic!("expected the init event");
+        };
+        assert_eq!(service.orientation, Orientation::LeftUp);
+
+        let sensor = &server
+            .object_server()
+            .interface::<_, FakeSensorProxy>(PATH)
+            .await
+            .unwrap();
+        assert!(sensor.get().await.claimed);
+
+        let listener = tokio::spawn(async move {
+            SensorProxyService::start_listening(state, &mut tx).await;
+        });
+
+        wait_for(
+            &mut rx,
+            || async move {
+                let mut fake = sensor.get_mut().await;
+                fake.orientation = "bottom-up".to_owned();
+                fake.accelerometer_orientation_changed(sensor.signal_emitter())
+                    .await
+                    .unwrap();
+            },
+            |event| {
+                matches!(
+                    event,
+                    ServiceEvent::Update(SensorProxyData {
+                        orientation: Orientation::BottomUp
+                    })
+                )
+            },
+        )
+        .await;
+
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn no_accelerometer() {
+        let bus = TestBus::start();
+        let _server = serve_sensor_proxy(&bus, false).await;
+        let (mut tx, _rx) = mpsc::channel(10);
+
+        let state = SensorProxyService::initialize(bus.client().await, &mut tx).await;
+        assert!(matches!(state, State::Error));
+    }
+}
@@ -0,0 +1,88 @@
+use iced::futures::{channel::mpsc::Receiver, StreamExt};
+use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+/// Private dbus-daemon standing in for the bus of a service, the fake
+/// services are served on it and the daemon is killed on drop. Every test
+/// gets its own daemon, so they can run in parallel
+pub struct TestBus {
+    daemon: Child,
+    address: String,
+}
+
+impl TestBus {
+    pub fn start() -> Self {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("dbus-daemon is required to run the service tests");
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().expect("dbus-daemon stdout"))
+            .read_line(&mut address)
+            .expect("Failed to read the dbus-daemon address");
+        let address = address.trim().to_owned();
+
+        Self { daemon, address }
+    }
+
+    /// Builder of the connection serving a fake service
+    pub fn server(&self) -> zbus::connection::Builder<'static> {
+        zbus::connection::Builder::address(self.address.as_str())
+            .expect("Invalid dbus-daemon address")
+    }
+
+    /// Connection handed to the service under test
+    pub async fn client(&self) -> zbus::Connection {
+        self.server()
+            .build()
+            .await
+            .expect("Failed to connect to the dbus-daemon")
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+/// Next event sent by a service, failing the test after a few seconds
+pub async fn next_event<T>(events: &mut Receiver<T>) -> T {
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .expect("Timed out waiting for a service event")
+        .expect("The service stopped sending events")
+}
+
+/// Repeats the change until the service reports the expected event,
+/// the first changes could be sent before the service is listening
+pub async fn wait_for<T, F, Fut>(
+    events: &mut Receiver<T>,
+    mut change: F,
+    expected: impl Fn(&T) -> bool,
+) where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            change().await;
+
+            while let Ok(Some(event)) =
+                tokio::time::timeout(Duration::from_millis(200), events.next()).await
+            {
+                if expected(&event) {
+                    return;
+                }
+            }
+        }
+    })
+    .await
+    .expect("Timed out waiting for the expected service event");
+}
@@ -0,0 +1,149 @@
+use evdev::{AbsoluteAxisType, Device, InputEventKind, Key, PropType, SwitchType};
+use iced::{
+    futures::{stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, info, warn};
+use std::any::TypeId;
+use tokio::sync::mpsc::unbounded_channel;
+
+/// First touch on a touchscreen, used to switch the bar to a touch
+/// friendly size only once the user actually interacts with it.
+///
+/// Reading the input devices requires the user to be in the `input` group.
+pub struct TouchInput;
+
+impl TouchInput {
+    fn touchscreens() -> Vec<Device> {
+        evdev::enumerate()
+            .map(|(_, device)| device)
+            .filter(|device| {
+                // touchpads report the same axes but are not direct input devices
+                device.properties().contains(PropType::DIRECT)
+                    && device
+                        .supported_absolute_axes()
+                        .is_some_and(|axes| axes.contains(AbsoluteAxisType::ABS_MT_POSITION_X))
+            })
+            .collect()
+    }
+
+    pub fn subscribe() -> Subscription<()> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(1, |mut output| async move {
+                let devices = TouchInput::touchscreens();
+
+                if devices.is_empty() {
+                    info!("No readable touchscreen found");
+                } else {
+                    let (tx, mut rx) = unbounded_channel();
+
+                    for device in devices {
+                        let name = device.name().unwrap_or_default().to_string();
+                        debug!("Listening for touches on {}", name);
+
+                        match device.into_event_stream() {
+                            Ok(mut events) => {
+                                let tx = tx.clone();
+                                tokio::spawn(async move {
+                                    while let Ok(event) = events.next_event().await {
+                                        if event.kind() == InputEventKind::Key(Key::BTN_TOUCH)
+                                            && event.value() == 1
+                                        {
+                                            let _ = tx.send(());
+                                            break;
+                                        }
+                                    }
+                                });
+                            }
+                            Err(err) => {
+                                warn!("Failed to listen for touches on {}: {}", name, err);
+                            }
+                        }
+                    }
+                    drop(tx);
+
+                    if rx.recv().await.is_some() {
+                        info!("Touch input detected");
+                        let _ = output.send(()).await;
+                    }
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
+
+/// State of the SW_TABLET_MODE switch of convertible laptops, true when
+/// the keyboard is folded away.
+pub struct TabletModeSwitch;
+
+impl TabletModeSwitch {
+    fn switches() -> Vec<Device> {
+        evdev::enumerate()
+            .map(|(_, device)| device)
+            .filter(|device| {
+                device
+                    .supported_switches()
+                    .is_some_and(|switches| switches.contains(SwitchType::SW_TABLET_MODE))
+            })
+            .collect()
+    }
+
+    pub fn subscribe() -> Subscription<bool> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                let devices = TabletModeSwitch::switches();
+
+                if devices.is_empty() {
+                    debug!("No tablet mode switch found");
+                } else {
+                    let (tx, mut rx) = unbounded_channel();
+
+                    for device in devices {
+                        let name = device.name().unwrap_or_default().to_string();
+                        debug!("Listening for tablet mode changes on {}", name);
+
+                        if let Ok(state) = device.get_switch_state() {
+                            let _ = tx.send(state.contains(SwitchType::SW_TABLET_MODE));
+                        }
+
+                        match device.into_event_stream() {
+                            Ok(mut events) => {
+                                let tx = tx.clone();
+                                tokio::spawn(async move {
+                                    while let Ok(event) = events.next_event().await {
+                                        if event.kind()
+                                            == InputEventKind::Switch(SwitchType::SW_TABLET_MODE)
+                                            && tx.send(event.value() == 1).is_err()
+                                        {
+                                            break;
+                                        }
+                                    }
+                                });
+                            }
+                            Err(err) => {
+                                warn!("Failed to listen for tablet mode on {}: {}", name, err);
+                            }
+                        }
+                    }
+                    drop(tx);
+
+                    while let Some(tablet_mode) = rx.recv().await {
+                        info!("Tablet mode: {}", tablet_mode);
+                        let _ = output.send(tablet_mode).await;
+                    }
+                }
+
+                let _ = pending::<u8>().next().await;
+            }),
+        )
+    }
+}
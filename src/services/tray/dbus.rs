@@ -154,8 +154,20 @@ pub struct Icon {
     pub bytes: Vec<u8>,
 }
 
+/// Tooltip of an item, the description can use a subset of HTML
+#[derive(Clone, Debug, zvariant::Value, zvariant::OwnedValue)]
+pub struct ToolTip {
+    pub icon_name: String,
+    pub icon_pixmap: Vec<Icon>,
+    pub title: String,
+    pub description: String,
+}
+
 #[proxy(interface = "org.kde.StatusNotifierItem")]
 pub trait StatusNotifierItem {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+
     #[zbus(property)]
     fn icon_name(&self) -> zbus::Result<String>;
 
@@ -164,6 +176,9 @@ pub trait StatusNotifierItem {
 
     #[zbus(property)]
     fn menu(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> zbus::Result<ToolTip>;
 }
 
 #[derive(Clone, Debug, Type)]
@@ -1,4 +1,5 @@
 use super::{ReadOnlyService, Service, ServiceEvent};
+use crate::utils::icons::{pixmap_handle, resolve_icon, AppIcon};
 use dbus::{
     DBusMenuProxy, Layout, StatusNotifierItemProxy, StatusNotifierWatcher,
     StatusNotifierWatcherProxy,
@@ -6,11 +7,10 @@ use dbus::{
 use iced::{
     futures::{
         channel::mpsc::Sender,
-        stream::{pending, select_all},
+        stream::{once, pending, select_all},
         stream_select, SinkExt, Stream, StreamExt,
     },
     stream::channel,
-    widget::image::Handle,
     Subscription, Task,
 };
 use log::{debug, error, info, trace};
@@ -21,17 +21,31 @@ pub mod dbus;
 #[derive(Debug, Clone)]
 pub enum TrayEvent {
     Registered(StatusNotifierItem),
-    IconChanged(String, Handle),
+    IconChanged(String, AppIcon),
     MenuLayoutChanged(String, Layout),
+    TooltipChanged(String, Option<Tooltip>),
     Unregistered(String),
     None,
 }
 
+#[derive(Debug, Clone)]
+pub struct Tooltip {
+    pub icon: Option<AppIcon>,
+    pub title: String,
+    pub description: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct StatusNotifierItem {
     pub name: String,
-    pub icon_pixmap: Option<Handle>,
+    // application chosen id, unlike the bus name it's the same across restarts
+    pub id: String,
+    // items that only publish an icon name start without an icon,
+    // it's resolved from the theme in the background
+    pub icon: Option<AppIcon>,
     pub menu: Layout,
+    // fetched on hover, the items don't notify when it changes
+    pub tooltip: Option<Tooltip>,
     item_proxy: StatusNotifierItemProxy<'static>,
     menu_proxy: DBusMenuProxy<'static>,
 }
@@ -50,7 +64,8 @@ impl StatusNotifierItem {
             .build()
             .await?;
 
-        let icon_pixmap = item_proxy
+        let id = item_proxy.id().await.unwrap_or_default();
+        let icon = item_proxy
             .icon_pixmap()
             .await
             .unwrap_or_default()
@@ -59,13 +74,7 @@ impl StatusNotifierItem {
                 trace!("tray icon w {}, h {}", i.width, i.height);
                 (i.width, i.height)
             })
-            .map(|mut i| {
-                // Convert ARGB to RGBA
-                for pixel in i.bytes.chunks_exact_mut(4) {
-                    pixel.rotate_left(1);
-                }
-                Handle::from_rgba(i.width as u32, i.height as u32, i.bytes)
-            });
+            .map(|i| AppIcon::Image(pixmap_handle(i.width, i.height, i.bytes)));
 
         let menu_path = item_proxy.menu().await?;
         let menu_proxy = dbus::DBusMenuProxy::builder(conn)
@@ -78,14 +87,39 @@ impl StatusNotifierItem {
 
         Ok(Self {
             name,
-            icon_pixmap,
+            id,
+            icon,
             menu,
+            tooltip: None,
             item_proxy,
             menu_proxy,
         })
     }
 }
 
+async fn fetch_tooltip(item_proxy: &StatusNotifierItemProxy<'_>) -> Option<Tooltip> {
+    let tooltip = item_proxy.tool_tip().await.ok()?;
+    if tooltip.title.is_empty() && tooltip.description.is_empty() {
+        return None;
+    }
+
+    let icon = match tooltip
+        .icon_pixmap
+        .into_iter()
+        .max_by_key(|i| (i.width, i.height))
+    {
+        Some(i) => Some(AppIcon::Image(pixmap_handle(i.width, i.height, i.bytes))),
+        None if !tooltip.icon_name.is_empty() => resolve_icon(tooltip.icon_name).await,
+        None => None,
+    };
+
+    Some(Tooltip {
+        icon,
+        title: tooltip.title,
+        description: tooltip.description,
+    })
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct TrayData(Vec<StatusNotifierItem>);
 
@@ -194,18 +228,12 @@ impl TrayService {
                                             trace!("tray icon w {}, h {}", i.width, i.height);
                                             (i.width, i.height)
                                         })
-                                        .map(|mut i| {
-                                            // Convert ARGB to RGBA
-                                            for pixel in i.bytes.chunks_exact_mut(4) {
-                                                pixel.rotate_left(1);
-                                            }
+                                        .map(|i| {
                                             TrayEvent::IconChanged(
                                                 name.to_owned(),
-                                                Handle::from_rgba(
-                                                    i.width as u32,
-                                                    i.height as u32,
-                                                    i.bytes,
-                                                ),
+                                                AppIcon::Image(pixmap_handle(
+                                                    i.width, i.height, i.bytes,
+                                                )),
                                             )
                                         })
                                 })
@@ -215,6 +243,28 @@ impl TrayService {
                     .boxed(),
             );
 
+            if item.icon.is_none() {
+                icon_pixel_change.push(
+                    once({
+                        let name = name.clone();
+                        let item_proxy = item.item_proxy.clone();
+                        async move {
+                            let icon_name = item_proxy
+                                .icon_name()
+                                .await
+                                .ok()
+                                .filter(|icon_name| !icon_name.is_empty())?;
+
+                            resolve_icon(icon_name)
+                                .await
+                                .map(|icon| TrayEvent::IconChanged(name.to_owned(), icon))
+                        }
+                    })
+                    .filter_map(|event| async move { event })
+                    .boxed(),
+                );
+            }
+
             let layout_updated = item.menu_proxy.receive_layout_updated().await;
             if let Ok(layout_updated) = layout_updated {
                 menu_layout_change.push(
@@ -354,9 +404,9 @@ impl ReadOnlyService for TrayService {
                     self.data.0.push(new_item);
                 }
             }
-            TrayEvent::IconChanged(name, handle) => {
+            TrayEvent::IconChanged(name, icon) => {
                 if let Some(item) = self.data.0.iter_mut().find(|item| item.name == name) {
-                    item.icon_pixmap = Some(handle);
+                    item.icon = Some(icon);
                 }
             }
             TrayEvent::MenuLayoutChanged(name, layout) => {
@@ -365,6 +415,11 @@ impl ReadOnlyService for TrayService {
                     item.menu = layout;
                 }
             }
+            TrayEvent::TooltipChanged(name, tooltip) => {
+                if let Some(item) = self.data.0.iter_mut().find(|item| item.name == name) {
+                    item.tooltip = tooltip;
+                }
+            }
             TrayEvent::Unregistered(name) => {
                 self.data.0.retain(|item| item.name != name);
             }
@@ -391,6 +446,7 @@ impl ReadOnlyService for TrayService {
 #[derive(Debug, Clone)]
 pub enum TrayCommand {
     MenuSelected(String, i32),
+    FetchTooltip(String),
 }
 
 impl Service for TrayService {
@@ -426,6 +482,24 @@ impl Service for TrayService {
                     Task::none()
                 }
             }
+            TrayCommand::FetchTooltip(name) => {
+                match self.data.iter().find(|item| item.name == name) {
+                    Some(item) => {
+                        let item_proxy = item.item_proxy.clone();
+
+                        Task::perform(
+                            async move { fetch_tooltip(&item_proxy).await },
+                            move |tooltip| {
+                                ServiceEvent::Update(TrayEvent::TooltipChanged(
+                                    name.clone(),
+                                    tooltip,
+                                ))
+                            },
+                        )
+                    }
+                    None => Task::none(),
+                }
+            }
         }
     }
 }
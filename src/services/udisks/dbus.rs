@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use sysinfo::Disks;
+use zbus::{
+    proxy,
+    zvariant::{OwnedObjectPath, OwnedValue},
+};
+
+use super::{MediaKind, RemovableMedia};
+
+type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>;
+
+pub struct UDisksDbus<'a> {
+    pub udisks: UDisksObjectManagerProxy<'a>,
+}
+
+impl UDisksDbus<'_> {
+    pub async fn new(conn: &zbus::Connection) -> anyhow::Result<Self> {
+        let udisks = UDisksObjectManagerProxy::new(conn).await?;
+
+        Ok(Self { udisks })
+    }
+
+    pub async fn media(&self) -> anyhow::Result<Vec<RemovableMedia>> {
+        let filesystems = self
+            .udisks
+            .get_managed_objects()
+            .await?
+            .into_iter()
+            .filter_map(|(key, item)| {
+                if item.contains_key("org.freedesktop.UDisks2.Filesystem") {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let disks = Disks::new_with_refreshed_list();
+        let conn = self.udisks.inner().connection();
+
+        let mut media = Vec::new();
+        for path in filesystems {
+            let block = BlockProxy::builder(conn).path(&path)?.build().await?;
+
+            if block.hint_system().await? || block.hint_ignore().await? {
+                continue;
+            }
+
+            let drive_path = block.drive().await?;
+            let ejectable = if drive_path.as_str() != "/" {
+                DriveProxy::builder(conn)
+                    .path(&drive_path)?
+                    .build()
+                    .await?
+                    .ejectable()
+                    .await?
+            } else {
+                false
+            };
+
+            let filesystem = FilesystemProxy::builder(conn).path(&path)?.build().await?;
+            let mount_point =
+                filesystem
+                    .mount_points()
+                    .await?
+                    .into_iter()
+                    .next()
+                    .map(|mount_point| {
+                        String::from_utf8_lossy(&mount_point)
+                            .trim_end_matches('\0')
+                            .to_string()
+                    });
+
+            let label = block.id_label().await?;
+            let label = if label.is_empty() {
+                String::from_utf8_lossy(&block.preferred_device().await?)
+                    .trim_end_matches('\0')
+                    .to_string()
+            } else {
+                label
+            };
+
+            let free = mount_point.as_ref().and_then(|mount_point| {
+                disks
+                    .iter()
+                    .find(|disk| disk.mount_point().to_string_lossy() == *mount_point)
+                    .map(|disk| disk.available_space())
+            });
+
+            media.push(RemovableMedia {
+                id: path.to_string(),
+                label,
+                kind: MediaKind::Block {
+                    path,
+                    drive: drive_path,
+                    ejectable,
+                },
+                size: block.size().await?,
+                mount_point,
+                free,
+            });
+        }
+
+        Ok(media)
+    }
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    default_path = "/org/freedesktop/UDisks2",
+    interface = "org.freedesktop.DBus.ObjectManager"
+)]
+pub trait UDisksObjectManager {
+    fn get_managed_objects(&self) -> zbus::Result<ManagedObjects>;
+
+    #[zbus(signal)]
+    fn interfaces_added(&self) -> Result<()>;
+
+    #[zbus(signal)]
+    fn interfaces_removed(&self) -> Result<()>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Block"
+)]
+trait Block {
+    #[zbus(property)]
+    fn drive(&self) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(property)]
+    fn id_label(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn preferred_device(&self) -> zbus::Result<Vec<u8>>;
+
+    #[zbus(property)]
+    fn size(&self) -> zbus::Result<u64>;
+
+    #[zbus(property)]
+    fn hint_system(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn hint_ignore(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Filesystem"
+)]
+pub trait Filesystem {
+    fn mount(&self, options: HashMap<&str, OwnedValue>) -> zbus::Result<String>;
+
+    fn unmount(&self, options: HashMap<&str, OwnedValue>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn mount_points(&self) -> zbus::Result<Vec<Vec<u8>>>;
+}
+
+#[proxy(
+    default_service = "org.freedesktop.UDisks2",
+    interface = "org.freedesktop.UDisks2.Drive"
+)]
+pub trait Drive {
+    fn eject(&self, options: HashMap<&str, OwnedValue>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn ejectable(&self) -> zbus::Result<bool>;
+}
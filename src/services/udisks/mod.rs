@@ -0,0 +1,369 @@
+use super::{ReadOnlyService, Service, ServiceEvent};
+use dbus::{DriveProxy, FilesystemProxy, UDisksDbus};
+use iced::{
+    futures::{
+        channel::mpsc::Sender,
+        stream::{pending, unfold},
+        stream_select, SinkExt, Stream, StreamExt,
+    },
+    stream::channel,
+    Subscription, Task,
+};
+use log::{debug, error, info, warn};
+use std::{any::TypeId, collections::HashMap, ops::Deref, time::Duration};
+use tokio::{process::Command, time::sleep};
+use zbus::zvariant::OwnedObjectPath;
+
+mod dbus;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaKind {
+    Block {
+        path: OwnedObjectPath,
+        drive: OwnedObjectPath,
+        ejectable: bool,
+    },
+    Mtp {
+        uri: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct RemovableMedia {
+    pub id: String,
+    pub label: String,
+    pub kind: MediaKind,
+    pub size: u64,
+    pub mount_point: Option<String>,
+    pub free: Option<u64>,
+}
+
+impl RemovableMedia {
+    pub fn is_mounted(&self) -> bool {
+        self.mount_point.is_some()
+    }
+
+    pub fn is_ejectable(&self) -> bool {
+        match &self.kind {
+            MediaKind::Block { ejectable, .. } => *ejectable,
+            MediaKind::Mtp { .. } => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UDisksData {
+    pub media: Vec<RemovableMedia>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UDisksService {
+    conn: zbus::Connection,
+    data: UDisksData,
+}
+
+impl Deref for UDisksService {
+    type Target = UDisksData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum UDisksCommand {
+    Mount(String),
+    Unmount(String),
+    Eject(String),
+}
+
+enum State {
+    Init,
+    Active(zbus::Connection),
+    Error,
+}
+
+impl UDisksService {
+    async fn initialize_data(conn: &zbus::Connection) -> anyhow::Result<UDisksData> {
+        let udisks = UDisksDbus::new(conn).await?;
+
+        let mut media = udisks.media().await?;
+        media.extend(UDisksService::mtp_devices().await);
+
+        Ok(UDisksData { media })
+    }
+
+    // MTP devices are not handled by UDisks2, they are exposed by the gvfs volume monitor
+    async fn mtp_devices() -> Vec<RemovableMedia> {
+        let output = Command::new("gio").arg("mount").arg("-li").output().await;
+
+        let output = match output {
+            Ok(output) => String::from_utf8_lossy(&output.stdout).into_owned(),
+            Err(err) => {
+                debug!("Failed to list gvfs volumes: {}", err);
+
+                return Vec::new();
+            }
+        };
+
+        let mut mounts = HashMap::new();
+        let mut volumes = Vec::new();
+        let mut current_volume = None;
+        for line in output.lines() {
+            let line = line.trim();
+
+            if let Some((_, name)) = line
+                .strip_prefix("Volume(")
+                .and_then(|line| line.split_once("): "))
+            {
+                current_volume = Some(name.to_string());
+            } else if let Some((name, uri)) = line
+                .strip_prefix("Mount(")
+                .and_then(|line| line.split_once("): "))
+                .and_then(|(_, mount)| mount.split_once(" -> "))
+            {
+                current_volume = None;
+                if uri.starts_with("mtp://") {
+                    mounts.insert(uri.to_string(), name.to_string());
+                }
+            } else if let Some(uri) = line.strip_prefix("activation_root=") {
+                if let Some(name) = current_volume.take().filter(|_| uri.starts_with("mtp://")) {
+                    volumes.push((name, uri.to_string()));
+                }
+            }
+        }
+
+        for (uri, name) in mounts.iter() {
+            if !volumes.iter().any(|(_, volume_uri)| volume_uri == uri) {
+                volumes.push((name.clone(), uri.clone()));
+            }
+        }
+
+        volumes
+            .into_iter()
+            .map(|(label, uri)| RemovableMedia {
+                id: uri.clone(),
+                label,
+                mount_point: mounts.contains_key(&uri).then(|| uri.clone()),
+                kind: MediaKind::Mtp { uri },
+                size: 0,
+                free: None,
+            })
+            .collect()
+    }
+
+    async fn events(conn: &zbus::Connection) -> anyhow::Result<impl Stream<Item = ()>> {
+        let udisks = UDisksDbus::new(conn).await?;
+
+        // mount point changes and MTP devices don't emit any ObjectManager signal
+        let poll = unfold((), |_| async {
+            sleep(Duration::from_secs(5)).await;
+
+            Some(((), ()))
+        });
+
+        Ok(stream_select!(
+            udisks.udisks.receive_interfaces_added().await?.map(|_| {}),
+            udisks
+                .udisks
+                .receive_interfaces_removed()
+                .await?
+                .map(|_| {}),
+            poll,
+        ))
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => {
+                    let data = UDisksService::initialize_data(&conn).await;
+
+                    match data {
+                        Ok(data) => {
+                            info!("UDisks service initialized");
+
+                            let _ = output
+                                .send(ServiceEvent::Init(UDisksService {
+                                    data,
+                                    conn: conn.clone(),
+                                }))
+                                .await;
+
+                            State::Active(conn)
+                        }
+                        Err(err) => {
+                            error!("Failed to initialize udisks service: {}", err);
+
+                            State::Error
+                        }
+                    }
+                }
+                Err(err) => {
+                    error!("Failed to connect to system bus: {}", err);
+
+                    State::Error
+                }
+            },
+            State::Active(conn) => {
+                info!("Listening for udisks events");
+
+                match UDisksService::events(&conn).await {
+                    Ok(events) => {
+                        let mut events = Box::pin(events);
+                        while events.next().await.is_some() {
+                            if let Ok(data) = UDisksService::initialize_data(&conn).await {
+                                let _ = output.send(ServiceEvent::Update(data)).await;
+                            }
+                        }
+
+                        State::Active(conn)
+                    }
+                    Err(err) => {
+                        error!("Failed to listen for udisks events: {}", err);
+                        State::Error
+                    }
+                }
+            }
+            State::Error => {
+                error!("UDisks service error");
+
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+
+    async fn gio_mount(args: &[&str], uri: &str) -> anyhow::Result<()> {
+        let output = Command::new("gio")
+            .arg("mount")
+            .args(args)
+            .arg(uri)
+            .output()
+            .await?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    async fn mount(conn: &zbus::Connection, kind: &MediaKind) -> anyhow::Result<()> {
+        match kind {
+            MediaKind::Block { path, .. } => {
+                let filesystem = FilesystemProxy::builder(conn).path(path)?.build().await?;
+                let mount_point = filesystem.mount(HashMap::new()).await?;
+                debug!("Mounted {} at {}", path.as_str(), mount_point);
+            }
+            MediaKind::Mtp { uri } => {
+                UDisksService::gio_mount(&[], uri).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn unmount(conn: &zbus::Connection, kind: &MediaKind) -> anyhow::Result<()> {
+        match kind {
+            MediaKind::Block { path, .. } => {
+                let filesystem = FilesystemProxy::builder(conn).path(path)?.build().await?;
+                filesystem.unmount(HashMap::new()).await?;
+            }
+            MediaKind::Mtp { uri } => {
+                UDisksService::gio_mount(&["-u"], uri).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn eject(conn: &zbus::Connection, media: &RemovableMedia) -> anyhow::Result<()> {
+        match &media.kind {
+            MediaKind::Block { drive, .. } => {
+                if media.is_mounted() {
+                    UDisksService::unmount(conn, &media.kind).await?;
+                }
+
+                let drive = DriveProxy::builder(conn).path(drive)?.build().await?;
+                drive.eject(HashMap::new()).await?;
+            }
+            MediaKind::Mtp { uri } => {
+                UDisksService::gio_mount(&["-e"], uri).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ReadOnlyService for UDisksService {
+    type UpdateEvent = UDisksData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(100, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = UDisksService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+impl Service for UDisksService {
+    type Command = UDisksCommand;
+
+    fn command(&mut self, command: Self::Command) -> Task<ServiceEvent<Self>> {
+        let id = match &command {
+            UDisksCommand::Mount(id) | UDisksCommand::Unmount(id) | UDisksCommand::Eject(id) => id,
+        };
+
+        if let Some(media) = self
+            .data
+            .media
+            .iter()
+            .find(|media| media.id == *id)
+            .cloned()
+        {
+            let conn = self.conn.clone();
+
+            Task::perform(
+                async move {
+                    let res = match command {
+                        UDisksCommand::Mount(_) => UDisksService::mount(&conn, &media.kind).await,
+                        UDisksCommand::Unmount(_) => {
+                            UDisksService::unmount(&conn, &media.kind).await
+                        }
+                        UDisksCommand::Eject(_) => UDisksService::eject(&conn, &media).await,
+                    };
+
+                    if let Err(err) = res {
+                        warn!("Failed to execute {:?}: {}", command, err);
+                    }
+
+                    UDisksService::initialize_data(&conn).await
+                },
+                |data| match data {
+                    Ok(data) => ServiceEvent::Update(data),
+                    Err(_) => ServiceEvent::Error(()),
+                },
+            )
+        } else {
+            Task::none()
+        }
+    }
+}
@@ -79,6 +79,9 @@ pub trait Device {
     #[zbus(property)]
     fn power_supply(&self) -> Result<bool>;
 
+    #[zbus(property)]
+    fn native_path(&self) -> Result<String>;
+
     #[zbus(property)]
     fn time_to_empty(&self) -> Result<i64>;
 
@@ -11,7 +11,7 @@ use log::{error, warn};
 use std::{any::TypeId, time::Duration};
 use zbus::zvariant::ObjectPath;
 
-mod dbus;
+pub mod dbus;
 
 #[derive(Clone, Copy, Debug)]
 pub struct BatteryData {
@@ -95,6 +95,17 @@ impl From<String> for PowerProfile {
     }
 }
 
+impl PowerProfile {
+    fn dbus_name(&self) -> Option<&'static str> {
+        match self {
+            PowerProfile::Balanced => Some("balanced"),
+            PowerProfile::Performance => Some("performance"),
+            PowerProfile::PowerSaver => Some("power-saver"),
+            PowerProfile::Unknown => None,
+        }
+    }
+}
+
 impl From<PowerProfile> for Icons {
     fn from(profile: PowerProfile) -> Self {
         match profile {
@@ -289,32 +300,34 @@ impl UPowerService {
         Ok(stream_select!(battery_event, power_profile_event))
     }
 
-    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
-        match state {
-            State::Init => match zbus::Connection::system().await {
-                Ok(conn) => {
-                    let (battery, battery_path, power_profile) =
-                        match UPowerService::initialize_data(&conn).await {
-                            Ok((Some((battery_data, battery_path)), power_profile)) => {
-                                (Some(battery_data), Some(battery_path), power_profile)
-                            }
-                            Ok((None, power_profile)) => (None, None, power_profile),
-                            Err(err) => {
-                                error!("Failed to initialize upower service: {}", err);
+    async fn initialize(conn: zbus::Connection, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        let (battery, battery_path, power_profile) =
+            match UPowerService::initialize_data(&conn).await {
+                Ok((Some((battery_data, battery_path)), power_profile)) => {
+                    (Some(battery_data), Some(battery_path), power_profile)
+                }
+                Ok((None, power_profile)) => (None, None, power_profile),
+                Err(err) => {
+                    error!("Failed to initialize upower service: {}", err);
 
-                                return State::Error;
-                            }
-                        };
+                    return State::Error;
+                }
+            };
+
+        let service = UPowerService {
+            battery,
+            power_profile,
+            conn: conn.clone(),
+        };
+        let _ = output.send(ServiceEvent::Init(service)).await;
 
-                    let service = UPowerService {
-                        battery,
-                        power_profile,
-                        conn: conn.clone(),
-                    };
-                    let _ = output.send(ServiceEvent::Init(service)).await;
+        State::Active(conn, battery_path)
+    }
 
-                    State::Active(conn, battery_path)
-                }
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => match zbus::Connection::system().await {
+                Ok(conn) => UPowerService::initialize(conn, output).await,
                 Err(err) => {
                     error!("Failed to connect to system bus for upower: {}", err);
                     State::Error
@@ -347,6 +360,7 @@ impl UPowerService {
 
 pub enum PowerProfileCommand {
     Toggle,
+    Set(PowerProfile),
 }
 
 impl Service for UPowerService {
@@ -384,6 +398,14 @@ impl Service for UPowerService {
                                 PowerProfile::Unknown => PowerProfile::Unknown,
                             }
                         }
+                        PowerProfileCommand::Set(profile) => match profile.dbus_name() {
+                            Some(name) => {
+                                let _ = powerprofiles.set_active_profile(name).await;
+
+                                profile
+                            }
+                            None => power_profile,
+                        },
                     }
                 }
             },
@@ -391,3 +413,260 @@ impl Service for UPowerService {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_bus::{next_event, wait_for, TestBus};
+    use iced::futures::channel::mpsc;
+    use zbus::{object_server::InterfaceRef, zvariant::OwnedObjectPath};
+
+    const BATTERY_PATH: &str = "/org/freedesktop/UPower/devices/battery_BAT0";
+
+    fn discharging(capacity: i64) -> BatteryData {
+        BatteryData {
+            capacity,
+            status: BatteryStatus::Discharging(Duration::from_secs(3600)),
+        }
+    }
+
+    #[test]
+    fn battery_icon_follows_the_capacity() {
+        assert_eq!(discharging(10).get_icon(), Icons::Battery0);
+        assert_eq!(discharging(20).get_icon(), Icons::Battery1);
+        assert_eq!(discharging(45).get_icon(), Icons::Battery2);
+        assert_eq!(discharging(79).get_icon(), Icons::Battery3);
+        assert_eq!(discharging(80).get_icon(), Icons::Battery4);
+    }
+
+    #[test]
+    fn charging_battery() {
+        let battery = BatteryData {
+            capacity: 10,
+            status: BatteryStatus::Charging(Duration::from_secs(3600)),
+        };
+
+        assert_eq!(battery.get_icon(), Icons::BatteryCharging);
+        assert_eq!(battery.get_indicator_state(), IndicatorState::Success);
+    }
+
+    #[test]
+    fn low_battery_is_a_danger() {
+        assert_eq!(
+            discharging(19).get_indicator_state(),
+            IndicatorState::Danger
+        );
+        assert_eq!(
+            discharging(20).get_indicator_state(),
+            IndicatorState::Normal
+        );
+    }
+
+    #[test]
+    fn full_battery() {
+        let battery = BatteryData {
+            capacity: 100,
+            status: BatteryStatus::Full,
+        };
+
+        assert_eq!(battery.get_icon(), Icons::Battery4);
+        assert_eq!(battery.get_indicator_state(), IndicatorState::Normal);
+    }
+
+    struct FakeUPower;
+
+    #[zbus::interface(name = "org.freedesktop.UPower")]
+    impl FakeUPower {
+        fn enumerate_devices(&self) -> Vec<OwnedObjectPath> {
+            vec![OwnedObjectPath::try_from(BATTERY_PATH).unwrap()]
+        }
+    }
+
+    struct FakeBattery {
+        percentage: f64,
+        state: u32,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.UPower.Device")]
+    impl FakeBattery {
+        #[zbus(property, name = "Type")]
+        fn device_type(&self) -> u32 {
+            2
+        }
+
+        #[zbus(property)]
+        fn power_supply(&self) -> bool {
+            true
+        }
+
+        #[zbus(property)]
+        fn native_path(&self) -> String {
+            "BAT0".to_owned()
+        }
+
+        #[zbus(property)]
+        fn time_to_empty(&self) -> i64 {
+            3600
+        }
+
+        #[zbus(property)]
+        fn time_to_full(&self) -> i64 {
+            1800
+        }
+
+        #[zbus(property)]
+        fn percentage(&self) -> f64 {
+            self.percentage
+        }
+
+        #[zbus(property)]
+        fn state(&self) -> u32 {
+            self.state
+        }
+    }
+
+    struct FakePowerProfiles {
+        active_profile: String,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.UPower.PowerProfiles")]
+    impl FakePowerProfiles {
+        #[zbus(property)]
+        fn active_profile(&self) -> String {
+            self.active_profile.clone()
+        }
+    }
+
+    async fn serve_upower(bus: &TestBus) -> zbus::Connection {
+        bus.server()
+            .name("org.freedesktop.UPower")
+            .unwrap()
+            .name("org.freedesktop.UPower.PowerProfiles")
+            .unwrap()
+            .serve_at("/org/freedesktop/UPower", FakeUPower)
+            .unwrap()
+            .serve_at(
+                BATTERY_PATH,
+                FakeBattery {
+                    percentage: 55.,
+                    state: 2,
+                },
+            )
+            .unwrap()
+            .serve_at(
+                "/org/freedesktop/UPower/PowerProfiles",
+                FakePowerProfiles {
+                    active_profile: "balanced".to_owned(),
+                },
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap()
+    }
+
+    async fn battery(server: &zbus::Connection) -> InterfaceRef<FakeBattery> {
+        server
+            .object_server()
+            .interface::<_, FakeBattery>(BATTERY_PATH)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn init_reads_battery_and_power_profile() {
+        let bus = TestBus::start();
+        let _server = serve_upower(&bus).await;
+        let (mut tx, mut rx) = mpsc::channel(10);
+
+        let state = UPowerService::initialize(bus.client().await, &mut tx).await;
+        assert!(matches!(state, State::Active(_, Some(_))));
+
+        let ServiceEvent::Init(service) = next_event(&mut rx).await else {
+            panic!("expected the init event");
+        };
+        let battery = service.battery.expect("battery");
+        assert_eq!(battery.capacity, 55);
+        assert!(matches!(battery.status, BatteryStatus::Discharging(d) if d.as_secs() == 3600));
+        assert_eq!(service.power_profile, PowerProfile::Balanced);
+    }
+
+    #[tokio::test]
+    async fn battery_changes_are_streamed() {
+        let bus = TestBus::start();
+        let server = serve_upower(&bus).await;
+        let (mut tx, mut rx) = mpsc::channel(10);
+
+        let state = UPowerService::initialize(bus.client().await, &mut tx).await;
+        next_event(&mut rx).await;
+        let listener = tokio::spawn(async move {
+            UPowerService::start_listening(state, &mut tx).await;
+        });
+
+        let battery = &battery(&server).await;
+        wait_for(
+            &mut rx,
+            || async move {
+                let mut fake = battery.get_mut().await;
+                fake.percentage = 40.;
+                fake.state = 1;
+                fake.percentage_changed(battery.signal_emitter())
+                    .await
+                    .unwrap();
+                fake.state_changed(battery.signal_emitter()).await.unwrap();
+            },
+            |event| {
+                matches!(
+                    event,
+                    ServiceEvent::Update(UPowerEvent::UpdateBattery(BatteryData {
+                        capacity: 40,
+                        status: BatteryStatus::Charging(_),
+                    }))
+                )
+            },
+        )
+        .await;
+
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn power_profile_changes_are_streamed() {
+        let bus = TestBus::start();
+        let server = serve_upower(&bus).await;
+        let (mut tx, mut rx) = mpsc::channel(10);
+
+        let state = UPowerService::initialize(bus.client().await, &mut tx).await;
+        next_event(&mut rx).await;
+        let listener = tokio::spawn(async move {
+            UPowerService::start_listening(state, &mut tx).await;
+        });
+
+        let profiles = &server
+            .object_server()
+            .interface::<_, FakePowerProfiles>("/org/freedesktop/UPower/PowerProfiles")
+            .await
+            .unwrap();
+        wait_for(
+            &mut rx,
+            || async move {
+                let mut fake = profiles.get_mut().await;
+                fake.active_profile = "performance".to_owned();
+                fake.active_profile_changed(profiles.signal_emitter())
+                    .await
+                    .unwrap();
+            },
+            |event| {
+                matches!(
+                    event,
+                    ServiceEvent::Update(UPowerEvent::UpdatePowerProfile(
+                        PowerProfile::Performance
+                    ))
+                )
+            },
+        )
+        .await;
+
+        listener.abort();
+    }
+}
@@ -0,0 +1,179 @@
+use iced::{futures::SinkExt, stream::channel, Color, Subscription};
+use log::{debug, info};
+use std::{any::TypeId, path::PathBuf, time::Duration};
+use tokio::process;
+
+// the wallpaper daemons don't notify when the wallpaper changes
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const HUE_BUCKETS: usize = 36;
+// accents darker than this are unreadable on the bar background
+const MIN_VALUE: f32 = 0.7;
+
+/// Accent color of the current wallpaper, set with swww or hyprpaper
+pub struct WallpaperAccent;
+
+fn parse_swww_query(output: &str) -> Option<PathBuf> {
+    output
+        .lines()
+        .find_map(|line| line.split_once("image: "))
+        .map(|(_, path)| PathBuf::from(path.trim()))
+}
+
+fn parse_hyprpaper_active(output: &str) -> Option<PathBuf> {
+    output
+        .lines()
+        .find_map(|line| line.split_once(" = "))
+        .map(|(_, path)| PathBuf::from(path.trim()))
+}
+
+async fn current_wallpaper() -> Option<PathBuf> {
+    if let Ok(output) = process::Command::new("swww").arg("query").output().await {
+        if let Some(path) = parse_swww_query(&String::from_utf8_lossy(&output.stdout)) {
+            return Some(path);
+        }
+    }
+
+    let output = process::Command::new("hyprctl")
+        .args(["hyprpaper", "listactive"])
+        .output()
+        .await
+        .ok()?;
+
+    parse_hyprpaper_active(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn to_hsv([r, g, b]: [u8; 3]) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255., g as f32 / 255., b as f32 / 255.);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let hue = if delta == 0. {
+        0.
+    } else if max == r {
+        60. * ((g - b) / delta).rem_euclid(6.)
+    } else if max == g {
+        60. * ((b - r) / delta + 2.)
+    } else {
+        60. * ((r - g) / delta + 4.)
+    };
+    let saturation = if max == 0. { 0. } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+/// Average color of the most prominent hue, grays and near black
+/// or white pixels don't make a good accent and are skipped
+fn accent_color(pixels: impl Iterator<Item = [u8; 3]>) -> Option<Color> {
+    let mut buckets = [(0., [0.; 3]); HUE_BUCKETS];
+
+    for pixel in pixels {
+        let (hue, saturation, value) = to_hsv(pixel);
+        if saturation < 0.25 || !(0.2..=0.95).contains(&value) {
+            continue;
+        }
+
+        let weight = saturation * value;
+        let bucket = &mut buckets[(hue / 360. * HUE_BUCKETS as f32) as usize % HUE_BUCKETS];
+        bucket.0 += weight;
+        for (sum, channel) in bucket.1.iter_mut().zip(pixel) {
+            *sum += channel as f32 * weight;
+        }
+    }
+
+    let (weight, sums) = buckets
+        .into_iter()
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .filter(|(weight, _)| *weight > 0.)?;
+    let [r, g, b] = sums.map(|sum| sum / weight / 255.);
+
+    // brightens dark accents keeping their hue
+    let scale = (MIN_VALUE / r.max(g).max(b)).max(1.);
+
+    Some(Color::from_rgb(
+        (r * scale).min(1.),
+        (g * scale).min(1.),
+        (b * scale).min(1.),
+    ))
+}
+
+async fn wallpaper_accent(path: PathBuf) -> Option<Color> {
+    tokio::task::spawn_blocking(move || {
+        let image = image::open(&path)
+            .inspect_err(|err| debug!("Failed to read the wallpaper {:?}: {}", path, err))
+            .ok()?
+            .thumbnail(64, 64)
+            .to_rgb8();
+
+        accent_color(image.pixels().map(|pixel| pixel.0))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+impl WallpaperAccent {
+    pub fn subscribe() -> Subscription<Option<Color>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(1, |mut output| async move {
+                let mut current = None;
+
+                loop {
+                    let wallpaper = current_wallpaper().await;
+                    if wallpaper != current {
+                        info!("Wallpaper changed: {:?}", wallpaper);
+                        current = wallpaper.clone();
+
+                        let accent = match wallpaper {
+                            Some(path) => wallpaper_accent(path).await,
+                            None => None,
+                        };
+                        let _ = output.send(accent).await;
+                    }
+
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wallpaper_paths() {
+        assert_eq!(
+            parse_swww_query(
+                "eDP-1: 1920x1080, scale: 1, currently displaying: image: /home/user/wall.jpg\n"
+            ),
+            Some(PathBuf::from("/home/user/wall.jpg"))
+        );
+        assert_eq!(
+            parse_hyprpaper_active("DP-1 = /home/user/wall.png\n"),
+            Some(PathBuf::from("/home/user/wall.png"))
+        );
+        assert_eq!(parse_hyprpaper_active("no wallpapers active\n"), None);
+    }
+
+    #[test]
+    fn most_prominent_hue_wins() {
+        let pixels = [[200, 40, 40]; 10]
+            .into_iter()
+            .chain([[40, 40, 200]; 3])
+            .chain([[128, 128, 128]; 50]);
+
+        let accent = accent_color(pixels).unwrap();
+        assert!(accent.r > accent.g && accent.r > accent.b);
+    }
+
+    #[test]
+    fn no_accent_in_grayscale() {
+        assert!(
+            accent_color([[10, 10, 10], [128, 128, 128], [250, 250, 250]].into_iter()).is_none()
+        );
+    }
+}
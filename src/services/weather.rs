@@ -0,0 +1,356 @@
+use super::{location::Coordinates, ReadOnlyService, ServiceEvent};
+use chrono::NaiveDate;
+use iced::{
+    futures::{channel::mpsc::Sender, stream::pending, SinkExt, StreamExt},
+    stream::channel,
+    Subscription,
+};
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use std::{
+    any::TypeId,
+    io::ErrorKind,
+    ops::Deref,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tokio::process;
+
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const FORECAST_DAYS: u8 = 3;
+
+/// Location, units and refresh interval of the forecast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherQuery {
+    pub coordinates: Coordinates,
+    pub imperial: bool,
+    pub interval: Duration,
+}
+
+static QUERY: Mutex<Option<WeatherQuery>> = Mutex::new(None);
+
+/// Forecast polled by the service, kept up to date by the weather module
+/// which knows the configured or the GeoClue location
+pub fn set_query(query: Option<WeatherQuery>) {
+    if let Ok(mut current) = QUERY.lock() {
+        *current = query;
+    }
+}
+
+fn query() -> Option<WeatherQuery> {
+    QUERY.lock().ok().and_then(|query| *query)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl Condition {
+    /// WMO weather interpretation code
+    fn from_code(code: u8) -> Self {
+        match code {
+            0 => Self::Clear,
+            1 | 2 => Self::PartlyCloudy,
+            45 | 48 => Self::Fog,
+            51..=57 => Self::Drizzle,
+            61..=67 | 80..=82 => Self::Rain,
+            71..=77 | 85 | 86 => Self::Snow,
+            95..=99 => Self::Thunderstorm,
+            _ => Self::Cloudy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CurrentWeather {
+    pub condition: Condition,
+    pub is_day: bool,
+    pub temperature: f32,
+    pub apparent_temperature: f32,
+    pub humidity: f32,
+    pub wind_speed: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    pub condition: Condition,
+    pub max: f32,
+    pub min: f32,
+    pub precipitation_probability: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WeatherData {
+    pub current: Option<CurrentWeather>,
+    pub daily: Vec<DailyForecast>,
+    // units of the last forecast, the configuration may have changed since
+    pub imperial: bool,
+}
+
+#[derive(Deserialize)]
+struct Forecast {
+    current: Current,
+    daily: Daily,
+}
+
+#[derive(Deserialize)]
+struct Current {
+    weather_code: u8,
+    is_day: u8,
+    temperature_2m: f32,
+    apparent_temperature: f32,
+    relative_humidity_2m: f32,
+    wind_speed_10m: f32,
+}
+
+#[derive(Deserialize)]
+struct Daily {
+    time: Vec<String>,
+    weather_code: Vec<u8>,
+    temperature_2m_max: Vec<f32>,
+    temperature_2m_min: Vec<f32>,
+    precipitation_probability_max: Vec<Option<f32>>,
+}
+
+fn parse_forecast(json: &[u8], imperial: bool) -> serde_json::Result<WeatherData> {
+    let forecast: Forecast = serde_json::from_slice(json)?;
+    let daily = forecast.daily;
+
+    Ok(WeatherData {
+        current: Some(CurrentWeather {
+            condition: Condition::from_code(forecast.current.weather_code),
+            is_day: forecast.current.is_day != 0,
+            temperature: forecast.current.temperature_2m,
+            apparent_temperature: forecast.current.apparent_temperature,
+            humidity: forecast.current.relative_humidity_2m,
+            wind_speed: forecast.current.wind_speed_10m,
+        }),
+        daily: daily
+            .time
+            .iter()
+            .zip(&daily.weather_code)
+            .zip(
+                daily
+                    .temperature_2m_max
+                    .iter()
+                    .zip(&daily.temperature_2m_min),
+            )
+            .enumerate()
+            .filter_map(|(i, ((date, code), (max, min)))| {
+                Some(DailyForecast {
+                    date: date.parse().ok()?,
+                    condition: Condition::from_code(*code),
+                    max: *max,
+                    min: *min,
+                    precipitation_probability: daily
+                        .precipitation_probability_max
+                        .get(i)
+                        .copied()
+                        .flatten(),
+                })
+            })
+            .collect(),
+        imperial,
+    })
+}
+
+fn forecast_url(query: &WeatherQuery) -> String {
+    format!(
+        "{}?latitude={:.4}&longitude={:.4}\
+        &current=weather_code,is_day,temperature_2m,apparent_temperature,relative_humidity_2m,wind_speed_10m\
+        &daily=weather_code,temperature_2m_max,temperature_2m_min,precipitation_probability_max\
+        &timezone=auto&forecast_days={}{}",
+        FORECAST_URL,
+        query.coordinates.latitude,
+        query.coordinates.longitude,
+        FORECAST_DAYS,
+        if query.imperial {
+            "&temperature_unit=fahrenheit&wind_speed_unit=mph"
+        } else {
+            ""
+        }
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct WeatherService {
+    data: WeatherData,
+}
+
+impl Deref for WeatherService {
+    type Target = WeatherData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+enum State {
+    Init,
+    // last query fetched and when
+    Active(Option<(WeatherQuery, Instant)>),
+    Error,
+}
+
+impl WeatherService {
+    async fn fetch(query: &WeatherQuery) -> std::io::Result<Option<WeatherData>> {
+        let output = process::Command::new("curl")
+            .args(["-sf", "--max-time", "15"])
+            .arg(forecast_url(query))
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            warn!("Failed to fetch the forecast: {}", output.status);
+            return Ok(None);
+        }
+
+        match parse_forecast(&output.stdout, query.imperial) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) => {
+                warn!("Failed to parse the forecast: {}", err);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn start_listening(state: State, output: &mut Sender<ServiceEvent<Self>>) -> State {
+        match state {
+            State::Init => {
+                info!("Weather service initialized");
+
+                let _ = output
+                    .send(ServiceEvent::Init(WeatherService {
+                        data: WeatherData::default(),
+                    }))
+                    .await;
+
+                State::Active(None)
+            }
+            State::Active(last) => {
+                let Some(query) = query() else {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    return State::Active(last);
+                };
+
+                // a new location or new units are fetched right away
+                let due = last.map_or(true, |(last_query, fetched)| {
+                    last_query != query || fetched.elapsed() >= query.interval
+                });
+                if !due {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    return State::Active(last);
+                }
+
+                debug!("Fetching the forecast for {:?}", query.coordinates);
+                match WeatherService::fetch(&query).await {
+                    Ok(data) => {
+                        if let Some(data) = data {
+                            let _ = output.send(ServiceEvent::Update(data)).await;
+                        }
+
+                        State::Active(Some((query, Instant::now())))
+                    }
+                    Err(err) if err.kind() == ErrorKind::NotFound => {
+                        error!("curl is needed to fetch the forecast");
+
+                        State::Error
+                    }
+                    Err(err) => {
+                        warn!("Failed to run curl: {}", err);
+
+                        State::Active(Some((query, Instant::now())))
+                    }
+                }
+            }
+            State::Error => {
+                error!("Weather service error");
+
+                let _ = pending::<u8>().next().await;
+                State::Error
+            }
+        }
+    }
+}
+
+impl ReadOnlyService for WeatherService {
+    type UpdateEvent = WeatherData;
+    type Error = ();
+
+    fn update(&mut self, event: Self::UpdateEvent) {
+        self.data = event;
+    }
+
+    fn subscribe() -> Subscription<ServiceEvent<Self>> {
+        let id = TypeId::of::<Self>();
+
+        Subscription::run_with_id(
+            id,
+            channel(10, |mut output| async move {
+                let mut state = State::Init;
+
+                loop {
+                    state = WeatherService::start_listening(state, &mut output).await;
+                }
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_forecast() {
+        let json = br#"{
+            "latitude": 45.46,
+            "longitude": 9.19,
+            "current": {
+                "time": "2025-06-01T14:00",
+                "weather_code": 61,
+                "is_day": 1,
+                "temperature_2m": 21.4,
+                "apparent_temperature": 20.8,
+                "relative_humidity_2m": 64,
+                "wind_speed_10m": 9.7
+            },
+            "daily": {
+                "time": ["2025-06-01", "2025-06-02"],
+                "weather_code": [61, 0],
+                "temperature_2m_max": [23.1, 27.5],
+                "temperature_2m_min": [15.2, 16.0],
+                "precipitation_probability_max": [80, null]
+            }
+        }"#;
+
+        let data = parse_forecast(json, false).unwrap();
+        let current = data.current.unwrap();
+        assert_eq!(current.condition, Condition::Rain);
+        assert!(current.is_day);
+        assert_eq!(current.temperature, 21.4);
+
+        assert_eq!(data.daily.len(), 2);
+        assert_eq!(data.daily[0].precipitation_probability, Some(80.));
+        assert_eq!(data.daily[1].condition, Condition::Clear);
+        assert_eq!(data.daily[1].precipitation_probability, None);
+    }
+
+    #[test]
+    fn wmo_codes() {
+        assert_eq!(Condition::from_code(2), Condition::PartlyCloudy);
+        assert_eq!(Condition::from_code(3), Condition::Cloudy);
+        assert_eq!(Condition::from_code(48), Condition::Fog);
+        assert_eq!(Condition::from_code(86), Condition::Snow);
+        assert_eq!(Condition::from_code(99), Condition::Thunderstorm);
+    }
+}
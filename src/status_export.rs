@@ -0,0 +1,201 @@
+use crate::{
+    config::StatusExportConfig,
+    services::upower::{BatteryData, BatteryStatus},
+};
+use flexi_logger::Duplicate;
+use log::warn;
+use serde::Serialize;
+use std::{
+    future::Future,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::Mutex;
+
+// the same threshold of the battery indicator
+const LOW_BATTERY: i64 = 20;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Battery {
+    pub capacity: i64,
+    pub charging: bool,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Volume {
+    pub level: i32,
+    pub muted: bool,
+}
+
+/// Summary of the bar state for scripts and other bars, the `text`, `tooltip`,
+/// `class` and `percentage` fields make it usable as a waybar custom module
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Status {
+    pub text: String,
+    pub tooltip: String,
+    pub class: Vec<&'static str>,
+    pub percentage: Option<i64>,
+    pub battery: Option<Battery>,
+    pub ssid: Option<String>,
+    pub volume: Option<Volume>,
+    pub workspace: Option<String>,
+    pub window_title: Option<String>,
+}
+
+impl Status {
+    pub fn new(
+        battery: Option<&BatteryData>,
+        ssid: Option<&str>,
+        volume: Option<Volume>,
+        workspace: Option<&str>,
+        window_title: Option<&str>,
+    ) -> Self {
+        let battery = battery.map(|battery| Battery {
+            capacity: battery.capacity,
+            charging: matches!(battery.status, BatteryStatus::Charging(_)),
+        });
+
+        let text = workspace
+            .into_iter()
+            .chain(window_title)
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+        let tooltip = [
+            battery.as_ref().map(|battery| {
+                format!(
+                    "battery: {}%{}",
+                    battery.capacity,
+                    if battery.charging { " (charging)" } else { "" }
+                )
+            }),
+            ssid.map(|ssid| format!("ssid: {}", ssid)),
+            volume.as_ref().map(|volume| {
+                format!(
+                    "volume: {}%{}",
+                    volume.level,
+                    if volume.muted { " (muted)" } else { "" }
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let class = [
+            battery
+                .as_ref()
+                .is_some_and(|battery| battery.charging)
+                .then_some("charging"),
+            battery
+                .as_ref()
+                .is_some_and(|battery| !battery.charging && battery.capacity < LOW_BATTERY)
+                .then_some("low-battery"),
+            volume
+                .as_ref()
+                .is_some_and(|volume| volume.muted)
+                .then_some("muted"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Self {
+            text,
+            tooltip,
+            class,
+            percentage: battery.as_ref().map(|battery| battery.capacity),
+            battery,
+            ssid: ssid.map(str::to_owned),
+            volume,
+            workspace: workspace.map(str::to_owned),
+            window_title: window_title.map(str::to_owned),
+        }
+    }
+}
+
+/// Logs aren't duplicated to stdout while it carries the status
+pub fn stdout_logs(export: Option<&StatusExportConfig>) -> Duplicate {
+    if export.is_some_and(|export| export.path.is_none()) {
+        Duplicate::None
+    } else {
+        Duplicate::All
+    }
+}
+
+static NEXT_WRITE: AtomicU64 = AtomicU64::new(1);
+// sequence number of the last status written
+static WRITTEN: Mutex<u64> = Mutex::const_new(0);
+
+/// Replaces the file at once, readers never see a partial status. The writes
+/// share the temporary file so they run one at a time, and a status older
+/// than the one already written is dropped
+pub fn write(path: PathBuf, content: String) -> impl Future<Output = ()> {
+    let sequence = NEXT_WRITE.fetch_add(1, Ordering::Relaxed);
+
+    async move {
+        let mut written = WRITTEN.lock().await;
+        if *written > sequence {
+            return;
+        }
+
+        let tmp = path.with_extension("tmp");
+
+        if let Some(dir) = path.parent() {
+            let _ = tokio::fs::create_dir_all(dir).await;
+        }
+        let result = match tokio::fs::write(&tmp, content).await {
+            Ok(_) => tokio::fs::rename(&tmp, &path).await,
+            Err(err) => Err(err),
+        };
+
+        match result {
+            Ok(_) => *written = sequence,
+            Err(err) => warn!("Failed to export the status to {:?}: {}", path, err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn waybar_fields() {
+        let battery = BatteryData {
+            capacity: 15,
+            status: BatteryStatus::Discharging(Duration::from_secs(1800)),
+        };
+        let status = Status::new(
+            Some(&battery),
+            Some("Home"),
+            Some(Volume {
+                level: 40,
+                muted: true,
+            }),
+            Some("2"),
+            Some("Firefox"),
+        );
+
+        assert_eq!(status.text, "2 · Firefox");
+        assert_eq!(
+            status.tooltip,
+            "battery: 15%\nssid: Home\nvolume: 40% (muted)"
+        );
+        assert_eq!(status.class, vec!["low-battery", "muted"]);
+        assert_eq!(status.percentage, Some(15));
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["battery"]["capacity"], 15);
+        assert_eq!(json["window_title"], "Firefox");
+    }
+
+    #[test]
+    fn nothing_known() {
+        let status = Status::new(None, None, None, None, None);
+
+        assert_eq!(status, Status::default());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::config::{Appearance, AppearanceColor};
+use crate::config::{Appearance, AppearanceColor, BarConfig, FontsConfig};
 use iced::{
     border::Radius,
     theme::{palette, Palette},
@@ -7,49 +7,150 @@ use iced::{
         container,
         text_input::{self},
     },
-    Background, Border, Color, Theme,
+    Background, Border, Color, Font, Shadow, Theme, Vector,
 };
+use log::{info, warn};
+use std::{collections::HashSet, process::Command, sync::OnceLock};
+
+const BUNDLED_ICON_FONT: &str = "Symbols Nerd Font";
+// room left below a floating bar for the blur of its shadow
+const BAR_SHADOW: u32 = 8;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AshellFonts {
+    pub text: Font,
+    pub icons: Font,
+    pub monospace: Option<Font>,
+}
+
+static FONTS: OnceLock<AshellFonts> = OnceLock::new();
+
+fn installed_font_families() -> Option<HashSet<String>> {
+    let output = Command::new("fc-list").args([":", "family"]).output();
+
+    match output {
+        Ok(output) if output.status.success() => Some(
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .flat_map(|line| line.split(','))
+                .map(|family| family.trim().to_string())
+                .collect(),
+        ),
+        _ => {
+            warn!("Failed to list the installed fonts, the first font of each chain will be used");
+
+            None
+        }
+    }
+}
+
+// picks the first installed family of the chain, glyphs missing
+// in the selected font are still resolved by the text shaper
+fn resolve_font(chain: &[String], installed: &Option<HashSet<String>>) -> Option<Font> {
+    chain
+        .iter()
+        .find(|family| {
+            installed
+                .as_ref()
+                .map_or(true, |installed| installed.contains(family.as_str()))
+        })
+        .map(|family| {
+            info!("Using font {}", family);
+
+            // iced requires a static font name, fonts are resolved once at startup
+            Font::with_name(Box::leak(family.clone().into_boxed_str()))
+        })
+}
+
+pub fn init_fonts(config: &FontsConfig) -> AshellFonts {
+    *FONTS.get_or_init(|| {
+        let installed = if config == &FontsConfig::default() {
+            None
+        } else {
+            installed_font_families()
+        };
+
+        AshellFonts {
+            text: resolve_font(&config.text, &installed).unwrap_or(Font::DEFAULT),
+            icons: resolve_font(&config.icons, &installed)
+                .unwrap_or(Font::with_name(BUNDLED_ICON_FONT)),
+            monospace: resolve_font(&config.monospace, &installed),
+        }
+    })
+}
+
+pub fn fonts() -> AshellFonts {
+    FONTS.get().copied().unwrap_or(AshellFonts {
+        text: Font::DEFAULT,
+        icons: Font::with_name(BUNDLED_ICON_FONT),
+        monospace: None,
+    })
+}
+
+/// Black or white, whichever is more readable on the given color
+fn contrast_text(color: Color) -> Color {
+    let luminance = 0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b;
+
+    if luminance > 0.5 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
+
+/// In high contrast mode the text is black or white and the custom
+/// color variants are replaced by the generated ones, which keep a
+/// stronger separation from the text.
+/// The wallpaper accent, when present, replaces the primary color
+pub fn ashell_theme(appearance: &Appearance, high_contrast: bool, accent: Option<Color>) -> Theme {
+    let background = appearance.background_color.get_base();
+    let text_for = |color: &AppearanceColor, fallback: Color| {
+        if high_contrast {
+            contrast_text(color.get_base())
+        } else {
+            color.get_text().unwrap_or(fallback)
+        }
+    };
 
-pub fn ashell_theme(appearance: &Appearance) -> Theme {
     Theme::custom_with_fn(
         "local".to_string(),
         Palette {
-            background: appearance.background_color.get_base(),
-            text: appearance.text_color.get_base(),
-            primary: appearance.primary_color.get_base(),
+            background,
+            text: if high_contrast {
+                contrast_text(background)
+            } else {
+                appearance.text_color.get_base()
+            },
+            primary: accent.unwrap_or_else(|| appearance.primary_color.get_base()),
             success: appearance.success_color.get_base(),
             danger: appearance.danger_color.get_base(),
         },
         |palette| {
             let default_bg = palette::Background::new(
                 palette.background,
-                appearance
-                    .background_color
-                    .get_text()
-                    .unwrap_or(palette.text),
+                text_for(&appearance.background_color, palette.text),
             );
             let default_primary = palette::Primary::generate(
                 palette.primary,
                 palette.background,
-                appearance.primary_color.get_text().unwrap_or(palette.text),
+                accent
+                    .map(contrast_text)
+                    .unwrap_or_else(|| text_for(&appearance.primary_color, palette.text)),
             );
             let default_secondary = palette::Primary::generate(
                 appearance.secondary_color.get_base(),
                 palette.background,
-                appearance
-                    .secondary_color
-                    .get_text()
-                    .unwrap_or(palette.text),
+                text_for(&appearance.secondary_color, palette.text),
             );
             let default_success = palette::Success::generate(
                 palette.success,
                 palette.background,
-                appearance.success_color.get_text().unwrap_or(palette.text),
+                text_for(&appearance.success_color, palette.text),
             );
             let default_danger = palette::Danger::generate(
                 palette.danger,
                 palette.background,
-                appearance.danger_color.get_text().unwrap_or(palette.text),
+                text_for(&appearance.danger_color, palette.text),
             );
 
             palette::Extended {
@@ -58,10 +159,12 @@ pub fn ashell_theme(appearance: &Appearance) -> Theme {
                     weak: appearance
                         .background_color
                         .get_weak_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_bg.weak),
                     strong: appearance
                         .background_color
                         .get_strong_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_bg.strong),
                 },
                 primary: palette::Primary {
@@ -69,10 +172,12 @@ pub fn ashell_theme(appearance: &Appearance) -> Theme {
                     weak: appearance
                         .primary_color
                         .get_weak_pair(palette.text)
+                        .filter(|_| !high_contrast && accent.is_none())
                         .unwrap_or(default_primary.weak),
                     strong: appearance
                         .primary_color
                         .get_strong_pair(palette.text)
+                        .filter(|_| !high_contrast && accent.is_none())
                         .unwrap_or(default_primary.strong),
                 },
                 secondary: palette::Secondary {
@@ -80,10 +185,12 @@ pub fn ashell_theme(appearance: &Appearance) -> Theme {
                     weak: appearance
                         .secondary_color
                         .get_weak_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_secondary.weak),
                     strong: appearance
                         .secondary_color
                         .get_strong_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_secondary.strong),
                 },
                 success: palette::Success {
@@ -91,10 +198,12 @@ pub fn ashell_theme(appearance: &Appearance) -> Theme {
                     weak: appearance
                         .success_color
                         .get_weak_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_success.weak),
                     strong: appearance
                         .success_color
                         .get_strong_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_success.strong),
                 },
                 danger: palette::Danger {
@@ -102,10 +211,12 @@ pub fn ashell_theme(appearance: &Appearance) -> Theme {
                     weak: appearance
                         .danger_color
                         .get_weak_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_danger.weak),
                     strong: appearance
                         .danger_color
                         .get_strong_pair(palette.text)
+                        .filter(|_| !high_contrast)
                         .unwrap_or(default_danger.strong),
                 },
                 is_dark: true,
@@ -128,6 +239,50 @@ pub fn module_label(theme: &Theme) -> container::Style {
     }
 }
 
+/// Space of the bar surface below the bar itself, the margins are
+/// left by the compositor outside of the surface
+pub fn bar_inset(bar: &BarConfig) -> u32 {
+    if bar.shadow {
+        BAR_SHADOW
+    } else {
+        0
+    }
+}
+
+/// The floating bar is drawn as an opaque panel, its shadow stays
+/// inside the layer surface
+pub fn floating_bar(theme: &Theme, bar: &BarConfig) -> container::Style {
+    container::Style {
+        background: Some(theme.palette().background.into()),
+        border: Border {
+            width: 0.0,
+            radius: bar.radius.into(),
+            color: Color::TRANSPARENT,
+        },
+        shadow: if bar.shadow {
+            Shadow {
+                color: Color::from_rgba(0., 0., 0., 0.4),
+                offset: Vector::new(0., 0.),
+                blur_radius: BAR_SHADOW as f32,
+            }
+        } else {
+            Shadow::default()
+        },
+        ..Default::default()
+    }
+}
+
+pub fn dragged_module(theme: &Theme) -> container::Style {
+    container::Style {
+        border: Border {
+            width: 1.0,
+            radius: 12.0.into(),
+            color: theme.palette().primary,
+        },
+        ..Default::default()
+    }
+}
+
 pub fn module_first_label(theme: &Theme) -> container::Style {
     let palette = theme.palette();
     container::Style {
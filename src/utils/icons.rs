@@ -0,0 +1,193 @@
+use super::image_cache::ImageCache;
+use iced::{
+    widget::{image, svg, Image, Svg},
+    Element, Length, Theme,
+};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
+
+// number of resolved names, misses included so that
+// a missing icon doesn't hit the disk on every lookup
+const ICONS_BUDGET: usize = 512;
+// bytes of the decoded tray pixmaps
+const PIXMAPS_BUDGET: usize = 16 * 1024 * 1024;
+
+// shared by all the modules, the same icon gets the same handle
+// and it's decoded and uploaded only once
+static ICONS: LazyLock<Mutex<ImageCache<String, Option<AppIcon>>>> =
+    LazyLock::new(|| Mutex::new(ImageCache::new(ICONS_BUDGET)));
+static PIXMAPS: LazyLock<Mutex<ImageCache<u64, image::Handle>>> =
+    LazyLock::new(|| Mutex::new(ImageCache::new(PIXMAPS_BUDGET)));
+
+#[derive(Debug, Clone)]
+pub enum AppIcon {
+    Image(image::Handle),
+    Svg(svg::Handle),
+    // drawn with the text color, like the icons of the font
+    Symbolic(svg::Handle),
+}
+
+impl AppIcon {
+    pub fn view<'a, Message: 'a>(&self, size: f32) -> Element<'a, Message> {
+        match self {
+            AppIcon::Image(handle) => Image::new(handle.clone())
+                .width(Length::Fixed(size))
+                .height(Length::Fixed(size))
+                .into(),
+            AppIcon::Svg(handle) => Svg::new(handle.clone())
+                .width(Length::Fixed(size))
+                .height(Length::Fixed(size))
+                .into(),
+            AppIcon::Symbolic(handle) => Svg::new(handle.clone())
+                .width(Length::Fixed(size))
+                .height(Length::Fixed(size))
+                .style(|theme: &Theme, _| svg::Style {
+                    color: Some(theme.palette().text),
+                })
+                .into(),
+        }
+    }
+}
+
+/// Resolves an icon name, or an absolute path, using the current icon theme
+fn get_icon_from_name(icon_name: &str) -> Option<AppIcon> {
+    if let Some(icon) = cached_icon(icon_name) {
+        return icon;
+    }
+
+    let icon = lookup_icon(icon_name);
+    if let Ok(mut icons) = ICONS.lock() {
+        icons.insert(icon_name.to_owned(), icon.clone(), 1);
+    }
+
+    icon
+}
+
+/// Same as `get_icon_from_name` but the theme lookup runs on the blocking pool,
+/// a cold cache on a slow home directory doesn't stall the caller
+pub async fn resolve_icon(icon_name: String) -> Option<AppIcon> {
+    if let Some(icon) = cached_icon(&icon_name) {
+        return icon;
+    }
+
+    tokio::task::spawn_blocking(move || get_icon_from_name(&icon_name))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn cached_icon(icon_name: &str) -> Option<Option<AppIcon>> {
+    ICONS.lock().ok().and_then(|mut icons| icons.get(icon_name))
+}
+
+fn lookup_icon(icon_name: &str) -> Option<AppIcon> {
+    let path = if Path::new(icon_name).is_absolute() {
+        Some(Path::new(icon_name).to_path_buf())
+    } else {
+        freedesktop_icons::lookup(icon_name)
+            .with_size(32)
+            .with_cache()
+            .find()
+    }?;
+
+    let symbolic = path
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().ends_with("-symbolic"));
+
+    if path.extension().is_some_and(|ext| ext == "svg") {
+        Some(if symbolic {
+            AppIcon::Symbolic(svg::Handle::from_path(path))
+        } else {
+            AppIcon::Svg(svg::Handle::from_path(path))
+        })
+    } else {
+        Some(AppIcon::Image(image::Handle::from_path(path)))
+    }
+}
+
+/// Crops the fully transparent border of an ARGB pixmap, the apps pad
+/// their tray icons differently and they would look of different sizes
+fn trim_pixmap(width: usize, height: usize, bytes: &[u8]) -> (usize, usize, Vec<u8>) {
+    let opaque = |x: usize, y: usize| bytes[(y * width + x) * 4] > 0;
+
+    let rows = (0..height).filter(|y| (0..width).any(|x| opaque(x, *y)));
+    let (top, bottom) = match rows.clone().next().zip(rows.last()) {
+        Some(bounds) => bounds,
+        None => return (width, height, bytes.to_vec()),
+    };
+    let columns = (0..width).filter(|x| (top..=bottom).any(|y| opaque(*x, y)));
+    let (left, right) = columns
+        .clone()
+        .next()
+        .zip(columns.last())
+        .unwrap_or((0, width - 1));
+
+    let trimmed = (top..=bottom)
+        .flat_map(|y| &bytes[(y * width + left) * 4..(y * width + right + 1) * 4])
+        .copied()
+        .collect();
+
+    (right - left + 1, bottom - top + 1, trimmed)
+}
+
+/// Handle of an ARGB pixmap, as sent by the tray items, shared with
+/// the items sending the same pixels
+pub fn pixmap_handle(width: i32, height: i32, bytes: Vec<u8>) -> image::Handle {
+    let mut hasher = DefaultHasher::new();
+    (width, height, &bytes).hash(&mut hasher);
+    let key = hasher.finish();
+
+    if let Some(handle) = PIXMAPS
+        .lock()
+        .ok()
+        .and_then(|mut pixmaps| pixmaps.get(&key))
+    {
+        return handle;
+    }
+
+    let (width, height, mut bytes) = if bytes.len() == width as usize * height as usize * 4 {
+        trim_pixmap(width as usize, height as usize, &bytes)
+    } else {
+        (width as usize, height as usize, bytes)
+    };
+
+    // Convert ARGB to RGBA
+    for pixel in bytes.chunks_exact_mut(4) {
+        pixel.rotate_left(1);
+    }
+    let cost = bytes.len();
+    let handle = image::Handle::from_rgba(width as u32, height as u32, bytes);
+    if let Ok(mut pixmaps) = PIXMAPS.lock() {
+        pixmaps.insert(key, handle.clone(), cost);
+    }
+
+    handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_transparent_border() {
+        // 4x3 pixmap with two opaque pixels at (1, 1) and (2, 1)
+        let mut bytes = vec![0; 4 * 3 * 4];
+        bytes[(4 + 1) * 4] = 255;
+        bytes[(4 + 2) * 4] = 255;
+
+        let (width, height, trimmed) = trim_pixmap(4, 3, &bytes);
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(trimmed.len(), 2 * 4);
+        assert!(trimmed.chunks(4).all(|pixel| pixel[0] == 255));
+    }
+
+    #[test]
+    fn keep_fully_transparent_pixmap() {
+        let bytes = vec![0; 2 * 2 * 4];
+
+        assert_eq!(trim_pixmap(2, 2, &bytes), (2, 2, bytes));
+    }
+}
@@ -0,0 +1,106 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// Least recently used cache with a size budget, the cost of each entry
+/// is chosen by the caller (a count, or the bytes of a decoded image)
+#[derive(Debug)]
+pub struct ImageCache<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    budget: usize,
+    used: usize,
+    tick: u64,
+}
+
+#[derive(Debug)]
+struct Entry<V> {
+    value: V,
+    cost: usize,
+    last_used: u64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> ImageCache<K, V> {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            budget,
+            used: 0,
+            tick: 0,
+        }
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.tick;
+
+        Some(entry.value.clone())
+    }
+
+    pub fn insert(&mut self, key: K, value: V, cost: usize) {
+        self.tick += 1;
+        if let Some(old) = self.entries.insert(
+            key,
+            Entry {
+                value,
+                cost,
+                last_used: self.tick,
+            },
+        ) {
+            self.used -= old.cost;
+        }
+        self.used += cost;
+
+        // the entry just inserted is the most recent one, it is kept
+        // even when it alone is over the budget
+        while self.used > self.budget && self.entries.len() > 1 {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&oldest) {
+                self.used -= entry.cost;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_least_recently_used() {
+        let mut cache = ImageCache::new(2);
+        cache.insert("a", 1, 1);
+        cache.insert("b", 2, 1);
+        assert_eq!(cache.get(&"a"), Some(1));
+
+        cache.insert("c", 3, 1);
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.get(&"c"), Some(3));
+    }
+
+    #[test]
+    fn keeps_the_budget_with_costly_entries() {
+        let mut cache = ImageCache::new(100);
+        cache.insert(1, "small", 10);
+        cache.insert(2, "small", 10);
+        cache.insert(3, "big", 95);
+        assert_eq!(cache.get(&1), None);
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&3), Some("big"));
+
+        cache.insert(3, "smaller", 20);
+        cache.insert(4, "small", 10);
+        assert_eq!(cache.get(&3), Some("smaller"));
+        assert_eq!(cache.get(&4), Some("small"));
+    }
+}
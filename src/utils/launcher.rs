@@ -1,4 +1,4 @@
-use std::process::Command;
+use std::{env, process::Command};
 
 pub fn execute_command(command: String) {
     tokio::spawn(async move {
@@ -22,6 +22,18 @@ pub fn suspend() {
     });
 }
 
+/// Suspends even if an application is holding a sleep inhibitor
+pub fn force_suspend() {
+    tokio::spawn(async move {
+        let _ = Command::new("bash")
+            .arg("-c")
+            .arg("systemctl suspend --check-inhibitors=no")
+            .spawn()
+            .expect("Failed to execute command.")
+            .wait();
+    });
+}
+
 pub fn shutdown() {
     tokio::spawn(async move {
         let _ = Command::new("bash")
@@ -54,3 +66,80 @@ pub fn logout() {
             .wait();
     });
 }
+
+pub fn is_niri() -> bool {
+    env::var_os("NIRI_SOCKET").is_some()
+}
+
+/// Turns off all the displays, or the named one, niri can only turn off all of them
+pub fn displays_off(output: Option<&str>) {
+    execute_command(if is_niri() {
+        "niri msg action power-off-monitors".to_owned()
+    } else {
+        format!("hyprctl dispatch dpms off {}", output.unwrap_or_default())
+    });
+}
+
+/// niri turns the displays on by itself on input, Hyprland needs to be asked
+pub fn displays_on() {
+    if !is_niri() {
+        execute_command("hyprctl dispatch dpms on".to_owned());
+    }
+}
+
+pub async fn copy_to_clipboard(value: String) {
+    let res = tokio::process::Command::new("wl-copy")
+        .arg("--")
+        .arg(&value)
+        .status()
+        .await;
+
+    if let Err(e) = res {
+        log::error!("Failed to copy {} to the clipboard: {:?}", value, e);
+    }
+}
+
+pub fn send_notification(summary: String, body: String) {
+    notify(summary, body, "normal");
+}
+
+/// Sends a notification with a button, true once the user pressed it
+pub async fn notification_action(summary: String, body: String, action: String) -> bool {
+    // notify-send waits for the notification to be closed and prints the chosen action
+    match tokio::process::Command::new("notify-send")
+        .arg("--app-name=ashell")
+        .arg(format!("--action=default={}", action))
+        .arg(&summary)
+        .arg(&body)
+        .output()
+        .await
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).trim() == "default",
+        Err(e) => {
+            log::warn!("Failed to send notification {}: {:?}", summary, e);
+            false
+        }
+    }
+}
+
+/// Critical notifications stay on screen until dismissed
+pub fn send_critical_notification(summary: String, body: String) {
+    notify(summary, body, "critical");
+}
+
+fn notify(summary: String, body: String, urgency: &'static str) {
+    tokio::spawn(async move {
+        match Command::new("notify-send")
+            .arg("--app-name=ashell")
+            .arg(format!("--urgency={}", urgency))
+            .arg(&summary)
+            .arg(&body)
+            .spawn()
+        {
+            Ok(mut child) => {
+                let _ = child.wait();
+            }
+            Err(e) => log::warn!("Failed to send notification {}: {:?}", summary, e),
+        }
+    });
+}
@@ -1,7 +1,17 @@
-use std::time::Duration;
+use crate::components::icons::Icons;
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
 
+pub mod icons;
+pub mod image_cache;
 pub mod launcher;
+pub mod screencopy;
+pub mod secrets;
+pub mod state;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum IndicatorState {
     Normal,
     Success,
@@ -9,6 +19,39 @@ pub enum IndicatorState {
     Danger,
 }
 
+static STATE_BADGES: AtomicBool = AtomicBool::new(false);
+
+/// Show a glyph next to the warning and danger indicators,
+/// so that their state doesn't rely on colors alone
+pub fn set_state_badges(value: bool) {
+    STATE_BADGES.store(value, Ordering::Relaxed);
+}
+
+impl IndicatorState {
+    /// Warning above the warn threshold, danger from the alert threshold
+    pub fn from_thresholds<T: PartialOrd>(value: T, warn: T, alert: T) -> Self {
+        if value >= alert {
+            IndicatorState::Danger
+        } else if value > warn {
+            IndicatorState::Warning
+        } else {
+            IndicatorState::Normal
+        }
+    }
+
+    pub fn badge(self) -> Option<Icons> {
+        if !STATE_BADGES.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        match self {
+            IndicatorState::Warning => Some(Icons::Alert),
+            IndicatorState::Danger => Some(Icons::AlertCircle),
+            _ => None,
+        }
+    }
+}
+
 pub fn format_duration(duration: &Duration) -> String {
     let h = duration.as_secs() / 60 / 60;
     let m = duration.as_secs() / 60 % 60;
@@ -18,3 +61,12 @@ pub fn format_duration(duration: &Duration) -> String {
         format!("{:>2}m", m)
     }
 }
+
+pub fn format_size(bytes: u64) -> String {
+    let gib = bytes as f64 / 1024. / 1024. / 1024.;
+    if gib >= 1. {
+        format!("{:.1} GiB", gib)
+    } else {
+        format!("{:.0} MiB", bytes as f64 / 1024. / 1024.)
+    }
+}
@@ -0,0 +1,345 @@
+use anyhow::{anyhow, bail};
+use iced::widget::image::Handle;
+use log::debug;
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom},
+    os::{fd::AsFd, unix::fs::OpenOptionsExt},
+    path::PathBuf,
+    process,
+};
+use wayland_client::{
+    protocol::{
+        wl_buffer::WlBuffer,
+        wl_output::{self, WlOutput},
+        wl_registry::{self, WlRegistry},
+        wl_shm::{Format, WlShm},
+        wl_shm_pool::WlShmPool,
+    },
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols_wlr::screencopy::v1::client::{
+    zwlr_screencopy_frame_v1::{self, Flags, ZwlrScreencopyFrameV1},
+    zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1,
+};
+
+/// Layout of the shm buffer requested by the compositor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BufferInfo {
+    format: Format,
+    width: u32,
+    height: u32,
+    stride: u32,
+}
+
+#[derive(Default)]
+struct CaptureData {
+    shm: Option<WlShm>,
+    manager: Option<ZwlrScreencopyManagerV1>,
+    outputs: Vec<(WlOutput, String)>,
+    buffer: Option<BufferInfo>,
+    // since version 3 every buffer type is listed before this event
+    buffer_done: bool,
+    y_invert: bool,
+    // outcome of the copy
+    result: Option<bool>,
+}
+
+/// Captures a single frame of the output through wlr-screencopy, scaled
+/// down by `scale`. It blocks until the compositor copied the frame.
+pub fn capture_output(name: &str, scale: f32) -> anyhow::Result<Handle> {
+    let connection = Connection::connect_to_env()?;
+    let mut event_queue = connection.new_event_queue();
+    let handle = event_queue.handle();
+    let _registry = connection.display().get_registry(&handle, ());
+    let mut data = CaptureData::default();
+
+    // the first one binds the globals, the second one gets the output names
+    event_queue.roundtrip(&mut data)?;
+    event_queue.roundtrip(&mut data)?;
+
+    let (Some(shm), Some(manager)) = (data.shm.clone(), data.manager.clone()) else {
+        bail!("the compositor doesn't support wlr-screencopy");
+    };
+    let output = data
+        .outputs
+        .iter()
+        .find(|(_, output_name)| output_name == name)
+        .map(|(output, _)| output.clone())
+        .ok_or_else(|| anyhow!("no output named {}", name))?;
+
+    let frame = manager.capture_output(0, &output, &handle, ());
+    while data.result.is_none()
+        && !(data.buffer.is_some() && (data.buffer_done || frame.version() < 3))
+    {
+        event_queue.blocking_dispatch(&mut data)?;
+    }
+    let Some(info) = data.buffer.filter(|_| data.result.is_none()) else {
+        frame.destroy();
+        bail!("no supported buffer format for output {}", name);
+    };
+
+    let size = info.stride * info.height;
+    let mut file = shm_file(size as u64)?;
+    let pool = shm.create_pool(file.as_fd(), size as i32, &handle, ());
+    let buffer = pool.create_buffer(
+        0,
+        info.width as i32,
+        info.height as i32,
+        info.stride as i32,
+        info.format,
+        &handle,
+        (),
+    );
+
+    frame.copy(&buffer);
+    while data.result.is_none() {
+        event_queue.blocking_dispatch(&mut data)?;
+    }
+    frame.destroy();
+    buffer.destroy();
+    pool.destroy();
+
+    if data.result != Some(true) {
+        bail!("the compositor failed to copy output {}", name);
+    }
+
+    let mut pixels = Vec::with_capacity(size as usize);
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut pixels)?;
+
+    let captured = image::RgbaImage::from_raw(
+        info.width,
+        info.height,
+        to_rgba(info, &pixels, data.y_invert),
+    )
+    .ok_or_else(|| anyhow!("incomplete frame of output {}", name))?;
+    let thumbnail = image::imageops::thumbnail(
+        &captured,
+        ((info.width as f32 * scale) as u32).max(1),
+        ((info.height as f32 * scale) as u32).max(1),
+    );
+    debug!(
+        "captured output {} in a {}x{} thumbnail",
+        name,
+        thumbnail.width(),
+        thumbnail.height()
+    );
+
+    Ok(Handle::from_rgba(
+        thumbnail.width(),
+        thumbnail.height(),
+        thumbnail.into_raw(),
+    ))
+}
+
+/// Private file backing the shm pool, unlinked as soon as it's created
+fn shm_file(size: u64) -> anyhow::Result<File> {
+    let path = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+        .join(format!("ashell-screencopy-{}", process::id()));
+
+    let _ = fs::remove_file(&path);
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)?;
+    let _ = fs::remove_file(&path);
+    file.set_len(size)?;
+
+    Ok(file)
+}
+
+/// Converts the little endian (A|X)RGB rows of the buffer to RGBA
+fn to_rgba(info: BufferInfo, pixels: &[u8], y_invert: bool) -> Vec<u8> {
+    let opaque = info.format == Format::Xrgb8888;
+    let row_len = info.width as usize * 4;
+    let mut rgba = Vec::with_capacity(row_len * info.height as usize);
+
+    for y in 0..info.height as usize {
+        let y = if y_invert {
+            info.height as usize - 1 - y
+        } else {
+            y
+        };
+        let start = y * info.stride as usize;
+        let Some(row) = pixels.get(start..start + row_len) else {
+            break;
+        };
+
+        for pixel in row.chunks_exact(4) {
+            rgba.extend_from_slice(&[
+                pixel[2],
+                pixel[1],
+                pixel[0],
+                if opaque { 255 } else { pixel[3] },
+            ]);
+        }
+    }
+
+    rgba
+}
+
+impl Dispatch<WlRegistry, ()> for CaptureData {
+    fn event(
+        state: &mut Self,
+        proxy: &WlRegistry,
+        event: <WlRegistry as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        handle: &QueueHandle<Self>,
+    ) {
+        if let wl_registry::Event::Global {
+            name,
+            interface,
+            version,
+        } = event
+        {
+            if interface == WlShm::interface().name && state.shm.is_none() {
+                state.shm = Some(proxy.bind(name, version.min(1), handle, ()));
+            } else if interface == ZwlrScreencopyManagerV1::interface().name
+                && state.manager.is_none()
+            {
+                state.manager = Some(proxy.bind(name, version.min(3), handle, ()));
+            } else if interface == WlOutput::interface().name && version >= 4 {
+                // the name of the output is only sent since version 4
+                state
+                    .outputs
+                    .push((proxy.bind(name, 4, handle, ()), String::new()));
+            }
+        }
+    }
+}
+
+impl Dispatch<WlOutput, ()> for CaptureData {
+    fn event(
+        state: &mut Self,
+        proxy: &WlOutput,
+        event: <WlOutput as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        if let wl_output::Event::Name { name } = event {
+            if let Some((_, output_name)) =
+                state.outputs.iter_mut().find(|(output, _)| output == proxy)
+            {
+                *output_name = name;
+            }
+        }
+    }
+}
+
+impl Dispatch<ZwlrScreencopyFrameV1, ()> for CaptureData {
+    fn event(
+        state: &mut Self,
+        _proxy: &ZwlrScreencopyFrameV1,
+        event: <ZwlrScreencopyFrameV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+        match event {
+            zwlr_screencopy_frame_v1::Event::Buffer {
+                format: WEnum::Value(format),
+                width,
+                height,
+                stride,
+            } if state.buffer.is_none()
+                && matches!(format, Format::Xrgb8888 | Format::Argb8888) =>
+            {
+                state.buffer = Some(BufferInfo {
+                    format,
+                    width,
+                    height,
+                    stride,
+                });
+            }
+            zwlr_screencopy_frame_v1::Event::BufferDone => state.buffer_done = true,
+            zwlr_screencopy_frame_v1::Event::Flags {
+                flags: WEnum::Value(flags),
+            } => state.y_invert = flags.contains(Flags::YInvert),
+            zwlr_screencopy_frame_v1::Event::Ready { .. } => state.result = Some(true),
+            zwlr_screencopy_frame_v1::Event::Failed => state.result = Some(false),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ZwlrScreencopyManagerV1, ()> for CaptureData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &ZwlrScreencopyManagerV1,
+        _event: <ZwlrScreencopyManagerV1 as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // This interface has no events.
+}
+
+impl Dispatch<WlShm, ()> for CaptureData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlShm,
+        _event: <WlShm as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // The formats are offered by the frame.
+}
+
+impl Dispatch<WlShmPool, ()> for CaptureData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlShmPool,
+        _event: <WlShmPool as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // This interface has no events.
+}
+
+impl Dispatch<WlBuffer, ()> for CaptureData {
+    fn event(
+        _state: &mut Self,
+        _proxy: &WlBuffer,
+        _event: <WlBuffer as Proxy>::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<Self>,
+    ) {
+    } // The buffer is dropped once the frame is ready.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_flipped_and_converted() {
+        let info = BufferInfo {
+            format: Format::Xrgb8888,
+            width: 1,
+            height: 2,
+            // rows are padded
+            stride: 8,
+        };
+        let pixels = [1, 2, 3, 0, 9, 9, 9, 9, 4, 5, 6, 0, 9, 9, 9, 9];
+
+        assert_eq!(
+            to_rgba(info, &pixels, false),
+            vec![3, 2, 1, 255, 6, 5, 4, 255]
+        );
+        assert_eq!(
+            to_rgba(info, &pixels, true),
+            vec![6, 5, 4, 255, 3, 2, 1, 255]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    config::{expand_home, Secret},
+    services::secret_service::lookup_secret,
+};
+use log::warn;
+use std::{collections::HashMap, fs, os::unix::fs::PermissionsExt, process::Stdio};
+use tokio::process;
+
+/// Retrieves the value of a secret referenced from the configuration
+pub async fn resolve_secret(secret: &Secret) -> anyhow::Result<String> {
+    match secret {
+        Secret::Keyring(attributes) => lookup_secret(
+            attributes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str()))
+                .collect(),
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("No keyring item matches {:?}", attributes)),
+        Secret::File(path) => {
+            let path = expand_home(path);
+            let mode = fs::metadata(&path)?.permissions().mode();
+            // like ssh, refuse the files other users can read
+            if mode & 0o077 != 0 {
+                return Err(anyhow::anyhow!(
+                    "The secret file {} must only be accessible by its owner (0600)",
+                    path
+                ));
+            }
+
+            Ok(fs::read_to_string(path)?.trim_end().to_string())
+        }
+        Secret::Command(cmd) => {
+            let output = process::Command::new("bash")
+                .arg("-c")
+                .arg(cmd)
+                .stdout(Stdio::piped())
+                .output()
+                .await?;
+            if !output.status.success() {
+                return Err(anyhow::anyhow!(
+                    "The secret command exited with {}",
+                    output.status
+                ));
+            }
+
+            Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+        }
+    }
+}
+
+/// Resolves the named secrets, the ones that fail are logged and skipped
+pub async fn resolve_secrets(secrets: &HashMap<String, Secret>) -> HashMap<String, String> {
+    let mut values = HashMap::with_capacity(secrets.len());
+    for (name, secret) in secrets {
+        match resolve_secret(secret).await {
+            Ok(value) => {
+                values.insert(name.clone(), value);
+            }
+            Err(err) => warn!("Failed to retrieve the secret {}: {}", name, err),
+        }
+    }
+
+    values
+}
@@ -0,0 +1,28 @@
+use log::warn;
+use std::{env, fs, path::PathBuf};
+
+fn state_dir() -> PathBuf {
+    env::var("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            let home_dir = env::var("HOME").expect("Could not get HOME environment variable");
+            PathBuf::from(home_dir).join(".local/state")
+        })
+        .join("ashell")
+}
+
+/// Read a value persisted across restarts
+pub fn read_state(name: &str) -> Option<String> {
+    fs::read_to_string(state_dir().join(name))
+        .ok()
+        .map(|content| content.trim().to_string())
+}
+
+/// Persist a value across restarts
+pub fn write_state(name: &str, content: &str) {
+    let dir = state_dir();
+
+    if let Err(err) = fs::create_dir_all(&dir).and_then(|_| fs::write(dir.join(name), content)) {
+        warn!("Failed to persist state {}: {}", name, err);
+    }
+}